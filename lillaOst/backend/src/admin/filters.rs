@@ -1,15 +1,19 @@
-use tokio::sync::mpsc::Sender;
 use warp::{filters::BoxedFilter, Filter, Reply};
 
 use super::handlers;
-use crate::command::CommandToBackend;
-use crate::common_filters::with_command_sender;
+use crate::command::Commands;
+use crate::common_filters::{with_command_sender, with_context};
+use crate::local_state::SharedContext;
 
-pub fn all_admin(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Reply,)> {
-    reset(tx.clone()).or(purge_all_events(tx)).boxed()
+pub fn all_admin(tx: Commands, context: SharedContext) -> BoxedFilter<(impl Reply,)> {
+    reset(tx.clone())
+        .or(purge_all_events(tx.clone()))
+        .or(integrity(context))
+        .or(repair_integrity(tx))
+        .boxed()
 }
 
-pub fn reset(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Reply,)> {
+pub fn reset(tx: Commands) -> BoxedFilter<(impl Reply,)> {
     warp::path!("api" / "admin" / "reset")
         .and(warp::post())
         .and(with_command_sender(tx))
@@ -17,10 +21,26 @@ pub fn reset(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Reply,)> {
         .boxed()
 }
 
-pub fn purge_all_events(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Reply,)> {
+pub fn purge_all_events(tx: Commands) -> BoxedFilter<(impl Reply,)> {
     warp::path!("api" / "admin" / "purge-all-events")
         .and(warp::post())
         .and(with_command_sender(tx))
         .and_then(handlers::purge_all_events)
         .boxed()
 }
+
+pub fn integrity(context: SharedContext) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("api" / "admin" / "integrity")
+        .and(warp::get())
+        .and(with_context(context))
+        .and_then(handlers::integrity)
+        .boxed()
+}
+
+pub fn repair_integrity(tx: Commands) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("api" / "admin" / "integrity" / "repair")
+        .and(warp::post())
+        .and(with_command_sender(tx))
+        .and_then(handlers::repair_integrity)
+        .boxed()
+}
@@ -1,21 +1,32 @@
 use std::convert::Infallible;
 
-use tokio::sync::mpsc::Sender;
 use tokio::sync::oneshot;
 
-use crate::command::CommandToBackend;
-use crate::common_handlers::send_command_to_server;
+use crate::command::{CommandSender, CommandToBackend};
+use crate::common_handlers::{read_from_context, send_command_to_server};
+use crate::local_state::SharedContext;
 
-pub async fn reset(tx: Sender<CommandToBackend>) -> Result<impl warp::Reply, Infallible> {
+pub async fn reset(tx: CommandSender) -> Result<impl warp::Reply, Infallible> {
     let (resp_tx, resp_rx) = oneshot::channel();
     let cmd = CommandToBackend::AdminReset { resp: resp_tx };
     Ok(send_command_to_server(tx, resp_rx, cmd).await)
 }
 
-pub async fn purge_all_events(
-    tx: Sender<CommandToBackend>,
-) -> Result<impl warp::Reply, Infallible> {
+pub async fn purge_all_events(tx: CommandSender) -> Result<impl warp::Reply, Infallible> {
     let (resp_tx, resp_rx) = oneshot::channel();
     let cmd = CommandToBackend::AdminPurgeEvents { resp: resp_tx };
     Ok(send_command_to_server(tx, resp_rx, cmd).await)
 }
+
+pub async fn integrity(context: SharedContext) -> Result<impl warp::Reply, Infallible> {
+    Ok(read_from_context(&context, |ost| {
+        serde_json::to_string(&ost.check_integrity()).unwrap()
+    })
+    .await)
+}
+
+pub async fn repair_integrity(tx: CommandSender) -> Result<impl warp::Reply, Infallible> {
+    let (resp_tx, resp_rx) = oneshot::channel();
+    let cmd = CommandToBackend::AdminRepairIntegrity { resp: resp_tx };
+    Ok(send_command_to_server(tx, resp_rx, cmd).await)
+}
@@ -0,0 +1,281 @@
+use std::collections::HashMap;
+use std::env;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+use chrono::{prelude::*, Duration};
+use openssl::hash::MessageDigest;
+use openssl::memcmp;
+use openssl::pkcs5::pbkdf2_hmac;
+use openssl::rand::rand_bytes;
+use serde_derive::{Deserialize, Serialize};
+
+use ost::error::OstError;
+use ost::role::Role;
+
+const PBKDF2_ITERATIONS: usize = 100_000;
+const SESSION_DAYS: i64 = 30;
+const MIN_PASSWORD_LENGTH: usize = 8;
+
+/// Caregivers and their sessions. Accounts persist as json, sessions only live as long as the server.
+pub type SharedAuth = Arc<RwLock<Auth>>;
+
+pub fn share_auth(auth: Auth) -> SharedAuth {
+    Arc::new(RwLock::new(auth))
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Account {
+    pub name: String,
+    #[serde(default)]
+    pub role: Role,
+    /// `pbkdf2-sha256$<iterations>$<salt>$<hash>`, hex encoded
+    password_hash: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AccountInfo {
+    pub name: String,
+    pub role: Role,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Credentials {
+    pub name: String,
+    pub password: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NewAccount {
+    pub name: String,
+    pub password: String,
+    pub role: Role,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SessionToken {
+    pub token: String,
+    pub role: Role,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Session {
+    pub account: String,
+    pub role: Role,
+    pub expires: DateTime<Utc>,
+}
+
+#[derive(Default)]
+pub struct Auth {
+    accounts_file: Option<String>,
+    accounts: Vec<Account>,
+    sessions: HashMap<String, Session>,
+}
+
+impl Auth {
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// A missing file starts with no accounts, it gets written on the first `add_account`
+    pub fn from_file(path: &str) -> Result<Self, OstError> {
+        let accounts = if Path::new(path).exists() {
+            serde_json::from_str(&std::fs::read_to_string(path)?)?
+        } else {
+            vec![]
+        };
+        Ok(Self {
+            accounts_file: Some(path.to_string()),
+            accounts,
+            sessions: HashMap::new(),
+        })
+    }
+
+    /// Accounts live in `OST_ACCOUNTS_FILE_PATH`, in memory when unset.
+    /// Without any account, `OST_INITIAL_ACCOUNT` and `OST_INITIAL_PASSWORD` create the first owner.
+    pub fn from_env() -> Result<Self, OstError> {
+        let mut auth = match env::var("OST_ACCOUNTS_FILE_PATH") {
+            Ok(path) => Self::from_file(&path)?,
+            Err(_) => Self::in_memory(),
+        };
+        if auth.accounts.is_empty() {
+            if let (Ok(name), Ok(password)) = (
+                env::var("OST_INITIAL_ACCOUNT"),
+                env::var("OST_INITIAL_PASSWORD"),
+            ) {
+                log::info!("Creating the initial account: {}", &name);
+                auth.add_account(&name, &password, Role::Owner)?;
+            }
+        }
+        Ok(auth)
+    }
+
+    pub fn accounts(&self) -> Vec<AccountInfo> {
+        self.accounts
+            .iter()
+            .map(|a| AccountInfo {
+                name: a.name.clone(),
+                role: a.role,
+            })
+            .collect()
+    }
+
+    pub fn add_account(&mut self, name: &str, password: &str, role: Role) -> Result<(), OstError> {
+        self.insert_account(Account::new(name, password, role)?)
+    }
+
+    /// Adds an account hashed by `Account::new`, the cheap half of `add_account`
+    pub fn insert_account(&mut self, account: Account) -> Result<(), OstError> {
+        if self.accounts.iter().any(|a| a.name == account.name) {
+            return Err(OstError::DuplicateName(format!(
+                "Account `{}` already exists",
+                account.name
+            )));
+        }
+        self.accounts.push(account);
+        self.persist()
+    }
+
+    /// Returns a fresh session token
+    pub fn login(&mut self, name: &str, password: &str) -> Result<SessionToken, OstError> {
+        match self.password_hash(name) {
+            Some(password_hash) if verify_password(password, &password_hash) => {
+                self.start_session(name)
+            }
+            _ => Err(wrong_credentials()),
+        }
+    }
+
+    /// What a login password is verified against, `None` for an unknown account
+    pub fn password_hash(&self, name: &str) -> Option<String> {
+        self.accounts
+            .iter()
+            .find(|a| a.name == name.trim())
+            .map(|a| a.password_hash.clone())
+    }
+
+    /// Returns a fresh session token for an account whose password got verified
+    pub fn start_session(&mut self, name: &str) -> Result<SessionToken, OstError> {
+        let account = match self.accounts.iter().find(|a| a.name == name.trim()) {
+            Some(account) => account,
+            None => return Err(wrong_credentials()),
+        };
+        let token = hex(&random_bytes(32));
+        let session = Session {
+            account: account.name.clone(),
+            role: account.role,
+            expires: Utc::now() + Duration::days(SESSION_DAYS),
+        };
+        let role = session.role;
+        self.sessions.insert(token.clone(), session);
+        Ok(SessionToken { token, role })
+    }
+
+    pub fn logout(&mut self, token: &str) {
+        self.sessions.remove(token);
+    }
+
+    pub fn session(&self, token: &str) -> Option<&Session> {
+        self.sessions
+            .get(token)
+            .filter(|session| session.expires > Utc::now())
+    }
+
+    fn persist(&self) -> Result<(), OstError> {
+        match &self.accounts_file {
+            Some(path) => Ok(std::fs::write(
+                path,
+                serde_json::to_string_pretty(&self.accounts)?,
+            )?),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Account {
+    /// Checks the name and password and hashes the password, the slow half of `add_account`
+    pub fn new(name: &str, password: &str, role: Role) -> Result<Self, OstError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(OstError::Validation("Account name is empty".to_string()));
+        }
+        if password.chars().count() < MIN_PASSWORD_LENGTH {
+            return Err(OstError::Validation(format!(
+                "Passwords need at least {} characters",
+                MIN_PASSWORD_LENGTH
+            )));
+        }
+        Ok(Self {
+            name: name.to_string(),
+            role,
+            password_hash: hash_password(password, &random_bytes(16)),
+        })
+    }
+}
+
+pub fn wrong_credentials() -> OstError {
+    OstError::Unauthorized("Unknown account or wrong password".to_string())
+}
+
+pub fn needs_role(required: Role) -> OstError {
+    OstError::Forbidden(format!("Needs the {:?} role", required))
+}
+
+fn hash_password(password: &str, salt: &[u8]) -> String {
+    format!(
+        "pbkdf2-sha256${}${}${}",
+        PBKDF2_ITERATIONS,
+        hex(salt),
+        hex(&derive_key(password, salt, PBKDF2_ITERATIONS))
+    )
+}
+
+/// Runs the whole key derivation, keep it off the async workers and outside of any lock
+pub fn verify_password(password: &str, password_hash: &str) -> bool {
+    let parts: Vec<&str> = password_hash.split('$').collect();
+    match parts.as_slice() {
+        ["pbkdf2-sha256", iterations, salt, hash] => {
+            match (iterations.parse(), unhex(salt), unhex(hash)) {
+                (Ok(iterations), Some(salt), Some(hash)) => {
+                    let candidate = derive_key(password, &salt, iterations);
+                    candidate.len() == hash.len() && memcmp::eq(&candidate, &hash)
+                }
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+fn derive_key(password: &str, salt: &[u8], iterations: usize) -> Vec<u8> {
+    let mut key = vec![0; 32];
+    pbkdf2_hmac(
+        password.as_bytes(),
+        salt,
+        iterations,
+        MessageDigest::sha256(),
+        &mut key,
+    )
+    .unwrap();
+    key
+}
+
+fn random_bytes(count: usize) -> Vec<u8> {
+    let mut bytes = vec![0; count];
+    rand_bytes(&mut bytes).unwrap();
+    bytes
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn unhex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
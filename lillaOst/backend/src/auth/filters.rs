@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+
+use serde::de::DeserializeOwned;
+use warp::path::FullPath;
+use warp::reply::Response;
+use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
+
+use super::accounts::{needs_role, SharedAuth};
+use super::handlers;
+use crate::v2::handlers::error_reply;
+
+use ost::error::OstError;
+use ost::role::Role;
+
+pub fn all_auth(auth: SharedAuth) -> BoxedFilter<(impl Reply,)> {
+    login(auth.clone())
+        .or(logout(auth.clone()))
+        .or(get_accounts(auth.clone()))
+        .or(add_account(auth))
+        .boxed()
+}
+
+pub fn login(auth: SharedAuth) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("api" / "auth" / "login")
+        .and(warp::post())
+        .and(with_auth(auth))
+        .and(json_body())
+        .and_then(handlers::login)
+        .boxed()
+}
+
+pub fn logout(auth: SharedAuth) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("api" / "auth" / "logout")
+        .and(warp::post())
+        .and(with_auth(auth))
+        .and(session_token())
+        .and_then(handlers::logout)
+        .boxed()
+}
+
+pub fn get_accounts(auth: SharedAuth) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("api" / "auth" / "accounts")
+        .and(warp::get())
+        .and(require_owner(auth.clone()))
+        .and(with_auth(auth))
+        .and_then(handlers::get_accounts)
+        .boxed()
+}
+
+pub fn add_account(auth: SharedAuth) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("api" / "auth" / "accounts")
+        .and(warp::post())
+        .and(require_owner(auth.clone()))
+        .and(with_auth(auth))
+        .and(json_body())
+        .and_then(handlers::add_account)
+        .boxed()
+}
+
+pub fn with_auth(
+    auth: SharedAuth,
+) -> impl Filter<Extract = (SharedAuth,), Error = std::convert::Infallible> + Clone {
+    warp::any().map(move || auth.clone())
+}
+
+fn json_body<T: DeserializeOwned + Send>(
+) -> impl Filter<Extract = (T,), Error = warp::Rejection> + Clone {
+    warp::body::content_length_limit(1024 * 16).and(warp::body::json())
+}
+
+/// Reachable without a session
+const OPEN_PATHS: [&str; 2] = ["/api/auth/login", "/api/openapi.json"];
+
+#[derive(Debug)]
+pub struct Unauthorized;
+
+impl warp::reject::Reject for Unauthorized {}
+
+#[derive(Debug)]
+pub struct Forbidden {
+    required: Role,
+}
+
+impl warp::reject::Reject for Forbidden {}
+
+/// The token out of `Authorization: Bearer <token>`, an empty string when there is none.
+/// Browsers can't set headers on a WebSocket, those pass it as `?access_token=<token>`.
+pub fn session_token() -> impl Filter<Extract = (String,), Error = Rejection> + Clone {
+    warp::header::optional::<String>("authorization")
+        .and(warp::query::<HashMap<String, String>>())
+        .map(
+            |header: Option<String>, mut query: HashMap<String, String>| {
+                header
+                    .as_deref()
+                    .and_then(|h| h.strip_prefix("Bearer "))
+                    .map(str::to_string)
+                    .or_else(|| query.remove("access_token"))
+                    .unwrap_or_default()
+            },
+        )
+}
+
+/// Every `/api` call outside `OPEN_PATHS` needs a live session, other paths pass untouched.
+/// What its role allows is checked where it's used, commands declare their own.
+pub fn require_session(auth: SharedAuth) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::path::full()
+        .and(session_token())
+        .and(with_auth(auth))
+        .and_then(
+            |path: FullPath, token: String, auth: SharedAuth| async move {
+                let path = path.as_str();
+                if !path.starts_with("/api/") || OPEN_PATHS.contains(&path) {
+                    return Ok(());
+                }
+                session_role(&auth, &token).map(|_| ())
+            },
+        )
+        .untuple_one()
+}
+
+/// Accounts aren't commands, only owners manage them
+fn require_owner(auth: SharedAuth) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    session_token()
+        .and(with_auth(auth))
+        .and_then(|token: String, auth: SharedAuth| async move {
+            let required = Role::Owner;
+            if session_role(&auth, &token)? >= required {
+                Ok(())
+            } else {
+                Err(warp::reject::custom(Forbidden { required }))
+            }
+        })
+        .untuple_one()
+}
+
+fn session_role(auth: &SharedAuth, token: &str) -> Result<Role, Rejection> {
+    match auth.read().unwrap().session(token) {
+        Some(session) => Ok(session.role),
+        None => Err(warp::reject::custom(Unauthorized)),
+    }
+}
+
+/// Turns `Unauthorized` into a 401 and `Forbidden` into a 403, other rejections pass through
+pub async fn unauthorized(rejection: Rejection) -> Result<Response, Rejection> {
+    if rejection.find::<Unauthorized>().is_some() {
+        return Ok(error_reply(OstError::Unauthorized(
+            "Log in to use the api".to_string(),
+        )));
+    }
+    match rejection.find::<Forbidden>() {
+        Some(Forbidden { required }) => Ok(error_reply(needs_role(*required))),
+        None => Err(rejection),
+    }
+}
@@ -0,0 +1,56 @@
+use std::convert::Infallible;
+
+use tokio::task::spawn_blocking;
+use warp::http::StatusCode;
+use warp::reply::Response;
+use warp::Reply;
+
+use super::accounts::{
+    verify_password, wrong_credentials, Account, Credentials, NewAccount, SharedAuth,
+};
+use crate::v2::handlers::error_reply;
+
+/// The key derivation runs on the blocking pool, the lock is only taken to read the hash
+/// and to start the session
+pub async fn login(auth: SharedAuth, credentials: Credentials) -> Result<Response, Infallible> {
+    let password_hash = auth.read().unwrap().password_hash(&credentials.name);
+    let verified = match password_hash {
+        Some(password_hash) => {
+            let password = credentials.password.clone();
+            spawn_blocking(move || verify_password(&password, &password_hash))
+                .await
+                .unwrap()
+        }
+        None => false,
+    };
+    let result = match verified {
+        true => auth.write().unwrap().start_session(&credentials.name),
+        false => Err(wrong_credentials()),
+    };
+    Ok(match result {
+        Ok(session) => warp::reply::json(&session).into_response(),
+        Err(err) => error_reply(err),
+    })
+}
+
+pub async fn logout(auth: SharedAuth, token: String) -> Result<Response, Infallible> {
+    auth.write().unwrap().logout(&token);
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+pub async fn get_accounts(auth: SharedAuth) -> Result<Response, Infallible> {
+    Ok(warp::reply::json(&auth.read().unwrap().accounts()).into_response())
+}
+
+/// Hashes the password on the blocking pool before taking the lock
+pub async fn add_account(auth: SharedAuth, account: NewAccount) -> Result<Response, Infallible> {
+    let hashed =
+        spawn_blocking(move || Account::new(&account.name, &account.password, account.role))
+            .await
+            .unwrap();
+    let result = hashed.and_then(|account| auth.write().unwrap().insert_account(account));
+    Ok(match result {
+        Ok(()) => StatusCode::CREATED.into_response(),
+        Err(err) => error_reply(err),
+    })
+}
@@ -1,16 +1,19 @@
+use tokio::sync::mpsc::Sender;
 use tokio::sync::oneshot;
 
+use crate::auth::accounts::{needs_role, SharedAuth};
 use crate::command_args::*;
 
+use ost::error::OstError;
+use ost::role::Role;
+
 type Responder<T> = oneshot::Sender<T>;
 
+/// Mutations only, reads go straight to the `SharedContext`
 #[allow(dead_code)]
 #[derive(Debug)]
 pub enum CommandToBackend {
     // ---- Persons
-    GetPersons {
-        resp: Responder<String>,
-    },
     AddPerson {
         args: ArgAddPerson,
         resp: Responder<String>,
@@ -23,14 +26,15 @@ pub enum CommandToBackend {
         args: ArgAModifyPerson,
         resp: Responder<String>,
     },
-    // Feedings
-    GetFeedings {
+    RemovePerson {
+        args: ArgRemovePerson,
         resp: Responder<String>,
     },
-    GetFeedingByKey {
-        args: ArgEntityKey,
+    MergePersons {
+        args: ArgMergePersons,
         resp: Responder<String>,
     },
+    // Feedings
     AddFakeFeedings {
         args: ArgFakeCount,
         resp: Responder<String>,
@@ -39,6 +43,10 @@ pub enum CommandToBackend {
         args: ArgAddFeeding,
         resp: Responder<String>,
     },
+    AddNursing {
+        args: ArgAddNursing,
+        resp: Responder<String>,
+    },
     ModifyFeeding {
         args: ArgAModifyFeeding,
         resp: Responder<String>,
@@ -48,9 +56,6 @@ pub enum CommandToBackend {
         resp: Responder<String>,
     },
     // Expulsions Section
-    GetExpulsions {
-        resp: Responder<String>,
-    },
     AddExpulsion {
         args: ArgAddExpulsion,
         resp: Responder<String>,
@@ -67,18 +72,7 @@ pub enum CommandToBackend {
         args: ArgEntityKey,
         resp: Responder<String>,
     },
-    GetExpulsionByKey {
-        args: ArgEntityKey,
-        resp: Responder<String>,
-    },
     // Events section
-    GetEvents {
-        resp: Responder<String>,
-    },
-    GetEventByKey {
-        args: ArgEntityKey,
-        resp: Responder<String>,
-    },
     AddEvent {
         args: ArgAddEvent,
         resp: Responder<String>,
@@ -95,6 +89,23 @@ pub enum CommandToBackend {
         args: ArgAModifyEvent,
         resp: Responder<String>,
     },
+    // Measurements section
+    AddMeasurement {
+        args: ArgAddMeasurement,
+        resp: Responder<String>,
+    },
+    ModifyMeasurement {
+        args: ArgModifyMeasurement,
+        resp: Responder<String>,
+    },
+    AddFakeMeasurements {
+        args: ArgFakeCount,
+        resp: Responder<String>,
+    },
+    RemoveMeasurement {
+        args: ArgEntityKey,
+        resp: Responder<String>,
+    },
     // Admin section
     AdminReset {
         resp: Responder<String>,
@@ -102,4 +113,97 @@ pub enum CommandToBackend {
     AdminPurgeEvents {
         resp: Responder<String>,
     },
+    AdminRepairIntegrity {
+        resp: Responder<String>,
+    },
+}
+
+impl CommandToBackend {
+    /// The least role allowed to send it. Caregivers log and correct feedings, expulsions,
+    /// events and measurements, persons, fake data and the admin operations stay with the owners.
+    pub fn required_role(&self) -> Role {
+        match self {
+            CommandToBackend::AddFeeding { .. }
+            | CommandToBackend::AddNursing { .. }
+            | CommandToBackend::ModifyFeeding { .. }
+            | CommandToBackend::RemoveFeeding { .. }
+            | CommandToBackend::AddExpulsion { .. }
+            | CommandToBackend::ModifyExpulsion { .. }
+            | CommandToBackend::RemoveExpulsion { .. }
+            | CommandToBackend::AddEvent { .. }
+            | CommandToBackend::ModifyEvent { .. }
+            | CommandToBackend::RemoveEvent { .. }
+            | CommandToBackend::AddMeasurement { .. }
+            | CommandToBackend::ModifyMeasurement { .. }
+            | CommandToBackend::RemoveMeasurement { .. } => Role::Caregiver,
+            CommandToBackend::AddPerson { .. }
+            | CommandToBackend::AddFakePerson { .. }
+            | CommandToBackend::ModifyPerson { .. }
+            | CommandToBackend::RemovePerson { .. }
+            | CommandToBackend::MergePersons { .. }
+            | CommandToBackend::AddFakeFeedings { .. }
+            | CommandToBackend::AddFakeExpulsions { .. }
+            | CommandToBackend::AddFakeEvents { .. }
+            | CommandToBackend::AddFakeMeasurements { .. }
+            | CommandToBackend::AdminReset { .. }
+            | CommandToBackend::AdminPurgeEvents { .. }
+            | CommandToBackend::AdminRepairIntegrity { .. } => Role::Owner,
+        }
+    }
+}
+
+/// The state task's inbox and the accounts whose sessions send to it
+#[derive(Clone)]
+pub struct Commands {
+    tx: Sender<CommandToBackend>,
+    auth: Option<SharedAuth>,
+}
+
+impl Commands {
+    pub fn new(tx: Sender<CommandToBackend>, auth: SharedAuth) -> Self {
+        Self {
+            tx,
+            auth: Some(auth),
+        }
+    }
+
+    /// Servers without accounts take every command
+    pub fn unrestricted(tx: Sender<CommandToBackend>) -> Self {
+        Self { tx, auth: None }
+    }
+
+    /// A session gone meanwhile may only read
+    pub fn for_session(&self, token: &str) -> CommandSender {
+        let role = match &self.auth {
+            Some(auth) => auth
+                .read()
+                .unwrap()
+                .session(token)
+                .map_or(Role::Viewer, |session| session.role),
+            None => Role::Owner,
+        };
+        CommandSender {
+            tx: self.tx.clone(),
+            role,
+        }
+    }
+}
+
+/// The inbox as one session sees it
+#[derive(Clone)]
+pub struct CommandSender {
+    tx: Sender<CommandToBackend>,
+    role: Role,
+}
+
+impl CommandSender {
+    /// Commands the role doesn't cover never reach the state task
+    pub async fn send(&self, cmd: CommandToBackend) -> Result<(), OstError> {
+        let required = cmd.required_role();
+        if self.role < required {
+            return Err(needs_role(required));
+        }
+        self.tx.send(cmd).await.unwrap();
+        Ok(())
+    }
 }
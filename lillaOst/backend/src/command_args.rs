@@ -3,6 +3,9 @@ use chrono::prelude::*;
 use ost::event::EventType;
 use ost::event_key::OstEventKey;
 use ost::expulsion::ExpulsionDegree;
+use ost::measurement::Measures;
+use ost::nursing::NursingSession;
+use ost::offline::ReplayId;
 use ost::person_key::OstPersonKey;
 
 use serde_derive::{Deserialize, Serialize};
@@ -23,6 +26,18 @@ pub struct ArgAModifyPerson {
     pub serialized_person: String,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ArgRemovePerson {
+    pub person_key: OstPersonKey,
+    pub cascade: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ArgMergePersons {
+    pub from: OstPersonKey,
+    pub into: OstPersonKey,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ArgEntityKey {
     pub event_key: OstEventKey,
@@ -32,6 +47,12 @@ pub struct ArgEntityKey {
 pub struct ArgAddEvent {
     pub person_key: OstPersonKey,
     pub new_event: EventType,
+    /// When it happened, now if missing. Set by clients replaying what they recorded offline.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_stamp: Option<DateTime<Utc>>,
+    /// Set on replayed entries, the backend applies each id only once
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replay_id: Option<ReplayId>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -45,6 +66,10 @@ pub struct ArgAModifyEvent {
 pub struct ArgAddExpulsion {
     pub person_key: OstPersonKey,
     pub expulsion_degree: ExpulsionDegree,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_stamp: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replay_id: Option<ReplayId>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -54,12 +79,35 @@ pub struct ArgModifyExpulsion {
     pub expulsion_degree: ExpulsionDegree,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ArgAddMeasurement {
+    pub person_key: OstPersonKey,
+    #[serde(flatten)]
+    pub measures: Measures,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_stamp: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replay_id: Option<ReplayId>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ArgModifyMeasurement {
+    pub event_key: OstEventKey,
+    pub time_stamp: DateTime<Utc>,
+    #[serde(flatten)]
+    pub measures: Measures,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ArgAddFeeding {
     pub person_key: OstPersonKey,
     pub breast_milk: u32,
     pub formula: u32,
     pub solids: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_stamp: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replay_id: Option<ReplayId>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -69,4 +117,15 @@ pub struct ArgAModifyFeeding {
     pub breast_milk: u32,
     pub formula: u32,
     pub solids: u32,
+    /// Replaced like the amounts, missing clears it
+    #[serde(default)]
+    pub nursing: Option<NursingSession>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ArgAddNursing {
+    pub person_key: OstPersonKey,
+    pub nursing: NursingSession,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replay_id: Option<ReplayId>,
 }
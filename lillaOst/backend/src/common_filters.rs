@@ -1,12 +1,21 @@
-use tokio::sync::mpsc::Sender;
 use warp::Filter;
 
-use crate::command::CommandToBackend;
+use crate::auth::filters::session_token;
+use crate::command::{CommandSender, Commands};
 use crate::command_args::{ArgEntityKey, ArgFakeCount};
+use crate::local_state::SharedContext;
+
+/// A sender limited to what the role of the request's session allows
 pub fn with_command_sender(
-    tx: Sender<CommandToBackend>,
-) -> impl Filter<Extract = (Sender<CommandToBackend>,), Error = std::convert::Infallible> + Clone {
-    warp::any().map(move || tx.clone())
+    tx: Commands,
+) -> impl Filter<Extract = (CommandSender,), Error = warp::Rejection> + Clone {
+    session_token().map(move |token: String| tx.for_session(&token))
+}
+
+pub fn with_context(
+    context: SharedContext,
+) -> impl Filter<Extract = (SharedContext,), Error = std::convert::Infallible> + Clone {
+    warp::any().map(move || context.clone())
 }
 
 pub fn json_args_fake_count(
@@ -1,14 +1,55 @@
-use crate::command::CommandToBackend;
-use tokio::sync::mpsc::Sender;
+use crate::command::{CommandSender, CommandToBackend};
+use crate::local_state::SharedContext;
 
 use tokio::sync::oneshot;
+use warp::http::StatusCode;
 
+use ost::context::Context;
+use ost::error::OstError;
+
+/// A command the session's role doesn't cover is answered like `/api/v2` does, with its status
 pub async fn send_command_to_server(
-    tx: Sender<CommandToBackend>,
+    tx: CommandSender,
     resp_rx: oneshot::Receiver<String>,
     cmd: CommandToBackend,
-) -> String {
-    tx.send(cmd).await.unwrap();
-    let res = resp_rx.await;
-    res.unwrap()
+) -> warp::reply::WithStatus<String> {
+    if let Err(err) = tx.send(cmd).await {
+        let status = error_status_code(&err);
+        return warp::reply::with_status(serde_json::to_string(&err).unwrap(), status);
+    }
+    let res = resp_rx.await.unwrap();
+    v1_reply(res)
+}
+
+/// Answers straight from the shared context, reads never wait for the state task
+pub async fn read_from_context(
+    context: &SharedContext,
+    read: impl FnOnce(&dyn Context) -> String,
+) -> warp::reply::WithStatus<String> {
+    let payload = read(context.read().await.as_ref());
+    v1_reply(payload)
+}
+
+/// The v1 routes keep their wire shape: always a 200, an `Err(OstError)` travels as the
+/// `Err(String)` of its message. Typed errors and status codes are for `/api/v2`.
+fn v1_reply(payload: String) -> warp::reply::WithStatus<String> {
+    let payload = match serde_json::from_str::<Result<serde_json::Value, OstError>>(&payload) {
+        Ok(Err(err)) => serde_json::to_string(&Err::<(), String>(err.to_string())).unwrap(),
+        _ => payload,
+    };
+    warp::reply::with_status(payload, StatusCode::OK)
+}
+
+pub fn error_status_code(err: &OstError) -> StatusCode {
+    match err {
+        OstError::NotFound(_) => StatusCode::NOT_FOUND,
+        OstError::DuplicateName(_) => StatusCode::CONFLICT,
+        OstError::Validation(_) => StatusCode::BAD_REQUEST,
+        OstError::PersistenceIo(_) | OstError::Serialization(_) => {
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+        OstError::Transport(_) => StatusCode::BAD_GATEWAY,
+        OstError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+        OstError::Forbidden(_) => StatusCode::FORBIDDEN,
+    }
 }
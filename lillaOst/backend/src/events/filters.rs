@@ -1,14 +1,16 @@
-use tokio::sync::mpsc::Sender;
 use warp::{filters::BoxedFilter, Filter, Reply};
 
 use super::handlers;
-use crate::command::CommandToBackend;
+use crate::command::Commands;
 use crate::command_args::{ArgAModifyEvent, ArgAddEvent};
-use crate::common_filters::{json_args_entity_key, json_args_fake_count, with_command_sender};
+use crate::common_filters::{
+    json_args_entity_key, json_args_fake_count, with_command_sender, with_context,
+};
+use crate::local_state::SharedContext;
 
-pub fn all_events(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Reply,)> {
-    get_events(tx.clone())
-        .or(get_event_by_key(tx.clone()))
+pub fn all_events(tx: Commands, context: SharedContext) -> BoxedFilter<(impl Reply,)> {
+    get_events(context.clone())
+        .or(get_event_by_key(context))
         .or(add_event(tx.clone()))
         .or(add_fake_events(tx.clone()))
         .or(remove_event(tx.clone()))
@@ -16,24 +18,24 @@ pub fn all_events(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Reply,)> {
         .boxed()
 }
 
-pub fn get_events(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Reply,)> {
+pub fn get_events(context: SharedContext) -> BoxedFilter<(impl Reply,)> {
     warp::get()
         .and(warp::path!("api" / "events"))
-        .and(with_command_sender(tx))
+        .and(with_context(context))
         .and_then(handlers::get_events)
         .boxed()
 }
 
-pub fn get_event_by_key(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Reply,)> {
+pub fn get_event_by_key(context: SharedContext) -> BoxedFilter<(impl Reply,)> {
     warp::post()
         .and(warp::path!("api" / "events"))
-        .and(with_command_sender(tx))
+        .and(with_context(context))
         .and(json_args_entity_key())
         .and_then(handlers::get_event_by_key)
         .boxed()
 }
 
-pub fn add_event(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Reply,)> {
+pub fn add_event(tx: Commands) -> BoxedFilter<(impl Reply,)> {
     warp::post()
         .and(warp::path!("api" / "events" / "add"))
         .and(with_command_sender(tx))
@@ -42,7 +44,7 @@ pub fn add_event(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Reply,)> {
         .boxed()
 }
 
-pub fn add_fake_events(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Reply,)> {
+pub fn add_fake_events(tx: Commands) -> BoxedFilter<(impl Reply,)> {
     warp::post()
         .and(warp::path!("api" / "events" / "add-fake-count"))
         .and(with_command_sender(tx))
@@ -51,7 +53,7 @@ pub fn add_fake_events(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Reply,
         .boxed()
 }
 
-pub fn remove_event(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Reply,)> {
+pub fn remove_event(tx: Commands) -> BoxedFilter<(impl Reply,)> {
     warp::post()
         .and(warp::path!("api" / "events" / "remove"))
         .and(with_command_sender(tx))
@@ -60,7 +62,7 @@ pub fn remove_event(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Reply,)>
         .boxed()
 }
 
-pub fn modify_event(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Reply,)> {
+pub fn modify_event(tx: Commands) -> BoxedFilter<(impl Reply,)> {
     warp::path!("api" / "event")
         .and(warp::post())
         .and(with_command_sender(tx))
@@ -1,31 +1,32 @@
 use std::convert::Infallible;
-use tokio::sync::mpsc::Sender;
 use tokio::sync::oneshot;
 
-use crate::command::CommandToBackend;
+use crate::command::{CommandSender, CommandToBackend};
 use crate::command_args::{ArgAModifyEvent, ArgAddEvent, ArgEntityKey, ArgFakeCount};
-use crate::common_handlers::send_command_to_server;
+use crate::common_handlers::{read_from_context, send_command_to_server};
+use crate::local_state::SharedContext;
 
-pub async fn get_events(tx: Sender<CommandToBackend>) -> Result<impl warp::Reply, Infallible> {
-    let (resp_tx, resp_rx) = oneshot::channel();
-    let cmd = CommandToBackend::GetEvents { resp: resp_tx };
-    Ok(send_command_to_server(tx, resp_rx, cmd).await)
+pub async fn get_events(context: SharedContext) -> Result<impl warp::Reply, Infallible> {
+    Ok(read_from_context(&context, |ost| {
+        let events: Vec<String> = ost.events().iter().map(|e| e.serialize()).collect();
+        serde_json::to_string(&events).unwrap()
+    })
+    .await)
 }
 
 pub async fn get_event_by_key(
-    tx: Sender<CommandToBackend>,
+    context: SharedContext,
     args: ArgEntityKey,
 ) -> Result<impl warp::Reply, Infallible> {
-    let (resp_tx, resp_rx) = oneshot::channel();
-    let cmd = CommandToBackend::GetEventByKey {
-        resp: resp_tx,
-        args,
-    };
-    Ok(send_command_to_server(tx, resp_rx, cmd).await)
+    Ok(read_from_context(&context, |ost| {
+        let found: Option<String> = ost.get_event_by_key(&args.event_key).map(|e| e.serialize());
+        serde_json::to_string(&found).unwrap()
+    })
+    .await)
 }
 
 pub async fn add_fake_events(
-    tx: Sender<CommandToBackend>,
+    tx: CommandSender,
     args: ArgFakeCount,
 ) -> Result<impl warp::Reply, Infallible> {
     let (resp_tx, resp_rx) = oneshot::channel();
@@ -37,7 +38,7 @@ pub async fn add_fake_events(
 }
 
 pub async fn remove_event(
-    tx: Sender<CommandToBackend>,
+    tx: CommandSender,
     args: ArgEntityKey,
 ) -> Result<impl warp::Reply, Infallible> {
     let (resp_tx, resp_rx) = oneshot::channel();
@@ -49,7 +50,7 @@ pub async fn remove_event(
 }
 
 pub async fn add_event(
-    tx: Sender<CommandToBackend>,
+    tx: CommandSender,
     args: ArgAddEvent,
 ) -> Result<impl warp::Reply, Infallible> {
     let (resp_tx, resp_rx) = oneshot::channel();
@@ -61,7 +62,7 @@ pub async fn add_event(
 }
 
 pub async fn modify_event(
-    tx: Sender<CommandToBackend>,
+    tx: CommandSender,
     args: ArgAModifyEvent,
 ) -> Result<impl warp::Reply, Infallible> {
     let (resp_tx, resp_rx) = oneshot::channel();
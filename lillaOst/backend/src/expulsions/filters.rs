@@ -1,30 +1,32 @@
-use tokio::sync::mpsc::Sender;
 use warp::{filters::BoxedFilter, Filter, Reply};
 
 use super::handlers;
-use crate::command::CommandToBackend;
+use crate::command::Commands;
 use crate::command_args::{ArgAddExpulsion, ArgModifyExpulsion};
-use crate::common_filters::{json_args_entity_key, json_args_fake_count, with_command_sender};
+use crate::common_filters::{
+    json_args_entity_key, json_args_fake_count, with_command_sender, with_context,
+};
+use crate::local_state::SharedContext;
 
-pub fn all_expulsions(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Reply,)> {
-    get_expulsions(tx.clone())
+pub fn all_expulsions(tx: Commands, context: SharedContext) -> BoxedFilter<(impl Reply,)> {
+    get_expulsions(context.clone())
         .or(add_expulsion(tx.clone()))
         .or(add_fake_expulsions(tx.clone()))
         .or(remove_expulsion(tx.clone()))
-        .or(get_expulsion_by_key(tx.clone()))
+        .or(get_expulsion_by_key(context))
         .or(modify_expulsion(tx))
         .boxed()
 }
 
-pub fn get_expulsions(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Reply,)> {
+pub fn get_expulsions(context: SharedContext) -> BoxedFilter<(impl Reply,)> {
     warp::path!("api" / "expulsions")
         .and(warp::get())
-        .and(with_command_sender(tx))
+        .and(with_context(context))
         .and_then(handlers::get_expulsions)
         .boxed()
 }
 
-pub fn add_expulsion(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Reply,)> {
+pub fn add_expulsion(tx: Commands) -> BoxedFilter<(impl Reply,)> {
     warp::post()
         .and(warp::path!("api" / "expulsions" / "add"))
         .and(with_command_sender(tx))
@@ -33,7 +35,7 @@ pub fn add_expulsion(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Reply,)>
         .boxed()
 }
 
-pub fn add_fake_expulsions(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Reply,)> {
+pub fn add_fake_expulsions(tx: Commands) -> BoxedFilter<(impl Reply,)> {
     warp::path!("api" / "expulsions" / "add-fake-count")
         .and(warp::post())
         .and(with_command_sender(tx))
@@ -42,7 +44,7 @@ pub fn add_fake_expulsions(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Re
         .boxed()
 }
 
-pub fn remove_expulsion(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Reply,)> {
+pub fn remove_expulsion(tx: Commands) -> BoxedFilter<(impl Reply,)> {
     warp::path!("api" / "expulsions" / "remove")
         .and(warp::post())
         .and(with_command_sender(tx))
@@ -51,17 +53,17 @@ pub fn remove_expulsion(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Reply
         .boxed()
 }
 
-pub fn get_expulsion_by_key(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Reply,)> {
+pub fn get_expulsion_by_key(context: SharedContext) -> BoxedFilter<(impl Reply,)> {
     //  -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
     warp::path!("api" / "expulsions")
         .and(warp::post())
-        .and(with_command_sender(tx))
+        .and(with_context(context))
         .and(json_args_entity_key())
         .and_then(handlers::get_expulsion_by_key)
         .boxed()
 }
 
-pub fn modify_expulsion(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Reply,)> {
+pub fn modify_expulsion(tx: Commands) -> BoxedFilter<(impl Reply,)> {
     warp::post()
         .and(warp::path!("api" / "expulsion"))
         .and(with_command_sender(tx))
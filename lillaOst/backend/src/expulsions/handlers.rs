@@ -1,19 +1,21 @@
 use std::convert::Infallible;
-use tokio::sync::mpsc::Sender;
 use tokio::sync::oneshot;
 
-use crate::command::CommandToBackend;
+use crate::command::{CommandSender, CommandToBackend};
 use crate::command_args::{ArgAddExpulsion, ArgEntityKey, ArgFakeCount, ArgModifyExpulsion};
-use crate::common_handlers::send_command_to_server;
+use crate::common_handlers::{read_from_context, send_command_to_server};
+use crate::local_state::SharedContext;
 
-pub async fn get_expulsions(tx: Sender<CommandToBackend>) -> Result<impl warp::Reply, Infallible> {
-    let (resp_tx, resp_rx) = oneshot::channel();
-    let cmd = CommandToBackend::GetExpulsions { resp: resp_tx };
-    Ok(send_command_to_server(tx, resp_rx, cmd).await)
+pub async fn get_expulsions(context: SharedContext) -> Result<impl warp::Reply, Infallible> {
+    Ok(read_from_context(&context, |ost| {
+        let expulsions: Vec<String> = ost.expulsions().iter().map(|e| e.serialize()).collect();
+        serde_json::to_string(&expulsions).unwrap()
+    })
+    .await)
 }
 
 pub async fn add_fake_expulsions(
-    tx: Sender<CommandToBackend>,
+    tx: CommandSender,
     args: ArgFakeCount,
 ) -> Result<impl warp::Reply, Infallible> {
     let (resp_tx, resp_rx) = oneshot::channel();
@@ -25,7 +27,7 @@ pub async fn add_fake_expulsions(
 }
 
 pub async fn remove_expulsion(
-    tx: Sender<CommandToBackend>,
+    tx: CommandSender,
     args: ArgEntityKey,
 ) -> Result<impl warp::Reply, Infallible> {
     let (resp_tx, resp_rx) = oneshot::channel();
@@ -37,19 +39,20 @@ pub async fn remove_expulsion(
 }
 
 pub async fn get_expulsion_by_key(
-    tx: Sender<CommandToBackend>,
+    context: SharedContext,
     args: ArgEntityKey,
 ) -> Result<impl warp::Reply, Infallible> {
-    let (resp_tx, resp_rx) = oneshot::channel();
-    let cmd = CommandToBackend::GetExpulsionByKey {
-        resp: resp_tx,
-        args,
-    };
-    Ok(send_command_to_server(tx, resp_rx, cmd).await)
+    Ok(read_from_context(&context, |ost| {
+        let found: Option<String> = ost
+            .get_expulsion_by_key(&args.event_key)
+            .map(|e| e.serialize());
+        serde_json::to_string(&found).unwrap()
+    })
+    .await)
 }
 
 pub async fn add_expulsion(
-    tx: Sender<CommandToBackend>,
+    tx: CommandSender,
     args: ArgAddExpulsion,
 ) -> Result<impl warp::Reply, Infallible> {
     let (resp_tx, resp_rx) = oneshot::channel();
@@ -61,7 +64,7 @@ pub async fn add_expulsion(
 }
 
 pub async fn modify_expulsion(
-    tx: Sender<CommandToBackend>,
+    tx: CommandSender,
     args: ArgModifyExpulsion,
 ) -> Result<impl warp::Reply, Infallible> {
     let (resp_tx, resp_rx) = oneshot::channel();
@@ -1,30 +1,33 @@
-use tokio::sync::mpsc::Sender;
 use warp::{filters::BoxedFilter, Filter, Reply};
 
 use super::handlers;
-use crate::command::CommandToBackend;
-use crate::command_args::{ArgAModifyFeeding, ArgAddFeeding};
-use crate::common_filters::{json_args_entity_key, json_args_fake_count, with_command_sender};
+use crate::command::Commands;
+use crate::command_args::{ArgAModifyFeeding, ArgAddFeeding, ArgAddNursing};
+use crate::common_filters::{
+    json_args_entity_key, json_args_fake_count, with_command_sender, with_context,
+};
+use crate::local_state::SharedContext;
 
-pub fn all_feedings(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Reply,)> {
-    get_feedings(tx.clone())
+pub fn all_feedings(tx: Commands, context: SharedContext) -> BoxedFilter<(impl Reply,)> {
+    get_feedings(context.clone())
         .or(add_feeding(tx.clone()))
+        .or(add_nursing(tx.clone()))
         .or(add_fake_feedings(tx.clone()))
         .or(remove_feeding(tx.clone()))
         .or(modify_feeding(tx.clone()))
-        .or(get_feeding_by_key(tx))
+        .or(get_feeding_by_key(context))
         .boxed()
 }
 
-pub fn get_feedings(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Reply,)> {
+pub fn get_feedings(context: SharedContext) -> BoxedFilter<(impl Reply,)> {
     warp::path!("api" / "feedings")
         .and(warp::get())
-        .and(with_command_sender(tx))
+        .and(with_context(context))
         .and_then(handlers::ost_get_feedings)
         .boxed()
 }
 
-pub fn add_feeding(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Reply,)> {
+pub fn add_feeding(tx: Commands) -> BoxedFilter<(impl Reply,)> {
     warp::post()
         .and(warp::path!("api" / "feedings" / "add"))
         .and(with_command_sender(tx))
@@ -33,7 +36,16 @@ pub fn add_feeding(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Reply,)> {
         .boxed()
 }
 
-pub fn add_fake_feedings(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Reply,)> {
+pub fn add_nursing(tx: Commands) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("api" / "feedings" / "add-nursing")
+        .and(warp::post())
+        .and(with_command_sender(tx))
+        .and(json_args_add_nursing())
+        .and_then(handlers::add_nursing)
+        .boxed()
+}
+
+pub fn add_fake_feedings(tx: Commands) -> BoxedFilter<(impl Reply,)> {
     warp::path!("api" / "feedings" / "add-fake-count")
         .and(warp::post())
         .and(with_command_sender(tx))
@@ -42,7 +54,7 @@ pub fn add_fake_feedings(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Repl
         .boxed()
 }
 
-pub fn remove_feeding(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Reply,)> {
+pub fn remove_feeding(tx: Commands) -> BoxedFilter<(impl Reply,)> {
     warp::path!("api" / "feedings" / "remove")
         .and(warp::post())
         .and(with_command_sender(tx))
@@ -51,16 +63,16 @@ pub fn remove_feeding(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Reply,)
         .boxed()
 }
 
-pub fn get_feeding_by_key(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Reply,)> {
+pub fn get_feeding_by_key(context: SharedContext) -> BoxedFilter<(impl Reply,)> {
     warp::path!("api" / "feedings")
         .and(warp::post())
-        .and(with_command_sender(tx))
+        .and(with_context(context))
         .and(json_args_entity_key())
         .and_then(handlers::get_feeding_by_key)
         .boxed()
 }
 
-pub fn modify_feeding(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Reply,)> {
+pub fn modify_feeding(tx: Commands) -> BoxedFilter<(impl Reply,)> {
     warp::post()
         .and(warp::path!("api" / "feed"))
         .and(with_command_sender(tx))
@@ -74,6 +86,11 @@ pub fn json_args_add_event(
     warp::body::content_length_limit(1024 * 16).and(warp::body::json())
 }
 
+pub fn json_args_add_nursing(
+) -> impl Filter<Extract = (ArgAddNursing,), Error = warp::Rejection> + Clone {
+    warp::body::content_length_limit(1024 * 16).and(warp::body::json())
+}
+
 pub fn json_args_modify_feeding(
 ) -> impl Filter<Extract = (ArgAModifyFeeding,), Error = warp::Rejection> + Clone {
     warp::body::content_length_limit(1024 * 16).and(warp::body::json())
@@ -1,21 +1,23 @@
 use std::convert::Infallible;
-use tokio::sync::mpsc::Sender;
 use tokio::sync::oneshot;
 
-use crate::command::CommandToBackend;
-use crate::command_args::{ArgAModifyFeeding, ArgAddFeeding, ArgEntityKey, ArgFakeCount};
-use crate::common_handlers::send_command_to_server;
+use crate::command::{CommandSender, CommandToBackend};
+use crate::command_args::{
+    ArgAModifyFeeding, ArgAddFeeding, ArgAddNursing, ArgEntityKey, ArgFakeCount,
+};
+use crate::common_handlers::{read_from_context, send_command_to_server};
+use crate::local_state::SharedContext;
 
-pub async fn ost_get_feedings(
-    tx: Sender<CommandToBackend>,
-) -> Result<impl warp::Reply, Infallible> {
-    let (resp_tx, resp_rx) = oneshot::channel();
-    let cmd = CommandToBackend::GetFeedings { resp: resp_tx };
-    Ok(send_command_to_server(tx, resp_rx, cmd).await)
+pub async fn ost_get_feedings(context: SharedContext) -> Result<impl warp::Reply, Infallible> {
+    Ok(read_from_context(&context, |ost| {
+        let feedings: Vec<String> = ost.feedings().iter().map(|e| e.serialize()).collect();
+        serde_json::to_string(&feedings).unwrap()
+    })
+    .await)
 }
 
 pub async fn add_fake_feedings(
-    tx: Sender<CommandToBackend>,
+    tx: CommandSender,
     args: ArgFakeCount,
 ) -> Result<impl warp::Reply, Infallible> {
     let (resp_tx, resp_rx) = oneshot::channel();
@@ -27,7 +29,7 @@ pub async fn add_fake_feedings(
 }
 
 pub async fn remove_feeding(
-    tx: Sender<CommandToBackend>,
+    tx: CommandSender,
     args: ArgEntityKey,
 ) -> Result<impl warp::Reply, Infallible> {
     let (resp_tx, resp_rx) = oneshot::channel();
@@ -39,23 +41,36 @@ pub async fn remove_feeding(
 }
 
 pub async fn get_feeding_by_key(
-    tx: Sender<CommandToBackend>,
+    context: SharedContext,
     args: ArgEntityKey,
+) -> Result<impl warp::Reply, Infallible> {
+    Ok(read_from_context(&context, |ost| {
+        let found: Option<String> = ost
+            .get_feeding_by_key(&args.event_key)
+            .map(|e| e.serialize());
+        serde_json::to_string(&found).unwrap()
+    })
+    .await)
+}
+
+pub async fn add_feeding(
+    tx: CommandSender,
+    args: ArgAddFeeding,
 ) -> Result<impl warp::Reply, Infallible> {
     let (resp_tx, resp_rx) = oneshot::channel();
-    let cmd = CommandToBackend::GetFeedingByKey {
+    let cmd = CommandToBackend::AddFeeding {
         resp: resp_tx,
         args,
     };
     Ok(send_command_to_server(tx, resp_rx, cmd).await)
 }
 
-pub async fn add_feeding(
-    tx: Sender<CommandToBackend>,
-    args: ArgAddFeeding,
+pub async fn add_nursing(
+    tx: CommandSender,
+    args: ArgAddNursing,
 ) -> Result<impl warp::Reply, Infallible> {
     let (resp_tx, resp_rx) = oneshot::channel();
-    let cmd = CommandToBackend::AddFeeding {
+    let cmd = CommandToBackend::AddNursing {
         resp: resp_tx,
         args,
     };
@@ -63,7 +78,7 @@ pub async fn add_feeding(
 }
 
 pub async fn modify_feeding(
-    tx: Sender<CommandToBackend>,
+    tx: CommandSender,
     args: ArgAModifyFeeding,
 ) -> Result<impl warp::Reply, Infallible> {
     let (resp_tx, resp_rx) = oneshot::channel();
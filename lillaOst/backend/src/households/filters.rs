@@ -0,0 +1,63 @@
+use warp::filters::BoxedFilter;
+use warp::path::FullPath;
+use warp::reply::Response;
+use warp::{Filter, Rejection, Reply};
+
+use super::handlers;
+use super::registry::{NewHousehold, SharedHouseholds};
+use crate::auth::filters::{session_token, unauthorized, Unauthorized};
+
+/// Household administration, outside of any household and guarded by `OST_ADMIN_TOKEN`
+pub fn all_households(households: SharedHouseholds) -> BoxedFilter<(impl Reply,)> {
+    let list = warp::path!("admin" / "households")
+        .and(warp::get())
+        .and(require_admin(households.clone()))
+        .and(with_households(households.clone()))
+        .and_then(handlers::list_households);
+    let create = warp::path!("admin" / "households")
+        .and(warp::post())
+        .and(require_admin(households.clone()))
+        .and(with_households(households.clone()))
+        .and(json_new_household())
+        .and_then(handlers::create_household);
+    let archive = warp::path!("admin" / "households" / String / "archive")
+        .and(warp::post())
+        .and(require_admin(households.clone()))
+        .and(with_households(households))
+        .and_then(handlers::archive_household);
+    list.or(create).or(archive).recover(unauthorized).boxed()
+}
+
+/// The frontend of one household, its `<base>` points below `/h/{household}/`
+pub fn household_index(id: &str) -> BoxedFilter<(Response,)> {
+    let base = format!("/h/{}/", id);
+    warp::get()
+        .and(warp::path::full())
+        .and_then(move |path: FullPath| handlers::household_index(base.clone(), path))
+        .boxed()
+}
+
+pub fn with_households(
+    households: SharedHouseholds,
+) -> impl Filter<Extract = (SharedHouseholds,), Error = std::convert::Infallible> + Clone {
+    warp::any().map(move || households.clone())
+}
+
+fn require_admin(
+    households: SharedHouseholds,
+) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    session_token()
+        .and(with_households(households))
+        .and_then(|token: String, households: SharedHouseholds| async move {
+            if households.read().unwrap().is_admin(&token) {
+                Ok(())
+            } else {
+                Err(warp::reject::custom(Unauthorized))
+            }
+        })
+        .untuple_one()
+}
+
+fn json_new_household() -> impl Filter<Extract = (NewHousehold,), Error = Rejection> + Clone {
+    warp::body::content_length_limit(1024 * 16).and(warp::body::json())
+}
@@ -0,0 +1,61 @@
+use std::convert::Infallible;
+use std::env;
+
+use tokio::task::spawn_blocking;
+use warp::http::StatusCode;
+use warp::path::FullPath;
+use warp::reply::Response;
+use warp::{Rejection, Reply};
+
+use super::registry::{NewHousehold, SharedHouseholds};
+use crate::auth::accounts::Account;
+use crate::v2::handlers::error_reply;
+
+use ost::role::Role;
+
+pub async fn list_households(households: SharedHouseholds) -> Result<Response, Infallible> {
+    Ok(warp::reply::json(&households.read().unwrap().households()).into_response())
+}
+
+pub async fn create_household(
+    households: SharedHouseholds,
+    new_household: NewHousehold,
+) -> Result<Response, Infallible> {
+    let owner = new_household.owner;
+    let hashed = spawn_blocking(move || Account::new(&owner.name, &owner.password, Role::Owner))
+        .await
+        .unwrap();
+    let result =
+        hashed.and_then(|owner| households.write().unwrap().create(&new_household.id, owner));
+    Ok(match result {
+        Ok(household) => {
+            warp::reply::with_status(warp::reply::json(&household), StatusCode::CREATED)
+                .into_response()
+        }
+        Err(err) => error_reply(err),
+    })
+}
+
+pub async fn archive_household(
+    id: String,
+    households: SharedHouseholds,
+) -> Result<Response, Infallible> {
+    let result = households.write().unwrap().archive(&id);
+    Ok(match result {
+        Ok(household) => warp::reply::json(&household).into_response(),
+        Err(err) => error_reply(err),
+    })
+}
+
+/// `STATIC_FILES_INDEX` with its `<base href="/">` moved to the household
+pub async fn household_index(base: String, path: FullPath) -> Result<Response, Rejection> {
+    if path.as_str().starts_with("/api/") {
+        return Err(warp::reject::not_found());
+    }
+    let index = env::var("STATIC_FILES_INDEX")
+        .ok()
+        .and_then(|index_file| std::fs::read_to_string(index_file).ok())
+        .ok_or_else(warp::reject::not_found)?;
+    let index = index.replacen("<base href=\"/\"", &format!("<base href=\"{}\"", base), 1);
+    Ok(warp::reply::html(index).into_response())
+}
@@ -0,0 +1,4 @@
+pub mod filters;
+pub mod handlers;
+pub mod registry;
+pub mod service;
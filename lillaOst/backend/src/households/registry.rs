@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+use openssl::memcmp;
+use serde_derive::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use warp::filters::BoxedFilter;
+use warp::reply::Response;
+use warp::Filter;
+
+use ost::error::OstError;
+
+use super::filters::household_index;
+use crate::auth::accounts::{share_auth, Account, Auth, Credentials};
+use crate::command::CommandToBackend;
+use crate::local_state::{ost_context, share};
+use crate::notifications::notifier;
+use crate::servers::api_routes::api_routes;
+use crate::storage::Storage;
+
+const REGISTRY_FILE: &str = "households.json";
+const ACCOUNTS_FILE: &str = "accounts.json";
+
+/// Independent contexts served from one process, each in `<dir>/<household>/`
+pub type SharedHouseholds = Arc<RwLock<Households>>;
+
+pub fn share_households(households: Households) -> SharedHouseholds {
+    Arc::new(RwLock::new(households))
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Household {
+    pub id: String,
+    #[serde(default)]
+    pub archived: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NewHousehold {
+    pub id: String,
+    /// The first account, it gets the owner role
+    pub owner: Credentials,
+}
+
+pub struct Households {
+    dir: PathBuf,
+    storage: Storage,
+    admin_token: Option<String>,
+    households: Vec<Household>,
+    /// The routes of every running household, each with its own state task
+    running: HashMap<String, BoxedFilter<(Response,)>>,
+}
+
+impl Households {
+    /// Starts every household that is not archived, must run inside the tokio runtime.
+    /// `storage` is the template, each household gets the same kind in its own directory.
+    pub fn open(
+        dir: &str,
+        storage: Storage,
+        admin_token: Option<String>,
+    ) -> Result<Self, OstError> {
+        let dir = PathBuf::from(dir);
+        fs::create_dir_all(&dir)?;
+        let registry = dir.join(REGISTRY_FILE);
+        let households: Vec<Household> = if registry.exists() {
+            serde_json::from_str(&fs::read_to_string(registry)?)?
+        } else {
+            vec![]
+        };
+        let mut running = HashMap::new();
+        for household in households.iter().filter(|h| !h.archived) {
+            log::info!("Starting household: {}", &household.id);
+            let routes = start(&dir.join(&household.id), &household.id, &storage, None)?;
+            running.insert(household.id.clone(), routes);
+        }
+        Ok(Self {
+            dir,
+            storage,
+            admin_token,
+            households,
+            running,
+        })
+    }
+
+    pub fn households(&self) -> Vec<Household> {
+        self.households.clone()
+    }
+
+    pub fn routes(&self, id: &str) -> Option<BoxedFilter<(Response,)>> {
+        self.running.get(id).cloned()
+    }
+
+    /// Without `OST_ADMIN_TOKEN` nobody administers households over http
+    pub fn is_admin(&self, token: &str) -> bool {
+        match &self.admin_token {
+            Some(admin_token) => {
+                admin_token.len() == token.len()
+                    && memcmp::eq(admin_token.as_bytes(), token.as_bytes())
+            }
+            None => false,
+        }
+    }
+
+    /// The owner comes hashed by `Account::new`, outside of the lock on the registry
+    pub fn create(&mut self, id: &str, owner: Account) -> Result<Household, OstError> {
+        let id = id.trim();
+        if id.is_empty()
+            || id.len() > 32
+            || !id
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        {
+            return Err(OstError::Validation(
+                "Household ids are 1 to 32 lowercase letters, digits or dashes".to_string(),
+            ));
+        }
+        if self.households.iter().any(|h| h.id == id) {
+            return Err(OstError::DuplicateName(format!(
+                "Household `{}` already exists",
+                id
+            )));
+        }
+        let routes = start(&self.dir.join(id), id, &self.storage, Some(owner))?;
+        let household = Household {
+            id: id.to_string(),
+            archived: false,
+        };
+        self.running.insert(household.id.clone(), routes);
+        self.households.push(household.clone());
+        self.persist()?;
+        Ok(household)
+    }
+
+    /// Stops serving the household, its files stay on disk
+    pub fn archive(&mut self, id: &str) -> Result<Household, OstError> {
+        let household = self
+            .households
+            .iter_mut()
+            .find(|h| h.id == id)
+            .ok_or_else(|| OstError::NotFound(format!("Household `{}`", id)))?;
+        household.archived = true;
+        let household = household.clone();
+        // The state task ends once the last sender, held by these routes, is gone
+        self.running.remove(id);
+        self.persist()?;
+        Ok(household)
+    }
+
+    fn persist(&self) -> Result<(), OstError> {
+        fs::write(
+            self.dir.join(REGISTRY_FILE),
+            serde_json::to_string_pretty(&self.households)?,
+        )?;
+        Ok(())
+    }
+}
+
+fn start(
+    dir: &Path,
+    id: &str,
+    storage: &Storage,
+    owner: Option<Account>,
+) -> Result<BoxedFilter<(Response,)>, OstError> {
+    fs::create_dir_all(dir)?;
+    let storage = storage.in_dir(dir);
+    let context = share(storage.construct()?);
+
+    let mut auth = Auth::from_file(&dir.join(ACCOUNTS_FILE).to_string_lossy())?;
+    if let Some(owner) = owner {
+        auth.insert_account(owner)?;
+    }
+
+    let (tx, rx) = mpsc::channel::<CommandToBackend>(32);
+    let notifier = notifier();
+    tokio::spawn(ost_context(rx, context.clone(), storage, notifier.clone()));
+
+    Ok(api_routes(tx, context, share_auth(auth), notifier)
+        .or(household_index(id))
+        .unify()
+        .boxed())
+}
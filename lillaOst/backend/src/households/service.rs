@@ -0,0 +1,78 @@
+use std::convert::Infallible;
+use std::net::SocketAddr;
+
+use warp::filters::BoxedFilter;
+use warp::http::Uri;
+use warp::hyper::service::{make_service_fn, service_fn, Service};
+use warp::hyper::{Body, Request, Server};
+use warp::reply::Response;
+
+use ost::error::OstError;
+
+use super::registry::SharedHouseholds;
+use crate::v2::handlers::error_reply;
+
+/// Warp routes are fixed once built, households come and go, so requests under
+/// `/h/{household}` are handed to that household's routes with the prefix stripped
+pub async fn route(
+    households: SharedHouseholds,
+    fallback: BoxedFilter<(Response,)>,
+    mut request: Request<Body>,
+) -> Response {
+    let (id, uri) = match split_household(request.uri()) {
+        Some(split) => split,
+        None => return call(fallback, request).await,
+    };
+    let routes = households.read().unwrap().routes(&id);
+    match routes {
+        Some(routes) => {
+            *request.uri_mut() = uri;
+            call(routes, request).await
+        }
+        None => error_reply(OstError::NotFound(format!("Household `{}`", id))),
+    }
+}
+
+/// `/h/{household}/rest?query` into the household and `/rest?query`
+pub fn split_household(uri: &Uri) -> Option<(String, Uri)> {
+    let rest = uri.path().strip_prefix("/h/")?;
+    let (id, path) = match rest.find('/') {
+        Some(slash) => rest.split_at(slash),
+        None => (rest, "/"),
+    };
+    if id.is_empty() {
+        return None;
+    }
+    let path_and_query = match uri.query() {
+        Some(query) => format!("{}?{}", path, query),
+        None => path.to_string(),
+    };
+    Some((id.to_string(), path_and_query.parse().ok()?))
+}
+
+async fn call(routes: BoxedFilter<(Response,)>, request: Request<Body>) -> Response {
+    match warp::service(routes).call(request).await {
+        Ok(response) => response,
+        Err(never) => match never {},
+    }
+}
+
+pub async fn serve(
+    households: SharedHouseholds,
+    fallback: BoxedFilter<(Response,)>,
+    address: SocketAddr,
+) {
+    let make_service = make_service_fn(move |_| {
+        let households = households.clone();
+        let fallback = fallback.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                let response = route(households.clone(), fallback.clone(), request);
+                async move { Ok::<_, Infallible>(response.await) }
+            }))
+        }
+    });
+    if let Err(err) = Server::bind(&address).serve(make_service).await {
+        log::error!("Households server stopped: {}", err);
+    }
+}
@@ -1,4 +1,5 @@
 pub mod admin;
+pub mod auth;
 pub mod command;
 pub mod command_args;
 pub mod common_filters;
@@ -6,9 +7,16 @@ pub mod common_handlers;
 pub mod events;
 pub mod expulsions;
 pub mod feedings;
+pub mod households;
 pub mod local_state;
 pub mod local_state_fake_in_memory;
+pub mod measurements;
+pub mod notifications;
+pub mod openapi;
 pub mod persons;
+pub mod query;
 pub mod servers;
 pub mod static_file_filters;
+pub mod storage;
 pub mod trivial_handlers;
+pub mod v2;
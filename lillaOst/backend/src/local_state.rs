@@ -1,247 +1,414 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use serde::Serialize;
 use tokio::sync::mpsc::Receiver;
+use tokio::sync::oneshot;
+use tokio::sync::RwLock;
 
 use crate::command::CommandToBackend;
+use crate::notifications::Notifier;
+use crate::storage::Storage;
 
-use ost::context::construct_monolith;
+use ost::change::{ChangeNotification, ChangeOp, EntityKind};
+use ost::context::Context;
+use ost::error::OstError;
+use ost::event_key::{EventType as EntryType, OstEventKey};
+use ost::offline::ReplayId;
 use ost::person::deserialize as person_deserialize;
 
-pub async fn file_based_ost_context(mut rx: Receiver<CommandToBackend>, file_path: &str) {
-    let mut ost = construct_monolith(file_path).unwrap();
+/// The context behind the server. Handlers take the read lock to answer reads in parallel,
+/// mutations queue up in the state task, which takes the write lock one command at a time.
+/// An async lock: waiting doesn't block a worker thread and a panic doesn't poison it.
+pub type SharedContext = Arc<RwLock<Box<dyn Context>>>;
+
+pub fn share(ost: Box<dyn Context>) -> SharedContext {
+    Arc::new(RwLock::new(ost))
+}
 
+/// Serves the commands on `rx` from any storage, announcing what changed on `notifier`
+pub async fn ost_context(
+    mut rx: Receiver<CommandToBackend>,
+    context: SharedContext,
+    storage: Storage,
+    notifier: Notifier,
+) {
     while let Some(cmd) = rx.recv().await {
         log::debug!("cmd received{:#?}", cmd);
-        match cmd {
-            // Admin Calls
-            CommandToBackend::AdminReset { resp } => {
-                ost.purge_all_data().unwrap();
-                let _ = resp.send("OK".to_string());
-            }
-            CommandToBackend::AdminPurgeEvents { resp } => {
-                let result = ost.purge_all_events();
-                let _ = resp.send(serde_json::to_string(&result).unwrap());
-            }
-            // Person
-            CommandToBackend::GetPersons { resp } => {
-                let persons: Vec<String> = ost.persons().iter().map(|p| p.serialize()).collect();
-                let serialized_response = serde_json::to_string(&persons).unwrap();
-                let _ = resp.send(serialized_response);
-            }
-            CommandToBackend::AddPerson { args, resp } => {
-                let result = ost.add_person(args.name.as_str());
-                let payload: Result<String, String> = match result {
-                    Ok(o) => Ok(o.serialize()),
-                    Err(err) => Err(err),
-                };
-                let to_wire = serde_json::to_string(&payload).unwrap();
-                let _ = resp.send(to_wire);
-            }
-            CommandToBackend::AddFakePerson { args, resp } => {
-                let result = ost.add_fake_persons(args.count);
-                let _ = resp.send(serde_json::to_string(&result).unwrap());
-            }
-            CommandToBackend::ModifyPerson { args, resp } => {
-                let deserialized_person = person_deserialize(&args.serialized_person).unwrap();
-                let result = ost.modify_person(&deserialized_person);
-                let _ = resp.send(serde_json::to_string(&result).unwrap());
-            }
-            // Feedings
-            CommandToBackend::GetFeedings { resp } => {
-                let persons: Vec<String> = ost.feedings().iter().map(|p| p.serialize()).collect();
-                let serialized_response = serde_json::to_string(&persons).unwrap();
-                let _ = resp.send(serialized_response);
-            }
-            CommandToBackend::GetFeedingByKey { args, resp } => {
-                let found = ost.get_feeding_by_key(&args.event_key);
-                match found {
-                    Some(o) => {
-                        let some_answer: Option<String> = Some(o.serialize());
-                        let _ = resp
-                            .send(serde_json::to_string(&some_answer).unwrap())
-                            .unwrap();
-                    }
-                    None => {
-                        let no_answer: Option<String> = None;
-                        let _ = resp
-                            .send(serde_json::to_string(&no_answer).unwrap())
-                            .unwrap();
-                    }
-                }
-            }
-            CommandToBackend::AddFakeFeedings { args, resp } => {
-                let result = ost.add_fake_feedings(args.count);
-                let _ = resp.send(serde_json::to_string(&result).unwrap());
-            }
-            CommandToBackend::AddFeeding { args, resp } => {
-                let message: Result<String, String> = match ost.get_person_by_key(args.person_key) {
-                    Some(target_person) => {
-                        match ost.add_feeding(
-                            &target_person,
-                            args.breast_milk,
-                            args.formula,
-                            args.solids,
-                        ) {
-                            Ok(new_event) => Ok(new_event.serialize()),
-                            Err(e) => Err(e),
-                        }
-                    }
-                    None => Err("Add feeding: person not found".to_string()),
-                };
-                let _ = resp.send(serde_json::to_string(&message).unwrap());
-            }
-            CommandToBackend::RemoveFeeding { args, resp } => {
-                if let Some(target_feeding) = ost.get_feeding_by_key(&args.event_key) {
-                    let _ = resp
-                        .send(serde_json::to_string(&ost.remove_feeding(target_feeding)).unwrap())
-                        .unwrap();
-                } else {
-                    let message: Result<String, String> = Err("Feeding not found".to_string());
-                    let _ = resp.send(serde_json::to_string(&message).unwrap());
-                }
-            }
-            // Expulsions
-            CommandToBackend::GetExpulsions { resp } => {
-                let expulsions: Vec<String> =
-                    ost.expulsions().iter().map(|p| p.serialize()).collect();
-                let serialized_response = serde_json::to_string(&expulsions).unwrap();
-                let _ = resp.send(serialized_response);
-            }
-            CommandToBackend::GetExpulsionByKey { args, resp } => {
-                let found = ost.get_expulsion_by_key(&args.event_key);
-                match found {
-                    Some(o) => {
-                        let some_answer: Option<String> = Some(o.serialize());
-                        let _ = resp
-                            .send(serde_json::to_string(&some_answer).unwrap())
-                            .unwrap();
-                    }
-                    None => {
-                        let no_answer: Option<String> = None;
-                        let _ = resp
-                            .send(serde_json::to_string(&no_answer).unwrap())
-                            .unwrap();
-                    }
-                }
-            }
-            CommandToBackend::AddFakeExpulsions { args, resp } => {
-                let result = ost.add_fake_expulsions(args.count);
-                let _ = resp.send(serde_json::to_string(&result).unwrap());
-            }
-            CommandToBackend::AddExpulsion { args, resp } => {
-                let message: Result<String, String> = match ost.get_person_by_key(args.person_key) {
-                    Some(target_person) => {
-                        match ost.add_expulsion(&target_person, args.expulsion_degree) {
-                            Ok(new_event) => Ok(new_event.serialize()),
-                            Err(e) => Err(e),
-                        }
-                    }
-                    None => Err("Add Expulsion: person not found".to_string()),
-                };
-                let _ = resp.send(serde_json::to_string(&message).unwrap());
-            }
-            CommandToBackend::RemoveExpulsion { args, resp } => {
-                if let Some(target_expulsion) = ost.get_expulsion_by_key(&args.event_key) {
-                    let _ = resp
-                        .send(
-                            serde_json::to_string(&ost.remove_expulsion(target_expulsion)).unwrap(),
-                        )
-                        .unwrap();
-                } else {
-                    let message: Result<String, String> = Err("Expulsion not found".to_string());
-                    let _ = resp.send(serde_json::to_string(&message).unwrap());
-                }
-            }
-            // Events
-            CommandToBackend::GetEvents { resp } => {
-                let events: Vec<String> = ost.events().iter().map(|p| p.serialize()).collect();
-                let serialized_response = serde_json::to_string(&events).unwrap();
-                let _ = resp.send(serialized_response);
-            }
-            CommandToBackend::GetEventByKey { args, resp } => {
-                let found = ost.get_event_by_key(&args.event_key);
-                match found {
-                    Some(o) => {
-                        let some_answer: Option<String> = Some(o.serialize());
-                        let _ = resp
-                            .send(serde_json::to_string(&some_answer).unwrap())
-                            .unwrap();
-                    }
-                    None => {
-                        let no_answer: Option<String> = None;
-                        let _ = resp
-                            .send(serde_json::to_string(&no_answer).unwrap())
-                            .unwrap();
-                    }
-                }
-            }
-            CommandToBackend::AddEvent { args, resp } => {
-                let message: Result<String, String> = match ost.get_person_by_key(args.person_key) {
-                    Some(target_person) => match ost.add_event(&target_person, args.new_event) {
-                        Ok(new_event) => Ok(new_event.serialize()),
-                        Err(e) => Err(e),
-                    },
-                    None => Err("Add Event: person not found".to_string()),
-                };
-                let _ = resp.send(serde_json::to_string(&message).unwrap());
-            }
-            CommandToBackend::AddFakeEvents { args, resp } => {
-                let result = ost.add_fake_events(args.count);
-                let _ = resp.send(serde_json::to_string(&result).unwrap());
-            }
-            CommandToBackend::RemoveEvent { args, resp } => {
-                if let Some(target_event) = ost.get_event_by_key(&args.event_key) {
-                    let _ = resp
-                        .send(serde_json::to_string(&ost.remove_event(target_event)).unwrap())
-                        .unwrap();
-                } else {
-                    let message: Result<String, String> = Err("Event not found".to_string());
-                    let _ = resp.send(serde_json::to_string(&message).unwrap());
+        let changes = {
+            let mut ost = context.write().await;
+            dispatch(ost.as_mut(), &storage, cmd)
+        };
+        for change in changes {
+            // Nobody listening is fine
+            let _no_subscribers = notifier.send(change);
+        }
+    }
+}
+
+/// Runs one command against the context and sends the serialized `Result` back.
+/// Returns the changes to announce, nothing when the command failed.
+pub fn dispatch(
+    ost: &mut dyn Context,
+    storage: &Storage,
+    cmd: CommandToBackend,
+) -> Vec<ChangeNotification> {
+    use ChangeNotification as Change;
+    use ChangeOp::*;
+    use EntityKind::*;
+
+    match cmd {
+        // Admin Calls
+        CommandToBackend::AdminReset { resp } => {
+            let result = ost.purge_all_data().and_then(|_| storage.after_reset(ost));
+            reply(resp, &result);
+            announce(&result, |_| vec![Change::many(All, Removed)])
+        }
+        CommandToBackend::AdminPurgeEvents { resp } => {
+            let result = ost.purge_all_events();
+            reply(resp, &result);
+            announce(&result, |_| every_entry(Removed))
+        }
+        CommandToBackend::AdminRepairIntegrity { resp } => {
+            let result = ost.repair_integrity();
+            reply(resp, &result);
+            announce(&result, |repaired| match repaired.is_empty() {
+                true => vec![],
+                false => vec![Change::many(All, Modified)],
+            })
+        }
+        // Person
+        CommandToBackend::AddPerson { args, resp } => {
+            let result = ost.add_person(&args.name);
+            let changes = announce(&result, |p| vec![Change::one(Person, p.key().id, Added)]);
+            reply(resp, &result.map(|p| p.serialize()));
+            changes
+        }
+        CommandToBackend::AddFakePerson { args, resp } => {
+            let result = ost.add_fake_persons(args.count);
+            reply(resp, &result);
+            announce(&result, |_| vec![Change::many(Person, Added)])
+        }
+        CommandToBackend::ModifyPerson { args, resp } => {
+            let result = person_deserialize(&args.serialized_person)
+                .map_err(OstError::Serialization)
+                .and_then(|person| ost.modify_person(&person).map(|_| person.key().id));
+            let changes = announce(&result, |id| vec![Change::one(Person, *id, Modified)]);
+            reply(resp, &result.map(|_| ()));
+            changes
+        }
+        CommandToBackend::RemovePerson { args, resp } => {
+            let result = match ost.get_person_by_key(args.person_key) {
+                Some(target_person) => ost.remove_person(target_person, args.cascade),
+                None => Err(not_found("Person")),
+            };
+            reply(resp, &result);
+            announce(&result, |_| {
+                let mut changes = vec![Change::one(Person, args.person_key.id, Removed)];
+                if args.cascade {
+                    changes.extend(every_entry(Removed));
                 }
+                changes
+            })
+        }
+        CommandToBackend::MergePersons { args, resp } => {
+            let result = match (
+                ost.get_person_by_key(args.from),
+                ost.get_person_by_key(args.into),
+            ) {
+                (Some(from), Some(into)) => ost.merge_persons(from, &into),
+                _ => Err(not_found("Person")),
+            };
+            reply(resp, &result);
+            // The entries of `from` now belong to `into`
+            announce(&result, |_| {
+                vec![
+                    Change::one(Person, args.from.id, Removed),
+                    Change::many(All, Modified),
+                ]
+            })
+        }
+        // Feedings
+        CommandToBackend::AddFakeFeedings { args, resp } => {
+            let result = ost.add_fake_feedings(args.count);
+            reply(resp, &result);
+            announce(&result, |_| vec![Change::many(Feeding, Added)])
+        }
+        CommandToBackend::AddFeeding { args, resp } => {
+            if let Some(result) = replayed_before(ost, &args.replay_id) {
+                reply(resp, &result);
+                return vec![];
             }
-            CommandToBackend::ModifyEvent { args, resp } => {
-                let mut message: Result<(), String> = Ok(());
-                if let Some(mut target_event) = ost.get_event_by_key(&args.event_key) {
-                    target_event.modify_event(args.time_stamp, args.event_payload);
-                    match ost.modify_event(&target_event) {
-                        Ok(_) => {}
-                        Err(e) => message = Err(e),
-                    }
-                } else {
-                    message = Err("Event not found".to_string());
-                }
-                let _ = resp.send(serde_json::to_string(&message).unwrap());
+            let result = match ost.get_person_by_key(args.person_key) {
+                Some(target_person) => ost.add_feeding_at(
+                    &target_person,
+                    args.breast_milk,
+                    args.formula,
+                    args.solids,
+                    args.time_stamp.unwrap_or_else(Utc::now),
+                    args.replay_id.as_ref(),
+                ),
+                None => Err(not_found("Person")),
+            };
+            let changes = announce(&result, |f| {
+                vec![Change::one(Feeding, f.key().id, Added).for_person(args.person_key.id)]
+            });
+            reply(resp, &result.map(|new_event| new_event.serialize()));
+            changes
+        }
+        CommandToBackend::AddNursing { args, resp } => {
+            if let Some(result) = replayed_before(ost, &args.replay_id) {
+                reply(resp, &result);
+                return vec![];
             }
-            CommandToBackend::ModifyExpulsion { args, resp } => {
-                let mut message: Result<(), String> = Ok(());
-                if let Some(mut target_event) = ost.get_expulsion_by_key(&args.event_key) {
-                    target_event.modify_expulsion(args.expulsion_degree, args.time_stamp);
-                    match ost.modify_expulsion(&target_event) {
-                        Ok(_) => {}
-                        Err(e) => message = Err(e),
-                    }
-                } else {
-                    message = Err("Event not found".to_string());
+            let result = match ost.get_person_by_key(args.person_key) {
+                Some(target_person) => {
+                    ost.add_nursing_replayed(&target_person, args.nursing, args.replay_id.as_ref())
                 }
-                let _ = resp.send(serde_json::to_string(&message).unwrap());
-            }
-            CommandToBackend::ModifyFeeding { args, resp } => {
-                let mut message: Result<(), String> = Ok(());
-                if let Some(mut target_event) = ost.get_feeding_by_key(&args.event_key) {
+                None => Err(not_found("Person")),
+            };
+            let changes = announce(&result, |f| {
+                vec![Change::one(Feeding, f.key().id, Added).for_person(args.person_key.id)]
+            });
+            reply(resp, &result.map(|new_event| new_event.serialize()));
+            changes
+        }
+        CommandToBackend::ModifyFeeding { args, resp } => {
+            let result = match ost.get_feeding_by_key(&args.event_key) {
+                Some(mut target_event) => {
                     target_event.modify_feed(
                         args.breast_milk,
                         args.formula,
                         args.solids,
                         args.time_stamp,
                     );
-
-                    match ost.modify_feeding(&target_event) {
-                        Ok(_) => {}
-                        Err(e) => message = Err(e),
-                    }
-                } else {
-                    message = Err("Event not found".to_string());
+                    target_event.modify_nursing(args.nursing);
+                    ost.modify_feeding(&target_event)
+                }
+                None => Err(not_found("Feeding")),
+            };
+            reply(resp, &result);
+            announce(&result, |_| {
+                vec![Change::one(Feeding, args.event_key.id, Modified)]
+            })
+        }
+        CommandToBackend::RemoveFeeding { args, resp } => {
+            let result = match ost.get_feeding_by_key(&args.event_key) {
+                Some(target_feeding) => ost.remove_feeding(target_feeding),
+                None => Err(not_found("Feeding")),
+            };
+            reply(resp, &result);
+            announce(&result, |_| {
+                vec![Change::one(Feeding, args.event_key.id, Removed)]
+            })
+        }
+        // Expulsions
+        CommandToBackend::AddFakeExpulsions { args, resp } => {
+            let result = ost.add_fake_expulsions(args.count);
+            reply(resp, &result);
+            announce(&result, |_| vec![Change::many(Expulsion, Added)])
+        }
+        CommandToBackend::AddExpulsion { args, resp } => {
+            if let Some(result) = replayed_before(ost, &args.replay_id) {
+                reply(resp, &result);
+                return vec![];
+            }
+            let result = match ost.get_person_by_key(args.person_key) {
+                Some(target_person) => ost.add_expulsion_at(
+                    &target_person,
+                    args.expulsion_degree,
+                    args.time_stamp.unwrap_or_else(Utc::now),
+                    args.replay_id.as_ref(),
+                ),
+                None => Err(not_found("Person")),
+            };
+            let changes = announce(&result, |e| {
+                vec![Change::one(Expulsion, e.key().id, Added).for_person(args.person_key.id)]
+            });
+            reply(resp, &result.map(|new_event| new_event.serialize()));
+            changes
+        }
+        CommandToBackend::ModifyExpulsion { args, resp } => {
+            let result = match ost.get_expulsion_by_key(&args.event_key) {
+                Some(mut target_event) => {
+                    target_event.modify_expulsion(args.expulsion_degree, args.time_stamp);
+                    ost.modify_expulsion(&target_event)
+                }
+                None => Err(not_found("Expulsion")),
+            };
+            reply(resp, &result);
+            announce(&result, |_| {
+                vec![Change::one(Expulsion, args.event_key.id, Modified)]
+            })
+        }
+        CommandToBackend::RemoveExpulsion { args, resp } => {
+            let result = match ost.get_expulsion_by_key(&args.event_key) {
+                Some(target_expulsion) => ost.remove_expulsion(target_expulsion),
+                None => Err(not_found("Expulsion")),
+            };
+            reply(resp, &result);
+            announce(&result, |_| {
+                vec![Change::one(Expulsion, args.event_key.id, Removed)]
+            })
+        }
+        // Events
+        CommandToBackend::AddEvent { args, resp } => {
+            if let Some(result) = replayed_before(ost, &args.replay_id) {
+                reply(resp, &result);
+                return vec![];
+            }
+            let result = match ost.get_person_by_key(args.person_key) {
+                Some(target_person) => ost.add_event_at(
+                    &target_person,
+                    args.new_event,
+                    args.time_stamp.unwrap_or_else(Utc::now),
+                    args.replay_id.as_ref(),
+                ),
+                None => Err(not_found("Person")),
+            };
+            let changes = announce(&result, |e| {
+                vec![Change::one(Event, e.key().id, Added).for_person(args.person_key.id)]
+            });
+            reply(resp, &result.map(|new_event| new_event.serialize()));
+            changes
+        }
+        CommandToBackend::AddFakeEvents { args, resp } => {
+            let result = ost.add_fake_events(args.count);
+            reply(resp, &result);
+            announce(&result, |_| vec![Change::many(Event, Added)])
+        }
+        CommandToBackend::ModifyEvent { args, resp } => {
+            let result = match ost.get_event_by_key(&args.event_key) {
+                Some(mut target_event) => {
+                    target_event.modify_event(args.time_stamp, args.event_payload);
+                    ost.modify_event(&target_event)
                 }
-                let _ = resp.send(serde_json::to_string(&message).unwrap());
+                None => Err(not_found("Event")),
+            };
+            reply(resp, &result);
+            announce(&result, |_| {
+                vec![Change::one(Event, args.event_key.id, Modified)]
+            })
+        }
+        CommandToBackend::RemoveEvent { args, resp } => {
+            let result = match ost.get_event_by_key(&args.event_key) {
+                Some(target_event) => ost.remove_event(target_event),
+                None => Err(not_found("Event")),
+            };
+            reply(resp, &result);
+            announce(&result, |_| {
+                vec![Change::one(Event, args.event_key.id, Removed)]
+            })
+        }
+        // Measurements
+        CommandToBackend::AddFakeMeasurements { args, resp } => {
+            let result = ost.add_fake_measurements(args.count);
+            reply(resp, &result);
+            announce(&result, |_| vec![Change::many(Measurement, Added)])
+        }
+        CommandToBackend::AddMeasurement { args, resp } => {
+            if let Some(result) = replayed_before(ost, &args.replay_id) {
+                reply(resp, &result);
+                return vec![];
             }
+            let result = match ost.get_person_by_key(args.person_key) {
+                Some(target_person) => ost.add_measurement_at(
+                    &target_person,
+                    args.measures,
+                    args.time_stamp.unwrap_or_else(Utc::now),
+                    args.replay_id.as_ref(),
+                ),
+                None => Err(not_found("Person")),
+            };
+            let changes = announce(&result, |m| {
+                vec![Change::one(Measurement, m.key().id, Added).for_person(args.person_key.id)]
+            });
+            reply(
+                resp,
+                &result.map(|new_measurement| new_measurement.serialize()),
+            );
+            changes
+        }
+        CommandToBackend::ModifyMeasurement { args, resp } => {
+            let result = match ost.get_measurement_by_key(&args.event_key) {
+                Some(mut target_measurement) => {
+                    target_measurement.modify_measurement(args.measures, args.time_stamp);
+                    ost.modify_measurement(&target_measurement)
+                }
+                None => Err(not_found("Measurement")),
+            };
+            reply(resp, &result);
+            announce(&result, |_| {
+                vec![Change::one(Measurement, args.event_key.id, Modified)]
+            })
+        }
+        CommandToBackend::RemoveMeasurement { args, resp } => {
+            let result = match ost.get_measurement_by_key(&args.event_key) {
+                Some(target_measurement) => ost.remove_measurement(target_measurement),
+                None => Err(not_found("Measurement")),
+            };
+            reply(resp, &result);
+            announce(&result, |_| {
+                vec![Change::one(Measurement, args.event_key.id, Removed)]
+            })
         }
     }
 }
+
+/// Feedings, expulsions, events and measurements, persons untouched
+fn every_entry(op: ChangeOp) -> Vec<ChangeNotification> {
+    [
+        EntityKind::Feeding,
+        EntityKind::Expulsion,
+        EntityKind::Event,
+        EntityKind::Measurement,
+    ]
+    .into_iter()
+    .map(|kind| ChangeNotification::many(kind, op))
+    .collect()
+}
+
+fn announce<T>(
+    result: &Result<T, OstError>,
+    changes: impl FnOnce(&T) -> Vec<ChangeNotification>,
+) -> Vec<ChangeNotification> {
+    result.as_ref().map(changes).unwrap_or_default()
+}
+
+fn reply<T: Serialize>(resp: oneshot::Sender<String>, result: &Result<T, OstError>) {
+    // The handler may have given up waiting, nobody is left to tell
+    let _ignore_closed = resp.send(serde_json::to_string(result).unwrap());
+}
+
+/// Outboxes replay oldest first, an id up to the client's mark was added before. The mark's
+/// own entry is sent back as it is now, in case the first reply got lost, older ones fail.
+fn replayed_before(
+    ost: &dyn Context,
+    replay_id: &Option<ReplayId>,
+) -> Option<Result<String, OstError>> {
+    let replay_id = replay_id.as_ref()?;
+    let mark = ost.replay_mark(&replay_id.client)?;
+    if replay_id.id > mark.id {
+        return None;
+    }
+    if replay_id.id < mark.id {
+        return Some(Err(OstError::Validation(format!(
+            "Entry {} of {} was replayed before",
+            replay_id.id, replay_id.client
+        ))));
+    }
+    Some(serialized_entry(ost, &mark.key).ok_or_else(|| not_found("Replayed entry")))
+}
+
+fn serialized_entry(ost: &dyn Context, key: &OstEventKey) -> Option<String> {
+    match key.t {
+        EntryType::Feed => ost.get_feeding_by_key(key).map(|f| f.serialize()),
+        EntryType::Expulsion => ost.get_expulsion_by_key(key).map(|e| e.serialize()),
+        EntryType::Event => ost.get_event_by_key(key).map(|e| e.serialize()),
+        EntryType::Measurement => ost.get_measurement_by_key(key).map(|m| m.serialize()),
+    }
+}
+
+fn not_found(what: &str) -> OstError {
+    OstError::NotFound(format!("{} not found", what))
+}
@@ -0,0 +1,82 @@
+use warp::{filters::BoxedFilter, Filter, Reply};
+
+use super::handlers;
+use crate::command::Commands;
+use crate::command_args::{ArgAddMeasurement, ArgModifyMeasurement};
+use crate::common_filters::{
+    json_args_entity_key, json_args_fake_count, with_command_sender, with_context,
+};
+use crate::local_state::SharedContext;
+
+pub fn all_measurements(tx: Commands, context: SharedContext) -> BoxedFilter<(impl Reply,)> {
+    get_measurements(context.clone())
+        .or(add_measurement(tx.clone()))
+        .or(add_fake_measurements(tx.clone()))
+        .or(remove_measurement(tx.clone()))
+        .or(get_measurement_by_key(context))
+        .or(modify_measurement(tx))
+        .boxed()
+}
+
+pub fn get_measurements(context: SharedContext) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("api" / "measurements")
+        .and(warp::get())
+        .and(with_context(context))
+        .and_then(handlers::get_measurements)
+        .boxed()
+}
+
+pub fn add_measurement(tx: Commands) -> BoxedFilter<(impl Reply,)> {
+    warp::post()
+        .and(warp::path!("api" / "measurements" / "add"))
+        .and(with_command_sender(tx))
+        .and(json_args_add_measurement())
+        .and_then(handlers::add_measurement)
+        .boxed()
+}
+
+pub fn add_fake_measurements(tx: Commands) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("api" / "measurements" / "add-fake-count")
+        .and(warp::post())
+        .and(with_command_sender(tx))
+        .and(json_args_fake_count())
+        .and_then(handlers::add_fake_measurements)
+        .boxed()
+}
+
+pub fn remove_measurement(tx: Commands) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("api" / "measurements" / "remove")
+        .and(warp::post())
+        .and(with_command_sender(tx))
+        .and(json_args_entity_key())
+        .and_then(handlers::remove_measurement)
+        .boxed()
+}
+
+pub fn get_measurement_by_key(context: SharedContext) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("api" / "measurements")
+        .and(warp::post())
+        .and(with_context(context))
+        .and(json_args_entity_key())
+        .and_then(handlers::get_measurement_by_key)
+        .boxed()
+}
+
+pub fn modify_measurement(tx: Commands) -> BoxedFilter<(impl Reply,)> {
+    warp::post()
+        .and(warp::path!("api" / "measurement"))
+        .and(with_command_sender(tx))
+        .and(json_args_modify_measurement())
+        .and_then(handlers::modify_measurement)
+        .boxed()
+}
+
+pub fn json_args_add_measurement(
+) -> impl Filter<Extract = (ArgAddMeasurement,), Error = warp::Rejection> + Clone {
+    warp::body::content_length_limit(1024 * 16).and(warp::body::json())
+}
+
+pub fn json_args_modify_measurement(
+) -> impl Filter<Extract = (ArgModifyMeasurement,), Error = warp::Rejection> + Clone {
+    warp::body::content_length_limit(1024 * 16).and(warp::body::json())
+}
@@ -0,0 +1,76 @@
+use std::convert::Infallible;
+use tokio::sync::oneshot;
+
+use crate::command::{CommandSender, CommandToBackend};
+use crate::command_args::{ArgAddMeasurement, ArgEntityKey, ArgFakeCount, ArgModifyMeasurement};
+use crate::common_handlers::{read_from_context, send_command_to_server};
+use crate::local_state::SharedContext;
+
+pub async fn get_measurements(context: SharedContext) -> Result<impl warp::Reply, Infallible> {
+    Ok(read_from_context(&context, |ost| {
+        let measurements: Vec<String> = ost.measurements().iter().map(|m| m.serialize()).collect();
+        serde_json::to_string(&measurements).unwrap()
+    })
+    .await)
+}
+
+pub async fn add_fake_measurements(
+    tx: CommandSender,
+    args: ArgFakeCount,
+) -> Result<impl warp::Reply, Infallible> {
+    let (resp_tx, resp_rx) = oneshot::channel();
+    let cmd = CommandToBackend::AddFakeMeasurements {
+        resp: resp_tx,
+        args,
+    };
+    Ok(send_command_to_server(tx, resp_rx, cmd).await)
+}
+
+pub async fn remove_measurement(
+    tx: CommandSender,
+    args: ArgEntityKey,
+) -> Result<impl warp::Reply, Infallible> {
+    let (resp_tx, resp_rx) = oneshot::channel();
+    let cmd = CommandToBackend::RemoveMeasurement {
+        resp: resp_tx,
+        args,
+    };
+    Ok(send_command_to_server(tx, resp_rx, cmd).await)
+}
+
+pub async fn get_measurement_by_key(
+    context: SharedContext,
+    args: ArgEntityKey,
+) -> Result<impl warp::Reply, Infallible> {
+    Ok(read_from_context(&context, |ost| {
+        let found: Option<String> = ost
+            .get_measurement_by_key(&args.event_key)
+            .map(|m| m.serialize());
+        serde_json::to_string(&found).unwrap()
+    })
+    .await)
+}
+
+pub async fn add_measurement(
+    tx: CommandSender,
+    args: ArgAddMeasurement,
+) -> Result<impl warp::Reply, Infallible> {
+    let (resp_tx, resp_rx) = oneshot::channel();
+    let cmd = CommandToBackend::AddMeasurement {
+        resp: resp_tx,
+        args,
+    };
+    Ok(send_command_to_server(tx, resp_rx, cmd).await)
+}
+
+pub async fn modify_measurement(
+    tx: CommandSender,
+    args: ArgModifyMeasurement,
+) -> Result<impl warp::Reply, Infallible> {
+    let (resp_tx, resp_rx) = oneshot::channel();
+    let cmd = CommandToBackend::ModifyMeasurement {
+        resp: resp_tx,
+        args,
+    };
+    Ok(send_command_to_server(tx, resp_rx, cmd).await)
+}
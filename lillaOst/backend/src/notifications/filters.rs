@@ -0,0 +1,25 @@
+use warp::{filters::BoxedFilter, Filter, Reply};
+
+use super::handlers;
+use super::Notifier;
+
+pub fn all_notifications(notifier: Notifier) -> BoxedFilter<(impl Reply,)> {
+    changes(notifier)
+}
+
+pub fn changes(notifier: Notifier) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("api" / "changes")
+        .and(warp::get())
+        .and(warp::ws())
+        .and(with_notifier(notifier))
+        .map(|ws: warp::ws::Ws, notifier: Notifier| {
+            ws.on_upgrade(move |socket| handlers::forward_changes(socket, notifier))
+        })
+        .boxed()
+}
+
+pub fn with_notifier(
+    notifier: Notifier,
+) -> impl Filter<Extract = (Notifier,), Error = std::convert::Infallible> + Clone {
+    warp::any().map(move || notifier.clone())
+}
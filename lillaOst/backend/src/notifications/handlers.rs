@@ -0,0 +1,32 @@
+use futures_util::{SinkExt, StreamExt};
+use tokio::sync::broadcast::error::RecvError;
+use warp::ws::{Message, WebSocket};
+
+use super::Notifier;
+
+use ost::change::{ChangeNotification, ChangeOp, EntityKind};
+
+/// Sends every change as a json text message until either side goes away
+pub async fn forward_changes(socket: WebSocket, notifier: Notifier) {
+    let (mut to_client, mut from_client) = socket.split();
+    let mut changes = notifier.subscribe();
+    loop {
+        let change = tokio::select! {
+            change = changes.recv() => match change {
+                Ok(change) => change,
+                // Missed some, the client has to reload everything
+                Err(RecvError::Lagged(_)) => ChangeNotification::many(EntityKind::All, ChangeOp::Modified),
+                Err(RecvError::Closed) => break,
+            },
+            incoming = from_client.next() => match incoming {
+                Some(Ok(message)) if !message.is_close() => continue,
+                _ => break,
+            },
+        };
+        let text = serde_json::to_string(&change).unwrap();
+        if to_client.send(Message::text(text)).await.is_err() {
+            break;
+        }
+    }
+    log::debug!("change subscriber left");
+}
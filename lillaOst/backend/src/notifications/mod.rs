@@ -0,0 +1,15 @@
+pub mod filters;
+pub mod handlers;
+
+use ost::change::ChangeNotification;
+use tokio::sync::broadcast;
+
+/// Fans the changes of the state task out to every connected socket
+pub type Notifier = broadcast::Sender<ChangeNotification>;
+
+/// Sockets falling further behind than this get a catch-all change instead
+const BACKLOG: usize = 64;
+
+pub fn notifier() -> Notifier {
+    broadcast::channel(BACKLOG).0
+}
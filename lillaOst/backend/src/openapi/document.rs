@@ -0,0 +1,571 @@
+use chrono::NaiveDate;
+use serde_json::{json, Map, Value};
+use warp::http::StatusCode;
+
+use ost::change::ChangeNotification;
+use ost::context::construct_monolith_in_memory;
+use ost::error::OstError;
+use ost::event::EventType;
+use ost::expulsion::ExpulsionDegree;
+use ost::integrity::IntegrityIssue;
+use ost::measurement::Measures;
+use ost::nursing::NursingSession;
+use ost::person::Sex;
+
+use super::schema::{infer, merge, schema_of, schema_of_values, ApiExample};
+use crate::auth::accounts::{AccountInfo, Credentials, NewAccount, SessionToken};
+use crate::command_args::*;
+use crate::households::registry::{Household, NewHousehold};
+use crate::v2::bodies::*;
+
+/// One route as the OpenAPI document describes it
+struct ApiRoute {
+    method: &'static str,
+    path: &'static str,
+    summary: &'static str,
+    query: Vec<(&'static str, Value)>,
+    body: Option<Value>,
+    responses: Vec<(StatusCode, Option<Value>)>,
+    public: bool,
+}
+
+impl ApiRoute {
+    fn new(method: &'static str, path: &'static str, summary: &'static str) -> Self {
+        Self {
+            method,
+            path,
+            summary,
+            query: vec![],
+            body: None,
+            responses: vec![],
+            public: false,
+        }
+    }
+
+    fn query(mut self, name: &'static str, schema: Value) -> Self {
+        self.query.push((name, schema));
+        self
+    }
+
+    fn body(mut self, schema: Value) -> Self {
+        self.body = Some(schema);
+        self
+    }
+
+    fn responds(mut self, status: StatusCode, schema: Value) -> Self {
+        self.responses.push((status, Some(schema)));
+        self
+    }
+
+    fn responds_empty(mut self, status: StatusCode) -> Self {
+        self.responses.push((status, None));
+        self
+    }
+
+    /// Reachable without a session token
+    fn public(mut self) -> Self {
+        self.public = true;
+        self
+    }
+
+    /// v2 errors carry the `OstError` as body
+    fn fails(mut self, statuses: &[StatusCode]) -> Self {
+        for status in statuses {
+            self.responses
+                .push((*status, Some(schema_of::<OstError>())));
+        }
+        self
+    }
+
+    /// v1 answers every call with a 200 and a json `Result`, errors are their message
+    fn v1(self, ok: Value) -> Self {
+        let wire = json!({
+            "type": "object",
+            "properties": { "Ok": ok, "Err": { "type": "string" } },
+        });
+        self.responds(StatusCode::OK, wire)
+    }
+
+    fn to_operation(&self) -> Value {
+        let mut parameters: Vec<Value> = path_parameters(self.path)
+            .map(|name| {
+                let kind = if name == "household" {
+                    "string"
+                } else {
+                    "integer"
+                };
+                json!({
+                    "name": name, "in": "path", "required": true,
+                    "schema": { "type": kind },
+                })
+            })
+            .collect();
+        parameters.extend(self.query.iter().map(|(name, schema)| {
+            json!({ "name": name, "in": "query", "required": false, "schema": schema })
+        }));
+        let responses: Map<String, Value> = self
+            .responses
+            .iter()
+            .map(|(status, schema)| {
+                let mut response = json!({
+                    "description": status.canonical_reason().unwrap_or_default(),
+                });
+                if let Some(schema) = schema {
+                    response["content"] = json!({ "application/json": { "schema": schema } });
+                }
+                (status.as_u16().to_string(), response)
+            })
+            .collect();
+
+        let mut operation = json!({
+            "summary": self.summary,
+            "parameters": parameters,
+            "responses": responses,
+        });
+        if self.public {
+            operation["security"] = json!([]);
+        }
+        if let Some(schema) = &self.body {
+            operation["requestBody"] = json!({
+                "required": true,
+                "content": { "application/json": { "schema": schema } },
+            });
+        }
+        operation
+    }
+}
+
+/// The OpenAPI 3 document served at `/api/openapi.json`
+pub fn document() -> Value {
+    let mut paths = Map::new();
+    for route in routes() {
+        let path = paths
+            .entry(route.path.to_string())
+            .or_insert_with(|| json!({}));
+        path[route.method] = route.to_operation();
+    }
+    json!({
+        "openapi": "3.0.3",
+        "info": { "title": "lilla ost", "version": env!("CARGO_PKG_VERSION") },
+        "servers": [
+            { "url": "/", "description": "A single household server" },
+            {
+                "url": "/h/{household}",
+                "description": "One household of a multi household server",
+                "variables": { "household": { "default": "home" } },
+            },
+        ],
+        "paths": paths,
+        "components": {
+            "securitySchemes": { "session": {
+                "type": "http",
+                "scheme": "bearer",
+                "description": "Viewers read, caregivers log feedings, expulsions and events, \
+                    owners manage persons, accounts and admin. Other calls answer 403.",
+            } },
+        },
+        "security": [{ "session": [] }],
+    })
+}
+
+fn path_parameters(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/')
+        .filter_map(|segment| segment.strip_prefix('{')?.strip_suffix('}'))
+}
+
+/// Entities as the backend writes them, taken from a scratch context
+struct Entities {
+    person: Value,
+    feeding: Value,
+    expulsion: Value,
+    event: Value,
+    measurement: Value,
+}
+
+fn entities() -> Entities {
+    let mut ost = construct_monolith_in_memory().unwrap();
+    let mut person = ost.add_person("Zardoz").unwrap();
+    // Every optional field set, so the schema lists them
+    person.set_birth_date(NaiveDate::from_ymd_opt(2021, 11, 4));
+    person.set_sex(Some(Sex::Female));
+    person.set_birth_weight(Some(3400));
+    person.set_colour(Some("#3273dc"));
+    let feedings = vec![
+        serialized(ost.add_feeding(&person, 120, 0, 0).unwrap().serialize()),
+        serialized(
+            ost.add_nursing(&person, NursingSession::examples().remove(0))
+                .unwrap()
+                .serialize(),
+        ),
+    ];
+    let expulsion = ost.add_expulsion(&person, ExpulsionDegree::Pee).unwrap();
+    let measurements: Vec<Value> = Measures::examples()
+        .into_iter()
+        .map(|measures| serialized(ost.add_measurement(&person, measures).unwrap().serialize()))
+        .collect();
+    let events: Vec<Value> = EventType::examples()
+        .into_iter()
+        .map(|event| serialized(ost.add_event(&person, event).unwrap().serialize()))
+        .collect();
+    Entities {
+        person: infer(&serialized(person.serialize())),
+        feeding: schema_of_values(feedings.into_iter()),
+        expulsion: infer(&serialized(expulsion.serialize())),
+        event: schema_of_values(events.into_iter()),
+        measurement: schema_of_values(measurements.into_iter()),
+    }
+}
+
+fn serialized(json: String) -> Value {
+    serde_json::from_str(&json).unwrap()
+}
+
+fn array_of(items: Value) -> Value {
+    json!({ "type": "array", "items": items })
+}
+
+/// v1 hands entities over as json strings
+fn serialized_entity() -> Value {
+    json!({ "type": "string", "description": "A serialized entity" })
+}
+
+fn nothing() -> Value {
+    json!({ "nullable": true })
+}
+
+fn routes() -> Vec<ApiRoute> {
+    let entities = entities();
+    let mut routes = v1_routes();
+    routes.extend(v2_routes(&entities));
+    routes.extend(auth_routes());
+    routes.extend(household_routes());
+    routes.push(
+        ApiRoute::new("get", "/api/query", "Filtered, paginated events")
+            .query("person", json!({ "type": "integer" }))
+            .query(
+                "kinds",
+                json!({ "type": "string", "description": "Comma separated: Feed,Expulsion,Event,Measurement" }),
+            )
+            .query("from", json!({ "type": "string", "format": "date-time" }))
+            .query("to", json!({ "type": "string", "format": "date-time" }))
+            .query("active_persons_only", json!({ "type": "boolean" }))
+            .query(
+                "sort",
+                json!({ "type": "string", "enum": ["NewestFirst", "OldestFirst"] }),
+            )
+            .query("offset", json!({ "type": "integer" }))
+            .query("limit", json!({ "type": "integer" }))
+            .v1(json!({ "type": "string", "description": "A serialized page" })),
+    );
+    routes.push(
+        ApiRoute::new(
+            "get",
+            "/api/changes",
+            "WebSocket, one json text message per change",
+        )
+        .query(
+            "access_token",
+            json!({ "type": "string", "description": "The session token, for browsers" }),
+        )
+        .responds(
+            StatusCode::SWITCHING_PROTOCOLS,
+            schema_of::<ChangeNotification>(),
+        )
+        .fails(&[StatusCode::UNAUTHORIZED]),
+    );
+    routes.push(
+        ApiRoute::new("get", "/api/openapi.json", "This document")
+            .public()
+            .responds(StatusCode::OK, json!({ "type": "object" })),
+    );
+    routes
+}
+
+fn v1_routes() -> Vec<ApiRoute> {
+    vec![
+        // Persons
+        ApiRoute::new("get", "/api/persons", "Every person")
+            .responds(StatusCode::OK, array_of(serialized_entity())),
+        ApiRoute::new("post", "/api/persons", "Add a person")
+            .body(schema_of::<ArgAddPerson>())
+            .v1(serialized_entity()),
+        ApiRoute::new("post", "/api/person", "Modify a person")
+            .body(schema_of::<ArgAModifyPerson>())
+            .v1(nothing()),
+        ApiRoute::new("post", "/api/persons/remove", "Remove a person")
+            .body(schema_of::<ArgRemovePerson>())
+            .v1(nothing()),
+        ApiRoute::new("post", "/api/persons/merge", "Merge two persons")
+            .body(schema_of::<ArgMergePersons>())
+            .v1(nothing()),
+        ApiRoute::new("post", "/api/persons/add-fake-count", "Add fake persons")
+            .body(schema_of::<ArgFakeCount>())
+            .v1(nothing()),
+        // Feedings
+        ApiRoute::new("get", "/api/feedings", "Every feeding")
+            .responds(StatusCode::OK, array_of(serialized_entity())),
+        ApiRoute::new("post", "/api/feedings", "A feeding by key")
+            .body(schema_of::<ArgEntityKey>())
+            .responds(StatusCode::OK, merge(serialized_entity(), nothing())),
+        ApiRoute::new("post", "/api/feedings/add", "Add a feeding")
+            .body(schema_of::<ArgAddFeeding>())
+            .v1(serialized_entity()),
+        ApiRoute::new("post", "/api/feedings/add-nursing", "Add a nursing session")
+            .body(schema_of::<ArgAddNursing>())
+            .v1(serialized_entity()),
+        ApiRoute::new("post", "/api/feedings/add-fake-count", "Add fake feedings")
+            .body(schema_of::<ArgFakeCount>())
+            .v1(nothing()),
+        ApiRoute::new("post", "/api/feedings/remove", "Remove a feeding")
+            .body(schema_of::<ArgEntityKey>())
+            .v1(nothing()),
+        ApiRoute::new("post", "/api/feed", "Modify a feeding")
+            .body(schema_of::<ArgAModifyFeeding>())
+            .v1(nothing()),
+        // Expulsions
+        ApiRoute::new("get", "/api/expulsions", "Every expulsion")
+            .responds(StatusCode::OK, array_of(serialized_entity())),
+        ApiRoute::new("post", "/api/expulsions", "An expulsion by key")
+            .body(schema_of::<ArgEntityKey>())
+            .responds(StatusCode::OK, merge(serialized_entity(), nothing())),
+        ApiRoute::new("post", "/api/expulsions/add", "Add an expulsion")
+            .body(schema_of::<ArgAddExpulsion>())
+            .v1(serialized_entity()),
+        ApiRoute::new(
+            "post",
+            "/api/expulsions/add-fake-count",
+            "Add fake expulsions",
+        )
+        .body(schema_of::<ArgFakeCount>())
+        .v1(nothing()),
+        ApiRoute::new("post", "/api/expulsions/remove", "Remove an expulsion")
+            .body(schema_of::<ArgEntityKey>())
+            .v1(nothing()),
+        ApiRoute::new("post", "/api/expulsion", "Modify an expulsion")
+            .body(schema_of::<ArgModifyExpulsion>())
+            .v1(nothing()),
+        // Events
+        ApiRoute::new("get", "/api/events", "Every event")
+            .responds(StatusCode::OK, array_of(serialized_entity())),
+        ApiRoute::new("post", "/api/events", "An event by key")
+            .body(schema_of::<ArgEntityKey>())
+            .responds(StatusCode::OK, merge(serialized_entity(), nothing())),
+        ApiRoute::new("post", "/api/events/add", "Add an event")
+            .body(schema_of::<ArgAddEvent>())
+            .v1(serialized_entity()),
+        ApiRoute::new("post", "/api/events/add-fake-count", "Add fake events")
+            .body(schema_of::<ArgFakeCount>())
+            .v1(nothing()),
+        ApiRoute::new("post", "/api/events/remove", "Remove an event")
+            .body(schema_of::<ArgEntityKey>())
+            .v1(nothing()),
+        ApiRoute::new("post", "/api/event", "Modify an event")
+            .body(schema_of::<ArgAModifyEvent>())
+            .v1(nothing()),
+        // Measurements
+        ApiRoute::new("get", "/api/measurements", "Every measurement")
+            .responds(StatusCode::OK, array_of(serialized_entity())),
+        ApiRoute::new("post", "/api/measurements", "A measurement by key")
+            .body(schema_of::<ArgEntityKey>())
+            .responds(StatusCode::OK, merge(serialized_entity(), nothing())),
+        ApiRoute::new("post", "/api/measurements/add", "Add a measurement")
+            .body(schema_of::<ArgAddMeasurement>())
+            .v1(serialized_entity()),
+        ApiRoute::new(
+            "post",
+            "/api/measurements/add-fake-count",
+            "Add fake measurements",
+        )
+        .body(schema_of::<ArgFakeCount>())
+        .v1(nothing()),
+        ApiRoute::new("post", "/api/measurements/remove", "Remove a measurement")
+            .body(schema_of::<ArgEntityKey>())
+            .v1(nothing()),
+        ApiRoute::new("post", "/api/measurement", "Modify a measurement")
+            .body(schema_of::<ArgModifyMeasurement>())
+            .v1(nothing()),
+        // Admin
+        ApiRoute::new("post", "/api/admin/reset", "Purge all data").v1(nothing()),
+        ApiRoute::new("post", "/api/admin/purge-all-events", "Purge every event").v1(nothing()),
+        ApiRoute::new(
+            "get",
+            "/api/admin/integrity",
+            "Integrity issues in the storage",
+        )
+        .v1(array_of(schema_of::<IntegrityIssue>())),
+        ApiRoute::new("post", "/api/admin/integrity/repair", "Repair the storage")
+            .v1(array_of(schema_of::<IntegrityIssue>())),
+    ]
+}
+
+/// Everything but the login answers 401 without a session and 403 when the role falls short
+fn auth_routes() -> Vec<ApiRoute> {
+    use StatusCode as S;
+    vec![
+        ApiRoute::new("post", "/api/auth/login", "Start a session")
+            .public()
+            .body(schema_of::<Credentials>())
+            .responds(S::OK, schema_of::<SessionToken>())
+            .fails(&[S::UNAUTHORIZED]),
+        ApiRoute::new("post", "/api/auth/logout", "End the current session")
+            .responds_empty(S::NO_CONTENT),
+        ApiRoute::new("get", "/api/auth/accounts", "Every account and its role")
+            .responds(S::OK, array_of(schema_of::<AccountInfo>()))
+            .fails(&[S::UNAUTHORIZED, S::FORBIDDEN]),
+        ApiRoute::new("post", "/api/auth/accounts", "Add an account")
+            .body(schema_of::<NewAccount>())
+            .responds_empty(S::CREATED)
+            .fails(&[
+                S::UNAUTHORIZED,
+                S::FORBIDDEN,
+                S::CONFLICT,
+                S::UNPROCESSABLE_ENTITY,
+            ]),
+    ]
+}
+
+/// Served at the root of a multi household server, the bearer is `OST_ADMIN_TOKEN`
+fn household_routes() -> Vec<ApiRoute> {
+    use StatusCode as S;
+    vec![
+        ApiRoute::new("get", "/admin/households", "Every household")
+            .responds(S::OK, array_of(schema_of::<Household>()))
+            .fails(&[S::UNAUTHORIZED]),
+        ApiRoute::new(
+            "post",
+            "/admin/households",
+            "Create a household and its owner",
+        )
+        .body(schema_of::<NewHousehold>())
+        .responds(S::CREATED, schema_of::<Household>())
+        .fails(&[S::UNAUTHORIZED, S::CONFLICT, S::UNPROCESSABLE_ENTITY]),
+        ApiRoute::new(
+            "post",
+            "/admin/households/{household}/archive",
+            "Stop serving a household, its files stay",
+        )
+        .responds(S::OK, schema_of::<Household>())
+        .fails(&[S::UNAUTHORIZED, S::NOT_FOUND]),
+    ]
+}
+
+/// Feedings, expulsions and events share one shape
+struct EventResource<'a> {
+    collection: &'static str,
+    item: &'static str,
+    summaries: [&'static str; 5],
+    entity: &'a Value,
+    new: Value,
+    changes: Value,
+}
+
+fn v2_routes(entities: &Entities) -> Vec<ApiRoute> {
+    use StatusCode as S;
+    let mut routes = vec![
+        ApiRoute::new("get", "/api/v2/persons", "Every person")
+            .responds(S::OK, array_of(entities.person.clone())),
+        ApiRoute::new("post", "/api/v2/persons", "Add a person")
+            .body(schema_of::<NewPerson>())
+            .responds(S::CREATED, entities.person.clone())
+            .fails(&[S::CONFLICT, S::UNPROCESSABLE_ENTITY]),
+        ApiRoute::new("get", "/api/v2/persons/{id}", "A person")
+            .responds(S::OK, entities.person.clone())
+            .fails(&[S::NOT_FOUND]),
+        ApiRoute::new("put", "/api/v2/persons/{id}", "Replace a person")
+            .body(entities.person.clone())
+            .responds(S::OK, entities.person.clone())
+            .fails(&[S::NOT_FOUND, S::CONFLICT, S::UNPROCESSABLE_ENTITY]),
+        ApiRoute::new("delete", "/api/v2/persons/{id}", "Remove a person")
+            .query("cascade", json!({ "type": "boolean" }))
+            .responds_empty(S::NO_CONTENT)
+            .fails(&[S::NOT_FOUND, S::UNPROCESSABLE_ENTITY]),
+        ApiRoute::new(
+            "post",
+            "/api/v2/persons/{from}/merge/{into}",
+            "Merge a person into another",
+        )
+        .responds(S::OK, entities.person.clone())
+        .fails(&[S::NOT_FOUND, S::UNPROCESSABLE_ENTITY]),
+    ];
+
+    let resources = [
+        EventResource {
+            collection: "/api/v2/feedings",
+            item: "/api/v2/feedings/{id}",
+            summaries: [
+                "Every feeding",
+                "Add a feeding",
+                "A feeding",
+                "Modify a feeding",
+                "Remove a feeding",
+            ],
+            entity: &entities.feeding,
+            new: schema_of::<NewFeeding>(),
+            changes: schema_of::<FeedingChanges>(),
+        },
+        EventResource {
+            collection: "/api/v2/expulsions",
+            item: "/api/v2/expulsions/{id}",
+            summaries: [
+                "Every expulsion",
+                "Add an expulsion",
+                "An expulsion",
+                "Modify an expulsion",
+                "Remove an expulsion",
+            ],
+            entity: &entities.expulsion,
+            new: schema_of::<NewExpulsion>(),
+            changes: schema_of::<ExpulsionChanges>(),
+        },
+        EventResource {
+            collection: "/api/v2/events",
+            item: "/api/v2/events/{id}",
+            summaries: [
+                "Every event",
+                "Add an event",
+                "An event",
+                "Modify an event",
+                "Remove an event",
+            ],
+            entity: &entities.event,
+            new: schema_of::<NewEvent>(),
+            changes: schema_of::<EventChanges>(),
+        },
+        EventResource {
+            collection: "/api/v2/measurements",
+            item: "/api/v2/measurements/{id}",
+            summaries: [
+                "Every measurement",
+                "Add a measurement",
+                "A measurement",
+                "Modify a measurement",
+                "Remove a measurement",
+            ],
+            entity: &entities.measurement,
+            new: schema_of::<NewMeasurement>(),
+            changes: schema_of::<MeasurementChanges>(),
+        },
+    ];
+    for resource in resources {
+        let [list, create, get, update, delete] = resource.summaries;
+        routes.extend([
+            ApiRoute::new("get", resource.collection, list)
+                .responds(S::OK, array_of(resource.entity.clone())),
+            ApiRoute::new("post", resource.collection, create)
+                .body(resource.new)
+                .responds(S::CREATED, resource.entity.clone())
+                .fails(&[S::NOT_FOUND, S::UNPROCESSABLE_ENTITY]),
+            ApiRoute::new("get", resource.item, get)
+                .responds(S::OK, resource.entity.clone())
+                .fails(&[S::NOT_FOUND]),
+            ApiRoute::new("put", resource.item, update)
+                .body(resource.changes)
+                .responds(S::OK, resource.entity.clone())
+                .fails(&[S::NOT_FOUND, S::UNPROCESSABLE_ENTITY]),
+            ApiRoute::new("delete", resource.item, delete)
+                .responds_empty(S::NO_CONTENT)
+                .fails(&[S::NOT_FOUND]),
+        ]);
+    }
+    routes
+}
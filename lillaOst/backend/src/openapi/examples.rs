@@ -0,0 +1,538 @@
+use chrono::prelude::*;
+
+use ost::change::{ChangeNotification, ChangeOp, EntityKind};
+use ost::error::OstError;
+use ost::event::EventType;
+use ost::event_key::{EventType as KeyType, OstEventKey};
+use ost::expulsion::ExpulsionDegree;
+use ost::integrity::IntegrityIssue;
+use ost::measurement::Measures;
+use ost::nursing::{BreastSide, NursingSession, NursingStretch};
+use ost::offline::ReplayId;
+use ost::person_key::OstPersonKey;
+use ost::role::Role;
+
+use super::schema::ApiExample;
+use crate::auth::accounts::{AccountInfo, Credentials, NewAccount, SessionToken};
+use crate::command_args::*;
+use crate::households::registry::{Household, NewHousehold};
+use crate::v2::bodies::*;
+
+// Shared by the args and bodies below
+impl ApiExample for OstEventKey {
+    fn examples() -> Vec<Self> {
+        [
+            KeyType::Event,
+            KeyType::Expulsion,
+            KeyType::Feed,
+            KeyType::Measurement,
+        ]
+        .into_iter()
+        .map(|t| OstEventKey { t, id: 1 })
+        .collect()
+    }
+}
+
+impl ApiExample for EventType {
+    fn examples() -> Vec<Self> {
+        vec![
+            EventType::Bath,
+            EventType::Medicine("Paracetamol".to_string()),
+            EventType::Sleep,
+            EventType::Awake,
+            EventType::Note("First smile".to_string()),
+            EventType::Temperature(37.5),
+        ]
+    }
+}
+
+impl ApiExample for ExpulsionDegree {
+    fn examples() -> Vec<Self> {
+        vec![
+            ExpulsionDegree::Clean,
+            ExpulsionDegree::Pee,
+            ExpulsionDegree::Shart,
+            ExpulsionDegree::Poopies,
+            ExpulsionDegree::Pooplosion,
+        ]
+    }
+}
+
+impl ApiExample for Measures {
+    fn examples() -> Vec<Self> {
+        vec![
+            Measures {
+                weight: Some(4200),
+                length: Some(55.5),
+                head_circumference: Some(37.0),
+            },
+            Measures {
+                weight: Some(3900),
+                ..Measures::default()
+            },
+        ]
+    }
+}
+
+impl ApiExample for OstError {
+    fn examples() -> Vec<Self> {
+        let message = "What went wrong".to_string();
+        vec![
+            OstError::NotFound(message.clone()),
+            OstError::DuplicateName(message.clone()),
+            OstError::Validation(message.clone()),
+            OstError::PersistenceIo(message.clone()),
+            OstError::Serialization(message.clone()),
+            OstError::Transport(message.clone()),
+            OstError::Unauthorized(message.clone()),
+            OstError::Forbidden(message),
+        ]
+    }
+}
+
+impl ApiExample for ChangeNotification {
+    fn examples() -> Vec<Self> {
+        vec![
+            ChangeNotification::one(EntityKind::Person, 1, ChangeOp::Added),
+            ChangeNotification::one(EntityKind::Feeding, 1, ChangeOp::Modified),
+            ChangeNotification::one(EntityKind::Expulsion, 1, ChangeOp::Removed),
+            ChangeNotification::one(EntityKind::Event, 1, ChangeOp::Added).for_person(1),
+            ChangeNotification::many(EntityKind::All, ChangeOp::Removed),
+        ]
+    }
+}
+
+impl ApiExample for IntegrityIssue {
+    fn examples() -> Vec<Self> {
+        let key = OstEventKey {
+            t: KeyType::Feed,
+            id: 1,
+        };
+        vec![
+            IntegrityIssue::DanglingPersonReference { key, person_id: 1 },
+            IntegrityIssue::DuplicatePersonId { person_id: 1 },
+            IntegrityIssue::DuplicateId { key },
+            IntegrityIssue::FutureTimeStamp {
+                key,
+                time_stamp: Utc::now(),
+            },
+            IntegrityIssue::DuplicatePersonName {
+                name: "Zardoz".to_string(),
+                person_ids: vec![1, 2],
+            },
+        ]
+    }
+}
+
+impl ApiExample for Credentials {
+    fn examples() -> Vec<Self> {
+        vec![Credentials {
+            name: "Zardoz".to_string(),
+            password: "correct horse battery".to_string(),
+        }]
+    }
+}
+
+impl ApiExample for Role {
+    fn examples() -> Vec<Self> {
+        vec![Role::Viewer, Role::Caregiver, Role::Owner]
+    }
+}
+
+impl ApiExample for SessionToken {
+    fn examples() -> Vec<Self> {
+        Role::examples()
+            .into_iter()
+            .map(|role| SessionToken {
+                token: "0f1e2d3c".to_string(),
+                role,
+            })
+            .collect()
+    }
+}
+
+impl ApiExample for NewAccount {
+    fn examples() -> Vec<Self> {
+        Role::examples()
+            .into_iter()
+            .map(|role| NewAccount {
+                name: "Zardoz".to_string(),
+                password: "correct horse battery".to_string(),
+                role,
+            })
+            .collect()
+    }
+}
+
+impl ApiExample for AccountInfo {
+    fn examples() -> Vec<Self> {
+        Role::examples()
+            .into_iter()
+            .map(|role| AccountInfo {
+                name: "Zardoz".to_string(),
+                role,
+            })
+            .collect()
+    }
+}
+
+impl ApiExample for Household {
+    fn examples() -> Vec<Self> {
+        vec![Household {
+            id: "home".to_string(),
+            archived: false,
+        }]
+    }
+}
+
+impl ApiExample for NewHousehold {
+    fn examples() -> Vec<Self> {
+        Credentials::examples()
+            .into_iter()
+            .map(|owner| NewHousehold {
+                id: "home".to_string(),
+                owner,
+            })
+            .collect()
+    }
+}
+
+// v1 args
+impl ApiExample for ArgAddPerson {
+    fn examples() -> Vec<Self> {
+        vec![ArgAddPerson {
+            name: "Zardoz".to_string(),
+        }]
+    }
+}
+
+impl ApiExample for ArgFakeCount {
+    fn examples() -> Vec<Self> {
+        vec![ArgFakeCount { count: 10 }]
+    }
+}
+
+impl ApiExample for ArgAModifyPerson {
+    fn examples() -> Vec<Self> {
+        vec![ArgAModifyPerson {
+            person_key: OstPersonKey { id: 1 },
+            serialized_person: r##"{"id":1,"name":"Zardoz","is_active":true,"birth_date":"2021-11-04","sex":"Female","birth_weight":3400,"colour":"#3273dc"}"##.to_string(),
+        }]
+    }
+}
+
+impl ApiExample for ArgRemovePerson {
+    fn examples() -> Vec<Self> {
+        vec![ArgRemovePerson {
+            person_key: OstPersonKey { id: 1 },
+            cascade: false,
+        }]
+    }
+}
+
+impl ApiExample for ArgMergePersons {
+    fn examples() -> Vec<Self> {
+        vec![ArgMergePersons {
+            from: OstPersonKey { id: 2 },
+            into: OstPersonKey { id: 1 },
+        }]
+    }
+}
+
+impl ApiExample for ArgEntityKey {
+    fn examples() -> Vec<Self> {
+        OstEventKey::examples()
+            .into_iter()
+            .map(|event_key| ArgEntityKey { event_key })
+            .collect()
+    }
+}
+
+/// What a client replaying its outbox sends along
+fn replayed() -> ReplayId {
+    ReplayId {
+        client: "5f0c9a7e21b84d6f9c3e8a1b7d2f4e60".to_string(),
+        id: 0,
+    }
+}
+
+impl ApiExample for ArgAddEvent {
+    fn examples() -> Vec<Self> {
+        EventType::examples()
+            .into_iter()
+            .enumerate()
+            .map(|(i, new_event)| ArgAddEvent {
+                person_key: OstPersonKey { id: 1 },
+                new_event,
+                // One of them replayed from offline
+                time_stamp: (i == 0).then(Utc::now),
+                replay_id: (i == 0).then(replayed),
+            })
+            .collect()
+    }
+}
+
+impl ApiExample for ArgAModifyEvent {
+    fn examples() -> Vec<Self> {
+        EventType::examples()
+            .into_iter()
+            .map(|event_payload| ArgAModifyEvent {
+                event_key: OstEventKey {
+                    t: KeyType::Event,
+                    id: 1,
+                },
+                time_stamp: Utc::now(),
+                event_payload,
+            })
+            .collect()
+    }
+}
+
+impl ApiExample for ArgAddExpulsion {
+    fn examples() -> Vec<Self> {
+        ExpulsionDegree::examples()
+            .into_iter()
+            .enumerate()
+            .map(|(i, expulsion_degree)| ArgAddExpulsion {
+                person_key: OstPersonKey { id: 1 },
+                expulsion_degree,
+                time_stamp: (i == 0).then(Utc::now),
+                replay_id: (i == 0).then(replayed),
+            })
+            .collect()
+    }
+}
+
+impl ApiExample for ArgModifyExpulsion {
+    fn examples() -> Vec<Self> {
+        ExpulsionDegree::examples()
+            .into_iter()
+            .map(|expulsion_degree| ArgModifyExpulsion {
+                event_key: OstEventKey {
+                    t: KeyType::Expulsion,
+                    id: 1,
+                },
+                time_stamp: Utc::now(),
+                expulsion_degree,
+            })
+            .collect()
+    }
+}
+
+impl ApiExample for ArgAddMeasurement {
+    fn examples() -> Vec<Self> {
+        Measures::examples()
+            .into_iter()
+            .enumerate()
+            .map(|(i, measures)| ArgAddMeasurement {
+                person_key: OstPersonKey { id: 1 },
+                measures,
+                time_stamp: (i == 0).then(Utc::now),
+                replay_id: (i == 0).then(replayed),
+            })
+            .collect()
+    }
+}
+
+impl ApiExample for ArgModifyMeasurement {
+    fn examples() -> Vec<Self> {
+        Measures::examples()
+            .into_iter()
+            .map(|measures| ArgModifyMeasurement {
+                event_key: OstEventKey {
+                    t: KeyType::Measurement,
+                    id: 1,
+                },
+                time_stamp: Utc::now(),
+                measures,
+            })
+            .collect()
+    }
+}
+
+impl ApiExample for ArgAddFeeding {
+    fn examples() -> Vec<Self> {
+        vec![
+            ArgAddFeeding {
+                person_key: OstPersonKey { id: 1 },
+                breast_milk: 120,
+                formula: 0,
+                solids: 0,
+                time_stamp: None,
+                replay_id: None,
+            },
+            // Recorded offline, replayed later
+            ArgAddFeeding {
+                person_key: OstPersonKey { id: 1 },
+                breast_milk: 0,
+                formula: 90,
+                solids: 0,
+                time_stamp: Some(Utc::now()),
+                replay_id: Some(replayed()),
+            },
+        ]
+    }
+}
+
+impl ApiExample for ArgAModifyFeeding {
+    fn examples() -> Vec<Self> {
+        [None]
+            .into_iter()
+            .chain(NursingSession::examples().into_iter().map(Some))
+            .map(|nursing| ArgAModifyFeeding {
+                event_key: OstEventKey {
+                    t: KeyType::Feed,
+                    id: 1,
+                },
+                time_stamp: Utc::now(),
+                breast_milk: 90,
+                formula: 30,
+                solids: 0,
+                nursing,
+            })
+            .collect()
+    }
+}
+
+/// Ten minutes on the left, then eight on the right
+impl ApiExample for NursingSession {
+    fn examples() -> Vec<Self> {
+        let start = Utc::now() - chrono::Duration::minutes(20);
+        let switched = start + chrono::Duration::minutes(10);
+        vec![NursingSession {
+            stretches: vec![
+                NursingStretch {
+                    side: BreastSide::Left,
+                    start,
+                    end: switched,
+                },
+                NursingStretch {
+                    side: BreastSide::Right,
+                    start: switched,
+                    end: switched + chrono::Duration::minutes(8),
+                },
+            ],
+        }]
+    }
+}
+
+impl ApiExample for ArgAddNursing {
+    fn examples() -> Vec<Self> {
+        NursingSession::examples()
+            .into_iter()
+            .map(|nursing| ArgAddNursing {
+                person_key: OstPersonKey { id: 1 },
+                nursing,
+                replay_id: None,
+            })
+            .collect()
+    }
+}
+
+// v2 bodies
+impl ApiExample for NewPerson {
+    fn examples() -> Vec<Self> {
+        vec![NewPerson {
+            name: "Zardoz".to_string(),
+        }]
+    }
+}
+
+impl ApiExample for NewFeeding {
+    fn examples() -> Vec<Self> {
+        vec![NewFeeding {
+            person_id: 1,
+            breast_milk: 120,
+            formula: 0,
+            solids: 0,
+        }]
+    }
+}
+
+impl ApiExample for FeedingChanges {
+    fn examples() -> Vec<Self> {
+        [None]
+            .into_iter()
+            .chain(NursingSession::examples().into_iter().map(Some))
+            .map(|nursing| FeedingChanges {
+                time_stamp: Utc::now(),
+                breast_milk: 90,
+                formula: 30,
+                solids: 0,
+                nursing,
+            })
+            .collect()
+    }
+}
+
+impl ApiExample for NewExpulsion {
+    fn examples() -> Vec<Self> {
+        ExpulsionDegree::examples()
+            .into_iter()
+            .map(|degree| NewExpulsion {
+                person_id: 1,
+                degree,
+            })
+            .collect()
+    }
+}
+
+impl ApiExample for ExpulsionChanges {
+    fn examples() -> Vec<Self> {
+        ExpulsionDegree::examples()
+            .into_iter()
+            .map(|degree| ExpulsionChanges {
+                time_stamp: Utc::now(),
+                degree,
+            })
+            .collect()
+    }
+}
+
+impl ApiExample for NewMeasurement {
+    fn examples() -> Vec<Self> {
+        Measures::examples()
+            .into_iter()
+            .map(|measures| NewMeasurement {
+                person_id: 1,
+                measures,
+            })
+            .collect()
+    }
+}
+
+impl ApiExample for MeasurementChanges {
+    fn examples() -> Vec<Self> {
+        Measures::examples()
+            .into_iter()
+            .map(|measures| MeasurementChanges {
+                time_stamp: Utc::now(),
+                measures,
+            })
+            .collect()
+    }
+}
+
+impl ApiExample for NewEvent {
+    fn examples() -> Vec<Self> {
+        EventType::examples()
+            .into_iter()
+            .map(|event| NewEvent {
+                person_id: 1,
+                event,
+            })
+            .collect()
+    }
+}
+
+impl ApiExample for EventChanges {
+    fn examples() -> Vec<Self> {
+        EventType::examples()
+            .into_iter()
+            .map(|event| EventChanges {
+                time_stamp: Utc::now(),
+                event,
+            })
+            .collect()
+    }
+}
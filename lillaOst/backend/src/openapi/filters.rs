@@ -0,0 +1,14 @@
+use warp::{filters::BoxedFilter, Filter, Reply};
+
+use super::handlers;
+
+pub fn all_openapi() -> BoxedFilter<(impl Reply,)> {
+    openapi()
+}
+
+pub fn openapi() -> BoxedFilter<(impl Reply,)> {
+    warp::path!("api" / "openapi.json")
+        .and(warp::get())
+        .and_then(handlers::openapi)
+        .boxed()
+}
@@ -0,0 +1,7 @@
+use std::convert::Infallible;
+
+use super::document::document;
+
+pub async fn openapi() -> Result<impl warp::Reply, Infallible> {
+    Ok(warp::reply::json(&document()))
+}
@@ -0,0 +1,5 @@
+pub mod document;
+pub mod examples;
+pub mod filters;
+pub mod handlers;
+pub mod schema;
@@ -0,0 +1,106 @@
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+/// Values that stand for a type in the OpenAPI document. Enums list one example per variant,
+/// so the schema is inferred from what serde actually writes.
+pub trait ApiExample: Serialize + Sized {
+    fn examples() -> Vec<Self>;
+}
+
+pub fn schema_of<T: ApiExample>() -> Value {
+    schema_of_values(
+        T::examples()
+            .iter()
+            .map(|example| serde_json::to_value(example).unwrap()),
+    )
+}
+
+pub fn schema_of_values(values: impl Iterator<Item = Value>) -> Value {
+    values
+        .map(|value| infer(&value))
+        .reduce(merge)
+        .unwrap_or_else(|| json!({}))
+}
+
+pub fn infer(value: &Value) -> Value {
+    match value {
+        Value::Null => json!({ "nullable": true }),
+        Value::Bool(_) => json!({ "type": "boolean" }),
+        Value::Number(number) if number.is_f64() => json!({ "type": "number" }),
+        Value::Number(_) => json!({ "type": "integer" }),
+        Value::String(_) => json!({ "type": "string" }),
+        Value::Array(items) => json!({
+            "type": "array",
+            "items": schema_of_values(items.iter().cloned()),
+        }),
+        Value::Object(fields) => {
+            let properties: Map<String, Value> = fields
+                .iter()
+                .map(|(name, field)| (name.clone(), infer(field)))
+                .collect();
+            let required: Vec<&String> = fields.keys().collect();
+            json!({ "type": "object", "properties": properties, "required": required })
+        }
+    }
+}
+
+/// Objects merge field by field, a field missing on one side is optional,
+/// anything else that differs becomes a `oneOf`
+pub fn merge(a: Value, b: Value) -> Value {
+    if a == b {
+        return a;
+    }
+    if a.get("nullable").is_some() && a.get("type").is_none() {
+        return nullable(b);
+    }
+    if b.get("nullable").is_some() && b.get("type").is_none() {
+        return nullable(a);
+    }
+    if a["type"] == "object" && b["type"] == "object" {
+        return merge_objects(a, b);
+    }
+    if a["type"] == "array" && b["type"] == "array" {
+        return json!({ "type": "array", "items": merge(a["items"].clone(), b["items"].clone()) });
+    }
+    let mut alternatives = one_of(a);
+    for alternative in one_of(b) {
+        if !alternatives.contains(&alternative) {
+            alternatives.push(alternative);
+        }
+    }
+    json!({ "oneOf": alternatives })
+}
+
+fn merge_objects(a: Value, b: Value) -> Value {
+    let mut properties = a["properties"].as_object().cloned().unwrap_or_default();
+    for (name, field) in b["properties"].as_object().cloned().unwrap_or_default() {
+        let merged = match properties.remove(&name) {
+            Some(existing) => merge(existing, field),
+            None => field,
+        };
+        properties.insert(name, merged);
+    }
+    let required_by = |schema: &Value| -> Vec<Value> {
+        schema["required"].as_array().cloned().unwrap_or_default()
+    };
+    let required_b = required_by(&b);
+    let required: Vec<Value> = required_by(&a)
+        .into_iter()
+        .filter(|name| required_b.contains(name))
+        .collect();
+    json!({ "type": "object", "properties": properties, "required": required })
+}
+
+fn one_of(schema: Value) -> Vec<Value> {
+    match schema.get("oneOf") {
+        Some(Value::Array(alternatives)) => alternatives.clone(),
+        _ => vec![schema],
+    }
+}
+
+fn nullable(mut schema: Value) -> Value {
+    if let Some(fields) = schema.as_object_mut() {
+        fields.insert("nullable".to_string(), Value::Bool(true));
+    }
+    schema
+}
@@ -1,33 +1,32 @@
-use tokio::sync::mpsc::Sender;
 use warp::{filters::BoxedFilter, Filter, Reply};
 
 use super::handlers;
-use crate::command::CommandToBackend;
-use crate::command_args::{ArgAModifyPerson, ArgAddPerson, ArgFakeCount};
+use crate::command::Commands;
+use crate::command_args::{
+    ArgAModifyPerson, ArgAddPerson, ArgFakeCount, ArgMergePersons, ArgRemovePerson,
+};
+use crate::common_filters::{with_command_sender, with_context};
+use crate::local_state::SharedContext;
 
-pub fn all_persons(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Reply,)> {
-    get_persons(tx.clone())
+pub fn all_persons(tx: Commands, context: SharedContext) -> BoxedFilter<(impl Reply,)> {
+    get_persons(context)
         .or(add_person(tx.clone()))
         .or(modify_person(tx.clone()))
+        .or(remove_person(tx.clone()))
+        .or(merge_persons(tx.clone()))
         .or(add_fake_persons(tx))
         .boxed()
 }
 
-pub fn get_persons(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Reply,)> {
+pub fn get_persons(context: SharedContext) -> BoxedFilter<(impl Reply,)> {
     warp::path!("api" / "persons")
         .and(warp::get())
-        .and(with_command_sender(tx))
+        .and(with_context(context))
         .and_then(handlers::ost_get_persons)
         .boxed()
 }
 
-fn with_command_sender(
-    tx: Sender<CommandToBackend>,
-) -> impl Filter<Extract = (Sender<CommandToBackend>,), Error = std::convert::Infallible> + Clone {
-    warp::any().map(move || tx.clone())
-}
-
-pub fn add_person(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Reply,)> {
+pub fn add_person(tx: Commands) -> BoxedFilter<(impl Reply,)> {
     warp::path!("api" / "persons")
         .and(warp::post())
         .and(with_command_sender(tx))
@@ -41,7 +40,7 @@ fn json_args_add_person() -> impl Filter<Extract = (ArgAddPerson,), Error = warp
     warp::body::content_length_limit(1024 * 16).and(warp::body::json())
 }
 
-pub fn modify_person(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Reply,)> {
+pub fn modify_person(tx: Commands) -> BoxedFilter<(impl Reply,)> {
     warp::path!("api" / "person")
         .and(warp::post())
         .and(with_command_sender(tx))
@@ -55,7 +54,35 @@ fn json_args_modify_person(
     warp::body::content_length_limit(1024 * 16).and(warp::body::json())
 }
 
-pub fn add_fake_persons(tx: Sender<CommandToBackend>) -> BoxedFilter<(impl Reply,)> {
+pub fn remove_person(tx: Commands) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("api" / "persons" / "remove")
+        .and(warp::post())
+        .and(with_command_sender(tx))
+        .and(json_args_remove_person())
+        .and_then(handlers::remove_person)
+        .boxed()
+}
+
+fn json_args_remove_person(
+) -> impl Filter<Extract = (ArgRemovePerson,), Error = warp::Rejection> + Clone {
+    warp::body::content_length_limit(1024 * 16).and(warp::body::json())
+}
+
+pub fn merge_persons(tx: Commands) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("api" / "persons" / "merge")
+        .and(warp::post())
+        .and(with_command_sender(tx))
+        .and(json_args_merge_persons())
+        .and_then(handlers::merge_persons)
+        .boxed()
+}
+
+fn json_args_merge_persons(
+) -> impl Filter<Extract = (ArgMergePersons,), Error = warp::Rejection> + Clone {
+    warp::body::content_length_limit(1024 * 16).and(warp::body::json())
+}
+
+pub fn add_fake_persons(tx: Commands) -> BoxedFilter<(impl Reply,)> {
     warp::path!("api" / "persons" / "add-fake-count")
         .and(warp::post())
         .and(with_command_sender(tx))
@@ -1,20 +1,24 @@
 use std::convert::Infallible;
 
-use tokio::sync::mpsc::Sender;
 use tokio::sync::oneshot;
 
-use crate::command::CommandToBackend;
-use crate::command_args::{ArgAModifyPerson, ArgAddPerson, ArgFakeCount};
-use crate::common_handlers::send_command_to_server;
+use crate::command::{CommandSender, CommandToBackend};
+use crate::command_args::{
+    ArgAModifyPerson, ArgAddPerson, ArgFakeCount, ArgMergePersons, ArgRemovePerson,
+};
+use crate::common_handlers::{read_from_context, send_command_to_server};
+use crate::local_state::SharedContext;
 
-pub async fn ost_get_persons(tx: Sender<CommandToBackend>) -> Result<impl warp::Reply, Infallible> {
-    let (resp_tx, resp_rx) = oneshot::channel();
-    let cmd = CommandToBackend::GetPersons { resp: resp_tx };
-    Ok(send_command_to_server(tx, resp_rx, cmd).await)
+pub async fn ost_get_persons(context: SharedContext) -> Result<impl warp::Reply, Infallible> {
+    Ok(read_from_context(&context, |ost| {
+        let persons: Vec<String> = ost.persons().iter().map(|e| e.serialize()).collect();
+        serde_json::to_string(&persons).unwrap()
+    })
+    .await)
 }
 
 pub async fn add_person(
-    tx: Sender<CommandToBackend>,
+    tx: CommandSender,
     args: ArgAddPerson,
 ) -> Result<impl warp::Reply, Infallible> {
     let (resp_tx, resp_rx) = oneshot::channel();
@@ -26,7 +30,7 @@ pub async fn add_person(
 }
 
 pub async fn modify_person(
-    tx: Sender<CommandToBackend>,
+    tx: CommandSender,
     args: ArgAModifyPerson,
 ) -> Result<impl warp::Reply, Infallible> {
     let (resp_tx, resp_rx) = oneshot::channel();
@@ -37,8 +41,32 @@ pub async fn modify_person(
     Ok(send_command_to_server(tx, resp_rx, cmd).await)
 }
 
+pub async fn remove_person(
+    tx: CommandSender,
+    args: ArgRemovePerson,
+) -> Result<impl warp::Reply, Infallible> {
+    let (resp_tx, resp_rx) = oneshot::channel();
+    let cmd = CommandToBackend::RemovePerson {
+        resp: resp_tx,
+        args,
+    };
+    Ok(send_command_to_server(tx, resp_rx, cmd).await)
+}
+
+pub async fn merge_persons(
+    tx: CommandSender,
+    args: ArgMergePersons,
+) -> Result<impl warp::Reply, Infallible> {
+    let (resp_tx, resp_rx) = oneshot::channel();
+    let cmd = CommandToBackend::MergePersons {
+        resp: resp_tx,
+        args,
+    };
+    Ok(send_command_to_server(tx, resp_rx, cmd).await)
+}
+
 pub async fn add_fake_persons(
-    tx: Sender<CommandToBackend>,
+    tx: CommandSender,
     args: ArgFakeCount,
 ) -> Result<impl warp::Reply, Infallible> {
     let (resp_tx, resp_rx) = oneshot::channel();
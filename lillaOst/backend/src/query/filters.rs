@@ -0,0 +1,25 @@
+use warp::{filters::BoxedFilter, Filter, Reply};
+
+use super::handlers;
+use crate::common_filters::with_context;
+use crate::local_state::SharedContext;
+
+pub fn all_query(context: SharedContext) -> BoxedFilter<(impl Reply,)> {
+    query(context)
+}
+
+/// `GET /api/query?person=1&kinds=Feed,Event&offset=0&limit=15`, see `EventQuery`
+pub fn query(context: SharedContext) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("api" / "query")
+        .and(warp::get())
+        .and(with_context(context))
+        .and(raw_query_string())
+        .and_then(handlers::query)
+        .boxed()
+}
+
+/// A request without query string asks for everything
+fn raw_query_string() -> impl Filter<Extract = (String,), Error = std::convert::Infallible> + Clone
+{
+    warp::query::raw().or(warp::any().map(String::new)).unify()
+}
@@ -0,0 +1,20 @@
+use std::convert::Infallible;
+
+use ost::error::OstError;
+use ost::query::EventQuery;
+
+use crate::common_handlers::read_from_context;
+use crate::local_state::SharedContext;
+
+pub async fn query(
+    context: SharedContext,
+    query_string: String,
+) -> Result<impl warp::Reply, Infallible> {
+    Ok(read_from_context(&context, |ost| {
+        let payload: Result<String, OstError> = EventQuery::from_query_string(&query_string)
+            .and_then(|args| ost.query(&args))
+            .map(|result| result.serialize());
+        serde_json::to_string(&payload).unwrap()
+    })
+    .await)
+}
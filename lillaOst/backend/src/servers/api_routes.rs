@@ -0,0 +1,53 @@
+use tokio::sync::mpsc::Sender;
+use warp::filters::BoxedFilter;
+use warp::reply::Response;
+use warp::{Filter, Reply};
+
+use crate::auth;
+use crate::auth::accounts::SharedAuth;
+use crate::command::{CommandToBackend, Commands};
+use crate::local_state::SharedContext;
+use crate::notifications::Notifier;
+
+use crate::admin;
+use crate::events;
+use crate::expulsions;
+use crate::feedings;
+use crate::measurements;
+use crate::notifications;
+use crate::openapi;
+use crate::persons;
+use crate::query;
+use crate::v2;
+
+/// Every `/api` route of one context, behind the session check
+pub fn api_routes(
+    tx: Sender<CommandToBackend>,
+    context: SharedContext,
+    auth: SharedAuth,
+    notifier: Notifier,
+) -> BoxedFilter<(Response,)> {
+    let tx = Commands::new(tx, auth.clone());
+    let api = persons::filters::all_persons(tx.clone(), context.clone())
+        .or(feedings::filters::all_feedings(tx.clone(), context.clone()))
+        .or(expulsions::filters::all_expulsions(
+            tx.clone(),
+            context.clone(),
+        ))
+        .or(events::filters::all_events(tx.clone(), context.clone()))
+        .or(measurements::filters::all_measurements(
+            tx.clone(),
+            context.clone(),
+        ))
+        .or(query::filters::all_query(context.clone()))
+        .or(admin::filters::all_admin(tx.clone(), context.clone()))
+        .or(v2::filters::all_v2(tx, context))
+        .or(openapi::filters::all_openapi())
+        .or(notifications::filters::all_notifications(notifier))
+        .or(auth::filters::all_auth(auth.clone()));
+    auth::filters::require_session(auth)
+        .and(api)
+        .recover(auth::filters::unauthorized)
+        .map(Reply::into_response)
+        .boxed()
+}
@@ -1,27 +1,26 @@
 use tokio::sync::mpsc;
-use tokio::task;
 
 use warp::Filter;
 
 use crate::command::CommandToBackend;
+use crate::local_state::ost_context;
+use crate::local_state_fake_in_memory::faked_context;
+use crate::storage::Storage;
 
-use crate::admin;
-use crate::events;
-use crate::expulsions;
-use crate::feedings;
-use crate::persons;
+use crate::auth::accounts::{share_auth, Auth};
+use crate::notifications::notifier;
 use crate::static_file_filters;
 
+use super::api_routes::api_routes;
+
 #[allow(dead_code)]
 pub async fn faked_context_server() {
-    let in_thread_server = task::LocalSet::new();
     let (tx, rx) = mpsc::channel::<CommandToBackend>(32);
+    let context = faked_context();
+    let auth = share_auth(Auth::from_env().unwrap());
+    let notifier = notifier();
 
-    let routes = persons::filters::all_persons(tx.clone())
-        .or(feedings::filters::all_feedings(tx.clone()))
-        .or(expulsions::filters::all_expulsions(tx.clone()))
-        .or(events::filters::all_events(tx.clone()))
-        .or(admin::filters::all_admin(tx.clone()))
+    let routes = api_routes(tx, context.clone(), auth, notifier.clone())
         .or(static_file_filters::get_index())
         .or(static_file_filters::get_static_file())
         .or(static_file_filters::serve_index_by_default_get());
@@ -32,11 +31,7 @@ pub async fn faked_context_server() {
 
     log::info!(">>> FAKE DATA ready");
 
-    in_thread_server
-        .run_until(
-            async move { crate::local_state_fake_in_memory::faked_state_ost_context(rx).await },
-        )
-        .await;
+    ost_context(rx, context, Storage::Fake, notifier).await;
 
     warp_server.await.unwrap();
 }
@@ -1,26 +1,47 @@
 use tokio::sync::mpsc;
-use tokio::task;
 
 use warp::Filter;
 
-use crate::command::CommandToBackend;
+use crate::command::{CommandToBackend, Commands};
+use crate::local_state::ost_context;
+use crate::local_state_fake_in_memory::faked_context;
+use crate::storage::Storage;
 
 use crate::admin;
 use crate::events;
 use crate::expulsions;
 use crate::feedings;
+use crate::measurements;
+use crate::notifications;
+use crate::openapi;
 use crate::persons;
+use crate::query;
+use crate::v2;
 
+/// No login in front of this one, the remote context tests in `ost` call it directly
 #[allow(dead_code)]
 pub async fn faked_ost_api() {
-    let in_thread_server = task::LocalSet::new();
     let (tx, rx) = mpsc::channel::<CommandToBackend>(32);
-
-    let routes = persons::filters::all_persons(tx.clone())
-        .or(feedings::filters::all_feedings(tx.clone()))
-        .or(expulsions::filters::all_expulsions(tx.clone()))
-        .or(events::filters::all_events(tx.clone()))
-        .or(admin::filters::all_admin(tx.clone()));
+    let context = faked_context();
+    let notifier = notifications::notifier();
+    let tx = Commands::unrestricted(tx);
+
+    let routes = persons::filters::all_persons(tx.clone(), context.clone())
+        .or(feedings::filters::all_feedings(tx.clone(), context.clone()))
+        .or(expulsions::filters::all_expulsions(
+            tx.clone(),
+            context.clone(),
+        ))
+        .or(events::filters::all_events(tx.clone(), context.clone()))
+        .or(measurements::filters::all_measurements(
+            tx.clone(),
+            context.clone(),
+        ))
+        .or(query::filters::all_query(context.clone()))
+        .or(admin::filters::all_admin(tx.clone(), context.clone()))
+        .or(v2::filters::all_v2(tx.clone(), context.clone()))
+        .or(openapi::filters::all_openapi())
+        .or(notifications::filters::all_notifications(notifier.clone()));
 
     let warp_server = tokio::spawn(async move {
         warp::serve(routes).run(([127, 0, 0, 1], 3030)).await;
@@ -28,11 +49,7 @@ pub async fn faked_ost_api() {
 
     log::info!(">>> FAKE DATA ready");
 
-    in_thread_server
-        .run_until(
-            async move { crate::local_state_fake_in_memory::faked_state_ost_context(rx).await },
-        )
-        .await;
+    ost_context(rx, context, Storage::Fake, notifier).await;
 
     warp_server.await.unwrap();
 }
@@ -0,0 +1,24 @@
+use std::env;
+
+use warp::{Filter, Reply};
+
+use crate::households;
+use crate::households::registry::{share_households, Households};
+use crate::static_file_filters;
+use crate::storage::Storage;
+
+/// Every household in `households_dir` behind `/h/{household}`, `OST_ADMIN_TOKEN` unlocks
+/// `/admin/households`
+pub async fn households_server(households_dir: &str, storage: Storage) {
+    log::info!("Hosting the households in: {}", households_dir);
+    let households = share_households(
+        Households::open(households_dir, storage, env::var("OST_ADMIN_TOKEN").ok()).unwrap(),
+    );
+
+    let fallback = households::filters::all_households(households.clone())
+        .or(static_file_filters::get_static_file())
+        .map(Reply::into_response)
+        .boxed();
+
+    households::service::serve(households, fallback, ([0, 0, 0, 0], 3030).into()).await;
+}
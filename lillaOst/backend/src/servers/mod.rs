@@ -1,3 +1,5 @@
+pub mod api_routes;
 pub mod faked_context_server;
 pub mod faked_ost_api;
+pub mod households_server;
 pub mod production_server;
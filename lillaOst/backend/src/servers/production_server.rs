@@ -1,29 +1,36 @@
 use std::env;
 
 use tokio::sync::mpsc;
-use tokio::task;
 
 use crate::command::CommandToBackend;
+use crate::local_state::{ost_context, share};
+use crate::storage::Storage;
 
 use warp::Filter;
 
-use crate::admin;
-use crate::events;
-use crate::expulsions;
-use crate::feedings;
-use crate::persons;
+use crate::auth::accounts::{share_auth, Auth};
+use crate::notifications::notifier;
 use crate::static_file_filters;
 
+use super::api_routes::api_routes;
+use super::households_server::households_server;
+
+/// One context from `OST_CONTEXT_FILE_PATH`, or one per household when `OST_HOUSEHOLDS_DIR` is set
 #[allow(dead_code)]
 pub async fn production_server() {
-    let in_thread_server = task::LocalSet::new();
+    let storage = Storage::from_env().unwrap();
+    if let Ok(households_dir) = env::var("OST_HOUSEHOLDS_DIR") {
+        return households_server(&households_dir, storage).await;
+    }
+
     let (tx, rx) = mpsc::channel::<CommandToBackend>(32);
 
-    let routes = persons::filters::all_persons(tx.clone())
-        .or(feedings::filters::all_feedings(tx.clone()))
-        .or(expulsions::filters::all_expulsions(tx.clone()))
-        .or(events::filters::all_events(tx.clone()))
-        .or(admin::filters::all_admin(tx.clone()))
+    log::info!("Trying to load: {:?}", &storage);
+    let context = share(storage.construct().unwrap());
+    let auth = share_auth(Auth::from_env().unwrap());
+    let notifier = notifier();
+
+    let routes = api_routes(tx, context.clone(), auth, notifier.clone())
         .or(static_file_filters::get_index())
         .or(static_file_filters::get_static_file())
         .or(static_file_filters::serve_index_by_default_get());
@@ -32,16 +39,7 @@ pub async fn production_server() {
         warp::serve(routes).run(([0, 0, 0, 0], 3030)).await;
     });
 
-    let ost_file_path =
-        env::var("OST_CONTEXT_FILE_PATH").expect("Missing env var: `OST_CONTEXT_FILE_PATH`");
-
-    log::info!("Trying to load: {}", &ost_file_path);
-
-    in_thread_server
-        .run_until(
-            async move { crate::local_state::file_based_ost_context(rx, &ost_file_path).await },
-        )
-        .await;
+    ost_context(rx, context, storage, notifier).await;
 
     warp_server.await.unwrap();
 }
@@ -0,0 +1,98 @@
+use std::env;
+use std::path::Path;
+
+use ost::context::{
+    construct_monolith, construct_monolith_in_memory, construct_monolith_journaled,
+    construct_sqlite, construct_sqlite_from_monolith, Context,
+};
+use ost::error::OstError;
+
+use crate::local_state_fake_in_memory::fill_with_fake_data;
+
+/// Where the server keeps its data, the dispatcher works the same on top of any of them
+#[derive(Clone, Debug, PartialEq)]
+pub enum Storage {
+    Monolith(String),
+    Journal(String),
+    Sqlite {
+        file_path: String,
+        import_file_path: Option<String>,
+    },
+    /// In memory, filled with fake data and refilled on reset
+    Fake,
+}
+
+impl Storage {
+    /// `OST_CONTEXT_BACKEND` picks the storage: `monolith` (default), `journal`, `sqlite` or `fake`.
+    /// All but `fake` read `OST_CONTEXT_FILE_PATH`, a missing sqlite database gets imported
+    /// from `OST_CONTEXT_IMPORT_FILE_PATH` when set.
+    pub fn from_env() -> Result<Storage, OstError> {
+        let backend = env::var("OST_CONTEXT_BACKEND").unwrap_or_else(|_| "monolith".to_string());
+        let file_path = || {
+            env::var("OST_CONTEXT_FILE_PATH").map_err(|_| {
+                OstError::Validation("Missing env var: `OST_CONTEXT_FILE_PATH`".to_string())
+            })
+        };
+        match backend.as_str() {
+            "monolith" => Ok(Storage::Monolith(file_path()?)),
+            "journal" => Ok(Storage::Journal(file_path()?)),
+            "sqlite" => Ok(Storage::Sqlite {
+                file_path: file_path()?,
+                import_file_path: env::var("OST_CONTEXT_IMPORT_FILE_PATH").ok(),
+            }),
+            "fake" => Ok(Storage::Fake),
+            unknown => Err(OstError::Validation(format!(
+                "Unknown `OST_CONTEXT_BACKEND`: {}",
+                unknown
+            ))),
+        }
+    }
+
+    pub fn construct(&self) -> Result<Box<dyn Context>, OstError> {
+        match self {
+            Storage::Monolith(file_path) => construct_monolith(file_path),
+            Storage::Journal(file_path) => construct_monolith_journaled(file_path),
+            Storage::Sqlite {
+                file_path,
+                import_file_path: Some(import_path),
+            } if !Path::new(file_path).exists() => {
+                log::info!("Importing {} into {}", import_path, file_path);
+                construct_sqlite_from_monolith(import_path, file_path)
+            }
+            Storage::Sqlite { file_path, .. } => construct_sqlite(file_path),
+            Storage::Fake => {
+                let mut ost = construct_monolith_in_memory()?;
+                fill_with_fake_data(ost.as_mut())?;
+                Ok(ost)
+            }
+        }
+    }
+
+    /// The same kind of storage with its file moved into `dir`, households keep one each
+    pub fn in_dir(&self, dir: &Path) -> Storage {
+        let relocated = |file_path: &str| {
+            let file_name = Path::new(file_path)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| "ost.json".to_string());
+            dir.join(file_name).to_string_lossy().to_string()
+        };
+        match self {
+            Storage::Monolith(file_path) => Storage::Monolith(relocated(file_path)),
+            Storage::Journal(file_path) => Storage::Journal(relocated(file_path)),
+            Storage::Sqlite { file_path, .. } => Storage::Sqlite {
+                file_path: relocated(file_path),
+                import_file_path: None,
+            },
+            Storage::Fake => Storage::Fake,
+        }
+    }
+
+    /// Runs after `purge_all_data`, so a reset fake server is not left empty
+    pub fn after_reset(&self, ost: &mut dyn Context) -> Result<(), OstError> {
+        match self {
+            Storage::Fake => fill_with_fake_data(ost),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use chrono::prelude::*;
+
+use ost::event::EventType;
+use ost::expulsion::ExpulsionDegree;
+use ost::measurement::Measures;
+use ost::nursing::NursingSession;
+
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NewPerson {
+    pub name: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct RemovePersonOptions {
+    #[serde(default)]
+    pub cascade: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NewFeeding {
+    pub person_id: u32,
+    pub breast_milk: u32,
+    pub formula: u32,
+    pub solids: u32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FeedingChanges {
+    pub time_stamp: DateTime<Utc>,
+    pub breast_milk: u32,
+    pub formula: u32,
+    pub solids: u32,
+    #[serde(default)]
+    pub nursing: Option<NursingSession>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NewExpulsion {
+    pub person_id: u32,
+    pub degree: ExpulsionDegree,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ExpulsionChanges {
+    pub time_stamp: DateTime<Utc>,
+    pub degree: ExpulsionDegree,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NewEvent {
+    pub person_id: u32,
+    pub event: EventType,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EventChanges {
+    pub time_stamp: DateTime<Utc>,
+    pub event: EventType,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NewMeasurement {
+    pub person_id: u32,
+    #[serde(flatten)]
+    pub measures: Measures,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MeasurementChanges {
+    pub time_stamp: DateTime<Utc>,
+    #[serde(flatten)]
+    pub measures: Measures,
+}
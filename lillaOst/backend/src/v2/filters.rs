@@ -0,0 +1,182 @@
+use serde::de::DeserializeOwned;
+use warp::filters::body::BodyDeserializeError;
+use warp::reply::Response;
+use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
+
+use super::bodies::RemovePersonOptions;
+use super::handlers;
+use crate::command::Commands;
+use crate::common_filters::{with_command_sender, with_context};
+use crate::local_state::SharedContext;
+
+use ost::error::OstError;
+
+/// Resource style routes under `/api/v2`, entities travel as json objects
+pub fn all_v2(tx: Commands, context: SharedContext) -> BoxedFilter<(impl Reply,)> {
+    persons_v2(tx.clone(), context.clone())
+        .or(feedings_v2(tx.clone(), context.clone()))
+        .or(expulsions_v2(tx.clone(), context.clone()))
+        .or(events_v2(tx.clone(), context.clone()))
+        .or(measurements_v2(tx, context))
+        .recover(unprocessable_body)
+        .boxed()
+}
+
+pub fn persons_v2(tx: Commands, context: SharedContext) -> BoxedFilter<(impl Reply,)> {
+    let list = warp::path!("api" / "v2" / "persons")
+        .and(warp::get())
+        .and(with_context(context.clone()))
+        .and_then(handlers::list_persons);
+    let create = warp::path!("api" / "v2" / "persons")
+        .and(warp::post())
+        .and(with_command_sender(tx.clone()))
+        .and(json_body())
+        .and_then(handlers::create_person);
+    let get = warp::path!("api" / "v2" / "persons" / u32)
+        .and(warp::get())
+        .and(with_context(context.clone()))
+        .and_then(handlers::get_person);
+    let update = warp::path!("api" / "v2" / "persons" / u32)
+        .and(warp::put())
+        .and(with_command_sender(tx.clone()))
+        .and(with_context(context.clone()))
+        .and(json_body())
+        .and_then(handlers::update_person);
+    let delete = warp::path!("api" / "v2" / "persons" / u32)
+        .and(warp::delete())
+        .and(with_command_sender(tx.clone()))
+        .and(warp::query::<RemovePersonOptions>())
+        .and_then(handlers::delete_person);
+    let merge = warp::path!("api" / "v2" / "persons" / u32 / "merge" / u32)
+        .and(warp::post())
+        .and(with_command_sender(tx))
+        .and(with_context(context))
+        .and_then(handlers::merge_persons);
+    list.or(create)
+        .or(get)
+        .or(update)
+        .or(delete)
+        .or(merge)
+        .boxed()
+}
+
+pub fn feedings_v2(tx: Commands, context: SharedContext) -> BoxedFilter<(impl Reply,)> {
+    let list = warp::path!("api" / "v2" / "feedings")
+        .and(warp::get())
+        .and(with_context(context.clone()))
+        .and_then(handlers::list_feedings);
+    let create = warp::path!("api" / "v2" / "feedings")
+        .and(warp::post())
+        .and(with_command_sender(tx.clone()))
+        .and(json_body())
+        .and_then(handlers::create_feeding);
+    let get = warp::path!("api" / "v2" / "feedings" / u32)
+        .and(warp::get())
+        .and(with_context(context.clone()))
+        .and_then(handlers::get_feeding);
+    let update = warp::path!("api" / "v2" / "feedings" / u32)
+        .and(warp::put())
+        .and(with_command_sender(tx.clone()))
+        .and(with_context(context))
+        .and(json_body())
+        .and_then(handlers::update_feeding);
+    let delete = warp::path!("api" / "v2" / "feedings" / u32)
+        .and(warp::delete())
+        .and(with_command_sender(tx))
+        .and_then(handlers::delete_feeding);
+    list.or(create).or(get).or(update).or(delete).boxed()
+}
+
+pub fn expulsions_v2(tx: Commands, context: SharedContext) -> BoxedFilter<(impl Reply,)> {
+    let list = warp::path!("api" / "v2" / "expulsions")
+        .and(warp::get())
+        .and(with_context(context.clone()))
+        .and_then(handlers::list_expulsions);
+    let create = warp::path!("api" / "v2" / "expulsions")
+        .and(warp::post())
+        .and(with_command_sender(tx.clone()))
+        .and(json_body())
+        .and_then(handlers::create_expulsion);
+    let get = warp::path!("api" / "v2" / "expulsions" / u32)
+        .and(warp::get())
+        .and(with_context(context.clone()))
+        .and_then(handlers::get_expulsion);
+    let update = warp::path!("api" / "v2" / "expulsions" / u32)
+        .and(warp::put())
+        .and(with_command_sender(tx.clone()))
+        .and(with_context(context))
+        .and(json_body())
+        .and_then(handlers::update_expulsion);
+    let delete = warp::path!("api" / "v2" / "expulsions" / u32)
+        .and(warp::delete())
+        .and(with_command_sender(tx))
+        .and_then(handlers::delete_expulsion);
+    list.or(create).or(get).or(update).or(delete).boxed()
+}
+
+pub fn events_v2(tx: Commands, context: SharedContext) -> BoxedFilter<(impl Reply,)> {
+    let list = warp::path!("api" / "v2" / "events")
+        .and(warp::get())
+        .and(with_context(context.clone()))
+        .and_then(handlers::list_events);
+    let create = warp::path!("api" / "v2" / "events")
+        .and(warp::post())
+        .and(with_command_sender(tx.clone()))
+        .and(json_body())
+        .and_then(handlers::create_event);
+    let get = warp::path!("api" / "v2" / "events" / u32)
+        .and(warp::get())
+        .and(with_context(context.clone()))
+        .and_then(handlers::get_event);
+    let update = warp::path!("api" / "v2" / "events" / u32)
+        .and(warp::put())
+        .and(with_command_sender(tx.clone()))
+        .and(with_context(context))
+        .and(json_body())
+        .and_then(handlers::update_event);
+    let delete = warp::path!("api" / "v2" / "events" / u32)
+        .and(warp::delete())
+        .and(with_command_sender(tx))
+        .and_then(handlers::delete_event);
+    list.or(create).or(get).or(update).or(delete).boxed()
+}
+
+pub fn measurements_v2(tx: Commands, context: SharedContext) -> BoxedFilter<(impl Reply,)> {
+    let list = warp::path!("api" / "v2" / "measurements")
+        .and(warp::get())
+        .and(with_context(context.clone()))
+        .and_then(handlers::list_measurements);
+    let create = warp::path!("api" / "v2" / "measurements")
+        .and(warp::post())
+        .and(with_command_sender(tx.clone()))
+        .and(json_body())
+        .and_then(handlers::create_measurement);
+    let get = warp::path!("api" / "v2" / "measurements" / u32)
+        .and(warp::get())
+        .and(with_context(context.clone()))
+        .and_then(handlers::get_measurement);
+    let update = warp::path!("api" / "v2" / "measurements" / u32)
+        .and(warp::put())
+        .and(with_command_sender(tx.clone()))
+        .and(with_context(context))
+        .and(json_body())
+        .and_then(handlers::update_measurement);
+    let delete = warp::path!("api" / "v2" / "measurements" / u32)
+        .and(warp::delete())
+        .and(with_command_sender(tx))
+        .and_then(handlers::delete_measurement);
+    list.or(create).or(get).or(update).or(delete).boxed()
+}
+
+fn json_body<T: DeserializeOwned + Send>(
+) -> impl Filter<Extract = (T,), Error = warp::Rejection> + Clone {
+    warp::body::content_length_limit(1024 * 16).and(warp::body::json())
+}
+
+/// A body that doesn't deserialize is a 422 in v2, other rejections pass through
+async fn unprocessable_body(rejection: Rejection) -> Result<Response, Rejection> {
+    match rejection.find::<BodyDeserializeError>() {
+        Some(err) => Ok(handlers::error_reply(OstError::Validation(err.to_string()))),
+        None => Err(rejection),
+    }
+}
@@ -0,0 +1,484 @@
+use std::convert::Infallible;
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use tokio::sync::oneshot;
+use warp::http::StatusCode;
+use warp::reply::Response;
+use warp::Reply;
+
+use super::bodies::{
+    EventChanges, ExpulsionChanges, FeedingChanges, MeasurementChanges, NewEvent, NewExpulsion,
+    NewFeeding, NewMeasurement, NewPerson, RemovePersonOptions,
+};
+use crate::command::{CommandSender, CommandToBackend};
+use crate::command_args::{
+    ArgAModifyEvent, ArgAModifyFeeding, ArgAModifyPerson, ArgAddEvent, ArgAddExpulsion,
+    ArgAddFeeding, ArgAddMeasurement, ArgAddPerson, ArgEntityKey, ArgMergePersons,
+    ArgModifyExpulsion, ArgModifyMeasurement, ArgRemovePerson,
+};
+use crate::common_handlers::error_status_code;
+use crate::local_state::SharedContext;
+
+use ost::context::Context;
+use ost::error::OstError;
+use ost::event_key::{EventType as KeyType, OstEventKey};
+use ost::person::deserialize as person_deserialize;
+use ost::person_key::OstPersonKey;
+
+type V2Reply = Result<Response, Infallible>;
+
+// Persons
+pub async fn list_persons(context: SharedContext) -> V2Reply {
+    let result = read(&context, |ost| {
+        entities(ost.persons().iter().map(|p| p.serialize()))
+    })
+    .await;
+    Ok(reply(result, StatusCode::OK))
+}
+
+pub async fn get_person(id: u32, context: SharedContext) -> V2Reply {
+    Ok(reply(
+        read(&context, |ost| find_person(ost, id)).await,
+        StatusCode::OK,
+    ))
+}
+
+pub async fn create_person(tx: CommandSender, body: NewPerson) -> V2Reply {
+    let result = execute::<String>(&tx, |resp| CommandToBackend::AddPerson {
+        args: ArgAddPerson { name: body.name },
+        resp,
+    })
+    .await
+    .and_then(entity);
+    Ok(reply(result, StatusCode::CREATED))
+}
+
+/// The body is a whole person, its id comes from the path
+pub async fn update_person(
+    id: u32,
+    tx: CommandSender,
+    context: SharedContext,
+    mut body: Value,
+) -> V2Reply {
+    let serialized_person = match body.as_object_mut() {
+        Some(fields) => {
+            fields.insert("id".to_string(), Value::from(id));
+            person_deserialize(&body.to_string())
+                .map(|person| person.serialize())
+                .map_err(OstError::Validation)
+        }
+        None => Err(OstError::Validation(
+            "A person is a json object".to_string(),
+        )),
+    };
+    let modified = match serialized_person {
+        Ok(serialized_person) => {
+            execute::<()>(&tx, |resp| CommandToBackend::ModifyPerson {
+                args: ArgAModifyPerson {
+                    person_key: OstPersonKey { id },
+                    serialized_person,
+                },
+                resp,
+            })
+            .await
+        }
+        Err(err) => Err(err),
+    };
+    let result = match modified {
+        Ok(()) => read(&context, |ost| find_person(ost, id)).await,
+        Err(err) => Err(err),
+    };
+    Ok(reply(result, StatusCode::OK))
+}
+
+pub async fn delete_person(id: u32, tx: CommandSender, options: RemovePersonOptions) -> V2Reply {
+    let result = execute::<()>(&tx, |resp| CommandToBackend::RemovePerson {
+        args: ArgRemovePerson {
+            person_key: OstPersonKey { id },
+            cascade: options.cascade,
+        },
+        resp,
+    })
+    .await;
+    Ok(reply_empty(result))
+}
+
+/// Answers with the person that is left
+pub async fn merge_persons(
+    from: u32,
+    into: u32,
+    tx: CommandSender,
+    context: SharedContext,
+) -> V2Reply {
+    let modified = execute::<()>(&tx, |resp| CommandToBackend::MergePersons {
+        args: ArgMergePersons {
+            from: OstPersonKey { id: from },
+            into: OstPersonKey { id: into },
+        },
+        resp,
+    })
+    .await;
+    let result = match modified {
+        Ok(()) => read(&context, |ost| find_person(ost, into)).await,
+        Err(err) => Err(err),
+    };
+    Ok(reply(result, StatusCode::OK))
+}
+
+// Feedings
+pub async fn list_feedings(context: SharedContext) -> V2Reply {
+    let result = read(&context, |ost| {
+        entities(ost.feedings().iter().map(|f| f.serialize()))
+    })
+    .await;
+    Ok(reply(result, StatusCode::OK))
+}
+
+pub async fn get_feeding(id: u32, context: SharedContext) -> V2Reply {
+    Ok(reply(
+        read(&context, |ost| find_feeding(ost, id)).await,
+        StatusCode::OK,
+    ))
+}
+
+pub async fn create_feeding(tx: CommandSender, body: NewFeeding) -> V2Reply {
+    let result = execute::<String>(&tx, |resp| CommandToBackend::AddFeeding {
+        args: ArgAddFeeding {
+            person_key: OstPersonKey { id: body.person_id },
+            breast_milk: body.breast_milk,
+            formula: body.formula,
+            solids: body.solids,
+            time_stamp: None,
+            replay_id: None,
+        },
+        resp,
+    })
+    .await
+    .and_then(entity);
+    Ok(reply(result, StatusCode::CREATED))
+}
+
+pub async fn update_feeding(
+    id: u32,
+    tx: CommandSender,
+    context: SharedContext,
+    body: FeedingChanges,
+) -> V2Reply {
+    let modified = execute::<()>(&tx, |resp| CommandToBackend::ModifyFeeding {
+        args: ArgAModifyFeeding {
+            event_key: event_key(KeyType::Feed, id),
+            time_stamp: body.time_stamp,
+            breast_milk: body.breast_milk,
+            formula: body.formula,
+            solids: body.solids,
+            nursing: body.nursing,
+        },
+        resp,
+    })
+    .await;
+    let result = match modified {
+        Ok(()) => read(&context, |ost| find_feeding(ost, id)).await,
+        Err(err) => Err(err),
+    };
+    Ok(reply(result, StatusCode::OK))
+}
+
+pub async fn delete_feeding(id: u32, tx: CommandSender) -> V2Reply {
+    let result = execute::<()>(&tx, |resp| CommandToBackend::RemoveFeeding {
+        args: ArgEntityKey {
+            event_key: event_key(KeyType::Feed, id),
+        },
+        resp,
+    })
+    .await;
+    Ok(reply_empty(result))
+}
+
+// Expulsions
+pub async fn list_expulsions(context: SharedContext) -> V2Reply {
+    let result = read(&context, |ost| {
+        entities(ost.expulsions().iter().map(|e| e.serialize()))
+    })
+    .await;
+    Ok(reply(result, StatusCode::OK))
+}
+
+pub async fn get_expulsion(id: u32, context: SharedContext) -> V2Reply {
+    Ok(reply(
+        read(&context, |ost| find_expulsion(ost, id)).await,
+        StatusCode::OK,
+    ))
+}
+
+pub async fn create_expulsion(tx: CommandSender, body: NewExpulsion) -> V2Reply {
+    let result = execute::<String>(&tx, |resp| CommandToBackend::AddExpulsion {
+        args: ArgAddExpulsion {
+            person_key: OstPersonKey { id: body.person_id },
+            expulsion_degree: body.degree,
+            time_stamp: None,
+            replay_id: None,
+        },
+        resp,
+    })
+    .await
+    .and_then(entity);
+    Ok(reply(result, StatusCode::CREATED))
+}
+
+pub async fn update_expulsion(
+    id: u32,
+    tx: CommandSender,
+    context: SharedContext,
+    body: ExpulsionChanges,
+) -> V2Reply {
+    let modified = execute::<()>(&tx, |resp| CommandToBackend::ModifyExpulsion {
+        args: ArgModifyExpulsion {
+            event_key: event_key(KeyType::Expulsion, id),
+            time_stamp: body.time_stamp,
+            expulsion_degree: body.degree,
+        },
+        resp,
+    })
+    .await;
+    let result = match modified {
+        Ok(()) => read(&context, |ost| find_expulsion(ost, id)).await,
+        Err(err) => Err(err),
+    };
+    Ok(reply(result, StatusCode::OK))
+}
+
+pub async fn delete_expulsion(id: u32, tx: CommandSender) -> V2Reply {
+    let result = execute::<()>(&tx, |resp| CommandToBackend::RemoveExpulsion {
+        args: ArgEntityKey {
+            event_key: event_key(KeyType::Expulsion, id),
+        },
+        resp,
+    })
+    .await;
+    Ok(reply_empty(result))
+}
+
+// Events
+pub async fn list_events(context: SharedContext) -> V2Reply {
+    let result = read(&context, |ost| {
+        entities(ost.events().iter().map(|e| e.serialize()))
+    })
+    .await;
+    Ok(reply(result, StatusCode::OK))
+}
+
+pub async fn get_event(id: u32, context: SharedContext) -> V2Reply {
+    Ok(reply(
+        read(&context, |ost| find_event(ost, id)).await,
+        StatusCode::OK,
+    ))
+}
+
+pub async fn create_event(tx: CommandSender, body: NewEvent) -> V2Reply {
+    let result = execute::<String>(&tx, |resp| CommandToBackend::AddEvent {
+        args: ArgAddEvent {
+            person_key: OstPersonKey { id: body.person_id },
+            new_event: body.event,
+            time_stamp: None,
+            replay_id: None,
+        },
+        resp,
+    })
+    .await
+    .and_then(entity);
+    Ok(reply(result, StatusCode::CREATED))
+}
+
+pub async fn update_event(
+    id: u32,
+    tx: CommandSender,
+    context: SharedContext,
+    body: EventChanges,
+) -> V2Reply {
+    let modified = execute::<()>(&tx, |resp| CommandToBackend::ModifyEvent {
+        args: ArgAModifyEvent {
+            event_key: event_key(KeyType::Event, id),
+            time_stamp: body.time_stamp,
+            event_payload: body.event,
+        },
+        resp,
+    })
+    .await;
+    let result = match modified {
+        Ok(()) => read(&context, |ost| find_event(ost, id)).await,
+        Err(err) => Err(err),
+    };
+    Ok(reply(result, StatusCode::OK))
+}
+
+pub async fn delete_event(id: u32, tx: CommandSender) -> V2Reply {
+    let result = execute::<()>(&tx, |resp| CommandToBackend::RemoveEvent {
+        args: ArgEntityKey {
+            event_key: event_key(KeyType::Event, id),
+        },
+        resp,
+    })
+    .await;
+    Ok(reply_empty(result))
+}
+
+// Measurements
+pub async fn list_measurements(context: SharedContext) -> V2Reply {
+    let result = read(&context, |ost| {
+        entities(ost.measurements().iter().map(|m| m.serialize()))
+    })
+    .await;
+    Ok(reply(result, StatusCode::OK))
+}
+
+pub async fn get_measurement(id: u32, context: SharedContext) -> V2Reply {
+    Ok(reply(
+        read(&context, |ost| find_measurement(ost, id)).await,
+        StatusCode::OK,
+    ))
+}
+
+pub async fn create_measurement(tx: CommandSender, body: NewMeasurement) -> V2Reply {
+    let result = execute::<String>(&tx, |resp| CommandToBackend::AddMeasurement {
+        args: ArgAddMeasurement {
+            person_key: OstPersonKey { id: body.person_id },
+            measures: body.measures,
+            time_stamp: None,
+            replay_id: None,
+        },
+        resp,
+    })
+    .await
+    .and_then(entity);
+    Ok(reply(result, StatusCode::CREATED))
+}
+
+pub async fn update_measurement(
+    id: u32,
+    tx: CommandSender,
+    context: SharedContext,
+    body: MeasurementChanges,
+) -> V2Reply {
+    let modified = execute::<()>(&tx, |resp| CommandToBackend::ModifyMeasurement {
+        args: ArgModifyMeasurement {
+            event_key: event_key(KeyType::Measurement, id),
+            time_stamp: body.time_stamp,
+            measures: body.measures,
+        },
+        resp,
+    })
+    .await;
+    let result = match modified {
+        Ok(()) => read(&context, |ost| find_measurement(ost, id)).await,
+        Err(err) => Err(err),
+    };
+    Ok(reply(result, StatusCode::OK))
+}
+
+pub async fn delete_measurement(id: u32, tx: CommandSender) -> V2Reply {
+    let result = execute::<()>(&tx, |resp| CommandToBackend::RemoveMeasurement {
+        args: ArgEntityKey {
+            event_key: event_key(KeyType::Measurement, id),
+        },
+        resp,
+    })
+    .await;
+    Ok(reply_empty(result))
+}
+
+/// Validation failures are a 422 in v2, the rest maps as in v1
+pub fn v2_status_code(err: &OstError) -> StatusCode {
+    match err {
+        OstError::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        other => error_status_code(other),
+    }
+}
+
+pub fn error_reply(err: OstError) -> Response {
+    let status = v2_status_code(&err);
+    warp::reply::with_status(warp::reply::json(&err), status).into_response()
+}
+
+fn reply(result: Result<Value, OstError>, success: StatusCode) -> Response {
+    match result {
+        Ok(value) => warp::reply::with_status(warp::reply::json(&value), success).into_response(),
+        Err(err) => error_reply(err),
+    }
+}
+
+fn reply_empty(result: Result<(), OstError>) -> Response {
+    match result {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(err) => error_reply(err),
+    }
+}
+
+/// Sends `cmd` to the state task and unpacks the `Result` it answers with
+async fn execute<T: DeserializeOwned>(
+    tx: &CommandSender,
+    cmd: impl FnOnce(oneshot::Sender<String>) -> CommandToBackend,
+) -> Result<T, OstError> {
+    let (resp_tx, resp_rx) = oneshot::channel();
+    tx.send(cmd(resp_tx)).await?;
+    let payload = resp_rx.await.unwrap();
+    serde_json::from_str::<Result<T, OstError>>(&payload)?
+}
+
+async fn read(
+    context: &SharedContext,
+    reader: impl FnOnce(&dyn Context) -> Result<Value, OstError>,
+) -> Result<Value, OstError> {
+    reader(context.read().await.as_ref())
+}
+
+/// Entities serialize to a json string, v2 sends them as objects
+fn entity(serialized: String) -> Result<Value, OstError> {
+    Ok(serde_json::from_str(&serialized)?)
+}
+
+fn entities(serialized: impl Iterator<Item = String>) -> Result<Value, OstError> {
+    serialized
+        .map(entity)
+        .collect::<Result<Vec<Value>, OstError>>()
+        .map(Value::from)
+}
+
+fn event_key(t: KeyType, id: u32) -> OstEventKey {
+    OstEventKey { t, id }
+}
+
+fn find_person(ost: &dyn Context, id: u32) -> Result<Value, OstError> {
+    match ost.get_person_by_key(OstPersonKey { id }) {
+        Some(person) => entity(person.serialize()),
+        None => Err(OstError::NotFound("Person not found".to_string())),
+    }
+}
+
+fn find_feeding(ost: &dyn Context, id: u32) -> Result<Value, OstError> {
+    match ost.get_feeding_by_key(&event_key(KeyType::Feed, id)) {
+        Some(feeding) => entity(feeding.serialize()),
+        None => Err(OstError::NotFound("Feeding not found".to_string())),
+    }
+}
+
+fn find_expulsion(ost: &dyn Context, id: u32) -> Result<Value, OstError> {
+    match ost.get_expulsion_by_key(&event_key(KeyType::Expulsion, id)) {
+        Some(expulsion) => entity(expulsion.serialize()),
+        None => Err(OstError::NotFound("Expulsion not found".to_string())),
+    }
+}
+
+fn find_event(ost: &dyn Context, id: u32) -> Result<Value, OstError> {
+    match ost.get_event_by_key(&event_key(KeyType::Event, id)) {
+        Some(event) => entity(event.serialize()),
+        None => Err(OstError::NotFound("Event not found".to_string())),
+    }
+}
+
+fn find_measurement(ost: &dyn Context, id: u32) -> Result<Value, OstError> {
+    match ost.get_measurement_by_key(&event_key(KeyType::Measurement, id)) {
+        Some(measurement) => entity(measurement.serialize()),
+        None => Err(OstError::NotFound("Measurement not found".to_string())),
+    }
+}
@@ -0,0 +1,3 @@
+pub mod bodies;
+pub mod filters;
+pub mod handlers;
@@ -6,14 +6,22 @@ use warp::test::request;
 use backend::admin;
 use backend::command;
 use backend::command_args::ArgAddPerson;
-use backend::local_state_fake_in_memory::faked_state_ost_context;
+use backend::local_state::{ost_context, share};
+use backend::local_state_fake_in_memory::faked_context;
+use backend::notifications::notifier;
 use backend::persons;
+use backend::storage::Storage;
+use ost::context::construct_monolith_from_file;
+use ost::error::OstError;
+use ost::integrity::IntegrityIssue;
 use ost::person::{deserialize as person_deserialize, Person};
 
 #[tokio::test]
 async fn can_reset_remote_context() {
     let in_thread_server = task::LocalSet::new();
     let (tx, rx) = mpsc::channel::<command::CommandToBackend>(32);
+    let context = faked_context();
+    let context_get = context.clone();
 
     let tx_add = tx.clone();
 
@@ -22,7 +30,8 @@ async fn can_reset_remote_context() {
     };
 
     let request_add_person = tokio::spawn(async move {
-        let f_add_person = persons::filters::add_person(tx_add.clone()).clone();
+        let f_add_person =
+            persons::filters::add_person(command::Commands::unrestricted(tx_add.clone())).clone();
         let response_add_person = request()
             .method("POST")
             .path("/api/persons")
@@ -33,10 +42,11 @@ async fn can_reset_remote_context() {
         assert_eq!(response_add_person.status(), StatusCode::OK);
     });
 
-    let tx_reset = tx.clone();
+    let tx_reset = tx;
 
     let request_context_reset = tokio::spawn(async move {
-        let f_context_reset = admin::filters::reset(tx_reset.clone()).clone();
+        let f_context_reset =
+            admin::filters::reset(command::Commands::unrestricted(tx_reset.clone())).clone();
         let response_context_reset = request()
             .method("POST")
             .path("/api/admin/reset")
@@ -47,7 +57,10 @@ async fn can_reset_remote_context() {
     });
 
     let request_get_persons = tokio::spawn(async move {
-        let f_ost_get_persons = persons::filters::get_persons(tx.clone()).clone();
+        request_add_person.await.unwrap();
+        request_context_reset.await.unwrap();
+
+        let f_ost_get_persons = persons::filters::get_persons(context_get).clone();
         let response = request()
             .method("GET")
             .path("/api/persons")
@@ -69,10 +82,72 @@ async fn can_reset_remote_context() {
     });
 
     in_thread_server
-        .run_until(async move { faked_state_ost_context(rx).await })
+        .run_until(async move { ost_context(rx, context, Storage::Fake, notifier()).await })
         .await;
 
-    request_add_person.await.unwrap();
-    request_context_reset.await.unwrap();
     request_get_persons.await.unwrap();
 }
+
+#[tokio::test]
+async fn can_check_and_repair_integrity() {
+    let in_thread_server = task::LocalSet::new();
+    let (tx, rx) = mpsc::channel::<command::CommandToBackend>(32);
+
+    let mono_file = std::env::temp_dir().join("backend_admin_integrity.json");
+    let mono_file = mono_file.to_str().unwrap().to_string();
+    let _ignore_fail = std::fs::remove_file(&mono_file);
+    let context = share(
+        construct_monolith_from_file(
+            "../ost/test_data/monolith_broken_integrity.json",
+            &mono_file,
+        )
+        .unwrap(),
+    );
+    let context_check = context.clone();
+    let mono_file_state = mono_file.clone();
+
+    let requests = tokio::spawn(async move {
+        let f_integrity = admin::filters::integrity(context_check).clone();
+        let f_repair_integrity =
+            admin::filters::repair_integrity(command::Commands::unrestricted(tx.clone())).clone();
+
+        let response = request()
+            .method("GET")
+            .path("/api/admin/integrity")
+            .reply(&f_integrity)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let issues: Result<Vec<IntegrityIssue>, OstError> =
+            serde_json::from_slice(response.body()).unwrap();
+        assert!(!issues.unwrap().is_empty());
+
+        let response = request()
+            .method("POST")
+            .path("/api/admin/integrity/repair")
+            .reply(&f_repair_integrity)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let repaired: Result<Vec<IntegrityIssue>, OstError> =
+            serde_json::from_slice(response.body()).unwrap();
+        assert!(!repaired.unwrap().is_empty());
+
+        let response = request()
+            .method("GET")
+            .path("/api/admin/integrity")
+            .reply(&f_integrity)
+            .await;
+        let issues: Result<Vec<IntegrityIssue>, OstError> =
+            serde_json::from_slice(response.body()).unwrap();
+        assert!(issues.unwrap().is_empty());
+    });
+
+    in_thread_server
+        .run_until(async move {
+            ost_context(rx, context, Storage::Monolith(mono_file_state), notifier()).await
+        })
+        .await;
+
+    requests.await.unwrap();
+    let _ignore_fail = std::fs::remove_file(&mono_file);
+    let _ignore_fail = std::fs::remove_file(format!("{}.bak", mono_file));
+}
@@ -0,0 +1,434 @@
+use chrono::Utc;
+use serde_json::{json, Value};
+use tokio::sync::{mpsc, oneshot};
+use warp::http::StatusCode;
+use warp::test::request;
+use warp::Filter;
+
+use backend::auth;
+use backend::auth::accounts::{share_auth, AccountInfo, Auth};
+use backend::command::{self, CommandToBackend, Commands};
+use backend::command_args::*;
+use backend::local_state_fake_in_memory::faked_context;
+use backend::v2;
+use ost::error::OstError;
+use ost::event::EventType;
+use ost::event_key::{EventType as KeyType, OstEventKey};
+use ost::expulsion::ExpulsionDegree;
+use ost::measurement::Measures;
+use ost::nursing::NursingSession;
+use ost::person_key::OstPersonKey;
+use ost::role::Role;
+
+#[test]
+fn accounts_login_and_logout() {
+    let mut auth = Auth::in_memory();
+    assert!(matches!(
+        auth.add_account("caregiver", "short", Role::Caregiver),
+        Err(OstError::Validation(_))
+    ));
+    auth.add_account("caregiver", "long enough", Role::Caregiver)
+        .unwrap();
+    assert!(matches!(
+        auth.add_account(" caregiver ", "long enough", Role::Caregiver),
+        Err(OstError::DuplicateName(_))
+    ));
+    assert!(matches!(
+        auth.login("caregiver", "wrong password"),
+        Err(OstError::Unauthorized(_))
+    ));
+
+    let token = auth.login("caregiver", "long enough").unwrap().token;
+    assert_eq!(auth.session(&token).unwrap().account, "caregiver");
+    auth.logout(&token);
+    assert!(auth.session(&token).is_none());
+}
+
+#[test]
+fn accounts_persist_hashed() {
+    let path = std::env::temp_dir().join(format!("ost_accounts_{}.json", std::process::id()));
+    let path = path.to_str().unwrap();
+    Auth::from_file(path)
+        .unwrap()
+        .add_account("caregiver", "long enough", Role::Caregiver)
+        .unwrap();
+    assert!(!std::fs::read_to_string(path)
+        .unwrap()
+        .contains("long enough"));
+
+    let mut reloaded = Auth::from_file(path).unwrap();
+    assert_eq!(
+        reloaded.accounts(),
+        vec![AccountInfo {
+            name: "caregiver".to_string(),
+            role: Role::Caregiver
+        }]
+    );
+    assert!(reloaded.login("caregiver", "long enough").is_ok());
+    std::fs::remove_file(path).unwrap();
+}
+
+#[tokio::test]
+async fn api_needs_a_session() {
+    let (tx, _rx) = mpsc::channel::<command::CommandToBackend>(32);
+    let mut accounts = Auth::in_memory();
+    accounts
+        .add_account("caregiver", "long enough", Role::Caregiver)
+        .unwrap();
+    let auth = share_auth(accounts);
+    let api = auth::filters::require_session(auth.clone())
+        .and(
+            v2::filters::all_v2(Commands::new(tx, auth.clone()), faked_context())
+                .or(auth::filters::all_auth(auth)),
+        )
+        .recover(auth::filters::unauthorized);
+
+    let response = request()
+        .method("GET")
+        .path("/api/v2/persons")
+        .reply(&api)
+        .await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let err: OstError = serde_json::from_slice(response.body()).unwrap();
+    assert!(matches!(err, OstError::Unauthorized(_)));
+
+    let response = request()
+        .method("POST")
+        .path("/api/auth/login")
+        .json(&json!({"name": "caregiver", "password": "wrong password"}))
+        .reply(&api)
+        .await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = request()
+        .method("POST")
+        .path("/api/auth/login")
+        .json(&json!({"name": "caregiver", "password": "long enough"}))
+        .reply(&api)
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let session: Value = serde_json::from_slice(response.body()).unwrap();
+    let bearer = format!("Bearer {}", session["token"].as_str().unwrap());
+
+    let response = request()
+        .method("GET")
+        .path("/api/v2/persons")
+        .header("authorization", &bearer)
+        .reply(&api)
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = request()
+        .method("POST")
+        .path("/api/auth/logout")
+        .header("authorization", &bearer)
+        .reply(&api)
+        .await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let response = request()
+        .method("GET")
+        .path("/api/v2/persons")
+        .header("authorization", &bearer)
+        .reply(&api)
+        .await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[test]
+fn roles_per_command() {
+    let resp = || oneshot::channel().0;
+    let person_key = OstPersonKey { id: 1 };
+    let key = |t| OstEventKey { t, id: 1 };
+    let entity_key = |t| ArgEntityKey { event_key: key(t) };
+    let fake_count = || ArgFakeCount { count: 1 };
+    let now = Utc::now();
+
+    let commands = vec![
+        (
+            CommandToBackend::AddPerson {
+                args: ArgAddPerson {
+                    name: "Early bird".to_string(),
+                },
+                resp: resp(),
+            },
+            Role::Owner,
+        ),
+        (
+            CommandToBackend::AddFakePerson {
+                args: fake_count(),
+                resp: resp(),
+            },
+            Role::Owner,
+        ),
+        (
+            CommandToBackend::ModifyPerson {
+                args: ArgAModifyPerson {
+                    person_key,
+                    serialized_person: String::new(),
+                },
+                resp: resp(),
+            },
+            Role::Owner,
+        ),
+        (
+            CommandToBackend::RemovePerson {
+                args: ArgRemovePerson {
+                    person_key,
+                    cascade: true,
+                },
+                resp: resp(),
+            },
+            Role::Owner,
+        ),
+        (
+            CommandToBackend::MergePersons {
+                args: ArgMergePersons {
+                    from: OstPersonKey { id: 2 },
+                    into: person_key,
+                },
+                resp: resp(),
+            },
+            Role::Owner,
+        ),
+        (
+            CommandToBackend::AddFakeFeedings {
+                args: fake_count(),
+                resp: resp(),
+            },
+            Role::Owner,
+        ),
+        (
+            CommandToBackend::AddFeeding {
+                args: ArgAddFeeding {
+                    person_key,
+                    breast_milk: 0,
+                    formula: 90,
+                    solids: 0,
+                    time_stamp: None,
+                    replay_id: None,
+                },
+                resp: resp(),
+            },
+            Role::Caregiver,
+        ),
+        (
+            CommandToBackend::AddNursing {
+                args: ArgAddNursing {
+                    person_key,
+                    nursing: NursingSession::default(),
+                    replay_id: None,
+                },
+                resp: resp(),
+            },
+            Role::Caregiver,
+        ),
+        (
+            CommandToBackend::ModifyFeeding {
+                args: ArgAModifyFeeding {
+                    event_key: key(KeyType::Feed),
+                    time_stamp: now,
+                    breast_milk: 0,
+                    formula: 120,
+                    solids: 0,
+                    nursing: None,
+                },
+                resp: resp(),
+            },
+            Role::Caregiver,
+        ),
+        (
+            CommandToBackend::RemoveFeeding {
+                args: entity_key(KeyType::Feed),
+                resp: resp(),
+            },
+            Role::Caregiver,
+        ),
+        (
+            CommandToBackend::AddExpulsion {
+                args: ArgAddExpulsion {
+                    person_key,
+                    expulsion_degree: ExpulsionDegree::Pee,
+                    time_stamp: None,
+                    replay_id: None,
+                },
+                resp: resp(),
+            },
+            Role::Caregiver,
+        ),
+        (
+            CommandToBackend::ModifyExpulsion {
+                args: ArgModifyExpulsion {
+                    event_key: key(KeyType::Expulsion),
+                    time_stamp: now,
+                    expulsion_degree: ExpulsionDegree::Poopies,
+                },
+                resp: resp(),
+            },
+            Role::Caregiver,
+        ),
+        (
+            CommandToBackend::AddFakeExpulsions {
+                args: fake_count(),
+                resp: resp(),
+            },
+            Role::Owner,
+        ),
+        (
+            CommandToBackend::RemoveExpulsion {
+                args: entity_key(KeyType::Expulsion),
+                resp: resp(),
+            },
+            Role::Caregiver,
+        ),
+        (
+            CommandToBackend::AddEvent {
+                args: ArgAddEvent {
+                    person_key,
+                    new_event: EventType::Note("Hiccups".to_string()),
+                    time_stamp: None,
+                    replay_id: None,
+                },
+                resp: resp(),
+            },
+            Role::Caregiver,
+        ),
+        (
+            CommandToBackend::AddFakeEvents {
+                args: fake_count(),
+                resp: resp(),
+            },
+            Role::Owner,
+        ),
+        (
+            CommandToBackend::RemoveEvent {
+                args: entity_key(KeyType::Event),
+                resp: resp(),
+            },
+            Role::Caregiver,
+        ),
+        (
+            CommandToBackend::ModifyEvent {
+                args: ArgAModifyEvent {
+                    event_key: key(KeyType::Event),
+                    time_stamp: now,
+                    event_payload: EventType::Note("Hiccups, twice".to_string()),
+                },
+                resp: resp(),
+            },
+            Role::Caregiver,
+        ),
+        (
+            CommandToBackend::AddMeasurement {
+                args: ArgAddMeasurement {
+                    person_key,
+                    measures: Measures::default(),
+                    time_stamp: None,
+                    replay_id: None,
+                },
+                resp: resp(),
+            },
+            Role::Caregiver,
+        ),
+        (
+            CommandToBackend::ModifyMeasurement {
+                args: ArgModifyMeasurement {
+                    event_key: key(KeyType::Measurement),
+                    time_stamp: now,
+                    measures: Measures::default(),
+                },
+                resp: resp(),
+            },
+            Role::Caregiver,
+        ),
+        (
+            CommandToBackend::AddFakeMeasurements {
+                args: fake_count(),
+                resp: resp(),
+            },
+            Role::Owner,
+        ),
+        (
+            CommandToBackend::RemoveMeasurement {
+                args: entity_key(KeyType::Measurement),
+                resp: resp(),
+            },
+            Role::Caregiver,
+        ),
+        (CommandToBackend::AdminReset { resp: resp() }, Role::Owner),
+        (
+            CommandToBackend::AdminPurgeEvents { resp: resp() },
+            Role::Owner,
+        ),
+        (
+            CommandToBackend::AdminRepairIntegrity { resp: resp() },
+            Role::Owner,
+        ),
+    ];
+    for (command, role) in commands {
+        assert_eq!(command.required_role(), role, "{:?}", command);
+    }
+}
+
+#[tokio::test]
+async fn roles_are_enforced() {
+    let (tx, _rx) = mpsc::channel::<command::CommandToBackend>(32);
+    let mut accounts = Auth::in_memory();
+    accounts
+        .add_account("grandparent", "long enough", Role::Viewer)
+        .unwrap();
+    accounts
+        .add_account("babysitter", "long enough", Role::Caregiver)
+        .unwrap();
+    let viewer = format!(
+        "Bearer {}",
+        accounts.login("grandparent", "long enough").unwrap().token
+    );
+    let caregiver = format!(
+        "Bearer {}",
+        accounts.login("babysitter", "long enough").unwrap().token
+    );
+    let auth = share_auth(accounts);
+    let api = auth::filters::require_session(auth.clone())
+        .and(
+            v2::filters::all_v2(Commands::new(tx, auth.clone()), faked_context())
+                .or(auth::filters::all_auth(auth)),
+        )
+        .recover(auth::filters::unauthorized);
+
+    let response = request()
+        .method("GET")
+        .path("/api/v2/feedings")
+        .header("authorization", &viewer)
+        .reply(&api)
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = request()
+        .method("POST")
+        .path("/api/v2/feedings")
+        .header("authorization", &viewer)
+        .json(&json!({"person_id": 1, "breast_milk": 120, "formula": 0, "solids": 0}))
+        .reply(&api)
+        .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let err: OstError = serde_json::from_slice(response.body()).unwrap();
+    assert!(matches!(err, OstError::Forbidden(_)));
+
+    let response = request()
+        .method("PUT")
+        .path("/api/v2/persons/1")
+        .header("authorization", &caregiver)
+        .json(&json!({"id": 1, "name": "Renamed", "is_active": true}))
+        .reply(&api)
+        .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = request()
+        .method("GET")
+        .path("/api/auth/accounts")
+        .header("authorization", &caregiver)
+        .reply(&api)
+        .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
@@ -0,0 +1,147 @@
+use chrono::{prelude::*, Duration};
+use tokio::sync::oneshot;
+use warp::http::StatusCode;
+use warp::Reply;
+
+use backend::command::CommandToBackend;
+use backend::command_args::ArgAddFeeding;
+use backend::common_handlers::read_from_context;
+use backend::local_state::dispatch;
+use backend::local_state_fake_in_memory::faked_context;
+use backend::storage::Storage;
+use ost::context::Context;
+use ost::error::OstError;
+use ost::event_key::{EventType, OstEventKey};
+use ost::offline::ReplayId;
+
+fn reset(storage: &Storage) -> usize {
+    let mut ost = storage.construct().unwrap();
+    let (resp, mut resp_rx) = oneshot::channel();
+    dispatch(ost.as_mut(), storage, CommandToBackend::AdminReset { resp });
+    let reply: Result<(), OstError> = serde_json::from_str(&resp_rx.try_recv().unwrap()).unwrap();
+    assert_eq!(reply, Ok(()));
+    ost.persons().len()
+}
+
+#[test]
+fn reset_refills_only_the_fake_storage() {
+    assert_eq!(reset(&Storage::Fake), 10);
+
+    let mono_file = std::env::temp_dir().join("backend_dispatch_reset.json");
+    let mono_file = mono_file.to_str().unwrap().to_string();
+    let _ignore_fail = std::fs::remove_file(&mono_file);
+    assert_eq!(reset(&Storage::Monolith(mono_file.clone())), 0);
+    let _ignore_fail = std::fs::remove_file(&mono_file);
+    let _ignore_fail = std::fs::remove_file(format!("{}.bak", mono_file));
+}
+
+#[test]
+fn replayed_feedings_keep_their_time() {
+    let storage = Storage::Fake;
+    let mut ost = storage.construct().unwrap();
+    let person_key = ost.persons()[0].key();
+    let recorded = Utc::now() - Duration::hours(5);
+
+    let (resp, mut resp_rx) = oneshot::channel();
+    let args = ArgAddFeeding {
+        person_key,
+        breast_milk: 0,
+        formula: 60,
+        solids: 0,
+        time_stamp: Some(recorded),
+        replay_id: None,
+    };
+    let changes = dispatch(
+        ost.as_mut(),
+        &storage,
+        CommandToBackend::AddFeeding { args, resp },
+    );
+    assert_eq!(changes.len(), 1);
+
+    let reply: Result<String, OstError> =
+        serde_json::from_str(&resp_rx.try_recv().unwrap()).unwrap();
+    let added: serde_json::Value = serde_json::from_str(&reply.unwrap()).unwrap();
+    let key = OstEventKey {
+        t: EventType::Feed,
+        id: added["id"].as_u64().unwrap() as u32,
+    };
+    assert_eq!(
+        *ost.get_feeding_by_key(&key).unwrap().time_stamp(),
+        recorded
+    );
+}
+
+fn replay_feeding(storage: &Storage, ost: &mut dyn Context, replay: u32) -> (usize, String) {
+    let person_key = ost.persons()[0].key();
+    let (resp, mut resp_rx) = oneshot::channel();
+    let args = ArgAddFeeding {
+        person_key,
+        breast_milk: 0,
+        formula: 60,
+        solids: 0,
+        time_stamp: Some(Utc::now()),
+        replay_id: Some(ReplayId {
+            client: "a-client".to_string(),
+            id: replay,
+        }),
+    };
+    let changes = dispatch(ost, storage, CommandToBackend::AddFeeding { args, resp });
+    (changes.len(), resp_rx.try_recv().unwrap())
+}
+
+#[test]
+fn a_replayed_entry_is_added_once() {
+    let storage = Storage::Fake;
+    let mut ost = storage.construct().unwrap();
+    let feedings = ost.feedings().len();
+
+    let (first_changes, first_reply) = replay_feeding(&storage, ost.as_mut(), 3);
+    let (again_changes, again_reply) = replay_feeding(&storage, ost.as_mut(), 3);
+    assert_eq!((first_changes, again_changes), (1, 0));
+    assert_eq!(first_reply, again_reply);
+    assert_eq!(ost.feedings().len(), feedings + 1);
+
+    let (_, older_reply) = replay_feeding(&storage, ost.as_mut(), 2);
+    let older_reply: Result<String, OstError> = serde_json::from_str(&older_reply).unwrap();
+    assert!(matches!(older_reply, Err(OstError::Validation(_))));
+    assert_eq!(ost.feedings().len(), feedings + 1);
+}
+
+#[test]
+fn replayed_entries_are_remembered_across_restarts() {
+    let mono_file = std::env::temp_dir().join("backend_dispatch_replays.json");
+    let mono_file = mono_file.to_str().unwrap().to_string();
+    let _ignore_fail = std::fs::remove_file(&mono_file);
+    let storage = Storage::Monolith(mono_file.clone());
+
+    let mut ost = storage.construct().unwrap();
+    ost.add_person("Replayed").unwrap();
+    let (_, first_reply) = replay_feeding(&storage, ost.as_mut(), 1);
+    drop(ost);
+
+    let mut restarted = storage.construct().unwrap();
+    let (changes, again_reply) = replay_feeding(&storage, restarted.as_mut(), 1);
+    assert_eq!(changes, 0);
+    assert_eq!(first_reply, again_reply);
+    assert_eq!(restarted.feedings().len(), 1);
+
+    let _ignore_fail = std::fs::remove_file(&mono_file);
+    let _ignore_fail = std::fs::remove_file(format!("{}.bak", mono_file));
+}
+
+#[tokio::test]
+async fn a_panicking_command_leaves_the_context_readable() {
+    let context = faked_context();
+    let panicking = context.clone();
+    let crashed = tokio::spawn(async move {
+        let _ost = panicking.write().await;
+        panic!("Dispatching went wrong");
+    })
+    .await;
+    assert!(crashed.is_err());
+
+    let response = read_from_context(&context, |ost| ost.persons().len().to_string())
+        .await
+        .into_response();
+    assert_eq!(response.status(), StatusCode::OK);
+}
@@ -0,0 +1,134 @@
+use serde_json::{json, Value};
+use warp::http::StatusCode;
+use warp::hyper::{body, Body, Request};
+use warp::reply::Response;
+use warp::{Filter, Reply};
+
+use backend::households;
+use backend::households::registry::{share_households, Households, SharedHouseholds};
+use backend::storage::Storage;
+
+const ADMIN_TOKEN: &str = "household admin";
+
+async fn send(
+    households: &SharedHouseholds,
+    method: &str,
+    path: &str,
+    token: &str,
+    payload: Option<Value>,
+) -> (StatusCode, Value) {
+    let fallback = households::filters::all_households(households.clone())
+        .map(Reply::into_response)
+        .boxed();
+    let payload = payload.map(|p| p.to_string()).unwrap_or_default();
+    let request = Request::builder()
+        .method(method)
+        .uri(path)
+        .header("authorization", format!("Bearer {}", token))
+        .header("content-type", "application/json")
+        .header("content-length", payload.len())
+        .body(Body::from(payload))
+        .unwrap();
+    let response: Response =
+        households::service::route(households.clone(), fallback, request).await;
+    let status = response.status();
+    let bytes = body::to_bytes(response.into_body()).await.unwrap();
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+async fn login(households: &SharedHouseholds, household: &str) -> String {
+    let (status, session) = send(
+        households,
+        "POST",
+        &format!("/h/{}/api/auth/login", household),
+        "",
+        Some(json!({"name": "parent", "password": "long enough"})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    session["token"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn households_are_independent() {
+    let dir = std::env::temp_dir().join(format!("ost_households_{}", std::process::id()));
+    let dir = dir.to_str().unwrap();
+    let storage = Storage::Monolith("ost.json".to_string());
+    let households = share_households(
+        Households::open(dir, storage.clone(), Some(ADMIN_TOKEN.to_string())).unwrap(),
+    );
+
+    let new_household =
+        |id: &str| json!({"id": id, "owner": {"name": "parent", "password": "long enough"}});
+    let (status, _) = send(
+        &households,
+        "POST",
+        "/admin/households",
+        "",
+        Some(new_household("home")),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    for id in ["home", "away"] {
+        let (status, _) = send(
+            &households,
+            "POST",
+            "/admin/households",
+            ADMIN_TOKEN,
+            Some(new_household(id)),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+    }
+    let (status, _) = send(
+        &households,
+        "POST",
+        "/admin/households",
+        ADMIN_TOKEN,
+        Some(new_household("home")),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let home = login(&households, "home").await;
+    let (status, _) = send(
+        &households,
+        "POST",
+        "/h/home/api/v2/persons",
+        &home,
+        Some(json!({"name": "Zardoz"})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let (_, persons) = send(&households, "GET", "/h/home/api/v2/persons", &home, None).await;
+    assert_eq!(persons.as_array().unwrap().len(), 1);
+
+    // A session of one household means nothing in another
+    let (status, _) = send(&households, "GET", "/h/away/api/v2/persons", &home, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let away = login(&households, "away").await;
+    let (_, persons) = send(&households, "GET", "/h/away/api/v2/persons", &away, None).await;
+    assert!(persons.as_array().unwrap().is_empty());
+
+    let (status, archived) = send(
+        &households,
+        "POST",
+        "/admin/households/home/archive",
+        ADMIN_TOKEN,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(archived["archived"], true);
+    let (status, _) = send(&households, "GET", "/h/home/api/v2/persons", &home, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let reopened = Households::open(dir, storage, None).unwrap();
+    assert_eq!(reopened.households().len(), 2);
+    assert!(reopened.routes("home").is_none());
+    assert!(reopened.routes("away").is_some());
+    std::fs::remove_dir_all(dir).unwrap();
+}
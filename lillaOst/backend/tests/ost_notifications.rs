@@ -0,0 +1,123 @@
+use serde_json::json;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task;
+use warp::http::StatusCode;
+use warp::test::request;
+
+use backend::command::{self, CommandToBackend};
+use backend::command_args::ArgAddPerson;
+use backend::local_state::{dispatch, ost_context};
+use backend::local_state_fake_in_memory::faked_context;
+use backend::notifications::{self, notifier};
+use backend::storage::Storage;
+use backend::v2;
+use ost::change::{ChangeNotification, ChangeOp, EntityKind};
+use ost::person::deserialize as person_deserialize;
+
+fn add_person(
+    name: &str,
+) -> (
+    Vec<ChangeNotification>,
+    Result<String, ost::error::OstError>,
+) {
+    let mut ost = Storage::Fake.construct().unwrap();
+    ost.add_person("Zardoz").unwrap();
+    let (resp, mut resp_rx) = oneshot::channel();
+    let args = ArgAddPerson {
+        name: name.to_string(),
+    };
+    let changes = dispatch(
+        ost.as_mut(),
+        &Storage::Fake,
+        CommandToBackend::AddPerson { args, resp },
+    );
+    let reply = serde_json::from_str(&resp_rx.try_recv().unwrap()).unwrap();
+    (changes, reply)
+}
+
+#[test]
+fn only_successful_mutations_announce() {
+    let (changes, reply) = add_person("Zed");
+    let zed = person_deserialize(&reply.unwrap()).unwrap();
+    assert_eq!(
+        changes,
+        vec![ChangeNotification::one(
+            EntityKind::Person,
+            zed.key().id,
+            ChangeOp::Added
+        )]
+    );
+
+    let (changes, reply) = add_person("Zardoz");
+    assert!(reply.is_err());
+    assert!(changes.is_empty());
+}
+
+#[tokio::test]
+async fn changes_reach_the_socket() {
+    let in_thread_server = task::LocalSet::new();
+    let (tx, rx) = mpsc::channel::<command::CommandToBackend>(32);
+    let context = faked_context();
+    let context_v2 = context.clone();
+    let notifier = notifier();
+    let notifier_ws = notifier.clone();
+
+    let requests = tokio::spawn(async move {
+        let f_changes = notifications::filters::all_notifications(notifier_ws);
+        let mut socket = warp::test::ws()
+            .path("/api/changes")
+            .handshake(f_changes)
+            .await
+            .unwrap();
+
+        let f_v2 = v2::filters::all_v2(command::Commands::unrestricted(tx), context_v2);
+        let response = request()
+            .method("POST")
+            .path("/api/v2/persons")
+            .json(&json!({"name": "Zardoz"}))
+            .reply(&f_v2)
+            .await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let zardoz: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+
+        let message = socket.recv().await.unwrap();
+        let change: ChangeNotification = serde_json::from_str(message.to_str().unwrap()).unwrap();
+        assert_eq!(
+            change,
+            ChangeNotification::one(
+                EntityKind::Person,
+                zardoz["id"].as_u64().unwrap() as u32,
+                ChangeOp::Added
+            )
+        );
+    });
+
+    in_thread_server
+        .run_until(async move { ost_context(rx, context, Storage::Fake, notifier).await })
+        .await;
+    requests.await.unwrap();
+}
+
+#[test]
+fn purging_events_leaves_the_persons() {
+    let mut ost = Storage::Fake.construct().unwrap();
+    let (resp, _resp_rx) = oneshot::channel();
+    let changes = dispatch(
+        ost.as_mut(),
+        &Storage::Fake,
+        CommandToBackend::AdminPurgeEvents { resp },
+    );
+    let kinds: Vec<EntityKind> = changes.iter().map(|change| change.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            EntityKind::Feeding,
+            EntityKind::Expulsion,
+            EntityKind::Event,
+            EntityKind::Measurement
+        ]
+    );
+    assert!(changes
+        .iter()
+        .all(|change| change.key.is_none() && change.op == ChangeOp::Removed));
+}
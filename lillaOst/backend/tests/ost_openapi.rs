@@ -0,0 +1,99 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+use warp::http::StatusCode;
+use warp::test::request;
+
+use backend::openapi;
+use backend::openapi::document::document;
+
+/// Every `warp::path!` under `src` as `(method, /path/{})`, read from the filter sources
+fn routes_in_sources(dir: &Path, routes: &mut BTreeSet<(String, String)>) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            routes_in_sources(&path, routes);
+        } else if path.extension().is_some_and(|e| e == "rs") {
+            let source = fs::read_to_string(&path).unwrap();
+            // One filter per `fn` or `let`, each with a single path and method
+            for filter in source.split("fn ").flat_map(|f| f.split("let ")) {
+                let segments = match filter.split_once("warp::path!(") {
+                    Some((_, rest)) => rest.split_once(')').unwrap().0,
+                    None => continue,
+                };
+                let method = ["get", "post", "put", "delete"]
+                    .into_iter()
+                    .find(|m| filter.contains(&format!("warp::{}()", m)))
+                    .unwrap_or_else(|| panic!("No method for {} in {:?}", segments, path));
+                let path = segments
+                    .split('/')
+                    .map(|segment| match segment.trim().strip_prefix('"') {
+                        Some(literal) => literal.trim_end_matches('"').to_string(),
+                        None => "{}".to_string(),
+                    })
+                    .collect::<Vec<String>>()
+                    .join("/");
+                routes.insert((method.to_string(), format!("/{}", path)));
+            }
+        }
+    }
+}
+
+fn described_routes() -> BTreeSet<(String, String)> {
+    let mut routes = BTreeSet::new();
+    for (path, operations) in document()["paths"].as_object().unwrap() {
+        let path: Vec<&str> = path
+            .split('/')
+            .map(|segment| {
+                if segment.starts_with('{') {
+                    "{}"
+                } else {
+                    segment
+                }
+            })
+            .collect();
+        for method in operations.as_object().unwrap().keys() {
+            routes.insert((method.clone(), path.join("/")));
+        }
+    }
+    routes
+}
+
+#[test]
+fn every_route_is_described() {
+    let mut routes = BTreeSet::new();
+    routes_in_sources(Path::new("./src"), &mut routes);
+    let described = described_routes();
+
+    let undescribed: Vec<_> = routes.difference(&described).collect();
+    assert!(
+        undescribed.is_empty(),
+        "Undescribed routes: {:?}",
+        undescribed
+    );
+    let stale: Vec<_> = described.difference(&routes).collect();
+    assert!(stale.is_empty(), "Described but not routed: {:?}", stale);
+}
+
+#[tokio::test]
+async fn openapi_document_is_served() {
+    let f_openapi = openapi::filters::all_openapi();
+    let response = request()
+        .method("GET")
+        .path("/api/openapi.json")
+        .reply(&f_openapi)
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let document: Value = serde_json::from_slice(response.body()).unwrap();
+    assert_eq!(document["openapi"], "3.0.3");
+    let new_feeding = &document["paths"]["/api/v2/feedings"]["post"];
+    assert!(
+        new_feeding["requestBody"]["content"]["application/json"]["schema"]["properties"]
+            ["person_id"]
+            .is_object()
+    );
+    assert!(new_feeding["responses"]["201"].is_object());
+}
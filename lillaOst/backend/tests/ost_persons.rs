@@ -4,18 +4,21 @@ use warp::http::StatusCode;
 use warp::test::request;
 
 use backend::command;
-use backend::command_args::ArgAddPerson;
-use backend::local_state_fake_in_memory::faked_state_ost_context;
+use backend::command_args::{ArgAddPerson, ArgMergePersons, ArgRemovePerson};
+use backend::local_state::ost_context;
+use backend::local_state_fake_in_memory::faked_context;
+use backend::notifications::notifier;
 use backend::persons;
+use backend::storage::Storage;
+use ost::error::OstError;
 use ost::person::{deserialize as person_deserialize, Person};
 
 #[tokio::test]
 async fn ost_get_persons_00() {
-    let in_thread_server = task::LocalSet::new();
-    let (tx, rx) = mpsc::channel::<command::CommandToBackend>(32);
+    let context = faked_context();
 
     let request = tokio::spawn(async move {
-        let f_ost_get_persons = persons::filters::get_persons(tx.clone()).clone();
+        let f_ost_get_persons = persons::filters::get_persons(context.clone()).clone();
         let response = request()
             .method("GET")
             .path("/api/persons")
@@ -36,9 +39,6 @@ async fn ost_get_persons_00() {
         assert_eq!(reconstructed.len(), 10);
     });
 
-    in_thread_server
-        .run_until(async move { faked_state_ost_context(rx).await })
-        .await;
     request.await.unwrap();
 }
 
@@ -46,15 +46,18 @@ async fn ost_get_persons_00() {
 async fn ost_add_person() {
     let in_thread_server = task::LocalSet::new();
     let (tx, rx) = mpsc::channel::<command::CommandToBackend>(32);
+    let context = faked_context();
+    let context_get = context.clone();
 
-    let tx_add = tx.clone();
+    let tx_add = tx;
 
     let arg = ArgAddPerson {
         name: "manolo".to_string(),
     };
 
     let request_add_person = tokio::spawn(async move {
-        let f_add_person = persons::filters::add_person(tx_add.clone()).clone();
+        let f_add_person =
+            persons::filters::add_person(command::Commands::unrestricted(tx_add.clone())).clone();
         let response_add_person = request()
             .method("POST")
             .path("/api/persons")
@@ -66,7 +69,10 @@ async fn ost_add_person() {
     });
 
     let request_get_persons = tokio::spawn(async move {
-        let f_ost_get_persons = persons::filters::get_persons(tx.clone()).clone();
+        // Reads don't queue behind writes anymore
+        request_add_person.await.unwrap();
+
+        let f_ost_get_persons = persons::filters::get_persons(context_get).clone();
         let response = request()
             .method("GET")
             .path("/api/persons")
@@ -88,9 +94,8 @@ async fn ost_add_person() {
     });
 
     in_thread_server
-        .run_until(async move { faked_state_ost_context(rx).await })
+        .run_until(async move { ost_context(rx, context, Storage::Fake, notifier()).await })
         .await;
-    request_add_person.await.unwrap();
     request_get_persons.await.unwrap();
 }
 
@@ -98,6 +103,8 @@ async fn ost_add_person() {
 async fn ost_fail_on_double_add() {
     let in_thread_server = task::LocalSet::new();
     let (tx, rx) = mpsc::channel::<command::CommandToBackend>(32);
+    let context = faked_context();
+    let context_get = context.clone();
 
     let tx_add = tx.clone();
 
@@ -108,7 +115,8 @@ async fn ost_fail_on_double_add() {
     let first_manolo = arg.clone();
 
     let request_add_person_first = tokio::spawn(async move {
-        let f_add_person = persons::filters::add_person(tx_add.clone()).clone();
+        let f_add_person =
+            persons::filters::add_person(command::Commands::unrestricted(tx_add.clone())).clone();
         let response_add_person = request()
             .method("POST")
             .path("/api/persons")
@@ -118,10 +126,12 @@ async fn ost_fail_on_double_add() {
         assert_eq!(response_add_person.status(), StatusCode::OK);
     });
 
-    let tx_double_add = tx.clone();
+    let tx_double_add = tx;
 
     let request_add_same_person = tokio::spawn(async move {
-        let f_add_person = persons::filters::add_person(tx_double_add.clone()).clone();
+        let f_add_person =
+            persons::filters::add_person(command::Commands::unrestricted(tx_double_add.clone()))
+                .clone();
         let response_add_person = request()
             .method("POST")
             .path("/api/persons")
@@ -133,11 +143,17 @@ async fn ost_fail_on_double_add() {
         let payload = response_add_person.body().to_vec();
         let message: Result<String, String> =
             serde_json::from_str(std::str::from_utf8(&payload).unwrap()).unwrap();
-        assert!(message.is_err());
+        assert!(matches!(
+            OstError::from_message(&message.unwrap_err()),
+            OstError::DuplicateName(_)
+        ));
     });
 
     let request_get_persons = tokio::spawn(async move {
-        let f_ost_get_persons = persons::filters::get_persons(tx.clone()).clone();
+        request_add_person_first.await.unwrap();
+        request_add_same_person.await.unwrap();
+
+        let f_ost_get_persons = persons::filters::get_persons(context_get).clone();
         let response = request()
             .method("GET")
             .path("/api/persons")
@@ -159,9 +175,103 @@ async fn ost_fail_on_double_add() {
     });
 
     in_thread_server
-        .run_until(async move { faked_state_ost_context(rx).await })
+        .run_until(async move { ost_context(rx, context, Storage::Fake, notifier()).await })
         .await;
-    request_add_person_first.await.unwrap();
-    request_add_same_person.await.unwrap();
     request_get_persons.await.unwrap();
 }
+
+#[tokio::test]
+async fn ost_merge_and_remove_persons() {
+    let in_thread_server = task::LocalSet::new();
+    let (tx, rx) = mpsc::channel::<command::CommandToBackend>(32);
+    let context = faked_context();
+    let context_get = context.clone();
+
+    let requests = tokio::spawn(async move {
+        let f_ost_get_persons = persons::filters::get_persons(context_get).clone();
+        let f_merge_persons =
+            persons::filters::merge_persons(command::Commands::unrestricted(tx.clone())).clone();
+        let f_remove_person =
+            persons::filters::remove_person(command::Commands::unrestricted(tx.clone())).clone();
+
+        let get_person_keys = || async {
+            let response = request()
+                .method("GET")
+                .path("/api/persons")
+                .reply(&f_ost_get_persons)
+                .await;
+            let vec_of_serialized_persons: Vec<String> =
+                serde_json::from_slice(response.body()).unwrap();
+            vec_of_serialized_persons
+                .iter()
+                .map(|s| person_deserialize(s).unwrap().key())
+                .collect::<Vec<_>>()
+        };
+
+        let keys = get_person_keys().await;
+        let response = request()
+            .method("POST")
+            .path("/api/persons/merge")
+            .json(&ArgMergePersons {
+                from: keys[0],
+                into: keys[1],
+            })
+            .reply(&f_merge_persons)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let merged: Result<(), OstError> = serde_json::from_slice(response.body()).unwrap();
+        assert!(merged.is_ok());
+        assert_eq!(get_person_keys().await.len(), 9);
+
+        let response = request()
+            .method("POST")
+            .path("/api/persons/remove")
+            .json(&ArgRemovePerson {
+                person_key: keys[1],
+                cascade: false,
+            })
+            .reply(&f_remove_person)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let refused: Result<(), String> = serde_json::from_slice(response.body()).unwrap();
+        assert!(matches!(
+            OstError::from_message(&refused.unwrap_err()),
+            OstError::Validation(_)
+        ));
+        assert_eq!(get_person_keys().await.len(), 9);
+
+        let response = request()
+            .method("POST")
+            .path("/api/persons/remove")
+            .json(&ArgRemovePerson {
+                person_key: keys[1],
+                cascade: true,
+            })
+            .reply(&f_remove_person)
+            .await;
+        let removed: Result<(), String> = serde_json::from_slice(response.body()).unwrap();
+        assert!(removed.is_ok());
+        assert_eq!(get_person_keys().await.len(), 8);
+
+        let response = request()
+            .method("POST")
+            .path("/api/persons/merge")
+            .json(&ArgMergePersons {
+                from: keys[0],
+                into: keys[2],
+            })
+            .reply(&f_merge_persons)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let missing: Result<(), String> = serde_json::from_slice(response.body()).unwrap();
+        assert!(matches!(
+            OstError::from_message(&missing.unwrap_err()),
+            OstError::NotFound(_)
+        ));
+    });
+
+    in_thread_server
+        .run_until(async move { ost_context(rx, context, Storage::Fake, notifier()).await })
+        .await;
+    requests.await.unwrap();
+}
@@ -0,0 +1,60 @@
+use warp::http::StatusCode;
+use warp::test::request;
+
+use backend::local_state_fake_in_memory::faked_context;
+use backend::query;
+use ost::error::OstError;
+use ost::event_key::EventType;
+use ost::query::EventQuery;
+
+#[tokio::test]
+async fn ost_query_returns_one_page_and_the_total() {
+    let context = faked_context();
+
+    let request = tokio::spawn(async move {
+        let f_query = query::filters::query(context.clone()).clone();
+
+        let page_query = EventQuery {
+            kinds: vec![EventType::Feed],
+            offset: 10,
+            limit: Some(15),
+            ..EventQuery::default()
+        };
+        let response = request()
+            .method("GET")
+            .path(&format!("/api/query?{}", page_query.to_query_string()))
+            .reply(&f_query)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let result: Result<String, OstError> = serde_json::from_slice(response.body()).unwrap();
+        let page: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+        assert_eq!(page["total"], 150);
+        let items = page["items"].as_array().unwrap();
+        assert_eq!(items.len(), 15);
+        assert!(items.iter().all(|item| item[0] == "Feed"));
+
+        let response = request()
+            .method("GET")
+            .path("/api/query")
+            .reply(&f_query)
+            .await;
+        let result: Result<String, OstError> = serde_json::from_slice(response.body()).unwrap();
+        let everything: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+        assert_eq!(everything["total"], 470);
+
+        let response = request()
+            .method("GET")
+            .path("/api/query?kinds=Nap")
+            .reply(&f_query)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let result: Result<String, String> = serde_json::from_slice(response.body()).unwrap();
+        assert!(matches!(
+            OstError::from_message(&result.unwrap_err()),
+            OstError::Validation(_)
+        ));
+    });
+
+    request.await.unwrap();
+}
@@ -0,0 +1,204 @@
+use serde_json::{json, Value};
+use tokio::sync::mpsc;
+use tokio::task;
+use warp::http::StatusCode;
+use warp::test::request;
+
+use backend::command;
+use backend::local_state::ost_context;
+use backend::local_state_fake_in_memory::faked_context;
+use backend::notifications::notifier;
+use backend::storage::Storage;
+use backend::v2;
+use ost::error::OstError;
+
+#[tokio::test]
+async fn feeding_lifecycle_v2() {
+    let in_thread_server = task::LocalSet::new();
+    let (tx, rx) = mpsc::channel::<command::CommandToBackend>(32);
+    let context = faked_context();
+    let context_v2 = context.clone();
+
+    let requests = tokio::spawn(async move {
+        let f_v2 = v2::filters::all_v2(command::Commands::unrestricted(tx), context_v2);
+
+        let response = request()
+            .method("GET")
+            .path("/api/v2/feedings")
+            .reply(&f_v2)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let feedings: Vec<Value> = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(feedings.len(), 150);
+
+        let response = request()
+            .method("POST")
+            .path("/api/v2/feedings")
+            .json(&json!({"person_id": 1, "breast_milk": 120, "formula": 0, "solids": 0}))
+            .reply(&f_v2)
+            .await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let created: Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(created["breast_milk"], 120);
+        let path = format!("/api/v2/feedings/{}", created["id"]);
+
+        let response = request()
+            .method("PUT")
+            .path(&path)
+            .json(&json!({
+                "time_stamp": created["time_stamp"],
+                "breast_milk": 90,
+                "formula": 30,
+                "solids": 0,
+                "nursing": {"stretches": [{
+                    "side": "Left",
+                    "start": created["time_stamp"],
+                    "end": created["time_stamp"]
+                }]}
+            }))
+            .reply(&f_v2)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let modified: Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(modified["formula"], 30);
+        assert_eq!(modified["nursing"]["stretches"][0]["side"], "Left");
+
+        let response = request()
+            .method("PUT")
+            .path(&path)
+            .json(&json!({"breast_milk": "a lot"}))
+            .reply(&f_v2)
+            .await;
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let response = request().method("DELETE").path(&path).reply(&f_v2).await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        let response = request().method("GET").path(&path).reply(&f_v2).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let missing: OstError = serde_json::from_slice(response.body()).unwrap();
+        assert!(matches!(missing, OstError::NotFound(_)));
+    });
+
+    in_thread_server
+        .run_until(async move { ost_context(rx, context, Storage::Fake, notifier()).await })
+        .await;
+    requests.await.unwrap();
+}
+
+#[tokio::test]
+async fn measurement_lifecycle_v2() {
+    let in_thread_server = task::LocalSet::new();
+    let (tx, rx) = mpsc::channel::<command::CommandToBackend>(32);
+    let context = faked_context();
+    let context_v2 = context.clone();
+
+    let requests = tokio::spawn(async move {
+        let f_v2 = v2::filters::all_v2(command::Commands::unrestricted(tx), context_v2);
+
+        let response = request()
+            .method("POST")
+            .path("/api/v2/measurements")
+            .json(&json!({"person_id": 1, "weight": 4200, "length": 55.5}))
+            .reply(&f_v2)
+            .await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let created: Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(created["weight"], 4200);
+        assert!(created.get("head_circumference").is_none());
+        let path = format!("/api/v2/measurements/{}", created["id"]);
+
+        let response = request()
+            .method("PUT")
+            .path(&path)
+            .json(&json!({
+                "time_stamp": created["time_stamp"],
+                "weight": 4250,
+                "head_circumference": 37.0
+            }))
+            .reply(&f_v2)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let modified: Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(modified["head_circumference"], 37.0);
+        assert!(modified.get("length").is_none());
+
+        let response = request()
+            .method("POST")
+            .path("/api/v2/measurements")
+            .json(&json!({"person_id": 1}))
+            .reply(&f_v2)
+            .await;
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let response = request().method("DELETE").path(&path).reply(&f_v2).await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        let response = request().method("GET").path(&path).reply(&f_v2).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    });
+
+    in_thread_server
+        .run_until(async move { ost_context(rx, context, Storage::Fake, notifier()).await })
+        .await;
+    requests.await.unwrap();
+}
+
+#[tokio::test]
+async fn person_errors_v2() {
+    let in_thread_server = task::LocalSet::new();
+    let (tx, rx) = mpsc::channel::<command::CommandToBackend>(32);
+    let context = faked_context();
+    let context_v2 = context.clone();
+
+    let requests = tokio::spawn(async move {
+        let f_v2 = v2::filters::all_v2(command::Commands::unrestricted(tx), context_v2);
+
+        let response = request()
+            .method("POST")
+            .path("/api/v2/persons")
+            .json(&json!({"name": "Zardoz"}))
+            .reply(&f_v2)
+            .await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let zardoz: Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(zardoz["name"], "Zardoz");
+
+        let response = request()
+            .method("POST")
+            .path("/api/v2/persons")
+            .json(&json!({"name": "Zardoz"}))
+            .reply(&f_v2)
+            .await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        let response = request()
+            .method("PUT")
+            .path(&format!("/api/v2/persons/{}", zardoz["id"]))
+            .json(&json!({"name": "Zed", "is_active": true}))
+            .reply(&f_v2)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let zed: Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(zed["name"], "Zed");
+
+        let response = request()
+            .method("POST")
+            .path(&format!("/api/v2/persons/{0}/merge/{0}", zardoz["id"]))
+            .reply(&f_v2)
+            .await;
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let response = request()
+            .method("DELETE")
+            .path("/api/v2/persons/9999")
+            .reply(&f_v2)
+            .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    });
+
+    in_thread_server
+        .run_until(async move { ost_context(rx, context, Storage::Fake, notifier()).await })
+        .await;
+    requests.await.unwrap();
+}
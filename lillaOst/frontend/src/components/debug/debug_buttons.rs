@@ -3,9 +3,10 @@ use serde_derive::{Deserialize, Serialize};
 use yew::prelude::*;
 use yew_agent::{Dispatched, Dispatcher};
 
-use crate::event_bus::{EventBus, Request};
+use crate::context::context;
+use crate::event_bus::{BusMessage, EventBus, Request};
 
-use ost::context_remote_async::AsyncRemoteMonolith;
+use ost::change::EntityKind;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ArgFakeCount {
@@ -16,7 +17,7 @@ pub enum MsgDebugControls {
     AppendRandomEventCount(u32),
     AppendRandomPerson,
     AppendFeedingsForToday,
-    CallFinished,
+    CallFinished(EntityKind),
 }
 
 pub struct DebugControls {
@@ -37,33 +38,33 @@ impl Component for DebugControls {
         match msg {
             MsgDebugControls::AppendRandomEventCount(count) => {
                 ctx.link().send_future(async move {
-                    let remote = AsyncRemoteMonolith {};
-                    let _ = remote.add_fake_events(count).await;
-                    MsgDebugControls::CallFinished
+                    let ost = context();
+                    let _ = ost.add_fake_events(count).await;
+                    MsgDebugControls::CallFinished(EntityKind::Event)
                 });
             }
             MsgDebugControls::AppendRandomPerson => {
                 ctx.link().send_future(async {
-                    let remote = AsyncRemoteMonolith {};
-                    let _ = remote.add_fake_persons(1).await;
-                    MsgDebugControls::CallFinished
+                    let ost = context();
+                    let _ = ost.add_fake_persons(1).await;
+                    MsgDebugControls::CallFinished(EntityKind::Person)
                 });
             }
             MsgDebugControls::AppendFeedingsForToday => {
                 ctx.link().send_future(async {
-                    let remote = AsyncRemoteMonolith {};
-                    let mut persons = remote.persons().await;
+                    let ost = context();
+                    let mut persons = ost.persons().await;
                     for _ in 0..persons.len() {
                         let p = persons.pop().unwrap();
-                        let _ = remote.add_feeding(&p, 100, 100, 100).await;
+                        let _ = ost.add_feeding(&p, 100, 100, 100).await;
                     }
-                    MsgDebugControls::CallFinished
+                    MsgDebugControls::CallFinished(EntityKind::Feeding)
                 });
             }
 
-            MsgDebugControls::CallFinished => {
+            MsgDebugControls::CallFinished(kind) => {
                 self.event_bus
-                    .send(Request::EventBusMsg("DebugControls inserted".to_owned()));
+                    .send(Request::EventBusMsg(BusMessage::ManyChanged(kind)));
                 return false;
             }
         }
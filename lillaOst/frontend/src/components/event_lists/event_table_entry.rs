@@ -10,9 +10,10 @@ use ost::event_base::EventBase as ost_EventBase;
 /// Date as in YYYY-MM-DD
 /// Time as in HH:MM
 /// Summary
+///
+/// Entries still in the outbox have no details page on the backend yet
 
-#[allow(clippy::borrowed_box)]
-pub fn event_entry(event: &Box<dyn ost_EventBase>) -> Html {
+pub fn event_entry(event: &dyn ost_EventBase, is_pending: bool) -> Html {
     let local_date_time = event.time_stamp().with_timezone(&Local);
     let formatted_time = local_date_time.format("%H:%M").to_string();
     let formatted_date = local_date_time.format("%x").to_string();
@@ -20,6 +21,7 @@ pub fn event_entry(event: &Box<dyn ost_EventBase>) -> Html {
     let ost_key = event.key();
 
     let to_details_route = match ost_key.t {
+        _ if is_pending => html! { <>{ formatted_date }</> },
         ost::event_key::EventType::Event => html! {
             <Link<LillaOstRoutes> to={ LillaOstRoutes::OstDetailsEvent{ id: ost_key.id } } >
                 { formatted_date }
@@ -35,8 +37,18 @@ pub fn event_entry(event: &Box<dyn ost_EventBase>) -> Html {
                 { formatted_date }
             </Link<LillaOstRoutes>>
         },
+        ost::event_key::EventType::Measurement => html! {
+            <Link<LillaOstRoutes> to={ LillaOstRoutes::OstDetailsMeasurement{ id: ost_key.id } } >
+                { formatted_date }
+            </Link<LillaOstRoutes>>
+        },
     };
 
+    let mut pending_tag = html!();
+    if is_pending {
+        pending_tag = html! { <span class="tag is-warning ml-2">{ "pending" }</span> };
+    }
+
     html! {
     <tr>
         <td>
@@ -50,6 +62,7 @@ pub fn event_entry(event: &Box<dyn ost_EventBase>) -> Html {
         </td>
         <td>
             { event.summary() }
+            { pending_tag }
         </td>
     </tr>
     }
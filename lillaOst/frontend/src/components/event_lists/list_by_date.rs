@@ -1,12 +1,15 @@
+use std::rc::Rc;
+
 use chrono::prelude::*;
 
 use yew::prelude::*;
 use yew_agent::{Bridge, Bridged};
 
-use crate::event_bus::EventBus;
+use crate::event_bus::{BusMessage, EventBus};
+use crate::store;
 
-use ost::context_remote_async::AsyncRemoteMonolith;
 use ost::event_base::EventBase as ost_EventBase;
+use ost::event_key::OstEventKey;
 
 #[derive(Clone, Debug, Eq, PartialEq, Properties)]
 pub struct Props {
@@ -15,14 +18,18 @@ pub struct Props {
 
 pub enum MsgListByDate {
     RequestLoadPage(Date<Utc>),
-    DataForPageReceived { data: Vec<Box<dyn ost_EventBase>> },
-    StorageChanged(String),
+    DataForPageReceived {
+        data: Vec<Rc<dyn ost_EventBase>>,
+        pending: Vec<OstEventKey>,
+    },
+    StorageChanged(BusMessage),
 }
 
 pub struct ListByDate {
     _producer: Box<dyn Bridge<EventBus>>,
     date: Date<Utc>,
-    slice_to_display: Vec<Box<dyn ost_EventBase>>,
+    slice_to_display: Vec<Rc<dyn ost_EventBase>>,
+    pending: Vec<OstEventKey>,
 }
 
 impl Component for ListByDate {
@@ -34,6 +41,7 @@ impl Component for ListByDate {
         Self {
             _producer: EventBus::bridge(ctx.link().callback(MsgListByDate::StorageChanged)),
             slice_to_display: vec![],
+            pending: vec![],
             date: ctx.props().date,
         }
     }
@@ -47,8 +55,9 @@ impl Component for ListByDate {
             MsgListByDate::RequestLoadPage(date) => {
                 ost_get_slice(ctx, date);
             }
-            MsgListByDate::DataForPageReceived { data } => {
+            MsgListByDate::DataForPageReceived { data, pending } => {
                 self.slice_to_display = data;
+                self.pending = pending;
                 return true;
             }
         }
@@ -75,7 +84,7 @@ impl Component for ListByDate {
                     </thead>
                     <tbody>
                         {
-                            for self.slice_to_display.iter().map(super::event_table_entry::event_entry)
+                            for self.slice_to_display.iter().map(|e| super::event_table_entry::event_entry(e.as_ref(), self.pending.contains(&e.key())))
                         }
                     </tbody>
                 </table>
@@ -86,41 +95,51 @@ impl Component for ListByDate {
 
 fn ost_get_slice(ctx: &Context<ListByDate>, date: Date<Utc>) {
     ctx.link().send_future(async move {
-        let remote_monolith = AsyncRemoteMonolith {};
-
-        let feeds = remote_monolith.feedings();
-        let events = remote_monolith.events();
-        let expulsions = remote_monolith.expulsions();
+        let feeds = store::feedings();
+        let events = store::events();
+        let expulsions = store::expulsions();
+        let measurements = store::measurements();
 
         let mut feeds = feeds.await;
         feeds.retain(|f| f.is_person_active());
-        let mut feeds_page: Vec<Box<dyn ost_EventBase>> = feeds
+        let mut feeds_page: Vec<Rc<dyn ost_EventBase>> = feeds
             .drain(..)
-            .map(|f| f as Box<dyn ost_EventBase>)
+            .map(|f| f as Rc<dyn ost_EventBase>)
             .collect();
 
         let mut expulsions = expulsions.await;
         expulsions.retain(|f| f.is_person_active());
-        let mut expulsions_page: Vec<Box<dyn ost_EventBase>> = expulsions
+        let mut expulsions_page: Vec<Rc<dyn ost_EventBase>> = expulsions
             .drain(..)
-            .map(|exp| exp as Box<dyn ost_EventBase>)
+            .map(|exp| exp as Rc<dyn ost_EventBase>)
             .collect();
 
         let mut events = events.await;
         events.retain(|f| f.is_person_active());
-        let mut events_page: Vec<Box<dyn ost_EventBase>> = events
+        let mut events_page: Vec<Rc<dyn ost_EventBase>> = events
+            .drain(..)
+            .map(|eve| eve as Rc<dyn ost_EventBase>)
+            .collect();
+
+        let mut measurements = measurements.await;
+        measurements.retain(|m| m.is_person_active());
+        let mut measurements_page: Vec<Rc<dyn ost_EventBase>> = measurements
             .drain(..)
-            .map(|eve| eve as Box<dyn ost_EventBase>)
+            .map(|m| m as Rc<dyn ost_EventBase>)
             .collect();
 
-        let mut result: Vec<Box<dyn ost_EventBase>> = vec![];
+        let mut result: Vec<Rc<dyn ost_EventBase>> = vec![];
 
         result.append(&mut feeds_page);
         result.append(&mut expulsions_page);
         result.append(&mut events_page);
+        result.append(&mut measurements_page);
         result.retain(|r| r.time_stamp().date() == date);
         result.sort_by(|a, b| b.time_stamp().cmp(a.time_stamp()));
 
-        MsgListByDate::DataForPageReceived { data: result }
+        MsgListByDate::DataForPageReceived {
+            data: result,
+            pending: store::pending().await,
+        }
     });
 }
@@ -1,10 +1,11 @@
 use yew::prelude::*;
 use yew_agent::{Bridge, Bridged};
 
-use crate::event_bus::EventBus;
+use crate::context::context;
+use crate::event_bus::{BusMessage, EventBus};
 
-use ost::context_remote_async::AsyncRemoteMonolith;
 use ost::event_base::EventBase as ost_EventBase;
+use ost::query::EventQuery;
 
 #[derive(Clone, Debug, Eq, PartialEq, Properties)]
 pub struct Props {
@@ -19,7 +20,7 @@ pub enum MsgListByPage {
         max_pages: u64,
         data: Vec<Box<dyn ost_EventBase>>,
     },
-    StorageChanged(String),
+    StorageChanged(BusMessage),
 }
 
 pub struct ListByPage {
@@ -98,7 +99,7 @@ impl Component for ListByPage {
                         </thead>
                         <tbody>
                             {
-                                for self.slice_to_display.iter().map(super::event_table_entry::event_entry)
+                                for self.slice_to_display.iter().map(|e| super::event_table_entry::event_entry(e.as_ref(), false))
                             }
                         </tbody>
                     </table>
@@ -110,68 +111,30 @@ impl Component for ListByPage {
 
 fn ost_get_slice(ctx: &Context<ListByPage>, page: u64, page_size: u64) {
     ctx.link().send_future(async move {
-        let remote_monolith = AsyncRemoteMonolith {};
+        let ost = context();
 
-        let from: usize = page as usize * page_size as usize;
-        let u_page_size = page_size as usize;
+        let query = EventQuery {
+            active_persons_only: true,
+            offset: page as usize * page_size as usize,
+            limit: Some(page_size as usize),
+            ..EventQuery::default()
+        };
 
-        let feeds = remote_monolith.feedings();
-        let events = remote_monolith.events();
-        let expulsions = remote_monolith.expulsions();
-
-        let mut feeds = feeds.await;
-        feeds.retain(|f| f.is_person_active());
-        let mut feeds_page: Vec<Box<dyn ost_EventBase>> = feeds
-            .drain(..)
-            .map(|f| f as Box<dyn ost_EventBase>)
-            .collect();
-
-        let mut expulsions = expulsions.await;
-        expulsions.retain(|f| f.is_person_active());
-        let mut expulsions_page: Vec<Box<dyn ost_EventBase>> = expulsions
-            .drain(..)
-            .map(|exp| exp as Box<dyn ost_EventBase>)
-            .collect();
-
-        let mut events = events.await;
-        events.retain(|f| f.is_person_active());
-        let mut events_page: Vec<Box<dyn ost_EventBase>> = events
-            .drain(..)
-            .map(|eve| eve as Box<dyn ost_EventBase>)
-            .collect();
-
-        let mut result: Vec<Box<dyn ost_EventBase>> = vec![];
-
-        let total_entries =
-            feeds_page.len() as u64 + expulsions_page.len() as u64 + events_page.len() as u64;
-        let total_pages = total_entries / page_size;
-
-        result.append(&mut feeds_page);
-        result.append(&mut expulsions_page);
-        result.append(&mut events_page);
-
-        if from > result.len() {
-            return MsgListByPage::DataForPageReceived {
+        match ost.query(&query).await {
+            Ok(result) => MsgListByPage::DataForPageReceived {
+                page,
+                max_pages: result.total as u64 / page_size,
+                data: result
+                    .items
+                    .into_iter()
+                    .map(|item| item.into_event_base())
+                    .collect(),
+            },
+            Err(_) => MsgListByPage::DataForPageReceived {
                 page,
-                max_pages: total_pages,
+                max_pages: 0,
                 data: vec![],
-            };
-        }
-
-        result.sort_by(|a, b| b.time_stamp().cmp(a.time_stamp()));
-
-        if from > 0 {
-            result.drain(0..from);
-        }
-
-        if result.len() > u_page_size {
-            result.drain(u_page_size..);
-        }
-
-        MsgListByPage::DataForPageReceived {
-            page,
-            max_pages: total_pages,
-            data: result,
+            },
         }
     });
 }
@@ -11,9 +11,11 @@ use plotters::prelude::*;
 use plotters_canvas::CanvasBackend;
 use web_sys::HtmlCanvasElement;
 
-use ost::context_remote_async::AsyncRemoteMonolith;
 use ost::person::Person as ost_Person;
 
+use crate::components::person_tag::person_label;
+use crate::store;
+
 pub type DrawResult<T> = Result<T, Box<dyn std::error::Error>>;
 
 #[derive(Clone, Properties)]
@@ -48,8 +50,7 @@ impl Component for GraphFeedingsIndividual {
         let p = ctx.props().person.clone();
 
         ctx.link().send_future(async move {
-            let remote = AsyncRemoteMonolith {};
-            let person_events = remote.feedings_by(&p).await;
+            let person_events = store::feedings_by(p.key()).await;
 
             let mut last_31_days_feedings = BTreeMap::<Date<Utc>, DailyFeedingsAccumulator>::new();
 
@@ -73,7 +74,7 @@ impl Component for GraphFeedingsIndividual {
         Self {
             props: ctx.props().clone(),
             canvas_31_days: NodeRef::default(),
-            person_name: ctx.props().person.name().to_string(),
+            person_name: person_label(&**ctx.props().person),
             is_loading: true,
             data: BTreeMap::<Date<Utc>, DailyFeedingsAccumulator>::new(),
         }
@@ -6,9 +6,11 @@ use std::rc::Rc;
 
 use yew::prelude::*;
 
-use ost::context_remote_async::AsyncRemoteMonolith;
 use ost::person::Person as ost_Person;
 
+use crate::components::person_tag::person_label;
+use crate::store;
+
 use itertools::Itertools;
 
 use plotters::prelude::*;
@@ -49,8 +51,7 @@ impl Component for GraphFeedingsIndividualAllTime {
         let p = ctx.props().person.clone();
 
         ctx.link().send_future(async move {
-            let remote = AsyncRemoteMonolith {};
-            let person_events = remote.feedings_by(&p).await;
+            let person_events = store::feedings_by(p.key()).await;
 
             let mut accumulated_feedings = BTreeMap::<Date<Utc>, DailyFeedingsAccumulator>::new();
 
@@ -70,7 +71,7 @@ impl Component for GraphFeedingsIndividualAllTime {
         Self {
             props: ctx.props().clone(),
             canvas_all_time: NodeRef::default(),
-            person_name: ctx.props().person.name().to_string(),
+            person_name: person_label(&**ctx.props().person),
             is_loading: true,
             data: BTreeMap::<Date<Utc>, DailyFeedingsAccumulator>::new(),
         }
@@ -0,0 +1,236 @@
+use std::rc::Rc;
+
+use chrono::prelude::*;
+
+use yew::prelude::*;
+
+use ost::growth::{self, GrowthIndicator, CHART_Z_SCORES};
+use ost::person::{Age, Person as ost_Person, Sex};
+
+use crate::components::person_tag::person_label;
+use crate::store;
+
+use plotters::prelude::*;
+use plotters_canvas::CanvasBackend;
+use web_sys::HtmlCanvasElement;
+
+pub type DrawResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+/// The WHO tables used by the chart stop at one year
+const LAST_CHARTED_DAY: u32 = 365;
+
+#[derive(Clone, Properties)]
+pub struct PropsGraphGrowth {
+    pub person: Rc<Box<dyn ost_Person>>,
+    pub id: u32,
+}
+
+impl PartialEq for PropsGraphGrowth {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+pub enum MsgGraphGrowth {
+    /// Weights in kilograms by age in days, oldest first
+    MsgDataReceived(Vec<(u32, f64)>),
+}
+
+/// Weight for age against the WHO 3rd, 15th, 50th, 85th and 97th percentile,
+/// for persons with a sex and a birth date
+pub struct GraphGrowth {
+    props: PropsGraphGrowth,
+    canvas_growth: NodeRef,
+    person_name: String,
+    data: Vec<(u32, f64)>,
+    is_loading: bool,
+}
+
+impl Component for GraphGrowth {
+    type Message = MsgGraphGrowth;
+    type Properties = PropsGraphGrowth;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let p = ctx.props().person.clone();
+
+        ctx.link().send_future(async move {
+            let measurements = store::measurements_by(p.key()).await;
+
+            let mut weights: Vec<(u32, f64)> = measurements
+                .iter()
+                .filter_map(|m| {
+                    let measured_on = m.time_stamp().with_timezone(&Local).date().naive_local();
+                    let age = p.age_on(measured_on)?;
+                    let weight = GrowthIndicator::WeightForAge.value_of(&m.measures())?;
+                    Some((age.days, weight))
+                })
+                .filter(|(days, _)| *days <= LAST_CHARTED_DAY)
+                .collect();
+            weights.sort_by_key(|(days, _)| *days);
+            MsgGraphGrowth::MsgDataReceived(weights)
+        });
+
+        Self {
+            props: ctx.props().clone(),
+            canvas_growth: NodeRef::default(),
+            person_name: person_label(&**ctx.props().person),
+            is_loading: true,
+            data: vec![],
+        }
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            MsgGraphGrowth::MsgDataReceived(d) => {
+                self.is_loading = false;
+                self.data = d;
+                true
+            }
+        }
+    }
+
+    fn changed(&mut self, _ctx: &Context<Self>) -> bool {
+        false
+    }
+
+    fn view(&self, _ctx: &Context<Self>) -> Html {
+        let person = &self.props.person;
+        if person.sex().is_none() || person.birth_date().is_none() {
+            return html! {
+                <div class="block">
+                    <p>
+                        { format!("{}: set a sex and a birth date in settings to see the growth chart", self.person_name) }
+                    </p>
+                </div>
+            };
+        }
+
+        let div_id = format!("canvas-growth-container-{}", self.props.id);
+        let canvas_growth_id = format!("canvas-growth-{}", self.props.id);
+
+        let mut script = html! {};
+        if !self.is_loading {
+            script = html! {
+                <script>
+                    {
+                        format!(
+                        "
+                        var cont = document.getElementById('canvas-growth-container-{}');
+                        var canv_growth = document.getElementById('canvas-growth-{}');
+                        if (canv_growth !== null ) {{
+                            canv_growth.width = cont.offsetWidth;
+                            canv_growth.height = cont.offsetWidth / 1.777;
+                        }}
+                        "
+                        , self.props.id, self.props.id)
+                    }
+                </script>
+            };
+        }
+
+        html! {
+        <div id={div_id} class="block">
+            <p>
+                { format!("{}: weight for age", self.person_name) }
+            </p>
+            <canvas id={canvas_growth_id} ref={self.canvas_growth.clone()}>
+            </canvas>
+            { script }
+        </div>
+        }
+    }
+
+    fn rendered(&mut self, _ctx: &Context<Self>, first_render: bool) {
+        if first_render {
+            return;
+        }
+        let sex = match self.props.person.sex() {
+            Some(sex) => sex,
+            None => return,
+        };
+
+        if let Some(canvas_growth) = self.canvas_growth.cast::<HtmlCanvasElement>() {
+            let _res = self.draw_growth(canvas_growth, sex, &self.data);
+        }
+    }
+}
+
+impl GraphGrowth {
+    pub fn draw_growth(
+        &self,
+        canvas: HtmlCanvasElement,
+        sex: Sex,
+        weights: &[(u32, f64)],
+    ) -> DrawResult<()> {
+        let area = CanvasBackend::with_canvas_object(canvas)
+            .unwrap()
+            .into_drawing_area();
+        area.fill(&WHITE.mix(0.75))?;
+
+        // Up to a month past the last measurement, so the lines around it are readable
+        let last_day = match weights.last() {
+            Some((days, _)) => (days + 30).min(LAST_CHARTED_DAY),
+            None => 90,
+        };
+
+        let percentile_line = |z: f64| {
+            (0..=last_day).step_by(7).filter_map(move |days| {
+                let lms = growth::lms(GrowthIndicator::WeightForAge, sex, Age { days })?;
+                Some((days, lms.value_at(z)))
+            })
+        };
+
+        let max_weight = percentile_line(CHART_Z_SCORES[CHART_Z_SCORES.len() - 1])
+            .map(|(_, kg)| kg)
+            .chain(weights.iter().map(|(_, kg)| *kg))
+            .fold(0.0_f64, f64::max)
+            * 1.05;
+
+        let mut ctx = ChartBuilder::on(&area)
+            .set_label_area_size(LabelAreaPosition::Right, 42)
+            .set_label_area_size(LabelAreaPosition::Bottom, 22)
+            .build_cartesian_2d(0..last_day, 0.0..max_weight)
+            .unwrap();
+
+        ctx.configure_mesh()
+            .x_labels(6)
+            .x_label_formatter(&|days| format!("{}w", days / 7))
+            .y_label_formatter(&|kg| format!("{:.1} kg", kg))
+            .draw()
+            .unwrap();
+
+        for z in CHART_Z_SCORES {
+            let style = if z == 0.0 {
+                BLUE.stroke_width(2)
+            } else {
+                BLUE.mix(0.4).stroke_width(1)
+            };
+            ctx.draw_series(LineSeries::new(percentile_line(z), style))
+                .unwrap();
+        }
+
+        ctx.draw_series(LineSeries::new(
+            weights.iter().copied(),
+            RED.stroke_width(2),
+        ))
+        .unwrap()
+        .label("Weight")
+        .legend(move |(x, y)| Rectangle::new([(x, y - 6), (x + 12, y + 6)], RED.filled()));
+        ctx.draw_series(
+            weights
+                .iter()
+                .map(|point| Circle::new(*point, 3, RED.filled())),
+        )
+        .unwrap();
+
+        ctx.configure_series_labels()
+            .position(SeriesLabelPosition::UpperLeft)
+            .background_style(&WHITE.mix(0.75))
+            .border_style(&BLACK.mix(0.5))
+            .legend_area_size(22)
+            .draw()
+            .unwrap();
+
+        Ok(())
+    }
+}
@@ -1,2 +1,3 @@
 pub mod graph_feedings_individual;
 pub mod graph_feedings_individual_all_time;
+pub mod graph_growth;
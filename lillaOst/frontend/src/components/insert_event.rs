@@ -10,12 +10,12 @@ use web_sys::InputEvent;
 use yew::prelude::*;
 use yew_agent::{Dispatched, Dispatcher};
 
-use crate::event_bus::{EventBus, Request};
+use crate::event_bus::{BusMessage, EventBus, Request};
+use crate::store;
 use crate::web_sys_utils::text_area_get_value_from_input_event;
 
 use super::inputs::f64_input_box::F64InputBox;
 
-use ost::context_remote_async::AsyncRemoteMonolith;
 use ost::event::{Event as ost_Event, EventType as ost_EventType};
 use ost::person::Person as ost_Person;
 
@@ -36,7 +36,7 @@ pub enum MsgInsertEvent {
     UpdateNote { note: String },
     UpdateFloat(f64),
     AddEvent,
-    CallFinished,
+    CallFinished(Option<BusMessage>),
 }
 
 impl fmt::Display for EventType {
@@ -99,9 +99,8 @@ impl Component for InsertEvent {
                 let p_cloned = self.props.ost_person.as_ref().unwrap().clone();
                 let ev_cloned = self.ost_event_type.clone();
                 ctx.link().send_future(async move {
-                    let remote = AsyncRemoteMonolith {};
-                    let _ = remote.add_event(&p_cloned, ev_cloned).await;
-                    MsgInsertEvent::CallFinished
+                    let added = store::add_event(&p_cloned, ev_cloned).await.ok();
+                    MsgInsertEvent::CallFinished(added)
                 });
                 self.ost_event_type = ost_EventType::Note(String::default());
                 return true;
@@ -130,10 +129,10 @@ impl Component for InsertEvent {
                 };
                 self.update_existing_event();
             }
-            MsgInsertEvent::CallFinished => {
-                self.event_bus.send(Request::EventBusMsg(
-                    "Insert events, call finished".to_owned(),
-                ));
+            MsgInsertEvent::CallFinished(added) => {
+                if let Some(added) = added {
+                    self.event_bus.send(Request::EventBusMsg(added));
+                }
                 return false;
             }
         }
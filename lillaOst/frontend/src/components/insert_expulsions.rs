@@ -11,9 +11,9 @@ use web_sys::InputEvent;
 use yew::prelude::*;
 use yew_agent::{Dispatched, Dispatcher};
 
-use crate::event_bus::{EventBus, Request};
+use crate::event_bus::{BusMessage, EventBus, Request};
+use crate::store;
 
-use ost::context_remote_async::AsyncRemoteMonolith;
 use ost::expulsion::{Expulsion as ost_Expulsion, ExpulsionDegree};
 use ost::person::Person as ost_Person;
 
@@ -28,9 +28,12 @@ pub enum ExpulsionAmount {
 
 #[derive(Clone, Debug)]
 pub enum MsgInsertExpulsions {
-    SelectChanged { e: ExpulsionAmount },
+    SelectChanged {
+        e: ExpulsionAmount,
+    },
     AddExpulsion,
-    CallFinished,
+    /// The expulsion added, none when the call failed
+    CallFinished(Option<BusMessage>),
 }
 
 impl fmt::Display for ExpulsionAmount {
@@ -96,16 +99,15 @@ impl Component for InsertExpulsions {
                 let p_cloned = self.props.ost_person.as_ref().unwrap().clone();
                 let expulsion_cloned = self.as_expulsion_degree();
                 ctx.link().send_future(async move {
-                    let remote = AsyncRemoteMonolith {};
-                    let _ = remote.add_expulsion(&p_cloned, expulsion_cloned).await;
-                    MsgInsertExpulsions::CallFinished
+                    let added = store::add_expulsion(&p_cloned, expulsion_cloned).await.ok();
+                    MsgInsertExpulsions::CallFinished(added)
                 });
                 self.currently_selected = ExpulsionAmount::Pee;
             }
-            MsgInsertExpulsions::CallFinished => {
-                self.event_bus.send(Request::EventBusMsg(
-                    "Insert expulsions, call finished".to_owned(),
-                ));
+            MsgInsertExpulsions::CallFinished(added) => {
+                if let Some(added) = added {
+                    self.event_bus.send(Request::EventBusMsg(added));
+                }
                 self.currently_selected = ExpulsionAmount::Pee;
             }
         }
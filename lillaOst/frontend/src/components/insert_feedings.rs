@@ -5,9 +5,9 @@ use gloo_console::error;
 use yew::prelude::*;
 use yew_agent::{Dispatched, Dispatcher};
 
-use crate::event_bus::{EventBus, Request};
+use crate::event_bus::{BusMessage, EventBus, Request};
+use crate::store;
 
-use ost::context_remote_async::AsyncRemoteMonolith;
 use ost::feed::Feed as ost_Feed;
 use ost::person::Person as ost_Person;
 
@@ -19,7 +19,8 @@ pub enum MsgInsertFeedings {
     BreastMilkChanged(u32),
     FormulaChanged(u32),
     SolidsChanged(u32),
-    CallFinished,
+    /// The feeding added, none when the call failed
+    CallFinished(Option<BusMessage>),
 }
 
 #[derive(Clone, Properties)]
@@ -98,10 +99,10 @@ impl Component for InsertFeedings {
                 add_feeding(ctx, p, self.breast_milk, self.formula, self.solids);
                 self.reset_internal_state();
             }
-            MsgInsertFeedings::CallFinished => {
-                self.event_bus.send(Request::EventBusMsg(
-                    "Insert feedings, call finished".to_owned(),
-                ));
+            MsgInsertFeedings::CallFinished(added) => {
+                if let Some(added) = added {
+                    self.event_bus.send(Request::EventBusMsg(added));
+                }
                 return false;
             }
             MsgInsertFeedings::BreastMilkChanged(b) => {
@@ -194,10 +195,9 @@ fn add_feeding(
 ) {
     let p_cloned = person.clone();
     ctx.link().send_future(async move {
-        let remote_context = AsyncRemoteMonolith {};
-        let _ = remote_context
-            .add_feeding(&p_cloned, breast_milk, formula, solids)
-            .await;
-        MsgInsertFeedings::CallFinished
+        let added = store::add_feeding(&p_cloned, breast_milk, formula, solids)
+            .await
+            .ok();
+        MsgInsertFeedings::CallFinished(added)
     });
 }
@@ -0,0 +1,266 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use chrono::prelude::*;
+use gloo_console::error;
+use web_sys::InputEvent;
+use yew::prelude::*;
+use yew_agent::{Dispatched, Dispatcher};
+
+use crate::event_bus::{BusMessage, EventBus, Request};
+use crate::store;
+use crate::web_sys_utils::input_get_value_from_input_event;
+
+use ost::growth;
+use ost::measurement::{Measurement as ost_Measurement, Measures};
+use ost::person::Person as ost_Person;
+
+pub enum MsgInsertMeasurement {
+    AddMeasurement,
+    WeightChanged(String),
+    LengthChanged(String),
+    HeadCircumferenceChanged(String),
+    /// The person of an existing measurement, needed for the percentiles
+    PersonLoaded(Option<Rc<Box<dyn ost_Person>>>),
+    /// The measurement added, none when the call failed
+    CallFinished(Option<BusMessage>),
+}
+
+#[derive(Clone, Properties)]
+pub struct PropsInsertMeasurement {
+    pub ost_person: Option<Rc<Box<dyn ost_Person>>>,
+    pub ost_measurement: Option<Rc<RefCell<Box<dyn ost_Measurement>>>>,
+}
+
+impl PartialEq for PropsInsertMeasurement {
+    fn eq(&self, _other: &Self) -> bool {
+        false
+    }
+}
+
+/// Values are kept as typed, a field that doesn't parse counts as not measured
+pub struct InsertMeasurement {
+    weight: String,
+    length: String,
+    head_circumference: String,
+
+    person: Option<Rc<Box<dyn ost_Person>>>,
+    props: PropsInsertMeasurement,
+    is_new_insertion: bool,
+    event_bus: Dispatcher<EventBus>,
+}
+
+impl InsertMeasurement {
+    fn reset_internal_state(&mut self) {
+        self.weight = String::default();
+        self.length = String::default();
+        self.head_circumference = String::default();
+    }
+
+    fn measures(&self) -> Measures {
+        Measures {
+            weight: self.weight.trim().parse::<u32>().ok(),
+            length: self.length.trim().parse::<f64>().ok(),
+            head_circumference: self.head_circumference.trim().parse::<f64>().ok(),
+        }
+    }
+}
+
+impl Component for InsertMeasurement {
+    type Message = MsgInsertMeasurement;
+    type Properties = PropsInsertMeasurement;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let mut is_new_insertion = true;
+        let mut measures = Measures::default();
+
+        if let Some(existing_measurement) = ctx.props().ost_measurement.as_ref() {
+            measures = existing_measurement.borrow().measures();
+            is_new_insertion = false;
+
+            let person_key = existing_measurement.borrow().person_key();
+            ctx.link().send_future(async move {
+                MsgInsertMeasurement::PersonLoaded(store::person(person_key).await)
+            });
+        }
+
+        Self {
+            weight: measures.weight.map(|w| w.to_string()).unwrap_or_default(),
+            length: measures.length.map(|l| l.to_string()).unwrap_or_default(),
+            head_circumference: measures
+                .head_circumference
+                .map(|h| h.to_string())
+                .unwrap_or_default(),
+            person: ctx.props().ost_person.clone(),
+            props: ctx.props().clone(),
+            is_new_insertion,
+            event_bus: EventBus::dispatcher(),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            MsgInsertMeasurement::AddMeasurement => {
+                let measures = self.measures();
+                if measures.validate().is_err() {
+                    return false;
+                }
+                if self.props.ost_person.is_none() {
+                    return false;
+                }
+
+                let p_cloned = self.props.ost_person.as_ref().unwrap().clone();
+                ctx.link().send_future(async move {
+                    let added = store::add_measurement(&p_cloned, measures)
+                        .await
+                        .map_err(|e| error!(format!("Adding a measurement failed: {}", e)))
+                        .ok();
+                    MsgInsertMeasurement::CallFinished(added)
+                });
+                self.reset_internal_state();
+            }
+            MsgInsertMeasurement::CallFinished(added) => {
+                if let Some(added) = added {
+                    self.event_bus.send(Request::EventBusMsg(added));
+                }
+                return false;
+            }
+            MsgInsertMeasurement::PersonLoaded(person) => {
+                self.person = person;
+            }
+            MsgInsertMeasurement::WeightChanged(w) => {
+                self.weight = w;
+                self.update_existing_measurement();
+            }
+            MsgInsertMeasurement::LengthChanged(l) => {
+                self.length = l;
+                self.update_existing_measurement();
+            }
+            MsgInsertMeasurement::HeadCircumferenceChanged(h) => {
+                self.head_circumference = h;
+                self.update_existing_measurement();
+            }
+        }
+        true
+    }
+
+    fn changed(&mut self, ctx: &Context<Self>) -> bool {
+        self.props = ctx.props().clone();
+        if self.is_new_insertion {
+            self.person = ctx.props().ost_person.clone();
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let has_data = self.measures().validate().is_ok();
+        let add_new_measurement_button = if self.is_new_insertion {
+            html! {
+                <div class="field is-grouped">
+                <div class="control">
+                    <button
+                        class={ if has_data { "button is-link" } else { "button is-dark" } }
+                        onclick={ctx.link().callback(|_| MsgInsertMeasurement::AddMeasurement)} >
+                            {"Add Measurement"}
+                    </button>
+                </div>
+            </div>
+            }
+        } else {
+            html! {}
+        };
+
+        html! {
+        <div class="block">
+            { self.text_field("weight", "Weight (g)", &self.weight,
+                ctx.link().callback(|e: InputEvent| MsgInsertMeasurement::WeightChanged(input_get_value_from_input_event(e)))) }
+            { self.text_field("length", "Length (cm)", &self.length,
+                ctx.link().callback(|e: InputEvent| MsgInsertMeasurement::LengthChanged(input_get_value_from_input_event(e)))) }
+            { self.text_field("head_circumference", "Head circumference (cm)", &self.head_circumference,
+                ctx.link().callback(|e: InputEvent| MsgInsertMeasurement::HeadCircumferenceChanged(input_get_value_from_input_event(e)))) }
+
+            { self.assessments() }
+
+            {add_new_measurement_button}
+        </div>
+        }
+    }
+}
+
+impl InsertMeasurement {
+    fn update_existing_measurement(&mut self) {
+        if self.props.ost_measurement.is_none() {
+            return;
+        }
+
+        let measures = self.measures();
+        if measures.validate().is_err() {
+            return;
+        }
+
+        if let Some(measurement) = &self.props.ost_measurement {
+            #[allow(clippy::clone_on_copy)]
+            let ts = measurement.borrow().time_stamp().clone();
+            measurement.borrow_mut().modify_measurement(measures, ts);
+        } else {
+            error!("Trying to modify a non existent measurement?");
+        }
+    }
+
+    fn text_field(
+        &self,
+        id: &str,
+        label: &str,
+        value: &str,
+        oninput: Callback<InputEvent>,
+    ) -> Html {
+        html! {
+            <div class="field">
+                <label class="label">{ label }</label>
+                <div class="control">
+                    <input
+                        class="input"
+                        type="number"
+                        step="any"
+                        min="0"
+                        id={format!("{}_measurement_input_field", id)}
+                        value={ value.to_string() }
+                        oninput={ oninput }
+                    />
+                </div>
+            </div>
+        }
+    }
+
+    /// WHO percentiles, when the person has a sex and a birth date and is in the first year
+    fn assessments(&self) -> Html {
+        let person = match self.person.as_ref() {
+            Some(person) => person,
+            None => return html!(),
+        };
+        let sex = match person.sex() {
+            Some(sex) => sex,
+            None => return html!(),
+        };
+        let measured_on = match &self.props.ost_measurement {
+            Some(measurement) => measurement
+                .borrow()
+                .time_stamp()
+                .with_timezone(&Local)
+                .date()
+                .naive_local(),
+            None => Local::today().naive_local(),
+        };
+        let age = match person.age_on(measured_on) {
+            Some(age) => age,
+            None => return html!(),
+        };
+
+        let assessments = growth::assess(sex, age, &self.measures());
+        html! {
+            <div class="field tags">
+                { for assessments.iter().map(|a| html! { <span class="tag is-info">{ a.to_string() }</span> }) }
+            </div>
+        }
+    }
+}
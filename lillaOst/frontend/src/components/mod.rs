@@ -6,6 +6,9 @@ pub mod inputs;
 pub mod insert_event;
 pub mod insert_expulsions;
 pub mod insert_feedings;
+pub mod insert_measurement;
+pub mod nursing_timer;
+pub mod person_tag;
 pub mod quick_insert;
 pub mod settings_individuals;
 pub mod summary_expulsions;
@@ -13,3 +16,4 @@ pub mod summary_feeding;
 pub mod summary_on_date;
 pub mod summary_person_expulsions_on_date;
 pub mod summary_person_feedings_on_date;
+pub mod sync_status;
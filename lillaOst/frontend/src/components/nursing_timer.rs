@@ -0,0 +1,273 @@
+use std::rc::Rc;
+
+use chrono::prelude::*;
+use chrono::Duration;
+use gloo_console::error;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use yew::prelude::*;
+use yew_agent::{Dispatched, Dispatcher};
+
+use crate::event_bus::{BusMessage, EventBus, Request};
+use crate::store;
+
+use ost::nursing::{BreastSide, RunningNursing};
+use ost::offline;
+use ost::person::Person as ost_Person;
+use ost::person_key::OstPersonKey;
+
+const TICK_EVERY_MS: i32 = 1_000;
+
+pub enum MsgNursingTimer {
+    Start(BreastSide),
+    SwitchSide,
+    Stop,
+    Discard,
+    Tick,
+    SuggestionLoaded(Option<BreastSide>),
+    /// The feed added, none when the call failed
+    CallFinished(Option<BusMessage>),
+}
+
+#[derive(Clone, Properties)]
+pub struct PropsNursingTimer {
+    pub ost_person: Option<Rc<Box<dyn ost_Person>>>,
+}
+
+impl PartialEq for PropsNursingTimer {
+    fn eq(&self, other: &Self) -> bool {
+        self.ost_person.as_ref().map(|p| p.key()) == other.ost_person.as_ref().map(|p| p.key())
+    }
+}
+
+/// Start, switch and stop a nursing session. The running session is kept in local storage
+/// on every change, a reload or another tab picks it up where it was.
+pub struct NursingTimer {
+    running: Option<RunningNursing>,
+    suggested: Option<BreastSide>,
+    now: DateTime<Utc>,
+
+    props: PropsNursingTimer,
+    event_bus: Dispatcher<EventBus>,
+    tick_handle: Option<i32>,
+    _on_tick: Closure<dyn FnMut()>,
+}
+
+impl NursingTimer {
+    fn person_key(&self) -> Option<OstPersonKey> {
+        self.props.ost_person.as_ref().map(|p| p.key())
+    }
+
+    /// The running session and the suggestion of the person now shown
+    fn load(&mut self, ctx: &Context<Self>) {
+        let person_key = match self.person_key() {
+            Some(person_key) => person_key,
+            None => {
+                self.running = None;
+                self.suggested = None;
+                return;
+            }
+        };
+        self.running = offline::load_running_nursing()
+            .into_iter()
+            .find(|r| r.person_key == person_key);
+        ctx.link().send_future(async move {
+            MsgNursingTimer::SuggestionLoaded(store::next_nursing_side(person_key).await)
+        });
+    }
+
+    fn persist(&self) {
+        if let Some(person_key) = self.person_key() {
+            if let Err(e) = offline::store_running_nursing(person_key, self.running.as_ref()) {
+                error!(format!("Keeping the nursing timer failed: {}", e));
+            }
+        }
+    }
+}
+
+impl Component for NursingTimer {
+    type Message = MsgNursingTimer;
+    type Properties = PropsNursingTimer;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let link = ctx.link().clone();
+        let on_tick = Closure::wrap(
+            Box::new(move || link.send_message(MsgNursingTimer::Tick)) as Box<dyn FnMut()>
+        );
+        let tick_handle = gloo_utils::window()
+            .set_interval_with_callback_and_timeout_and_arguments_0(
+                on_tick.as_ref().unchecked_ref(),
+                TICK_EVERY_MS,
+            )
+            .ok();
+
+        let mut timer = Self {
+            running: None,
+            suggested: None,
+            now: Utc::now(),
+            props: ctx.props().clone(),
+            event_bus: EventBus::dispatcher(),
+            tick_handle,
+            _on_tick: on_tick,
+        };
+        timer.load(ctx);
+        timer
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        self.now = Utc::now();
+        match msg {
+            MsgNursingTimer::Start(side) => {
+                if let Some(person_key) = self.person_key() {
+                    self.running = Some(RunningNursing::start(person_key, side, self.now));
+                    self.persist();
+                }
+            }
+            MsgNursingTimer::SwitchSide => {
+                if let Some(running) = self.running.as_mut() {
+                    running.switch_side(self.now);
+                    self.persist();
+                }
+            }
+            MsgNursingTimer::Stop => {
+                let (person, running) = match (&self.props.ost_person, self.running.take()) {
+                    (Some(person), Some(running)) => (person.clone(), running),
+                    _ => return false,
+                };
+                self.persist();
+                let nursing = running.finish(self.now);
+                self.suggested = nursing.suggested_next_side();
+                ctx.link().send_future(async move {
+                    let added = store::add_nursing(&person, nursing)
+                        .await
+                        .map_err(|e| error!(format!("Adding a nursing session failed: {}", e)))
+                        .ok();
+                    MsgNursingTimer::CallFinished(added)
+                });
+            }
+            MsgNursingTimer::Discard => {
+                self.running = None;
+                self.persist();
+            }
+            MsgNursingTimer::Tick => return self.running.is_some(),
+            MsgNursingTimer::SuggestionLoaded(suggested) => {
+                self.suggested = suggested;
+            }
+            MsgNursingTimer::CallFinished(added) => {
+                if let Some(added) = added {
+                    self.event_bus.send(Request::EventBusMsg(added));
+                }
+                return false;
+            }
+        }
+        true
+    }
+
+    fn changed(&mut self, ctx: &Context<Self>) -> bool {
+        self.props = ctx.props().clone();
+        self.load(ctx);
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let running = match &self.running {
+            Some(running) => running,
+            None => return self.view_idle(ctx),
+        };
+
+        html! {
+        <div class="block">
+            <div class="field is-grouped">
+                { self.side_clock(running, BreastSide::Left) }
+                { self.side_clock(running, BreastSide::Right) }
+            </div>
+            <div class="field is-grouped">
+                <div class="control">
+                    <button class="button is-link"
+                        onclick={ctx.link().callback(|_| MsgNursingTimer::SwitchSide)}>
+                        { format!("Switch to {}", running.side.other()) }
+                    </button>
+                </div>
+                <div class="control">
+                    <button class="button is-success"
+                        onclick={ctx.link().callback(|_| MsgNursingTimer::Stop)}>
+                        { "Stop & save" }
+                    </button>
+                </div>
+                <div class="control">
+                    <button class="button is-light"
+                        onclick={ctx.link().callback(|_| MsgNursingTimer::Discard)}>
+                        { "Discard" }
+                    </button>
+                </div>
+            </div>
+        </div>
+        }
+    }
+
+    fn destroy(&mut self, _ctx: &Context<Self>) {
+        if let Some(handle) = self.tick_handle.take() {
+            gloo_utils::window().clear_interval_with_handle(handle);
+        }
+    }
+}
+
+impl NursingTimer {
+    /// Both sides to start on, the suggested one stands out
+    fn view_idle(&self, ctx: &Context<Self>) -> Html {
+        let mut suggestion = html!();
+        if let Some(side) = self.suggested {
+            suggestion = html! {
+                <p class="help">{ format!("Start on the {} side", side.to_string().to_lowercase()) }</p>
+            };
+        }
+
+        let start_button = |side: BreastSide| {
+            let class = if self.suggested == Some(side) {
+                "button is-link"
+            } else {
+                "button is-dark"
+            };
+            html! {
+                <div class="control">
+                    <button class={class} onclick={ctx.link().callback(move |_| MsgNursingTimer::Start(side))}>
+                        { format!("Start {}", side) }
+                    </button>
+                </div>
+            }
+        };
+
+        html! {
+        <div class="block">
+            <div class="field is-grouped">
+                { start_button(BreastSide::Left) }
+                { start_button(BreastSide::Right) }
+            </div>
+            { suggestion }
+        </div>
+        }
+    }
+
+    fn side_clock(&self, running: &RunningNursing, side: BreastSide) -> Html {
+        let class = if running.side == side {
+            "tag is-large is-link"
+        } else {
+            "tag is-large is-light"
+        };
+        html! {
+            <div class="control">
+                <span class={class}>
+                    { format!("{} {}", side, clock(running.elapsed_on(side, self.now))) }
+                </span>
+            </div>
+        }
+    }
+}
+
+fn clock(elapsed: Duration) -> String {
+    format!(
+        "{:02}:{:02}",
+        elapsed.num_minutes(),
+        elapsed.num_seconds() % 60
+    )
+}
@@ -0,0 +1,36 @@
+use chrono::prelude::*;
+use yew::prelude::*;
+
+use ost::person::{Age, Person};
+
+/// Today's age, none without a birth date
+pub fn age_today(person: &dyn Person) -> Option<Age> {
+    person.age_on(Local::today().naive_local())
+}
+
+/// Like `Zardoz, week 6`, for captions that only take text
+pub fn person_label(person: &dyn Person) -> String {
+    match age_today(person) {
+        Some(age) => format!("{}, {}", person.name(), age),
+        None => person.name().to_string(),
+    }
+}
+
+/// The name in the person's colour, followed by the age
+pub fn person_tag(person: &dyn Person) -> Html {
+    let mut age = html!();
+    if let Some(age_today) = age_today(person) {
+        age = html! { <span class="tag is-light ml-2">{ age_today.to_string() }</span> };
+    }
+    let style = person
+        .colour()
+        .map(|colour| format!("color: {}", colour))
+        .unwrap_or_default();
+
+    html! {
+        <>
+            <span style={style}>{ person.name() }</span>
+            { age }
+        </>
+    }
+}
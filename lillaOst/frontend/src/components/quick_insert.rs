@@ -5,8 +5,10 @@ use yew::prelude::*;
 use super::insert_event::InsertEvent;
 use super::insert_expulsions::InsertExpulsions;
 use super::insert_feedings::InsertFeedings;
+use super::insert_measurement::InsertMeasurement;
+use super::nursing_timer::NursingTimer;
+use crate::store;
 
-use ost::context_remote_async::AsyncRemoteMonolith;
 use ost::person::Person as ost_Person;
 
 #[derive(PartialEq)]
@@ -14,6 +16,8 @@ pub enum QuickInsertMode {
     Event,
     Expulsion,
     Feeds,
+    Growth,
+    Nursing,
 }
 
 pub enum MsgQuickInsert {
@@ -35,17 +39,8 @@ impl Component for QuickInsert {
     type Properties = ();
 
     fn create(ctx: &Context<Self>) -> Self {
-        ctx.link().send_future(async {
-            let remote = AsyncRemoteMonolith {};
-            let persons = remote
-                .persons()
-                .await
-                .drain(..)
-                .filter(|p| p.is_active())
-                .map(Rc::new)
-                .collect();
-            MsgQuickInsert::PersonsLoaded(persons)
-        });
+        ctx.link()
+            .send_future(async { MsgQuickInsert::PersonsLoaded(store::active_persons().await) });
 
         Self {
             currently_selected: QuickInsertMode::Feeds,
@@ -131,6 +126,18 @@ impl Component for QuickInsert {
             None
         };
 
+        let growth_class: Option<&str> = if self.currently_selected == QuickInsertMode::Growth {
+            Some("is-active")
+        } else {
+            None
+        };
+
+        let nursing_class: Option<&str> = if self.currently_selected == QuickInsertMode::Nursing {
+            Some("is-active")
+        } else {
+            None
+        };
+
         let to_prop_active_person = self.active_person.as_ref().unwrap().clone();
 
         let active_form = match self.currently_selected {
@@ -143,6 +150,12 @@ impl Component for QuickInsert {
             QuickInsertMode::Event => {
                 html! { < InsertEvent  ost_person={Some(to_prop_active_person)} /> }
             }
+            QuickInsertMode::Growth => {
+                html! { < InsertMeasurement ost_person={Some(to_prop_active_person)} /> }
+            }
+            QuickInsertMode::Nursing => {
+                html! { < NursingTimer ost_person={Some(to_prop_active_person)} /> }
+            }
         };
 
         html! {
@@ -153,8 +166,10 @@ impl Component for QuickInsert {
                 <div class="tabs is-toggle is-fullwidth ">
                     <ul>
                         <li class={classes!(feeds_class)}><a onclick={ctx.link().callback(|_| MsgQuickInsert::ModeSelection(QuickInsertMode::Feeds))} >{"Feeds"}</a></li>
+                        <li class={classes!(nursing_class)}><a onclick={ctx.link().callback(|_| MsgQuickInsert::ModeSelection(QuickInsertMode::Nursing))}>{"Nursing"}</a></li>
                         <li class={classes!(expulsion_class)}><a onclick={ctx.link().callback(|_| MsgQuickInsert::ModeSelection(QuickInsertMode::Expulsion))}>{"Nappy"}</a></li>
                         <li class={classes!(event_class)}><a onclick={ctx.link().callback(|_| MsgQuickInsert::ModeSelection(QuickInsertMode::Event))}>{"Event"}</a></li>
+                        <li class={classes!(growth_class)}><a onclick={ctx.link().callback(|_| MsgQuickInsert::ModeSelection(QuickInsertMode::Growth))}>{"Growth"}</a></li>
                     </ul>
                 </div>
                 { active_form }
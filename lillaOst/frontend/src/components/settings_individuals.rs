@@ -1,30 +1,71 @@
 use std::rc::Rc;
 
+use chrono::NaiveDate;
 use web_sys::InputEvent;
 use yew::prelude::*;
+use yew_agent::{Bridge, Bridged};
 
-use ost::context_remote_async::AsyncRemoteMonolith;
-use ost::person::Person as ost_Person;
+use ost::person::{Person as ost_Person, Sex};
 
 use ost::person_key::OstPersonKey;
 
-use crate::web_sys_utils::input_get_value_from_input_event;
+use crate::components::person_tag::age_today;
+use crate::context::context;
+use crate::event_bus::{BusMessage, EventBus, Request};
+use crate::store;
+use crate::web_sys_utils::{input_get_value_from_input_event, select_get_value_from_input_event};
+
+/// Colour of the picker while a person has none
+const DEFAULT_COLOUR: &str = "#3273dc";
+
+#[derive(Clone)]
+pub enum ProfileChange {
+    BirthDate(Option<NaiveDate>),
+    Sex(Option<Sex>),
+    BirthWeight(Option<u32>),
+    Colour(Option<String>),
+}
 
 #[derive(Clone)]
 pub enum MsgSettingsIndividuals {
     CreateNewPerson,
-    DataModified,
-    DeactivatePerson { p_key: OstPersonKey },
+    DataModified(BusMessage),
+    DeactivatePerson {
+        p_key: OstPersonKey,
+    },
+    MergePersons {
+        from: OstPersonKey,
+        into: OstPersonKey,
+    },
+    OperationFailed(String),
     PersonsLoaded(Vec<Rc<Box<dyn ost_Person>>>),
-    ReactivatePerson { p_key: OstPersonKey },
-    UpdateInputName { name: String, p_key: OstPersonKey },
-    UpdateNewName { name: String },
+    ReactivatePerson {
+        p_key: OstPersonKey,
+    },
+    RemovePerson {
+        p_key: OstPersonKey,
+        cascade: bool,
+    },
+    UpdateInputName {
+        name: String,
+        p_key: OstPersonKey,
+    },
+    UpdateNewName {
+        name: String,
+    },
+    UpdateProfile {
+        p_key: OstPersonKey,
+        change: ProfileChange,
+    },
+    StorageChanged(BusMessage),
 }
 
 pub struct SettingsIndividuals {
     new_person_name: String,
     persons: Vec<Rc<Box<dyn ost_Person>>>,
     is_loaded: bool,
+    error_message: Option<String>,
+    event_bus: Box<dyn Bridge<EventBus>>,
 }
 
 impl Component for SettingsIndividuals {
@@ -35,8 +76,12 @@ impl Component for SettingsIndividuals {
         load_persons(ctx);
         Self {
             is_loaded: false,
+            error_message: None,
             new_person_name: "".to_string(),
             persons: vec![],
+            event_bus: EventBus::bridge(
+                ctx.link().callback(MsgSettingsIndividuals::StorageChanged),
+            ),
         }
     }
 
@@ -54,9 +99,13 @@ impl Component for SettingsIndividuals {
                 let new_name = self.new_person_name.trim().to_string();
 
                 ctx.link().send_future(async move {
-                    let remote = AsyncRemoteMonolith {};
-                    let _ = remote.add_person(&new_name).await;
-                    MsgSettingsIndividuals::DataModified
+                    let ost = context();
+                    match ost.add_person(&new_name).await {
+                        Ok(p) => {
+                            MsgSettingsIndividuals::DataModified(BusMessage::PersonAdded(p.key()))
+                        }
+                        Err(err) => MsgSettingsIndividuals::OperationFailed(err.to_string()),
+                    }
                 });
 
                 self.new_person_name.clear();
@@ -64,43 +113,99 @@ impl Component for SettingsIndividuals {
             }
             MsgSettingsIndividuals::DeactivatePerson { p_key } => {
                 ctx.link().send_future(async move {
-                    let remote = AsyncRemoteMonolith {};
+                    let ost = context();
 
-                    let p = remote.get_person_by_key(p_key).await;
+                    let p = ost.get_person_by_key(p_key).await;
 
                     if let Some(mut p) = p {
                         p.set_is_active(false);
-                        let _ = remote.modify_person(&p).await;
+                        let _ = ost.modify_person(&p).await;
                     }
-                    MsgSettingsIndividuals::DataModified
+                    MsgSettingsIndividuals::DataModified(BusMessage::PersonModified(p_key))
                 });
                 return false;
             }
             MsgSettingsIndividuals::ReactivatePerson { p_key } => {
                 ctx.link().send_future(async move {
-                    let remote = AsyncRemoteMonolith {};
+                    let ost = context();
 
-                    let p = remote.get_person_by_key(p_key).await;
+                    let p = ost.get_person_by_key(p_key).await;
 
                     if let Some(mut p) = p {
                         p.set_is_active(true);
-                        let _ = remote.modify_person(&p).await;
+                        let _ = ost.modify_person(&p).await;
+                    }
+                    MsgSettingsIndividuals::DataModified(BusMessage::PersonModified(p_key))
+                });
+                return false;
+            }
+            MsgSettingsIndividuals::RemovePerson { p_key, cascade } => {
+                ctx.link().send_future(async move {
+                    let ost = context();
+
+                    let p = ost.get_person_by_key(p_key).await;
+
+                    if let Some(p) = p {
+                        if let Err(err) = ost.remove_person(p, cascade).await {
+                            return MsgSettingsIndividuals::OperationFailed(err.to_string());
+                        }
+                    }
+                    MsgSettingsIndividuals::DataModified(BusMessage::PersonRemoved(p_key))
+                });
+                return false;
+            }
+            MsgSettingsIndividuals::MergePersons { from, into } => {
+                ctx.link().send_future(async move {
+                    let ost = context();
+
+                    let merged = ost.get_person_by_key(from).await;
+                    let into = ost.get_person_by_key(into).await;
+
+                    if let (Some(merged), Some(into)) = (merged, into) {
+                        if let Err(err) = ost.merge_persons(merged, &into).await {
+                            return MsgSettingsIndividuals::OperationFailed(err.to_string());
+                        }
                     }
-                    MsgSettingsIndividuals::DataModified
+                    // The merged entries changed hands too
+                    MsgSettingsIndividuals::DataModified(BusMessage::PersonRemoved(from))
                 });
                 return false;
             }
+            MsgSettingsIndividuals::OperationFailed(err) => {
+                self.error_message = Some(err);
+            }
             MsgSettingsIndividuals::UpdateInputName { p_key, name } => {
                 ctx.link().send_future(async move {
-                    let remote = AsyncRemoteMonolith {};
+                    let ost = context();
 
-                    let p = remote.get_person_by_key(p_key).await;
+                    let p = ost.get_person_by_key(p_key).await;
 
                     if let Some(mut p) = p {
                         p.set_name(&name);
-                        let _ = remote.modify_person(&p).await;
+                        let _ = ost.modify_person(&p).await;
                     }
-                    MsgSettingsIndividuals::DataModified
+                    MsgSettingsIndividuals::DataModified(BusMessage::PersonModified(p_key))
+                });
+                return false;
+            }
+            MsgSettingsIndividuals::UpdateProfile { p_key, change } => {
+                ctx.link().send_future(async move {
+                    let ost = context();
+
+                    let p = ost.get_person_by_key(p_key).await;
+
+                    if let Some(mut p) = p {
+                        match change {
+                            ProfileChange::BirthDate(birth_date) => p.set_birth_date(birth_date),
+                            ProfileChange::Sex(sex) => p.set_sex(sex),
+                            ProfileChange::BirthWeight(grams) => p.set_birth_weight(grams),
+                            ProfileChange::Colour(colour) => p.set_colour(colour.as_deref()),
+                        }
+                        if let Err(err) = ost.modify_person(&p).await {
+                            return MsgSettingsIndividuals::OperationFailed(err.to_string());
+                        }
+                    }
+                    MsgSettingsIndividuals::DataModified(BusMessage::PersonModified(p_key))
                 });
                 return false;
             }
@@ -108,8 +213,16 @@ impl Component for SettingsIndividuals {
                 self.persons = p;
                 self.is_loaded = true;
             }
-            MsgSettingsIndividuals::DataModified => {
-                load_persons(ctx);
+            MsgSettingsIndividuals::DataModified(change) => {
+                self.error_message = None;
+                // Comes back on the bridge once the store forgot the persons
+                self.event_bus.send(Request::EventBusMsg(change));
+                return false;
+            }
+            MsgSettingsIndividuals::StorageChanged(change) => {
+                if change.touches_persons() {
+                    load_persons(ctx);
+                }
                 return false;
             }
         }
@@ -165,6 +278,13 @@ impl Component for SettingsIndividuals {
             };
         }
 
+        let mut error_message = html!();
+        if let Some(err) = &self.error_message {
+            error_message = html! {
+                <div class="notification is-danger">{err}</div>
+            };
+        }
+
         html! {
         <div class="block">
             <div class="card">
@@ -187,6 +307,7 @@ impl Component for SettingsIndividuals {
                             </div>
                         </div>
                     </div>
+                    {error_message}
                     {active_individuals}
                     {deactivated_individiuals}
                 </div>
@@ -203,7 +324,9 @@ impl SettingsIndividuals {
         }
         let person_name = person.name().to_string();
         let person_to_deactivate = person.clone();
+        let profile = self.profile_entry(&person, ctx);
         html! {
+            <>
             <div class="field is-horizontal">
                 <div class="field-body">
                     <div class="field">
@@ -217,6 +340,85 @@ impl SettingsIndividuals {
                     </div>
                 </div>
             </div>
+            { profile }
+            </>
+        }
+    }
+
+    /// Birth date, sex, birth weight and colour, each saved as soon as it changes
+    fn profile_entry(&self, person: &Rc<Box<dyn ost_Person>>, ctx: &Context<Self>) -> Html {
+        let p_key = person.key();
+        let birth_date = person
+            .birth_date()
+            .map(|date| date.format("%Y-%m-%d").to_string())
+            .unwrap_or_default();
+        let birth_weight = person
+            .birth_weight()
+            .map(|grams| grams.to_string())
+            .unwrap_or_default();
+        let colour = person.colour().unwrap_or(DEFAULT_COLOUR).to_string();
+        let sex = person.sex();
+
+        let mut age = html!();
+        if let Some(age_today) = age_today(&***person) {
+            age = html! {
+                <span class="tag is-light">{ format!("{} days, {}", age_today.days, age_today) }</span>
+            };
+        }
+
+        html! {
+            <div class="field is-horizontal">
+                <div class="field-body">
+                    <div class="field">
+                        <input class="input" type="date" title="Birth date" value={birth_date}
+                            oninput={ctx.link().callback(move |e: InputEvent| MsgSettingsIndividuals::UpdateProfile {
+                                p_key,
+                                change: ProfileChange::BirthDate(NaiveDate::parse_from_str(&input_get_value_from_input_event(e), "%Y-%m-%d").ok()),
+                            })}
+                        />
+                    </div>
+                    <div class="field">
+                        <div class="select">
+                            <select title="Sex" oninput={ctx.link().callback(move |e: InputEvent| MsgSettingsIndividuals::UpdateProfile {
+                                p_key,
+                                change: ProfileChange::Sex(match select_get_value_from_input_event(e).as_str() {
+                                    "Female" => Some(Sex::Female),
+                                    "Male" => Some(Sex::Male),
+                                    _ => None,
+                                }),
+                            })}>
+                                <option value="" selected={sex.is_none()}>{"Sex"}</option>
+                                <option value="Female" selected={sex == Some(Sex::Female)}>{"Female"}</option>
+                                <option value="Male" selected={sex == Some(Sex::Male)}>{"Male"}</option>
+                            </select>
+                        </div>
+                    </div>
+                    <div class="field has-addons">
+                        <div class="control">
+                            <input class="input" type="number" min="0" placeholder="Birth weight" value={birth_weight}
+                                oninput={ctx.link().callback(move |e: InputEvent| MsgSettingsIndividuals::UpdateProfile {
+                                    p_key,
+                                    change: ProfileChange::BirthWeight(input_get_value_from_input_event(e).trim().parse::<u32>().ok()),
+                                })}
+                            />
+                        </div>
+                        <div class="control">
+                            <a class="button is-static">{"g"}</a>
+                        </div>
+                    </div>
+                    <div class="field">
+                        <input class="input" type="color" title="Colour" value={colour}
+                            oninput={ctx.link().callback(move |e: InputEvent| MsgSettingsIndividuals::UpdateProfile {
+                                p_key,
+                                change: ProfileChange::Colour(Some(input_get_value_from_input_event(e))),
+                            })}
+                        />
+                    </div>
+                    <div class="control">
+                        { age }
+                    </div>
+                </div>
+            </div>
         }
     }
 
@@ -227,6 +429,8 @@ impl SettingsIndividuals {
 
         let person_name = person.name().to_string();
         let person_to_activate = person.clone();
+        let person_to_remove = person.key();
+        let person_to_merge = person.key();
 
         html! {
             <div class="field is-horizontal">
@@ -239,6 +443,29 @@ impl SettingsIndividuals {
                     <div class="control">
                         <button class="button is-link" onclick={ctx.link().callback( move |_| MsgSettingsIndividuals::ReactivatePerson{ p_key: person_to_activate.key() } ) }  >{"Re Activate"}</button>
                     </div>
+                    <div class="control">
+                        <div class="select">
+                            <select oninput={ ctx.link().batch_callback( move |e: InputEvent| {
+                                select_get_value_from_input_event(e)
+                                    .parse::<u32>()
+                                    .ok()
+                                    .map(|id| MsgSettingsIndividuals::MergePersons { from: person_to_merge, into: OstPersonKey { id } })
+                            }) }>
+                                <option value="" selected=true>{"Merge into"}</option>
+                                {
+                                    for self.persons.iter().filter(|p| p.key() != person_to_merge).map(|p| html! {
+                                        <option value={p.key().id.to_string()}>{p.name()}</option>
+                                    })
+                                }
+                            </select>
+                        </div>
+                    </div>
+                    <div class="control">
+                        <button class="button is-danger" onclick={ctx.link().callback( move |_| MsgSettingsIndividuals::RemovePerson{ p_key: person_to_remove, cascade: false } ) } >{"Remove"}</button>
+                    </div>
+                    <div class="control">
+                        <button class="button is-danger is-outlined" onclick={ctx.link().callback( move |_| MsgSettingsIndividuals::RemovePerson{ p_key: person_to_remove, cascade: true } ) } >{"Remove with entries"}</button>
+                    </div>
                 </div>
             </div>
         }
@@ -246,9 +473,6 @@ impl SettingsIndividuals {
 }
 
 fn load_persons(ctx: &Context<SettingsIndividuals>) {
-    ctx.link().send_future(async {
-        let remote = AsyncRemoteMonolith {};
-        let persons = remote.persons().await.drain(..).map(Rc::new).collect();
-        MsgSettingsIndividuals::PersonsLoaded(persons)
-    });
+    ctx.link()
+        .send_future(async { MsgSettingsIndividuals::PersonsLoaded(store::persons().await) });
 }
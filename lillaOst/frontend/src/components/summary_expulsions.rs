@@ -5,8 +5,10 @@ use chrono::prelude::*;
 use yew::prelude::*;
 use yew_agent::{Bridge, Bridged};
 
-use crate::event_bus::EventBus;
-use ost::context_remote_async::AsyncRemoteMonolith;
+use crate::components::person_tag::person_tag;
+use crate::event_bus::{BusMessage, EventBus};
+use crate::store;
+use ost::event_key::EventType;
 use ost::person::Person;
 use ost::person_key::OstPersonKey;
 
@@ -22,7 +24,7 @@ pub enum MsgSummaryExpulsions {
         feeding_summary: CompExpulsion,
     },
     PersonsLoaded(Vec<Rc<Box<dyn Person>>>),
-    RemoteDataChanged(String),
+    RemoteDataChanged(BusMessage),
 }
 
 pub struct SummaryExpulsions {
@@ -48,44 +50,25 @@ impl Component for SummaryExpulsions {
 
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
-            MsgSummaryExpulsions::RemoteDataChanged(_) => {
-                request_active_persons(ctx);
+            MsgSummaryExpulsions::RemoteDataChanged(change) => {
+                if !change.touches(EventType::Expulsion) {
+                    return false;
+                }
+                // A new expulsion only moves the counts of its person
+                let person = change
+                    .person()
+                    .and_then(|key| self.active_persons.iter().find(|p| p.key() == key));
+                match person {
+                    Some(person) => request_expulsions_for(ctx, person.clone()),
+                    None => request_active_persons(ctx),
+                }
                 return false;
             }
             MsgSummaryExpulsions::PersonsLoaded(mut persons) => {
                 self.active_persons = persons.clone();
-                persons.drain(..).for_each(|p| {
-                    let cloned_p = p.clone();
-                    ctx.link().send_future(async move {
-                        let remote = AsyncRemoteMonolith {};
-                        let expulsions = remote.expulsions_by(&cloned_p).await;
-                        let today = Utc::today();
-
-                        let mut summary_expulsions = CompExpulsion::default();
-                        expulsions
-                            .iter()
-                            .filter(|f| f.time_stamp().date() == today)
-                            .for_each(|exp| {
-                                summary_expulsions.total += 1;
-                                match exp.degree() {
-                                    ost::expulsion::ExpulsionDegree::Shart => {
-                                        summary_expulsions.solid_expulsion += 1
-                                    }
-                                    ost::expulsion::ExpulsionDegree::Poopies => {
-                                        summary_expulsions.solid_expulsion += 1
-                                    }
-                                    ost::expulsion::ExpulsionDegree::Pooplosion => {
-                                        summary_expulsions.solid_expulsion += 1
-                                    }
-                                    _ => {}
-                                }
-                            });
-                        MsgSummaryExpulsions::ExpulsionForPerson {
-                            person_key: p.key(),
-                            feeding_summary: summary_expulsions,
-                        }
-                    });
-                });
+                persons
+                    .drain(..)
+                    .for_each(|p| request_expulsions_for(ctx, p));
             }
             MsgSummaryExpulsions::ExpulsionForPerson {
                 person_key,
@@ -137,7 +120,7 @@ impl SummaryExpulsions {
             html! {
                 <tr>
                     <td>
-                        { person.name() }
+                        { person_tag(&**person) }
                     </td>
                     <td>
                         { summary_expulsion.solid_expulsion }
@@ -151,7 +134,7 @@ impl SummaryExpulsions {
             html! {
                 <tr>
                     <td>
-                        { person.name() }
+                        { person_tag(&**person) }
                     </td>
                     <td>
                         { "loading" }
@@ -165,16 +148,38 @@ impl SummaryExpulsions {
     }
 }
 
-fn request_active_persons(ctx: &Context<SummaryExpulsions>) {
-    ctx.link().send_future(async {
-        let remote = AsyncRemoteMonolith {};
-        let persons = remote
-            .persons()
-            .await
-            .drain(..)
-            .filter(|p| p.is_active())
-            .map(Rc::new)
-            .collect();
-        MsgSummaryExpulsions::PersonsLoaded(persons)
+fn request_expulsions_for(ctx: &Context<SummaryExpulsions>, person: Rc<Box<dyn Person>>) {
+    ctx.link().send_future(async move {
+        let expulsions = store::expulsions_by(person.key()).await;
+        let today = Utc::today();
+
+        let mut summary_expulsions = CompExpulsion::default();
+        expulsions
+            .iter()
+            .filter(|f| f.time_stamp().date() == today)
+            .for_each(|exp| {
+                summary_expulsions.total += 1;
+                match exp.degree() {
+                    ost::expulsion::ExpulsionDegree::Shart => {
+                        summary_expulsions.solid_expulsion += 1
+                    }
+                    ost::expulsion::ExpulsionDegree::Poopies => {
+                        summary_expulsions.solid_expulsion += 1
+                    }
+                    ost::expulsion::ExpulsionDegree::Pooplosion => {
+                        summary_expulsions.solid_expulsion += 1
+                    }
+                    _ => {}
+                }
+            });
+        MsgSummaryExpulsions::ExpulsionForPerson {
+            person_key: person.key(),
+            feeding_summary: summary_expulsions,
+        }
     });
 }
+
+fn request_active_persons(ctx: &Context<SummaryExpulsions>) {
+    ctx.link()
+        .send_future(async { MsgSummaryExpulsions::PersonsLoaded(store::active_persons().await) });
+}
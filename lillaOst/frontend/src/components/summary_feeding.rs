@@ -5,9 +5,11 @@ use chrono::prelude::*;
 use yew::prelude::*;
 use yew_agent::{Bridge, Bridged};
 
-use crate::event_bus::EventBus;
+use crate::components::person_tag::person_tag;
+use crate::event_bus::{BusMessage, EventBus};
+use crate::store;
 
-use ost::context_remote_async::AsyncRemoteMonolith;
+use ost::event_key::EventType;
 use ost::person::Person;
 use ost::person_key::OstPersonKey;
 
@@ -25,7 +27,7 @@ pub enum MsgSummaryFeeding {
         person_key: OstPersonKey,
         feeding_summary: CompFeeding,
     },
-    RemoteDataChanged(String),
+    RemoteDataChanged(BusMessage),
 }
 
 pub struct SummaryFeeding {
@@ -51,27 +53,7 @@ impl Component for SummaryFeeding {
         match msg {
             MsgSummaryFeeding::PersonsLoaded(mut p) => {
                 self.active_persons = p.clone();
-                p.drain(..).for_each(|p| {
-                    let cloned_p = p.clone();
-                    ctx.link().send_future(async move {
-                        let remote = AsyncRemoteMonolith {};
-                        let feedings = remote.feedings_by(&cloned_p).await;
-                        let today = Utc::today();
-                        let mut composed_feeding = CompFeeding::default();
-                        feedings
-                            .iter()
-                            .filter(|f| f.time_stamp().date() == today)
-                            .for_each(|feed| {
-                                composed_feeding.breast_milk += feed.breast_milk() as u64;
-                                composed_feeding.formula += feed.formula() as u64;
-                                composed_feeding.solids += feed.solids() as u64;
-                            });
-                        MsgSummaryFeeding::FeedingsForPerson {
-                            person_key: p.key(),
-                            feeding_summary: composed_feeding,
-                        }
-                    });
-                });
+                p.drain(..).for_each(|p| request_feedings_for(ctx, p));
                 true
             }
             MsgSummaryFeeding::FeedingsForPerson {
@@ -81,8 +63,18 @@ impl Component for SummaryFeeding {
                 let _ = self.feedings.insert(person_key, feeding_summary);
                 true
             }
-            MsgSummaryFeeding::RemoteDataChanged(_) => {
-                request_active_persons(ctx);
+            MsgSummaryFeeding::RemoteDataChanged(change) => {
+                if !change.touches(EventType::Feed) {
+                    return false;
+                }
+                // A new feeding only moves the totals of its person
+                let person = change
+                    .person()
+                    .and_then(|key| self.active_persons.iter().find(|p| p.key() == key));
+                match person {
+                    Some(person) => request_feedings_for(ctx, person.clone()),
+                    None => request_active_persons(ctx),
+                }
                 false
             }
         }
@@ -130,7 +122,7 @@ impl SummaryFeeding {
             html! {
                 <tr>
                     <td>
-                        { person.name() }
+                        { person_tag(&**person) }
                     </td>
                     <td>
                         { composed_feeding.breast_milk }
@@ -147,7 +139,7 @@ impl SummaryFeeding {
             html! {
                 <tr>
                     <td>
-                        { person.name() }
+                        { person_tag(&**person) }
                     </td>
                     <td>
                         { "loading" }
@@ -164,16 +156,27 @@ impl SummaryFeeding {
     }
 }
 
-fn request_active_persons(ctx: &Context<SummaryFeeding>) {
-    ctx.link().send_future(async {
-        let remote = AsyncRemoteMonolith {};
-        let persons = remote
-            .persons()
-            .await
-            .drain(..)
-            .filter(|p| p.is_active())
-            .map(Rc::new)
-            .collect();
-        MsgSummaryFeeding::PersonsLoaded(persons)
+fn request_feedings_for(ctx: &Context<SummaryFeeding>, person: Rc<Box<dyn Person>>) {
+    ctx.link().send_future(async move {
+        let feedings = store::feedings_by(person.key()).await;
+        let today = Utc::today();
+        let mut composed_feeding = CompFeeding::default();
+        feedings
+            .iter()
+            .filter(|f| f.time_stamp().date() == today)
+            .for_each(|feed| {
+                composed_feeding.breast_milk += feed.breast_milk() as u64;
+                composed_feeding.formula += feed.formula() as u64;
+                composed_feeding.solids += feed.solids() as u64;
+            });
+        MsgSummaryFeeding::FeedingsForPerson {
+            person_key: person.key(),
+            feeding_summary: composed_feeding,
+        }
     });
 }
+
+fn request_active_persons(ctx: &Context<SummaryFeeding>) {
+    ctx.link()
+        .send_future(async { MsgSummaryFeeding::PersonsLoaded(store::active_persons().await) });
+}
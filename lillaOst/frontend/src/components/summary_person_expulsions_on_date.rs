@@ -1,9 +1,10 @@
 use chrono::prelude::*;
 use yew::prelude::*;
 
-use ost::context_remote_async::AsyncRemoteMonolith;
 use ost::person_key::OstPersonKey;
 
+use crate::store;
+
 #[derive(Default, Debug, PartialEq, Eq)]
 pub struct CompExpulsion {
     pub total: u16,
@@ -75,12 +76,10 @@ fn fetch_expulsion_data_for(
     date: Date<Utc>,
 ) {
     ctx.link().send_future(async move {
-        let remote = AsyncRemoteMonolith {};
-        let person = remote.get_person_by_key(person_key).await;
-        if person.is_none() {
+        if store::person(person_key).await.is_none() {
             return MsgSummaryPersonExpulsionsOnDate::Expulsions(None);
         }
-        let feedings = remote.expulsions_by(&person.unwrap()).await;
+        let feedings = store::expulsions_by(person_key).await;
         let mut composed_expulsion = CompExpulsion::default();
         feedings
             .iter()
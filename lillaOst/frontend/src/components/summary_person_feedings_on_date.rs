@@ -1,9 +1,10 @@
 use chrono::prelude::*;
 use yew::prelude::*;
 
-use ost::context_remote_async::AsyncRemoteMonolith;
 use ost::person_key::OstPersonKey;
 
+use crate::store;
+
 #[derive(Default, Debug, PartialEq, Eq)]
 pub struct CompFeeding {
     pub breast_milk: u64,
@@ -76,12 +77,10 @@ fn fetch_feeding_data_for(
     date: Date<Utc>,
 ) {
     ctx.link().send_future(async move {
-        let remote = AsyncRemoteMonolith {};
-        let person = remote.get_person_by_key(person_key).await;
-        if person.is_none() {
+        if store::person(person_key).await.is_none() {
             return MsgSummaryPersonFeedingsOnDate::Feedings(None);
         }
-        let feedings = remote.feedings_by(&person.unwrap()).await;
+        let feedings = store::feedings_by(person_key).await;
         let mut composed_feeding = CompFeeding::default();
         feedings
             .iter()
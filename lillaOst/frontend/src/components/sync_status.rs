@@ -0,0 +1,111 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use yew::prelude::*;
+use yew_agent::{Bridge, Bridged};
+
+use ost::change::EntityKind;
+
+use crate::event_bus::{BusMessage, EventBus, Request};
+use crate::store;
+
+/// How often the outbox is retried while entries wait in it
+const RETRY_EVERY_MS: i32 = 30_000;
+
+pub enum MsgSyncStatus {
+    Flush,
+    Flushed(usize),
+    StorageChanged(BusMessage),
+}
+
+/// Shows whether everything recorded reached the backend, and replays the outbox when it's back
+pub struct SyncStatus {
+    event_bus: Box<dyn Bridge<EventBus>>,
+    retry_handle: Option<i32>,
+    _on_online: Closure<dyn FnMut()>,
+    _on_retry: Closure<dyn FnMut()>,
+}
+
+impl Component for SyncStatus {
+    type Message = MsgSyncStatus;
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        ctx.link().send_message(MsgSyncStatus::Flush);
+
+        let window = gloo_utils::window();
+        let link = ctx.link().clone();
+        let on_online = Closure::wrap(
+            Box::new(move || link.send_message(MsgSyncStatus::Flush)) as Box<dyn FnMut()>
+        );
+        window.set_ononline(Some(on_online.as_ref().unchecked_ref()));
+
+        let link = ctx.link().clone();
+        let on_retry = Closure::wrap(
+            Box::new(move || link.send_message(MsgSyncStatus::Flush)) as Box<dyn FnMut()>
+        );
+        let retry_handle = window
+            .set_interval_with_callback_and_timeout_and_arguments_0(
+                on_retry.as_ref().unchecked_ref(),
+                RETRY_EVERY_MS,
+            )
+            .ok();
+
+        Self {
+            event_bus: EventBus::bridge(ctx.link().callback(MsgSyncStatus::StorageChanged)),
+            retry_handle,
+            _on_online: on_online,
+            _on_retry: on_retry,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            MsgSyncStatus::Flush => {
+                if store::pending_count() > 0 {
+                    ctx.link()
+                        .send_future(async { MsgSyncStatus::Flushed(store::flush().await) });
+                }
+                false
+            }
+            MsgSyncStatus::Flushed(flushed) => {
+                // The replayed entries come back with their keys from the backend
+                if flushed > 0 {
+                    self.event_bus
+                        .send(Request::EventBusMsg(BusMessage::ManyChanged(
+                            EntityKind::All,
+                        )));
+                }
+                true
+            }
+            MsgSyncStatus::StorageChanged(_) => true,
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        match (store::pending_count(), store::is_online()) {
+            (0, true) => html! {
+                <span class="navbar-item">
+                    <span class="tag is-success">{ "Synced" }</span>
+                </span>
+            },
+            (0, false) => html! {
+                <span class="navbar-item">
+                    <span class="tag is-light">{ "Offline" }</span>
+                </span>
+            },
+            (pending, _) => html! {
+                <a class="navbar-item" title="Send now" onclick={ctx.link().callback(|_| MsgSyncStatus::Flush)}>
+                    <span class="tag is-warning">{ format!("{} pending", pending) }</span>
+                </a>
+            },
+        }
+    }
+
+    fn destroy(&mut self, _ctx: &Context<Self>) {
+        let window = gloo_utils::window();
+        window.set_ononline(None);
+        if let Some(handle) = self.retry_handle.take() {
+            window.clear_interval_with_handle(handle);
+        }
+    }
+}
@@ -0,0 +1,70 @@
+//! What the components read from and write to. The backend, or with `?demo` in the address
+//! an in-memory context with made up entries, so the UI can be tried without one.
+
+use std::rc::Rc;
+
+use ost::context::construct_monolith_in_memory;
+use ost::context_async::{AsyncAdapter, AsyncContext};
+use ost::context_remote_async::{fetch, AsyncRemoteMonolith, Endpoint};
+use ost::role::Role;
+
+/// Set when building a frontend hosted apart from the backend, like `https://ost.example.com`
+const API_URL: Option<&str> = option_env!("OST_API_URL");
+
+const DEMO_PERSONS: u32 = 2;
+const DEMO_ENTRIES: u32 = 60;
+
+thread_local! {
+    static IS_DEMO: bool = gloo_utils::window()
+        .location()
+        .search()
+        .map(|search| search.contains("demo"))
+        .unwrap_or(false);
+    static CONTEXT: Rc<dyn AsyncContext> = match IS_DEMO.with(|is_demo| *is_demo) {
+        true => Rc::new(demo()),
+        false => Rc::new(remote()),
+    };
+}
+
+/// Picks the context while the address still tells whether it's a demo, before routing changes it
+pub fn start() {
+    CONTEXT.with(|_| ());
+}
+
+pub fn context() -> Rc<dyn AsyncContext> {
+    CONTEXT.with(Rc::clone)
+}
+
+/// The backend, for what only it does: sessions, live updates and the offline outbox
+pub fn remote() -> AsyncRemoteMonolith {
+    let endpoint = Endpoint {
+        base_url: API_URL.unwrap_or_default().to_string(),
+        headers: vec![],
+    };
+    AsyncRemoteMonolith::new(endpoint, fetch)
+}
+
+pub fn is_demo() -> bool {
+    IS_DEMO.with(|is_demo| *is_demo)
+}
+
+/// A demo has no session, everything is allowed
+pub fn is_logged_in() -> bool {
+    is_demo() || remote().is_logged_in()
+}
+
+pub fn role() -> Option<Role> {
+    match is_demo() {
+        true => Some(Role::Owner),
+        false => remote().role(),
+    }
+}
+
+fn demo() -> AsyncAdapter {
+    let mut ost = construct_monolith_in_memory().expect("An in-memory context always opens");
+    let _ = ost.add_fake_persons(DEMO_PERSONS);
+    let _ = ost.add_fake_feedings(DEMO_ENTRIES);
+    let _ = ost.add_fake_expulsions(DEMO_ENTRIES);
+    let _ = ost.add_fake_events(DEMO_ENTRIES / 4);
+    AsyncAdapter::new(ost)
+}
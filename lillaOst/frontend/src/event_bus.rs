@@ -2,9 +2,117 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use yew_agent::{Agent, AgentLink, Context, HandlerId};
 
+use ost::change::{ChangeNotification, ChangeOp, EntityKind};
+use ost::event_key::{EventType, OstEventKey};
+use ost::person_key::OstPersonKey;
+
+use crate::store;
+
+/// What changed in the backend, subscribers refresh only what it concerns
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BusMessage {
+    FeedAdded(OstEventKey, OstPersonKey),
+    ExpulsionAdded(OstEventKey, OstPersonKey),
+    EventAdded(OstEventKey, OstPersonKey),
+    MeasurementAdded(OstEventKey, OstPersonKey),
+    /// Modifications and removals don't tell the person
+    EntryModified(OstEventKey),
+    EntryRemoved(OstEventKey),
+    PersonAdded(OstPersonKey),
+    PersonModified(OstPersonKey),
+    PersonRemoved(OstPersonKey),
+    /// Fake data, repairs and other bulk changes of one kind
+    ManyChanged(EntityKind),
+    AllEventsPurged,
+    AllDataPurged,
+}
+
+impl BusMessage {
+    /// The person whose entries changed, none when it can't be told
+    pub fn person(&self) -> Option<OstPersonKey> {
+        match self {
+            BusMessage::FeedAdded(_, person)
+            | BusMessage::ExpulsionAdded(_, person)
+            | BusMessage::EventAdded(_, person)
+            | BusMessage::MeasurementAdded(_, person) => Some(*person),
+            _ => None,
+        }
+    }
+
+    /// Persons might have been added, renamed, deactivated or removed
+    pub fn touches_persons(&self) -> bool {
+        matches!(
+            self,
+            BusMessage::PersonAdded(_)
+                | BusMessage::PersonModified(_)
+                | BusMessage::PersonRemoved(_)
+                | BusMessage::ManyChanged(EntityKind::Person | EntityKind::All)
+                | BusMessage::AllDataPurged
+        )
+    }
+
+    /// Entries of type `t` might have changed. Person changes count, they rename and hide rows.
+    pub fn touches(&self, t: EventType) -> bool {
+        match self {
+            BusMessage::FeedAdded(key, _)
+            | BusMessage::ExpulsionAdded(key, _)
+            | BusMessage::EventAdded(key, _)
+            | BusMessage::MeasurementAdded(key, _)
+            | BusMessage::EntryModified(key)
+            | BusMessage::EntryRemoved(key) => key.t == t,
+            BusMessage::ManyChanged(kind) => match kind {
+                EntityKind::Feeding => t == EventType::Feed,
+                EntityKind::Expulsion => t == EventType::Expulsion,
+                EntityKind::Event => t == EventType::Event,
+                EntityKind::Measurement => t == EventType::Measurement,
+                EntityKind::Person | EntityKind::All => true,
+            },
+            BusMessage::PersonAdded(_)
+            | BusMessage::PersonModified(_)
+            | BusMessage::PersonRemoved(_)
+            | BusMessage::AllEventsPurged
+            | BusMessage::AllDataPurged => true,
+        }
+    }
+}
+
+impl From<ChangeNotification> for BusMessage {
+    fn from(change: ChangeNotification) -> Self {
+        let entry_key = |t| change.key.map(|id| OstEventKey { t, id });
+        let entry_type = match change.kind {
+            EntityKind::Feeding => Some(EventType::Feed),
+            EntityKind::Expulsion => Some(EventType::Expulsion),
+            EntityKind::Event => Some(EventType::Event),
+            EntityKind::Measurement => Some(EventType::Measurement),
+            EntityKind::Person | EntityKind::All => None,
+        };
+        let person = change.person.map(|id| OstPersonKey { id });
+
+        match (change.kind, change.op, change.key) {
+            (EntityKind::All, ChangeOp::Removed, None) => BusMessage::AllDataPurged,
+            (EntityKind::Person, op, Some(id)) => match op {
+                ChangeOp::Added => BusMessage::PersonAdded(OstPersonKey { id }),
+                ChangeOp::Modified => BusMessage::PersonModified(OstPersonKey { id }),
+                ChangeOp::Removed => BusMessage::PersonRemoved(OstPersonKey { id }),
+            },
+            (kind, op, _) => match (entry_type.and_then(entry_key), op, person) {
+                (Some(key), ChangeOp::Added, Some(person)) => match key.t {
+                    EventType::Feed => BusMessage::FeedAdded(key, person),
+                    EventType::Expulsion => BusMessage::ExpulsionAdded(key, person),
+                    EventType::Event => BusMessage::EventAdded(key, person),
+                    EventType::Measurement => BusMessage::MeasurementAdded(key, person),
+                },
+                (Some(key), ChangeOp::Modified, _) => BusMessage::EntryModified(key),
+                (Some(key), ChangeOp::Removed, _) => BusMessage::EntryRemoved(key),
+                _ => BusMessage::ManyChanged(kind),
+            },
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub enum Request {
-    EventBusMsg(String),
+    EventBusMsg(BusMessage),
 }
 
 pub struct EventBus {
@@ -16,7 +124,7 @@ impl Agent for EventBus {
     type Reach = Context<Self>;
     type Message = ();
     type Input = Request;
-    type Output = String;
+    type Output = BusMessage;
 
     fn create(link: AgentLink<Self>) -> Self {
         Self {
@@ -29,9 +137,11 @@ impl Agent for EventBus {
 
     fn handle_input(&mut self, msg: Self::Input, _id: HandlerId) {
         match msg {
-            Request::EventBusMsg(s) => {
+            Request::EventBusMsg(msg) => {
+                // Subscribers refetch right away, the store has to forget first
+                store::apply(&msg);
                 for sub in self.subscribers.iter() {
-                    self.link.respond(*sub, s.clone());
+                    self.link.respond(*sub, msg);
                 }
             }
         }
@@ -0,0 +1,184 @@
+//! Keeps a WebSocket on `/api/changes` and forwards every change into the `EventBus`
+
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{MessageEvent, WebSocket};
+use yew_agent::{Dispatched, Dispatcher};
+
+use ost::change::{ChangeNotification, EntityKind};
+
+use crate::context::remote;
+use crate::event_bus::{BusMessage, EventBus, Request};
+
+/// The wait before reconnecting a dropped socket, doubled after every failed attempt
+const RETRY_FIRST_MS: i32 = 1_000;
+const RETRY_MAX_MS: i32 = 60_000;
+
+pub struct LiveUpdates {
+    connection: Rc<RefCell<Connection>>,
+}
+
+struct Connection {
+    socket: Option<WebSocket>,
+    retry_ms: i32,
+    retry_handle: Option<i32>,
+    /// A socket was open before, what changed while it was down has to be reloaded
+    was_open: bool,
+    /// Shared by every socket of the connection
+    handlers: Option<Handlers>,
+}
+
+struct Handlers {
+    on_message: Closure<dyn FnMut(MessageEvent)>,
+    on_open: Closure<dyn FnMut()>,
+    on_close: Closure<dyn FnMut()>,
+    on_retry: Closure<dyn FnMut()>,
+}
+
+impl LiveUpdates {
+    /// None without a session or when the browser refuses the socket
+    pub fn connect() -> Option<Self> {
+        let url = remote().changes_url()?;
+        let socket = match WebSocket::new(&url) {
+            Ok(socket) => socket,
+            Err(err) => {
+                gloo_console::error!("Can't open the change notifications:", err);
+                return None;
+            }
+        };
+
+        let connection = Rc::new(RefCell::new(Connection {
+            socket: None,
+            retry_ms: RETRY_FIRST_MS,
+            retry_handle: None,
+            was_open: false,
+            handlers: None,
+        }));
+        connection.borrow_mut().handlers = Some(Handlers::new(Rc::downgrade(&connection)));
+        connection.borrow_mut().attach(socket);
+
+        Some(Self { connection })
+    }
+}
+
+impl Handlers {
+    fn new(connection: Weak<RefCell<Connection>>) -> Self {
+        let mut event_bus: Dispatcher<EventBus> = EventBus::dispatcher();
+        let on_message = Closure::wrap(Box::new(move |e: MessageEvent| {
+            let text = match e.data().as_string() {
+                Some(text) => text,
+                None => return,
+            };
+            if let Ok(change) = serde_json::from_str::<ChangeNotification>(&text) {
+                event_bus.send(Request::EventBusMsg(BusMessage::from(change)));
+            }
+        }) as Box<dyn FnMut(MessageEvent)>);
+
+        let mut event_bus: Dispatcher<EventBus> = EventBus::dispatcher();
+        let opened = connection.clone();
+        let on_open = Closure::wrap(Box::new(move || {
+            let connection = match opened.upgrade() {
+                Some(connection) => connection,
+                None => return,
+            };
+            let was_open = {
+                let mut connection = connection.borrow_mut();
+                connection.retry_ms = RETRY_FIRST_MS;
+                std::mem::replace(&mut connection.was_open, true)
+            };
+            // Changes sent while it was down never arrived
+            if was_open {
+                event_bus.send(Request::EventBusMsg(BusMessage::ManyChanged(
+                    EntityKind::All,
+                )));
+            }
+        }) as Box<dyn FnMut()>);
+
+        // An error closes the socket as well, the retry is only scheduled once
+        let closed = connection.clone();
+        let on_close = Closure::wrap(Box::new(move || {
+            if let Some(connection) = closed.upgrade() {
+                connection.borrow_mut().retry_later();
+            }
+        }) as Box<dyn FnMut()>);
+
+        let on_retry = Closure::wrap(Box::new(move || {
+            if let Some(connection) = connection.upgrade() {
+                connection.borrow_mut().reconnect();
+            }
+        }) as Box<dyn FnMut()>);
+
+        Self {
+            on_message,
+            on_open,
+            on_close,
+            on_retry,
+        }
+    }
+}
+
+impl Connection {
+    fn attach(&mut self, socket: WebSocket) {
+        if let Some(handlers) = &self.handlers {
+            socket.set_onmessage(Some(handlers.on_message.as_ref().unchecked_ref()));
+            socket.set_onopen(Some(handlers.on_open.as_ref().unchecked_ref()));
+            socket.set_onclose(Some(handlers.on_close.as_ref().unchecked_ref()));
+            socket.set_onerror(Some(handlers.on_close.as_ref().unchecked_ref()));
+        }
+        self.socket = Some(socket);
+    }
+
+    fn detach(&mut self) {
+        if let Some(socket) = self.socket.take() {
+            socket.set_onmessage(None);
+            socket.set_onopen(None);
+            socket.set_onclose(None);
+            socket.set_onerror(None);
+            let _ = socket.close();
+        }
+    }
+
+    fn retry_later(&mut self) {
+        self.detach();
+        if self.retry_handle.is_some() {
+            return;
+        }
+        let on_retry = match &self.handlers {
+            Some(handlers) => handlers.on_retry.as_ref().unchecked_ref(),
+            None => return,
+        };
+        self.retry_handle = gloo_utils::window()
+            .set_timeout_with_callback_and_timeout_and_arguments_0(on_retry, self.retry_ms)
+            .ok();
+        self.retry_ms = (self.retry_ms * 2).min(RETRY_MAX_MS);
+    }
+
+    /// Gives up once the session is gone, the next login connects anew
+    fn reconnect(&mut self) {
+        self.retry_handle = None;
+        let url = match remote().changes_url() {
+            Some(url) => url,
+            None => return,
+        };
+        match WebSocket::new(&url) {
+            Ok(socket) => self.attach(socket),
+            Err(err) => {
+                gloo_console::error!("Can't reopen the change notifications:", err);
+                self.retry_later();
+            }
+        }
+    }
+}
+
+impl Drop for LiveUpdates {
+    fn drop(&mut self) {
+        let mut connection = self.connection.borrow_mut();
+        if let Some(handle) = connection.retry_handle.take() {
+            gloo_utils::window().clear_timeout_with_handle(handle);
+        }
+        connection.detach();
+    }
+}
@@ -4,12 +4,16 @@
 #![allow(incomplete_features)]
 
 mod components;
+mod context;
 mod event_bus;
+mod live_updates;
 mod pages;
 mod root_spa;
 use root_spa::RootSpa;
+mod store;
 mod web_sys_utils;
 
 fn main() {
+    context::start();
     yew::start_app::<RootSpa>();
 }
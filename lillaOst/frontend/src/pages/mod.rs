@@ -1,4 +1,5 @@
 pub mod page_details;
+pub mod page_login;
 pub mod page_main;
 pub mod page_not_found;
 pub mod page_settings;
@@ -4,22 +4,27 @@ use std::rc::Rc;
 use chrono::{prelude::*, Local, Utc};
 
 use gloo_console::{error, warn};
-use ost::context_remote_async::AsyncRemoteMonolith;
 use web_sys::InputEvent;
 use yew::prelude::*;
+use yew_agent::{Dispatched, Dispatcher};
 use yew_router::prelude::*;
 
+use crate::context::context;
+use crate::event_bus::{BusMessage, EventBus, Request};
+use crate::store;
 use crate::web_sys_utils::input_get_value_from_input_event;
 
 use super::routes::LillaOstRoutes;
 use crate::components::{
-    insert_event::InsertEvent, insert_expulsions::InsertExpulsions, insert_feedings::InsertFeedings,
+    insert_event::InsertEvent, insert_expulsions::InsertExpulsions,
+    insert_feedings::InsertFeedings, insert_measurement::InsertMeasurement,
 };
 
 use ost::event::Event as ost_Event;
 use ost::event_key::{EventType, OstEventKey};
 use ost::expulsion::Expulsion as ost_Expulsion;
 use ost::feed::Feed as ost_Feed;
+use ost::measurement::Measurement as ost_Measurement;
 
 pub enum MsgPageDetails {
     // Deletion block
@@ -33,8 +38,11 @@ pub enum MsgPageDetails {
     EventLoaded(Option<Box<dyn ost_Event>>),
     ExpulsionLoaded(Option<Box<dyn ost_Expulsion>>),
     FeedingLoaded(Option<Box<dyn ost_Feed>>),
+    MeasurementLoaded(Option<Box<dyn ost_Measurement>>),
     // async update events
     UpdatedAndThenNavigateHome,
+    // saved or queued in the outbox, none when it failed
+    Saved(Option<BusMessage>),
     NavigateHome,
 }
 
@@ -44,11 +52,13 @@ pub struct Props {
 }
 
 pub struct PageDetails {
+    event_bus: Dispatcher<EventBus>,
     event_found_in_db: bool,
     is_loading: bool,
     ost_existing_event: Vec<Rc<RefCell<Box<dyn ost_Event>>>>,
     ost_existing_expulsion: Vec<Rc<RefCell<Box<dyn ost_Expulsion>>>>,
     ost_existing_feeding: Vec<Rc<RefCell<Box<dyn ost_Feed>>>>,
+    ost_existing_measurement: Vec<Rc<RefCell<Box<dyn ost_Measurement>>>>,
     props: Props,
     show_delete_dialog: bool,
 }
@@ -64,33 +74,42 @@ impl Component for PageDetails {
         match ctx.props().ost_event_key.t {
             EventType::Event => {
                 ctx.link().send_future(async move {
-                    let remote = AsyncRemoteMonolith {};
-                    let event = remote.get_event_by_key(&event_key).await;
+                    let ost = context();
+                    let event = ost.get_event_by_key(&event_key).await;
                     MsgPageDetails::EventLoaded(event)
                 });
             }
             EventType::Expulsion => {
                 ctx.link().send_future(async move {
-                    let remote = AsyncRemoteMonolith {};
-                    let expulsion = remote.get_expulsion_by_key(&event_key).await;
+                    let ost = context();
+                    let expulsion = ost.get_expulsion_by_key(&event_key).await;
                     MsgPageDetails::ExpulsionLoaded(expulsion)
                 });
             }
             EventType::Feed => {
                 ctx.link().send_future(async move {
-                    let remote = AsyncRemoteMonolith {};
-                    let feeding = remote.get_feeding_by_key(&event_key).await;
+                    let ost = context();
+                    let feeding = ost.get_feeding_by_key(&event_key).await;
                     MsgPageDetails::FeedingLoaded(feeding)
                 });
             }
+            EventType::Measurement => {
+                ctx.link().send_future(async move {
+                    let ost = context();
+                    let measurement = ost.get_measurement_by_key(&event_key).await;
+                    MsgPageDetails::MeasurementLoaded(measurement)
+                });
+            }
         }
         Self {
+            event_bus: EventBus::dispatcher(),
             props: ctx.props().clone(),
             event_found_in_db: false,
             show_delete_dialog: false,
             ost_existing_event: vec![],
             ost_existing_expulsion: vec![],
             ost_existing_feeding: vec![],
+            ost_existing_measurement: vec![],
             is_loading: true,
         }
     }
@@ -109,32 +128,46 @@ impl Component for PageDetails {
                 match &self.props.ost_event_key.t {
                     EventType::Event => {
                         ctx.link().send_future(async move {
-                            let remote = AsyncRemoteMonolith {};
-                            let ev = remote.get_event_by_key(&k).await;
-                            if let Some(event) = ev {
-                                let _ = remote.remove_event(event).await;
+                            let ost = context();
+                            match ost.get_event_by_key(&k).await {
+                                Some(event) => {
+                                    MsgPageDetails::Saved(store::remove_event(event).await.ok())
+                                }
+                                None => MsgPageDetails::NavigateHome,
                             }
-                            MsgPageDetails::UpdatedAndThenNavigateHome
                         });
                     }
                     EventType::Expulsion => {
                         ctx.link().send_future(async move {
-                            let remote = AsyncRemoteMonolith {};
-                            let ex = remote.get_expulsion_by_key(&k).await;
-                            if let Some(event) = ex {
-                                let _ = remote.remove_expulsion(event).await;
+                            let ost = context();
+                            match ost.get_expulsion_by_key(&k).await {
+                                Some(expulsion) => MsgPageDetails::Saved(
+                                    store::remove_expulsion(expulsion).await.ok(),
+                                ),
+                                None => MsgPageDetails::NavigateHome,
                             }
-                            MsgPageDetails::UpdatedAndThenNavigateHome
                         });
                     }
                     EventType::Feed => {
                         ctx.link().send_future(async move {
-                            let remote = AsyncRemoteMonolith {};
-                            let fe = remote.get_feeding_by_key(&k).await;
-                            if let Some(event) = fe {
-                                let _ = remote.remove_feeding(event).await;
+                            let ost = context();
+                            match ost.get_feeding_by_key(&k).await {
+                                Some(feeding) => {
+                                    MsgPageDetails::Saved(store::remove_feeding(feeding).await.ok())
+                                }
+                                None => MsgPageDetails::NavigateHome,
+                            }
+                        });
+                    }
+                    EventType::Measurement => {
+                        ctx.link().send_future(async move {
+                            let ost = context();
+                            match ost.get_measurement_by_key(&k).await {
+                                Some(measurement) => MsgPageDetails::Saved(
+                                    store::remove_measurement(measurement).await.ok(),
+                                ),
+                                None => MsgPageDetails::NavigateHome,
                             }
-                            MsgPageDetails::UpdatedAndThenNavigateHome
                         });
                     }
                 }
@@ -206,47 +239,56 @@ impl Component for PageDetails {
                     self.ost_existing_feeding.push(Rc::new(RefCell::new(fe)));
                 }
             }
+            MsgPageDetails::MeasurementLoaded(me) => {
+                self.is_loading = false;
+                if let Some(me) = me {
+                    self.event_found_in_db = true;
+                    self.ost_existing_measurement
+                        .push(Rc::new(RefCell::new(me)));
+                }
+            }
             MsgPageDetails::UpdatedAndThenNavigateHome => {
-                #[allow(clippy::clone_on_copy)]
-                let k = self.props.ost_event_key.clone();
                 #[allow(clippy::single_match)]
                 match self.props.ost_event_key.t {
                     EventType::Event => {
                         let n_event = self.ost_existing_event.get(0).unwrap().clone();
                         ctx.link().send_future(async move {
-                            let remote = AsyncRemoteMonolith {};
-                            let ev = remote.get_event_by_key(&k).await;
-                            if ev.is_some() {
-                                let _ = remote.modify_event(&n_event.borrow()).await;
-                            }
-                            MsgPageDetails::NavigateHome
+                            let saved = store::modify_event(&n_event.borrow()).await;
+                            MsgPageDetails::Saved(saved.ok())
                         });
                     }
                     EventType::Expulsion => {
                         let n_expulsion = self.ost_existing_expulsion.get(0).unwrap().clone();
 
                         ctx.link().send_future(async move {
-                            let remote = AsyncRemoteMonolith {};
-                            let ex = remote.get_expulsion_by_key(&k).await;
-                            if ex.is_some() {
-                                let _ = remote.modify_expulsion(&n_expulsion.borrow()).await;
-                            }
-                            MsgPageDetails::NavigateHome
+                            let saved = store::modify_expulsion(&n_expulsion.borrow()).await;
+                            MsgPageDetails::Saved(saved.ok())
                         });
                     }
                     EventType::Feed => {
                         let n_feed = self.ost_existing_feeding.get(0).unwrap().clone();
 
                         ctx.link().send_future(async move {
-                            let remote = AsyncRemoteMonolith {};
-                            let fe = remote.get_feeding_by_key(&k).await;
-                            if fe.is_some() {
-                                let _ = remote.modify_feeding(&n_feed.borrow()).await;
-                            }
-                            MsgPageDetails::NavigateHome
+                            let saved = store::modify_feeding(&n_feed.borrow()).await;
+                            MsgPageDetails::Saved(saved.ok())
                         });
                     }
+                    EventType::Measurement => {
+                        let n_measurement = self.ost_existing_measurement.get(0).unwrap().clone();
+
+                        ctx.link().send_future(async move {
+                            let saved = store::modify_measurement(&n_measurement.borrow()).await;
+                            MsgPageDetails::Saved(saved.ok())
+                        });
+                    }
+                }
+                return false;
+            }
+            MsgPageDetails::Saved(change) => {
+                if let Some(change) = change {
+                    self.event_bus.send(Request::EventBusMsg(change));
                 }
+                ctx.link().send_message(MsgPageDetails::NavigateHome);
                 return false;
             }
             MsgPageDetails::NavigateHome => {
@@ -362,6 +404,13 @@ impl PageDetails {
                 let so = feeding.borrow().solids();
                 feeding.borrow_mut().modify_feed(bm, fo, so, new_time_stamp);
             }
+            EventType::Measurement => {
+                let measurement = self.ost_existing_measurement.get_mut(0).unwrap();
+                let measures = measurement.borrow().measures();
+                measurement
+                    .borrow_mut()
+                    .modify_measurement(measures, new_time_stamp);
+            }
         }
     }
 
@@ -392,6 +441,14 @@ impl PageDetails {
                 .borrow()
                 .time_stamp()
                 .clone(),
+            EventType::Measurement => self
+                .ost_existing_measurement
+                .get(0)
+                .as_ref()
+                .unwrap()
+                .borrow()
+                .time_stamp()
+                .clone(),
         };
         ts.with_timezone(&Local)
     }
@@ -423,6 +480,14 @@ impl PageDetails {
                 .borrow()
                 .time_stamp()
                 .clone(),
+            EventType::Measurement => self
+                .ost_existing_measurement
+                .get(0)
+                .as_ref()
+                .unwrap()
+                .borrow()
+                .time_stamp()
+                .clone(),
         }
     }
 
@@ -449,6 +514,13 @@ impl PageDetails {
                 .unwrap()
                 .borrow()
                 .person_name(),
+            EventType::Measurement => self
+                .ost_existing_measurement
+                .get(0)
+                .as_ref()
+                .unwrap()
+                .borrow()
+                .person_name(),
         }
     }
 
@@ -477,6 +549,12 @@ impl PageDetails {
                     <InsertEvent ost_event={ rc_event } />
                 }
             }
+            EventType::Measurement => {
+                let rc_measurement = Some(self.ost_existing_measurement.get(0).unwrap().clone());
+                html! {
+                    <InsertMeasurement ost_measurement={ rc_measurement } />
+                }
+            }
         }
     }
 
@@ -487,6 +565,12 @@ impl PageDetails {
                 format!("No Expulsion found with ID {}", self.props.ost_event_key.id)
             }
             EventType::Feed => format!("No Feeding found with ID {}", self.props.ost_event_key.id),
+            EventType::Measurement => {
+                format!(
+                    "No Measurement found with ID {}",
+                    self.props.ost_event_key.id
+                )
+            }
         };
 
         html! {
@@ -0,0 +1,108 @@
+use web_sys::InputEvent;
+use yew::prelude::*;
+
+use crate::context::remote;
+use crate::web_sys_utils::input_get_value_from_input_event;
+
+pub enum MsgPageLogin {
+    UpdateName(String),
+    UpdatePassword(String),
+    Login,
+    LoggedIn,
+    LoginFailed(String),
+}
+
+#[derive(Properties, PartialEq)]
+pub struct PageLoginProps {
+    pub on_login: Callback<()>,
+}
+
+pub struct PageLogin {
+    name: String,
+    password: String,
+    error_message: Option<String>,
+}
+
+impl Component for PageLogin {
+    type Message = MsgPageLogin;
+    type Properties = PageLoginProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self {
+            name: "".to_string(),
+            password: "".to_string(),
+            error_message: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            MsgPageLogin::UpdateName(name) => {
+                self.name = name;
+                return false;
+            }
+            MsgPageLogin::UpdatePassword(password) => {
+                self.password = password;
+                return false;
+            }
+            MsgPageLogin::Login => {
+                let name = self.name.clone();
+                let password = self.password.clone();
+                ctx.link().send_future(async move {
+                    let remote = remote();
+                    match remote.login(&name, &password).await {
+                        Ok(()) => MsgPageLogin::LoggedIn,
+                        Err(err) => MsgPageLogin::LoginFailed(err.message().to_string()),
+                    }
+                });
+                return false;
+            }
+            MsgPageLogin::LoggedIn => {
+                self.password.clear();
+                self.error_message = None;
+                ctx.props().on_login.emit(());
+            }
+            MsgPageLogin::LoginFailed(err) => {
+                self.error_message = Some(err);
+            }
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let mut error_message = html!();
+        if let Some(err) = &self.error_message {
+            error_message = html! {
+                <div class="notification is-danger">{err}</div>
+            };
+        }
+
+        html! {
+        <div class="block">
+            <div class="card">
+                <header class="card-header">
+                    <p class="card-header-title">
+                        {"Log in"}
+                    </p>
+                </header>
+                <div class="card-content">
+                    <div class="field">
+                        <label class="label">{"Name"}</label>
+                        <input class="input" value={ self.name.clone() }
+                            oninput={ ctx.link().callback(|e: InputEvent| MsgPageLogin::UpdateName(input_get_value_from_input_event(e))) }
+                        />
+                    </div>
+                    <div class="field">
+                        <label class="label">{"Password"}</label>
+                        <input class="input" type="password" value={ self.password.clone() }
+                            oninput={ ctx.link().callback(|e: InputEvent| MsgPageLogin::UpdatePassword(input_get_value_from_input_event(e))) }
+                        />
+                    </div>
+                    {error_message}
+                    <button class="button is-primary" onclick={ ctx.link().callback(|_| MsgPageLogin::Login) }>{"Log in"}</button>
+                </div>
+            </div>
+        </div>
+        }
+    }
+}
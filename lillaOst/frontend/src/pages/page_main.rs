@@ -5,9 +5,9 @@ use crate::components::event_listing::EventListing;
 use crate::components::quick_insert::QuickInsert;
 use crate::components::summary_expulsions::SummaryExpulsions;
 use crate::components::summary_feeding::SummaryFeeding;
+use crate::context::role;
 use crate::pages::routes::LillaOstRoutes;
-
-use ost::context_remote_async::AsyncRemoteMonolith;
+use crate::store;
 
 #[derive(Clone, Debug, Eq, PartialEq, Properties)]
 pub struct PropsPageMain {
@@ -34,8 +34,7 @@ impl Component for PageMain {
 
     fn create(ctx: &Context<Self>) -> Self {
         ctx.link().send_future(async {
-            let remote = AsyncRemoteMonolith {};
-            let persons = remote.persons().await;
+            let persons = store::persons().await;
 
             let remote_has_persons = !persons.is_empty();
             let remote_has_active_persons = persons.iter().filter(|p| p.is_active()).count() > 0;
@@ -154,10 +153,17 @@ impl Component for PageMain {
                 </div>
             };
         }
+        let can_log = role().is_some_and(|role| role.can_log());
+        let quick_insert = if can_log {
+            html! { <QuickInsert /> }
+        } else {
+            html!()
+        };
+
         html! {
             <div>
                 <div class="block"></div>
-                <QuickInsert />
+                { quick_insert }
                 <SummaryFeeding />
                 <SummaryExpulsions />
                 <div class="block">
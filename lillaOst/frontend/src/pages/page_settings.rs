@@ -3,9 +3,10 @@ use yew::virtual_dom::VNode;
 use yew_agent::{Dispatched, Dispatcher};
 use yew_router::prelude::*;
 
-use crate::event_bus::{EventBus, Request};
+use crate::event_bus::{BusMessage, EventBus, Request};
 
-use ost::context_remote_async::AsyncRemoteMonolith;
+
+use crate::context::{context, role};
 
 use crate::components::settings_individuals::SettingsIndividuals;
 
@@ -21,7 +22,7 @@ pub enum MsgPageSettings {
     RequestDisplayDeleteEvents,
     RequestHideDeleteEvents,
     ActuallyDeleteEvents,
-    CallDone,
+    CallDone(BusMessage),
 }
 
 #[allow(dead_code)]
@@ -53,9 +54,9 @@ impl Component for PageSettings {
             }
             MsgPageSettings::ActuallyDeleteAll => {
                 ctx.link().send_future(async {
-                    let remote = AsyncRemoteMonolith {};
-                    let _ = remote.purge_all_data().await;
-                    MsgPageSettings::CallDone
+                    let ost = context();
+                    let _ = ost.purge_all_data().await;
+                    MsgPageSettings::CallDone(BusMessage::AllDataPurged)
                 });
                 return false;
             }
@@ -68,17 +69,15 @@ impl Component for PageSettings {
             }
             MsgPageSettings::ActuallyDeleteEvents => {
                 ctx.link().send_future(async {
-                    let remote = AsyncRemoteMonolith {};
-                    let _ = remote.purge_all_events().await;
-                    MsgPageSettings::CallDone
+                    let ost = context();
+                    let _ = ost.purge_all_events().await;
+                    MsgPageSettings::CallDone(BusMessage::AllEventsPurged)
                 });
             }
-            MsgPageSettings::CallDone => {
+            MsgPageSettings::CallDone(purged) => {
                 self.show_delete_events_dialog = false;
                 self.show_delete_dialog = false;
-                self.event_bus.send(Request::EventBusMsg(
-                    "Page settings, call finished".to_owned(),
-                ));
+                self.event_bus.send(Request::EventBusMsg(purged));
             }
         }
         true
@@ -89,6 +88,14 @@ impl Component for PageSettings {
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
+        // Renames, purges and fake data are the owners' business, the backend answers 403 to the rest
+        let can_manage = role().is_some_and(|role| role.can_manage());
+        if !can_manage {
+            return html! {
+                <div class="notification is-info">{"Only owners can change the settings"}</div>
+            };
+        }
+
         let are_you_sure_about_delete = self.construct_are_you_sure_delete_modal(ctx);
         let are_you_sure_about_delete_events = self.construct_are_you_sure_delete_events_modal(ctx);
 
@@ -4,9 +4,11 @@ use yew::prelude::*;
 
 use crate::components::graphs::graph_feedings_individual::GraphFeedingsIndividual;
 use crate::components::graphs::graph_feedings_individual_all_time::GraphFeedingsIndividualAllTime;
+use crate::components::graphs::graph_growth::GraphGrowth;
+use crate::components::person_tag::person_label;
 use crate::components::summary_on_date::SummaryOnDate;
+use crate::store;
 
-use ost::context_remote_async::AsyncRemoteMonolith;
 use ost::person::Person as ost_Person;
 
 #[derive(Debug)]
@@ -24,17 +26,8 @@ impl Component for PageSummary {
     type Properties = ();
 
     fn create(ctx: &Context<Self>) -> Self {
-        ctx.link().send_future(async {
-            let remote = AsyncRemoteMonolith {};
-            let persons = remote
-                .persons()
-                .await
-                .drain(..)
-                .filter(|p| p.is_active())
-                .map(Rc::new)
-                .collect();
-            MsgPageSummary::MsgPersonsLoaded(persons)
-        });
+        ctx.link()
+            .send_future(async { MsgPageSummary::MsgPersonsLoaded(store::active_persons().await) });
 
         Self {
             active_persons: vec![],
@@ -91,12 +84,13 @@ impl Component for PageSummary {
 
 impl PageSummary {
     fn summarize_individual(&self, person: Rc<Box<dyn ost_Person>>, id: u32) -> Html {
-        let person_name = person.name().to_string();
+        let person_name = person_label(&**person);
         html! {
             <div class="block">
                 <SummaryOnDate person_key={ person.key() } person_name={ person_name } />
                 <GraphFeedingsIndividual person={person.clone()} id={id} />
                 <GraphFeedingsIndividualAllTime person={person.clone()} id={id} />
+                <GraphGrowth person={person.clone()} id={id} />
             </div>
         }
     }
@@ -11,6 +11,9 @@ pub enum LillaOstRoutes {
     #[at("/ost-details/expulsion/:id")]
     OstDetailsExpulsion { id: u32 },
 
+    #[at("/ost-details/measurement/:id")]
+    OstDetailsMeasurement { id: u32 },
+
     #[at("/")]
     Home,
 
@@ -5,19 +5,29 @@
 use yew::prelude::*;
 use yew_router::prelude::*;
 
+use ost::context_remote_async::household;
 use ost::event_key::{EventType, OstEventKey};
 
+use crate::components::sync_status::SyncStatus;
+use crate::context::{is_logged_in, remote, role};
+use crate::live_updates::LiveUpdates;
 use crate::pages::{
-    page_details::PageDetails, page_main::PageMain, page_not_found::PageNotFound,
-    page_settings::PageSettings, page_summary::PageSummary, routes::LillaOstRoutes,
+    page_details::PageDetails, page_login::PageLogin, page_main::PageMain,
+    page_not_found::PageNotFound, page_settings::PageSettings, page_summary::PageSummary,
+    routes::LillaOstRoutes,
 };
 
 pub enum MsgRootSpa {
     ToggleNavbar,
+    LoggedIn,
+    Logout,
+    LoggedOut,
 }
 
 pub struct RootSpa {
     navbar_active: bool,
+    /// Open while logged in
+    live_updates: Option<LiveUpdates>,
 }
 
 impl RootSpa {
@@ -26,10 +36,38 @@ impl RootSpa {
 
         let active_class = if navbar_active { "is-active" } else { "" };
 
+        // A multi household server puts the household in the url
+        let title = match household() {
+            Some(id) => format!("LillaOst · {}", id),
+            None => "LillaOst".to_string(),
+        };
+
+        let can_manage = role().is_some_and(|role| role.can_manage());
+        let mut settings = html!();
+        if can_manage {
+            settings = html! {
+                <Link<LillaOstRoutes> classes={classes!("navbar-item")} to={LillaOstRoutes::Settings}>
+                    { "Settings" }
+                </Link<LillaOstRoutes>>
+            };
+        }
+
+        let mut logout = html!();
+        // A demo has no session to end
+        let has_session = remote().is_logged_in();
+        if has_session {
+            logout = html! {
+                <div class="navbar-end">
+                    <SyncStatus />
+                    <a class={classes!("navbar-item")} onclick={ctx.link().callback(|_| MsgRootSpa::Logout)}>{ "Log out" }</a>
+                </div>
+            };
+        }
+
         html! {
             <nav class="navbar is-primary" role="navigation" aria-label="main navigation">
                 <div class="navbar-brand">
-                    <h1 class="navbar-item is-size-3">{ "LillaOst" }</h1>
+                    <h1 class="navbar-item is-size-3">{ title }</h1>
                     <button
                         class={classes!("navbar-burger", "burger", active_class)}
                         aria-label="menu" aria-expanded="false"
@@ -52,12 +90,11 @@ impl RootSpa {
                         <Link<LillaOstRoutes> classes={classes!("navbar-item")} to={LillaOstRoutes::Summary}>
                             { "Summary" }
                         </Link<LillaOstRoutes>>
-                        <Link<LillaOstRoutes> classes={classes!("navbar-item")} to={LillaOstRoutes::Settings}>
-                            { "Settings" }
-                        </Link<LillaOstRoutes>>
+                        { settings }
                         <a class={classes!("navbar-item")} target="_blank" onclick={ctx.link().callback(|_| MsgRootSpa::ToggleNavbar)} href="https://github.com/jcbellido/LillaOst-Feedback/issues">{ "Report an issue" }</a>
                         <a class={classes!("navbar-item")} target="_blank" onclick={ctx.link().callback(|_| MsgRootSpa::ToggleNavbar)} href="https://jcbellido.netlify.app/tags/lillaost">{ "Articles about LillaOst" }</a>
                     </div>
+                    { logout }
                 </div>
             </nav>
         }
@@ -80,6 +117,9 @@ fn switch(routes: &LillaOstRoutes) -> Html {
         LillaOstRoutes::OstDetailsExpulsion { id } => {
             html! { <PageDetails ost_event_key= {OstEventKey { t: EventType::Expulsion, id: *id }}/>}
         }
+        LillaOstRoutes::OstDetailsMeasurement { id } => {
+            html! { <PageDetails ost_event_key= {OstEventKey { t: EventType::Measurement, id: *id }}/>}
+        }
         _ => html! { <PageNotFound /> },
     }
 }
@@ -91,14 +131,27 @@ impl Component for RootSpa {
     fn create(_ctx: &Context<Self>) -> Self {
         Self {
             navbar_active: false,
+            live_updates: LiveUpdates::connect(),
         }
     }
 
-    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
             MsgRootSpa::ToggleNavbar => {
                 self.navbar_active = !self.navbar_active;
             }
+            MsgRootSpa::LoggedIn | MsgRootSpa::LoggedOut => {
+                self.navbar_active = false;
+                self.live_updates = LiveUpdates::connect();
+            }
+            MsgRootSpa::Logout => {
+                ctx.link().send_future(async {
+                    let remote = remote();
+                    let _ = remote.logout().await;
+                    MsgRootSpa::LoggedOut
+                });
+                return false;
+            }
         }
         true
     }
@@ -108,12 +161,19 @@ impl Component for RootSpa {
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
+        // Without a session every api call answers 401, so nothing but the login is shown
+        let main = if is_logged_in() {
+            html! { <Switch<LillaOstRoutes> render={Switch::render(switch)} /> }
+        } else {
+            html! { <PageLogin on_login={ctx.link().callback(|_| MsgRootSpa::LoggedIn)} /> }
+        };
+
         html! {
             <BrowserRouter>
                 { self.view_nav(ctx) }
                 <div class="container">
                     <main>
-                        <Switch<LillaOstRoutes> render={Switch::render(switch)} />
+                        { main }
                     </main>
                 </div>
                 <footer class="footer">
@@ -0,0 +1,604 @@
+//! Persons and entries shared by every component. They are downloaded once, or read from the
+//! replica when the backend is out of reach, reads arriving meanwhile wait on the same request,
+//! and `apply` drops them when a change message says they are stale.
+//! Changes made while offline wait in the outbox until `flush` gets them to the backend.
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::ops::Deref;
+use std::rc::Rc;
+
+use chrono::prelude::*;
+use futures::future::{FutureExt, LocalBoxFuture, Shared};
+
+use ost::change::EntityKind;
+use ost::context_async::AsyncContext;
+use ost::error::OstError;
+use ost::event::{Event, EventType as EventPayload};
+use ost::event_base::EventBase;
+use ost::event_key::{EventType, OstEventKey};
+use ost::expulsion::{Expulsion, ExpulsionDegree};
+use ost::feed::Feed;
+use ost::measurement::{Measurement, Measures};
+use ost::nursing::{self, BreastSide, NursingSession};
+use ost::offline::{self, Mutation, QueuedEntry, Replica, Target};
+use ost::person::Person;
+use ost::person_key::OstPersonKey;
+
+use crate::context::{context, is_demo, remote};
+use crate::event_bus::BusMessage;
+
+#[derive(Clone, Default)]
+struct Snapshot {
+    persons: Vec<Rc<Box<dyn Person>>>,
+    /// Entries newest first, as the query api sorts them
+    feedings: Vec<Rc<dyn Feed>>,
+    expulsions: Vec<Rc<dyn Expulsion>>,
+    events: Vec<Rc<dyn Event>>,
+    measurements: Vec<Rc<dyn Measurement>>,
+    /// Entries only the outbox knows about, their keys are made up locally
+    pending: Vec<QueuedEntry>,
+}
+
+impl From<Replica> for Snapshot {
+    fn from(replica: Replica) -> Self {
+        let ost = replica.context;
+        Snapshot::new(
+            ost.persons(),
+            ost.feedings(),
+            ost.expulsions(),
+            ost.events(),
+            ost.measurements(),
+            replica.pending,
+        )
+    }
+}
+
+impl Snapshot {
+    fn new(
+        persons: Vec<Box<dyn Person>>,
+        feedings: Vec<Box<dyn Feed>>,
+        expulsions: Vec<Box<dyn Expulsion>>,
+        events: Vec<Box<dyn Event>>,
+        measurements: Vec<Box<dyn Measurement>>,
+        pending: Vec<QueuedEntry>,
+    ) -> Self {
+        let mut feedings: Vec<Rc<dyn Feed>> = feedings.into_iter().map(Rc::from).collect();
+        let mut expulsions: Vec<Rc<dyn Expulsion>> = expulsions.into_iter().map(Rc::from).collect();
+        let mut events: Vec<Rc<dyn Event>> = events.into_iter().map(Rc::from).collect();
+        let mut measurements: Vec<Rc<dyn Measurement>> =
+            measurements.into_iter().map(Rc::from).collect();
+        newest_first(&mut feedings);
+        newest_first(&mut expulsions);
+        newest_first(&mut events);
+        newest_first(&mut measurements);
+
+        Self {
+            persons: persons.into_iter().map(Rc::new).collect(),
+            feedings,
+            expulsions,
+            events,
+            measurements,
+            pending,
+        }
+    }
+
+    async fn read(ost: &dyn AsyncContext) -> Self {
+        Snapshot::new(
+            ost.persons().await,
+            ost.feedings().await,
+            ost.expulsions().await,
+            ost.events().await,
+            ost.measurements().await,
+            vec![],
+        )
+    }
+
+    /// A made up key of a pending entry can match a new one of the backend
+    fn holds<T: Deref>(&self, entries: &[T], key: OstEventKey) -> bool
+    where
+        T::Target: EventBase,
+    {
+        !self.pending.iter().any(|queued| queued.key == key)
+            && entries.iter().any(|entry| entry.key() == key)
+    }
+}
+
+enum Slot {
+    Empty,
+    /// The number of the load, from `Store::loads`
+    Loading(u64, Shared<LocalBoxFuture<'static, Rc<Snapshot>>>),
+    Ready(Rc<Snapshot>),
+}
+
+struct Store {
+    snapshot: Slot,
+    /// Loads started so far, a finished load only fills the slot it started in
+    loads: u64,
+    /// The last call to the backend went through
+    is_online: bool,
+    is_flushing: bool,
+}
+
+thread_local! {
+    static STORE: RefCell<Store> = RefCell::new(Store {
+        snapshot: Slot::Empty,
+        loads: 0,
+        is_online: true,
+        is_flushing: false,
+    });
+}
+
+pub async fn persons() -> Vec<Rc<Box<dyn Person>>> {
+    read().await.persons.clone()
+}
+
+pub async fn active_persons() -> Vec<Rc<Box<dyn Person>>> {
+    let mut persons = persons().await;
+    persons.retain(|p| p.is_active());
+    persons
+}
+
+pub async fn person(key: OstPersonKey) -> Option<Rc<Box<dyn Person>>> {
+    persons().await.into_iter().find(|p| p.key() == key)
+}
+
+pub async fn feedings() -> Vec<Rc<dyn Feed>> {
+    read().await.feedings.clone()
+}
+
+pub async fn feedings_by(person: OstPersonKey) -> Vec<Rc<dyn Feed>> {
+    let mut feedings = feedings().await;
+    feedings.retain(|f| f.person_key() == person);
+    feedings
+}
+
+/// The side to start on, after the person's latest nursing session
+pub async fn next_nursing_side(person: OstPersonKey) -> Option<BreastSide> {
+    nursing::next_side(feedings_by(person).await.iter().filter_map(|f| f.nursing()))
+}
+
+pub async fn expulsions() -> Vec<Rc<dyn Expulsion>> {
+    read().await.expulsions.clone()
+}
+
+pub async fn expulsions_by(person: OstPersonKey) -> Vec<Rc<dyn Expulsion>> {
+    let mut expulsions = expulsions().await;
+    expulsions.retain(|e| e.person_key() == person);
+    expulsions
+}
+
+pub async fn events() -> Vec<Rc<dyn Event>> {
+    read().await.events.clone()
+}
+
+pub async fn measurements() -> Vec<Rc<dyn Measurement>> {
+    read().await.measurements.clone()
+}
+
+pub async fn measurements_by(person: OstPersonKey) -> Vec<Rc<dyn Measurement>> {
+    let mut measurements = measurements().await;
+    measurements.retain(|m| m.person_key() == person);
+    measurements
+}
+
+/// Keys of the entries still waiting in the outbox
+pub async fn pending() -> Vec<OstEventKey> {
+    read()
+        .await
+        .pending
+        .iter()
+        .map(|queued| queued.key)
+        .collect()
+}
+
+/// A demo never queues, the outbox belongs to the backend's session
+pub fn pending_count() -> usize {
+    match is_demo() {
+        true => 0,
+        false => offline::load_outbox().len(),
+    }
+}
+
+pub fn is_online() -> bool {
+    STORE.with(|store| store.borrow().is_online)
+}
+
+/// Saved by the backend or, when it can't be reached, queued in the outbox.
+/// Returns the message to put on the bus.
+#[allow(clippy::borrowed_box)]
+pub async fn add_feeding(
+    person: &Box<dyn Person>,
+    breast_milk: u32,
+    formula: u32,
+    solids: u32,
+) -> Result<BusMessage, OstError> {
+    match context()
+        .add_feeding(person, breast_milk, formula, solids)
+        .await
+    {
+        Ok(feed) => {
+            let feed: Rc<dyn Feed> = Rc::from(feed);
+            let change = BusMessage::FeedAdded(feed.key(), person.key());
+            update(|snapshot| {
+                snapshot.feedings.push(feed);
+                newest_first(&mut snapshot.feedings);
+            });
+            Ok(change)
+        }
+        Err(OstError::Transport(_)) => queue(Mutation::AddFeeding {
+            person_key: person.key(),
+            breast_milk,
+            formula,
+            solids,
+        }),
+        Err(err) => Err(err),
+    }
+}
+
+#[allow(clippy::borrowed_box)]
+pub async fn add_nursing(
+    person: &Box<dyn Person>,
+    nursing: NursingSession,
+) -> Result<BusMessage, OstError> {
+    match context().add_nursing(person, nursing.clone()).await {
+        Ok(feed) => {
+            let feed: Rc<dyn Feed> = Rc::from(feed);
+            let change = BusMessage::FeedAdded(feed.key(), person.key());
+            update(|snapshot| {
+                snapshot.feedings.push(feed);
+                newest_first(&mut snapshot.feedings);
+            });
+            Ok(change)
+        }
+        Err(OstError::Transport(_)) => queue(Mutation::AddNursing {
+            person_key: person.key(),
+            nursing,
+        }),
+        Err(err) => Err(err),
+    }
+}
+
+#[allow(clippy::borrowed_box)]
+pub async fn add_expulsion(
+    person: &Box<dyn Person>,
+    degree: ExpulsionDegree,
+) -> Result<BusMessage, OstError> {
+    match context().add_expulsion(person, degree.clone()).await {
+        Ok(expulsion) => {
+            let expulsion: Rc<dyn Expulsion> = Rc::from(expulsion);
+            let change = BusMessage::ExpulsionAdded(expulsion.key(), person.key());
+            update(|snapshot| {
+                snapshot.expulsions.push(expulsion);
+                newest_first(&mut snapshot.expulsions);
+            });
+            Ok(change)
+        }
+        Err(OstError::Transport(_)) => queue(Mutation::AddExpulsion {
+            person_key: person.key(),
+            degree,
+        }),
+        Err(err) => Err(err),
+    }
+}
+
+#[allow(clippy::borrowed_box)]
+pub async fn add_event(
+    person: &Box<dyn Person>,
+    payload: EventPayload,
+) -> Result<BusMessage, OstError> {
+    match context().add_event(person, payload.clone()).await {
+        Ok(event) => {
+            let event: Rc<dyn Event> = Rc::from(event);
+            let change = BusMessage::EventAdded(event.key(), person.key());
+            update(|snapshot| {
+                snapshot.events.push(event);
+                newest_first(&mut snapshot.events);
+            });
+            Ok(change)
+        }
+        Err(OstError::Transport(_)) => queue(Mutation::AddEvent {
+            person_key: person.key(),
+            event: payload,
+        }),
+        Err(err) => Err(err),
+    }
+}
+
+#[allow(clippy::borrowed_box)]
+pub async fn add_measurement(
+    person: &Box<dyn Person>,
+    measures: Measures,
+) -> Result<BusMessage, OstError> {
+    match context().add_measurement(person, measures).await {
+        Ok(measurement) => {
+            let measurement: Rc<dyn Measurement> = Rc::from(measurement);
+            let change = BusMessage::MeasurementAdded(measurement.key(), person.key());
+            update(|snapshot| {
+                snapshot.measurements.push(measurement);
+                newest_first(&mut snapshot.measurements);
+            });
+            Ok(change)
+        }
+        Err(OstError::Transport(_)) => queue(Mutation::AddMeasurement {
+            person_key: person.key(),
+            measures,
+        }),
+        Err(err) => Err(err),
+    }
+}
+
+/// Like the additions, saved by the backend or queued in the outbox
+#[allow(clippy::borrowed_box)]
+pub async fn modify_feeding(feed: &Box<dyn Feed>) -> Result<BusMessage, OstError> {
+    let key = feed.key();
+    let ost = context();
+    let modification = |target| Mutation::ModifyFeeding {
+        target,
+        breast_milk: feed.breast_milk(),
+        formula: feed.formula(),
+        solids: feed.solids(),
+        nursing: feed.nursing(),
+        time_stamp: *feed.time_stamp(),
+    };
+    let saved = ost.modify_feeding(feed);
+    save_or_queue(key, saved, modification, BusMessage::EntryModified(key)).await
+}
+
+pub async fn remove_feeding(feed: Box<dyn Feed>) -> Result<BusMessage, OstError> {
+    let key = feed.key();
+    let ost = context();
+    let removal = |target| Mutation::RemoveFeeding { target };
+    let saved = ost.remove_feeding(feed);
+    save_or_queue(key, saved, removal, BusMessage::EntryRemoved(key)).await
+}
+
+#[allow(clippy::borrowed_box)]
+pub async fn modify_expulsion(expulsion: &Box<dyn Expulsion>) -> Result<BusMessage, OstError> {
+    let key = expulsion.key();
+    let ost = context();
+    let modification = |target| Mutation::ModifyExpulsion {
+        target,
+        degree: expulsion.degree(),
+        time_stamp: *expulsion.time_stamp(),
+    };
+    let saved = ost.modify_expulsion(expulsion);
+    save_or_queue(key, saved, modification, BusMessage::EntryModified(key)).await
+}
+
+pub async fn remove_expulsion(expulsion: Box<dyn Expulsion>) -> Result<BusMessage, OstError> {
+    let key = expulsion.key();
+    let ost = context();
+    let removal = |target| Mutation::RemoveExpulsion { target };
+    let saved = ost.remove_expulsion(expulsion);
+    save_or_queue(key, saved, removal, BusMessage::EntryRemoved(key)).await
+}
+
+#[allow(clippy::borrowed_box)]
+pub async fn modify_event(event: &Box<dyn Event>) -> Result<BusMessage, OstError> {
+    let key = event.key();
+    let ost = context();
+    let modification = |target| Mutation::ModifyEvent {
+        target,
+        event: event.event(),
+        time_stamp: *event.time_stamp(),
+    };
+    let saved = ost.modify_event(event);
+    save_or_queue(key, saved, modification, BusMessage::EntryModified(key)).await
+}
+
+pub async fn remove_event(event: Box<dyn Event>) -> Result<BusMessage, OstError> {
+    let key = event.key();
+    let ost = context();
+    let removal = |target| Mutation::RemoveEvent { target };
+    let saved = ost.remove_event(event);
+    save_or_queue(key, saved, removal, BusMessage::EntryRemoved(key)).await
+}
+
+#[allow(clippy::borrowed_box)]
+pub async fn modify_measurement(
+    measurement: &Box<dyn Measurement>,
+) -> Result<BusMessage, OstError> {
+    let key = measurement.key();
+    let ost = context();
+    let modification = |target| Mutation::ModifyMeasurement {
+        target,
+        measures: measurement.measures(),
+        time_stamp: *measurement.time_stamp(),
+    };
+    let saved = ost.modify_measurement(measurement);
+    save_or_queue(key, saved, modification, BusMessage::EntryModified(key)).await
+}
+
+pub async fn remove_measurement(measurement: Box<dyn Measurement>) -> Result<BusMessage, OstError> {
+    let key = measurement.key();
+    let ost = context();
+    let removal = |target| Mutation::RemoveMeasurement { target };
+    let saved = ost.remove_measurement(measurement);
+    save_or_queue(key, saved, removal, BusMessage::EntryRemoved(key)).await
+}
+
+/// Replays the outbox oldest first and returns how many entries left it.
+/// Stops while the backend can't be reached, the session is gone or the backend fails,
+/// only entries it rejects as invalid or about something missing are dropped.
+pub async fn flush() -> usize {
+    if is_demo() {
+        return 0;
+    }
+    let already_flushing = STORE.with(|store| {
+        let mut store = store.borrow_mut();
+        std::mem::replace(&mut store.is_flushing, true)
+    });
+    if already_flushing {
+        return 0;
+    }
+
+    let remote = remote();
+    let mut flushed = 0;
+    while let Some(pending) = offline::load_outbox().oldest().cloned() {
+        let saved = match remote.replay(&pending).await {
+            Ok(saved) => {
+                set_online(true);
+                saved
+            }
+            Err(OstError::Transport(_)) => {
+                set_online(false);
+                break;
+            }
+            Err(OstError::Unauthorized(_)) => break,
+            Err(err @ (OstError::Validation(_) | OstError::NotFound(_))) => {
+                gloo_console::warn!(format!("Dropped an offline entry: {}", err));
+                None
+            }
+            // Kept for the next flush, the same replay id won't add it twice
+            Err(err) => {
+                gloo_console::warn!(format!("Kept an offline entry: {}", err));
+                break;
+            }
+        };
+        // Reloaded, more may have been queued while this one was on its way
+        let mut outbox = offline::load_outbox();
+        if let Some(key) = saved {
+            outbox.resolve(pending.id, key);
+        }
+        outbox.confirm(pending.id);
+        if offline::store_outbox(&outbox).is_err() {
+            break;
+        }
+        flushed += 1;
+    }
+
+    STORE.with(|store| store.borrow_mut().is_flushing = false);
+    flushed
+}
+
+/// Forgets what `change` made stale. Additions the store made itself are kept.
+pub fn apply(change: &BusMessage) {
+    STORE.with(|store| {
+        let mut store = store.borrow_mut();
+        let known = match (&store.snapshot, change) {
+            (Slot::Ready(snapshot), BusMessage::FeedAdded(key, _)) => {
+                snapshot.holds(&snapshot.feedings, *key)
+            }
+            (Slot::Ready(snapshot), BusMessage::ExpulsionAdded(key, _)) => {
+                snapshot.holds(&snapshot.expulsions, *key)
+            }
+            (Slot::Ready(snapshot), BusMessage::EventAdded(key, _)) => {
+                snapshot.holds(&snapshot.events, *key)
+            }
+            (Slot::Ready(snapshot), BusMessage::MeasurementAdded(key, _)) => {
+                snapshot.holds(&snapshot.measurements, *key)
+            }
+            _ => false,
+        };
+        if !known {
+            store.snapshot = Slot::Empty;
+        }
+    });
+}
+
+/// Saved by the backend or, when it can't be reached, queued in the outbox. Changes to entries
+/// the outbox still holds wait there too, behind what is queued already.
+async fn save_or_queue(
+    key: OstEventKey,
+    saved: impl Future<Output = Result<(), OstError>>,
+    mutation: impl FnOnce(Target) -> Mutation,
+    change: BusMessage,
+) -> Result<BusMessage, OstError> {
+    let queued = read()
+        .await
+        .pending
+        .iter()
+        .find(|queued| queued.key == key)
+        .map(|queued| queued.id);
+    let target = match queued {
+        Some(id) => Target::Queued(id),
+        None if pending_count() > 0 => Target::Saved(key),
+        None => match saved.await {
+            Ok(()) => return Ok(change),
+            Err(OstError::Transport(_)) => Target::Saved(key),
+            Err(err) => return Err(err),
+        },
+    };
+    queue(mutation(target))
+}
+
+fn queue(mutation: Mutation) -> Result<BusMessage, OstError> {
+    let kind = match mutation.entry_type() {
+        EventType::Feed => EntityKind::Feeding,
+        EventType::Expulsion => EntityKind::Expulsion,
+        EventType::Event => EntityKind::Event,
+        EventType::Measurement => EntityKind::Measurement,
+    };
+    let mut outbox = offline::load_outbox();
+    outbox.push(mutation, Utc::now());
+    offline::store_outbox(&outbox)?;
+    set_online(false);
+    // The next read puts the queued entry on top of the replica
+    Ok(BusMessage::ManyChanged(kind))
+}
+
+fn set_online(is_online: bool) {
+    STORE.with(|store| store.borrow_mut().is_online = is_online);
+}
+
+/// Changes a loaded snapshot, nothing to do while it's loading or empty
+fn update(change: impl FnOnce(&mut Snapshot)) {
+    STORE.with(|store| {
+        if let Slot::Ready(snapshot) = &mut store.borrow_mut().snapshot {
+            change(Rc::make_mut(snapshot));
+        }
+    });
+}
+
+/// The loaded snapshot, or the request loading it, started when there is none
+async fn read() -> Rc<Snapshot> {
+    let loading = STORE.with(|store| {
+        let mut store = store.borrow_mut();
+        match &store.snapshot {
+            Slot::Ready(snapshot) => return Err(snapshot.clone()),
+            Slot::Loading(load_number, loading) => return Ok((*load_number, loading.clone())),
+            Slot::Empty => {}
+        }
+        store.loads += 1;
+        let loading = load().boxed_local().shared();
+        store.snapshot = Slot::Loading(store.loads, loading.clone());
+        Ok((store.loads, loading))
+    });
+    let (load_number, loading) = match loading {
+        Ok(loading) => loading,
+        Err(ready) => return ready,
+    };
+
+    let snapshot = loading.await;
+    STORE.with(|store| {
+        let mut store = store.borrow_mut();
+        // Dropped by `apply` while loading, the next read starts over
+        if matches!(&store.snapshot, Slot::Loading(current, _) if *current == load_number) {
+            store.snapshot = Slot::Ready(snapshot.clone());
+        }
+    });
+    snapshot
+}
+
+async fn load() -> Rc<Snapshot> {
+    if is_demo() {
+        return Rc::new(Snapshot::read(context().as_ref()).await);
+    }
+    let remote = remote();
+    match offline::load_replica(&remote).await {
+        Ok(replica) => {
+            set_online(replica.is_online);
+            Rc::new(Snapshot::from(replica))
+        }
+        // Most likely the session is gone, the login page takes over
+        Err(_) => Rc::new(Snapshot::default()),
+    }
+}
+
+fn newest_first<T: Deref>(entries: &mut [T])
+where
+    T::Target: EventBase,
+{
+    entries.sort_by(|a, b| b.time_stamp().cmp(a.time_stamp()));
+}
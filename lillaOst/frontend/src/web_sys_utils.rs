@@ -2,6 +2,7 @@ use wasm_bindgen::JsCast;
 use wasm_bindgen::UnwrapThrowExt;
 use web_sys::Event;
 use web_sys::HtmlInputElement;
+use web_sys::HtmlSelectElement;
 use web_sys::HtmlTextAreaElement;
 use web_sys::InputEvent;
 
@@ -18,3 +19,10 @@ pub fn text_area_get_value_from_input_event(e: InputEvent) -> String {
     let target: HtmlTextAreaElement = event_target.dyn_into().unwrap_throw();
     target.value()
 }
+
+pub fn select_get_value_from_input_event(e: InputEvent) -> String {
+    let event: Event = e.dyn_into().unwrap_throw();
+    let event_target = event.target().unwrap_throw();
+    let target: HtmlSelectElement = event_target.dyn_into().unwrap_throw();
+    target.value()
+}
@@ -0,0 +1,56 @@
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EntityKind {
+    Person,
+    Feeding,
+    Expulsion,
+    Event,
+    Measurement,
+    /// Bulk operations touch every kind
+    All,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChangeOp {
+    Added,
+    Modified,
+    Removed,
+}
+
+/// Sent to every connected client after a mutation went through
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangeNotification {
+    pub kind: EntityKind,
+    /// The id of the entity, `None` when many of them changed at once
+    pub key: Option<u32>,
+    pub op: ChangeOp,
+    /// The person owning the entry, known when it was added
+    #[serde(default)]
+    pub person: Option<u32>,
+}
+
+impl ChangeNotification {
+    pub fn one(kind: EntityKind, id: u32, op: ChangeOp) -> Self {
+        Self {
+            kind,
+            key: Some(id),
+            op,
+            person: None,
+        }
+    }
+
+    pub fn many(kind: EntityKind, op: ChangeOp) -> Self {
+        Self {
+            kind,
+            key: None,
+            op,
+            person: None,
+        }
+    }
+
+    pub fn for_person(mut self, person_id: u32) -> Self {
+        self.person = Some(person_id);
+        self
+    }
+}
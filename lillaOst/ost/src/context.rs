@@ -1,25 +1,52 @@
+use chrono::prelude::*;
+
+use crate::error::OstError;
 use crate::event::{Event, EventType};
 use crate::event_base::EventBase;
 use crate::event_key::OstEventKey;
 use crate::expulsion::{Expulsion, ExpulsionDegree};
 use crate::feed::Feed;
+use crate::integrity::IntegrityIssue;
+use crate::measurement::{Measurement, Measures};
+use crate::nursing::{self, BreastSide, NursingSession};
+use crate::offline::{ReplayId, ReplayMark};
 use crate::person::Person;
 use crate::person_key::OstPersonKey;
+use crate::query::{EventQuery, QueryResult};
 
 use crate::context_impl::context_persistence_in_memory::new_monolith as new_monolith_in_memory;
+use crate::context_impl::context_persistence_journal::new_monolith as new_monolith_journaled;
 use crate::context_impl::context_persistence_local_storage::new_monolith as new_monolith_in_local_storage;
 use crate::context_impl::context_persistence_single_file::{from_file, new_monolith};
 use crate::context_impl::context_remote::new_monolith as new_remote_monolith;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::context_impl::context_sqlite::{from_monolith_file, new_context as new_sqlite_context};
+
+pub use crate::context_impl::context_persistence::CURRENT_SCHEMA_VERSION;
 
-pub trait Context {
-    fn purge_all_data(&mut self) -> Result<(), String>;
-    fn purge_all_events(&mut self) -> Result<(), String>;
+/// Implementations are `Send + Sync` so a single context can serve concurrent readers.
+pub trait Context: Send + Sync {
+    fn purge_all_data(&mut self) -> Result<(), OstError>;
+    fn purge_all_events(&mut self) -> Result<(), OstError>;
+    fn compact_storage(&mut self) -> Result<(), OstError>;
+    fn check_integrity(&self) -> Result<Vec<IntegrityIssue>, OstError>;
+    fn repair_integrity(&mut self) -> Result<Vec<IntegrityIssue>, OstError>;
     fn get_base_event_by_key(&self, key: &OstEventKey) -> Option<Box<dyn EventBase>>;
+    /// Feedings, expulsions, events and measurements matching `query`, one page at a time
+    fn query(&self, query: &EventQuery) -> Result<QueryResult, OstError>;
 
     fn persons(&self) -> Vec<Box<dyn Person>>;
-    fn add_person(&mut self, name: &str) -> Result<Box<dyn Person>, String>;
-    fn add_fake_persons(&mut self, count: u32) -> Result<(), String>;
-    fn modify_person(&mut self, person: &Box<dyn Person>) -> Result<(), String>;
+    fn add_person(&mut self, name: &str) -> Result<Box<dyn Person>, OstError>;
+    fn add_fake_persons(&mut self, count: u32) -> Result<(), OstError>;
+    fn modify_person(&mut self, person: &Box<dyn Person>) -> Result<(), OstError>;
+    /// With `cascade` the person's entries are removed too, otherwise a person with entries is kept
+    fn remove_person(&mut self, person: Box<dyn Person>, cascade: bool) -> Result<(), OstError>;
+    /// Moves every entry of `from` to `into` and removes `from`
+    fn merge_persons(
+        &mut self,
+        from: Box<dyn Person>,
+        into: &Box<dyn Person>,
+    ) -> Result<(), OstError>;
 
     fn get_person_by_key(&self, key: OstPersonKey) -> Option<Box<dyn Person>>;
 
@@ -32,10 +59,44 @@ pub trait Context {
         breast_milk: u32,
         formula: u32,
         solids: u32,
-    ) -> Result<Box<dyn Feed>, String>;
-    fn add_fake_feedings(&mut self, count: u32) -> Result<(), String>;
-    fn modify_feeding(&mut self, feed: &Box<dyn Feed>) -> Result<(), String>;
-    fn remove_feeding(&mut self, feed: Box<dyn Feed>) -> Result<(), String>;
+    ) -> Result<Box<dyn Feed>, OstError> {
+        self.add_feeding_at(person, breast_milk, formula, solids, Utc::now(), None)
+    }
+    /// Recorded at `time_stamp` instead of now. With a `replay_id` the feed also becomes
+    /// the client's replay mark, in the same mutation.
+    #[allow(clippy::borrowed_box)]
+    fn add_feeding_at(
+        &mut self,
+        person: &Box<dyn Person>,
+        breast_milk: u32,
+        formula: u32,
+        solids: u32,
+        time_stamp: DateTime<Utc>,
+        replay_id: Option<&ReplayId>,
+    ) -> Result<Box<dyn Feed>, OstError>;
+    /// A feed holding only `nursing`, timed when the session started. Fails on an invalid session.
+    fn add_nursing(
+        &mut self,
+        person: &Box<dyn Person>,
+        nursing: NursingSession,
+    ) -> Result<Box<dyn Feed>, OstError> {
+        self.add_nursing_replayed(person, nursing, None)
+    }
+    /// With a `replay_id` the feed also becomes the client's replay mark
+    #[allow(clippy::borrowed_box)]
+    fn add_nursing_replayed(
+        &mut self,
+        person: &Box<dyn Person>,
+        nursing: NursingSession,
+        replay_id: Option<&ReplayId>,
+    ) -> Result<Box<dyn Feed>, OstError>;
+    /// The side to start the next nursing session on, none before the first one
+    fn next_nursing_side(&self, person: &Box<dyn Person>) -> Option<BreastSide> {
+        nursing::next_side(self.feedings_by(person).iter().filter_map(|f| f.nursing()))
+    }
+    fn add_fake_feedings(&mut self, count: u32) -> Result<(), OstError>;
+    fn modify_feeding(&mut self, feed: &Box<dyn Feed>) -> Result<(), OstError>;
+    fn remove_feeding(&mut self, feed: Box<dyn Feed>) -> Result<(), OstError>;
     fn get_feeding_by_key(&self, key: &OstEventKey) -> Option<Box<dyn Feed>>;
 
     // Expulsion block
@@ -45,10 +106,20 @@ pub trait Context {
         &mut self,
         person: &Box<dyn Person>,
         degree: ExpulsionDegree,
-    ) -> Result<Box<dyn Expulsion>, String>;
-    fn add_fake_expulsions(&mut self, count: u32) -> Result<(), String>;
-    fn modify_expulsion(&mut self, expulsion: &Box<dyn Expulsion>) -> Result<(), String>;
-    fn remove_expulsion(&mut self, expulsion: Box<dyn Expulsion>) -> Result<(), String>;
+    ) -> Result<Box<dyn Expulsion>, OstError> {
+        self.add_expulsion_at(person, degree, Utc::now(), None)
+    }
+    #[allow(clippy::borrowed_box)]
+    fn add_expulsion_at(
+        &mut self,
+        person: &Box<dyn Person>,
+        degree: ExpulsionDegree,
+        time_stamp: DateTime<Utc>,
+        replay_id: Option<&ReplayId>,
+    ) -> Result<Box<dyn Expulsion>, OstError>;
+    fn add_fake_expulsions(&mut self, count: u32) -> Result<(), OstError>;
+    fn modify_expulsion(&mut self, expulsion: &Box<dyn Expulsion>) -> Result<(), OstError>;
+    fn remove_expulsion(&mut self, expulsion: Box<dyn Expulsion>) -> Result<(), OstError>;
     fn get_expulsion_by_key(&self, key: &OstEventKey) -> Option<Box<dyn Expulsion>>;
 
     fn events(&self) -> Vec<Box<dyn Event>>;
@@ -57,31 +128,75 @@ pub trait Context {
         &mut self,
         person: &Box<dyn Person>,
         event_type: EventType,
-    ) -> Result<Box<dyn Event>, String>;
-    fn add_fake_events(&mut self, count: u32) -> Result<(), String>;
-    fn modify_event(&mut self, event: &Box<dyn Event>) -> Result<(), String>;
-    fn remove_event(&mut self, event: Box<dyn Event>) -> Result<(), String>;
+    ) -> Result<Box<dyn Event>, OstError> {
+        self.add_event_at(person, event_type, Utc::now(), None)
+    }
+    #[allow(clippy::borrowed_box)]
+    fn add_event_at(
+        &mut self,
+        person: &Box<dyn Person>,
+        event_type: EventType,
+        time_stamp: DateTime<Utc>,
+        replay_id: Option<&ReplayId>,
+    ) -> Result<Box<dyn Event>, OstError>;
+    fn add_fake_events(&mut self, count: u32) -> Result<(), OstError>;
+    fn modify_event(&mut self, event: &Box<dyn Event>) -> Result<(), OstError>;
+    fn remove_event(&mut self, event: Box<dyn Event>) -> Result<(), OstError>;
     fn get_event_by_key(&self, key: &OstEventKey) -> Option<Box<dyn Event>>;
+
+    // Measurements block
+    fn measurements(&self) -> Vec<Box<dyn Measurement>>;
+    fn measurements_by(&self, person: &Box<dyn Person>) -> Vec<Box<dyn Measurement>>;
+    /// Fails unless `measures` holds at least one value
+    fn add_measurement(
+        &mut self,
+        person: &Box<dyn Person>,
+        measures: Measures,
+    ) -> Result<Box<dyn Measurement>, OstError> {
+        self.add_measurement_at(person, measures, Utc::now(), None)
+    }
+    #[allow(clippy::borrowed_box)]
+    fn add_measurement_at(
+        &mut self,
+        person: &Box<dyn Person>,
+        measures: Measures,
+        time_stamp: DateTime<Utc>,
+        replay_id: Option<&ReplayId>,
+    ) -> Result<Box<dyn Measurement>, OstError>;
+    fn add_fake_measurements(&mut self, count: u32) -> Result<(), OstError>;
+    fn modify_measurement(&mut self, measurement: &Box<dyn Measurement>) -> Result<(), OstError>;
+    fn remove_measurement(&mut self, measurement: Box<dyn Measurement>) -> Result<(), OstError>;
+    fn get_measurement_by_key(&self, key: &OstEventKey) -> Option<Box<dyn Measurement>>;
+
+    /// The latest entry `client` replayed from its outbox, none before its first
+    fn replay_mark(&self, client: &str) -> Option<ReplayMark>;
 }
 
-pub fn construct_monolith_in_memory() -> Result<Box<dyn Context>, String> {
+pub fn construct_monolith_in_memory() -> Result<Box<dyn Context>, OstError> {
     Ok(Box::new(new_monolith_in_memory()?))
 }
 
-pub fn construct_monolith_in_local_storage(storage_key: &str) -> Result<Box<dyn Context>, String> {
+pub fn construct_monolith_in_local_storage(
+    storage_key: &str,
+) -> Result<Box<dyn Context>, OstError> {
     Ok(Box::new(new_monolith_in_local_storage(storage_key)?))
 }
 
-pub fn construct_monolith(path_to_monolith: &str) -> Result<Box<dyn Context>, String> {
+pub fn construct_monolith(path_to_monolith: &str) -> Result<Box<dyn Context>, OstError> {
     Ok(Box::new(new_monolith(path_to_monolith)?))
 }
 
+/// Appends every mutation to a journal instead of rewriting the whole monolith file.
+pub fn construct_monolith_journaled(path_to_monolith: &str) -> Result<Box<dyn Context>, OstError> {
+    Ok(Box::new(new_monolith_journaled(path_to_monolith)?))
+}
+
 pub fn construct_monolith_remote(
     remote_endpoint: &str,
     get_call: fn(&str) -> Result<String, String>,
     post_call: fn(&str, String) -> Result<String, String>,
     post_empty_call: fn(&str) -> Result<String, String>,
-) -> Result<Box<dyn Context>, String> {
+) -> Result<Box<dyn Context>, OstError> {
     Ok(Box::new(new_remote_monolith(
         remote_endpoint,
         get_call,
@@ -93,6 +208,23 @@ pub fn construct_monolith_remote(
 pub fn construct_monolith_from_file(
     path_to_source: &str,
     path_to_output: &str,
-) -> Result<Box<dyn Context>, String> {
+) -> Result<Box<dyn Context>, OstError> {
     Ok(Box::new(from_file(path_to_source, path_to_output)?))
 }
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn construct_sqlite(path_to_database: &str) -> Result<Box<dyn Context>, OstError> {
+    Ok(Box::new(new_sqlite_context(path_to_database)?))
+}
+
+/// Imports a single file monolith into a new SQLite database
+#[cfg(not(target_arch = "wasm32"))]
+pub fn construct_sqlite_from_monolith(
+    path_to_monolith: &str,
+    path_to_database: &str,
+) -> Result<Box<dyn Context>, OstError> {
+    Ok(Box::new(from_monolith_file(
+        path_to_monolith,
+        path_to_database,
+    )?))
+}
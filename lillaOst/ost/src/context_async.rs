@@ -0,0 +1,314 @@
+use std::cell::RefCell;
+
+use async_trait::async_trait;
+
+use crate::context::Context;
+use crate::error::OstError;
+use crate::event::{Event, EventType};
+use crate::event_base::EventBase;
+use crate::event_key::OstEventKey;
+use crate::expulsion::{Expulsion, ExpulsionDegree};
+use crate::feed::Feed;
+use crate::measurement::{Measurement, Measures};
+use crate::nursing::{self, BreastSide, NursingSession};
+use crate::person::Person;
+use crate::person_key::OstPersonKey;
+use crate::query::{EventQuery, QueryResult};
+
+/// `Context` for the UI, where nothing may block. The futures aren't `Send`, browsers have one thread.
+#[allow(clippy::borrowed_box)]
+#[async_trait(?Send)]
+pub trait AsyncContext {
+    async fn purge_all_data(&self) -> Result<(), OstError>;
+    async fn purge_all_events(&self) -> Result<(), OstError>;
+    async fn get_base_event_by_key(&self, key: &OstEventKey) -> Option<Box<dyn EventBase>>;
+    /// Feedings, expulsions, events and measurements matching `query`, one page at a time
+    async fn query(&self, query: &EventQuery) -> Result<QueryResult, OstError>;
+
+    async fn persons(&self) -> Vec<Box<dyn Person>>;
+    async fn add_person(&self, name: &str) -> Result<Box<dyn Person>, OstError>;
+    async fn add_fake_persons(&self, count: u32) -> Result<(), OstError>;
+    async fn modify_person(&self, person: &Box<dyn Person>) -> Result<(), OstError>;
+    /// With `cascade` the person's entries are removed too, otherwise a person with entries is kept
+    async fn remove_person(&self, person: Box<dyn Person>, cascade: bool) -> Result<(), OstError>;
+    /// Moves every entry of `from` to `into` and removes `from`
+    async fn merge_persons(
+        &self,
+        from: Box<dyn Person>,
+        into: &Box<dyn Person>,
+    ) -> Result<(), OstError>;
+    async fn get_person_by_key(&self, key: OstPersonKey) -> Option<Box<dyn Person>>;
+
+    // Feedings block
+    async fn feedings(&self) -> Vec<Box<dyn Feed>>;
+    async fn feedings_by(&self, person: &Box<dyn Person>) -> Vec<Box<dyn Feed>>;
+    async fn add_feeding(
+        &self,
+        person: &Box<dyn Person>,
+        breast_milk: u32,
+        formula: u32,
+        solids: u32,
+    ) -> Result<Box<dyn Feed>, OstError>;
+    /// A feed holding only `nursing`, timed when the session started
+    async fn add_nursing(
+        &self,
+        person: &Box<dyn Person>,
+        nursing: NursingSession,
+    ) -> Result<Box<dyn Feed>, OstError>;
+    /// The side to start the next nursing session on, none before the first one
+    async fn next_nursing_side(&self, person: &Box<dyn Person>) -> Option<BreastSide> {
+        let feedings = self.feedings_by(person).await;
+        nursing::next_side(feedings.iter().filter_map(|f| f.nursing()))
+    }
+    async fn add_fake_feedings(&self, count: u32) -> Result<(), OstError>;
+    async fn modify_feeding(&self, feed: &Box<dyn Feed>) -> Result<(), OstError>;
+    async fn remove_feeding(&self, feed: Box<dyn Feed>) -> Result<(), OstError>;
+    async fn get_feeding_by_key(&self, key: &OstEventKey) -> Option<Box<dyn Feed>>;
+
+    // Expulsions block
+    async fn expulsions(&self) -> Vec<Box<dyn Expulsion>>;
+    async fn expulsions_by(&self, person: &Box<dyn Person>) -> Vec<Box<dyn Expulsion>>;
+    async fn add_expulsion(
+        &self,
+        person: &Box<dyn Person>,
+        degree: ExpulsionDegree,
+    ) -> Result<Box<dyn Expulsion>, OstError>;
+    async fn add_fake_expulsions(&self, count: u32) -> Result<(), OstError>;
+    async fn modify_expulsion(&self, expulsion: &Box<dyn Expulsion>) -> Result<(), OstError>;
+    async fn remove_expulsion(&self, expulsion: Box<dyn Expulsion>) -> Result<(), OstError>;
+    async fn get_expulsion_by_key(&self, key: &OstEventKey) -> Option<Box<dyn Expulsion>>;
+
+    // Events block
+    async fn events(&self) -> Vec<Box<dyn Event>>;
+    async fn events_by(&self, person: &Box<dyn Person>) -> Vec<Box<dyn Event>>;
+    async fn add_event(
+        &self,
+        person: &Box<dyn Person>,
+        event: EventType,
+    ) -> Result<Box<dyn Event>, OstError>;
+    async fn add_fake_events(&self, count: u32) -> Result<(), OstError>;
+    async fn modify_event(&self, event: &Box<dyn Event>) -> Result<(), OstError>;
+    async fn remove_event(&self, event: Box<dyn Event>) -> Result<(), OstError>;
+    async fn get_event_by_key(&self, key: &OstEventKey) -> Option<Box<dyn Event>>;
+
+    // Measurements block
+    async fn measurements(&self) -> Vec<Box<dyn Measurement>>;
+    async fn measurements_by(&self, person: &Box<dyn Person>) -> Vec<Box<dyn Measurement>>;
+    async fn add_measurement(
+        &self,
+        person: &Box<dyn Person>,
+        measures: Measures,
+    ) -> Result<Box<dyn Measurement>, OstError>;
+    async fn add_fake_measurements(&self, count: u32) -> Result<(), OstError>;
+    async fn modify_measurement(&self, measurement: &Box<dyn Measurement>) -> Result<(), OstError>;
+    async fn remove_measurement(&self, measurement: Box<dyn Measurement>) -> Result<(), OstError>;
+    async fn get_measurement_by_key(&self, key: &OstEventKey) -> Option<Box<dyn Measurement>>;
+}
+
+/// Any synchronous `Context` behind the async api, for demos and tests without a backend
+pub struct AsyncAdapter {
+    context: RefCell<Box<dyn Context>>,
+}
+
+impl AsyncAdapter {
+    pub fn new(context: Box<dyn Context>) -> Self {
+        Self {
+            context: RefCell::new(context),
+        }
+    }
+
+    pub fn into_inner(self) -> Box<dyn Context> {
+        self.context.into_inner()
+    }
+}
+
+#[async_trait(?Send)]
+impl AsyncContext for AsyncAdapter {
+    async fn purge_all_data(&self) -> Result<(), OstError> {
+        self.context.borrow_mut().purge_all_data()
+    }
+
+    async fn purge_all_events(&self) -> Result<(), OstError> {
+        self.context.borrow_mut().purge_all_events()
+    }
+
+    async fn get_base_event_by_key(&self, key: &OstEventKey) -> Option<Box<dyn EventBase>> {
+        self.context.borrow().get_base_event_by_key(key)
+    }
+
+    async fn query(&self, query: &EventQuery) -> Result<QueryResult, OstError> {
+        self.context.borrow().query(query)
+    }
+
+    async fn persons(&self) -> Vec<Box<dyn Person>> {
+        self.context.borrow().persons()
+    }
+
+    async fn add_person(&self, name: &str) -> Result<Box<dyn Person>, OstError> {
+        self.context.borrow_mut().add_person(name)
+    }
+
+    async fn add_fake_persons(&self, count: u32) -> Result<(), OstError> {
+        self.context.borrow_mut().add_fake_persons(count)
+    }
+
+    async fn modify_person(&self, person: &Box<dyn Person>) -> Result<(), OstError> {
+        self.context.borrow_mut().modify_person(person)
+    }
+
+    async fn remove_person(&self, person: Box<dyn Person>, cascade: bool) -> Result<(), OstError> {
+        self.context.borrow_mut().remove_person(person, cascade)
+    }
+
+    async fn merge_persons(
+        &self,
+        from: Box<dyn Person>,
+        into: &Box<dyn Person>,
+    ) -> Result<(), OstError> {
+        self.context.borrow_mut().merge_persons(from, into)
+    }
+
+    async fn get_person_by_key(&self, key: OstPersonKey) -> Option<Box<dyn Person>> {
+        self.context.borrow().get_person_by_key(key)
+    }
+
+    async fn feedings(&self) -> Vec<Box<dyn Feed>> {
+        self.context.borrow().feedings()
+    }
+
+    async fn feedings_by(&self, person: &Box<dyn Person>) -> Vec<Box<dyn Feed>> {
+        self.context.borrow().feedings_by(person)
+    }
+
+    async fn add_feeding(
+        &self,
+        person: &Box<dyn Person>,
+        breast_milk: u32,
+        formula: u32,
+        solids: u32,
+    ) -> Result<Box<dyn Feed>, OstError> {
+        self.context
+            .borrow_mut()
+            .add_feeding(person, breast_milk, formula, solids)
+    }
+
+    async fn add_nursing(
+        &self,
+        person: &Box<dyn Person>,
+        nursing: NursingSession,
+    ) -> Result<Box<dyn Feed>, OstError> {
+        self.context.borrow_mut().add_nursing(person, nursing)
+    }
+
+    async fn add_fake_feedings(&self, count: u32) -> Result<(), OstError> {
+        self.context.borrow_mut().add_fake_feedings(count)
+    }
+
+    async fn modify_feeding(&self, feed: &Box<dyn Feed>) -> Result<(), OstError> {
+        self.context.borrow_mut().modify_feeding(feed)
+    }
+
+    async fn remove_feeding(&self, feed: Box<dyn Feed>) -> Result<(), OstError> {
+        self.context.borrow_mut().remove_feeding(feed)
+    }
+
+    async fn get_feeding_by_key(&self, key: &OstEventKey) -> Option<Box<dyn Feed>> {
+        self.context.borrow().get_feeding_by_key(key)
+    }
+
+    async fn expulsions(&self) -> Vec<Box<dyn Expulsion>> {
+        self.context.borrow().expulsions()
+    }
+
+    async fn expulsions_by(&self, person: &Box<dyn Person>) -> Vec<Box<dyn Expulsion>> {
+        self.context.borrow().expulsions_by(person)
+    }
+
+    async fn add_expulsion(
+        &self,
+        person: &Box<dyn Person>,
+        degree: ExpulsionDegree,
+    ) -> Result<Box<dyn Expulsion>, OstError> {
+        self.context.borrow_mut().add_expulsion(person, degree)
+    }
+
+    async fn add_fake_expulsions(&self, count: u32) -> Result<(), OstError> {
+        self.context.borrow_mut().add_fake_expulsions(count)
+    }
+
+    async fn modify_expulsion(&self, expulsion: &Box<dyn Expulsion>) -> Result<(), OstError> {
+        self.context.borrow_mut().modify_expulsion(expulsion)
+    }
+
+    async fn remove_expulsion(&self, expulsion: Box<dyn Expulsion>) -> Result<(), OstError> {
+        self.context.borrow_mut().remove_expulsion(expulsion)
+    }
+
+    async fn get_expulsion_by_key(&self, key: &OstEventKey) -> Option<Box<dyn Expulsion>> {
+        self.context.borrow().get_expulsion_by_key(key)
+    }
+
+    async fn events(&self) -> Vec<Box<dyn Event>> {
+        self.context.borrow().events()
+    }
+
+    async fn events_by(&self, person: &Box<dyn Person>) -> Vec<Box<dyn Event>> {
+        self.context.borrow().events_by(person)
+    }
+
+    async fn add_event(
+        &self,
+        person: &Box<dyn Person>,
+        event: EventType,
+    ) -> Result<Box<dyn Event>, OstError> {
+        self.context.borrow_mut().add_event(person, event)
+    }
+
+    async fn add_fake_events(&self, count: u32) -> Result<(), OstError> {
+        self.context.borrow_mut().add_fake_events(count)
+    }
+
+    async fn modify_event(&self, event: &Box<dyn Event>) -> Result<(), OstError> {
+        self.context.borrow_mut().modify_event(event)
+    }
+
+    async fn remove_event(&self, event: Box<dyn Event>) -> Result<(), OstError> {
+        self.context.borrow_mut().remove_event(event)
+    }
+
+    async fn get_event_by_key(&self, key: &OstEventKey) -> Option<Box<dyn Event>> {
+        self.context.borrow().get_event_by_key(key)
+    }
+
+    async fn measurements(&self) -> Vec<Box<dyn Measurement>> {
+        self.context.borrow().measurements()
+    }
+
+    async fn measurements_by(&self, person: &Box<dyn Person>) -> Vec<Box<dyn Measurement>> {
+        self.context.borrow().measurements_by(person)
+    }
+
+    async fn add_measurement(
+        &self,
+        person: &Box<dyn Person>,
+        measures: Measures,
+    ) -> Result<Box<dyn Measurement>, OstError> {
+        self.context.borrow_mut().add_measurement(person, measures)
+    }
+
+    async fn add_fake_measurements(&self, count: u32) -> Result<(), OstError> {
+        self.context.borrow_mut().add_fake_measurements(count)
+    }
+
+    async fn modify_measurement(&self, measurement: &Box<dyn Measurement>) -> Result<(), OstError> {
+        self.context.borrow_mut().modify_measurement(measurement)
+    }
+
+    async fn remove_measurement(&self, measurement: Box<dyn Measurement>) -> Result<(), OstError> {
+        self.context.borrow_mut().remove_measurement(measurement)
+    }
+
+    async fn get_measurement_by_key(&self, key: &OstEventKey) -> Option<Box<dyn Measurement>> {
+        self.context.borrow().get_measurement_by_key(key)
+    }
+}
@@ -1,6 +1,8 @@
+use std::collections::{HashMap, HashSet};
 use std::ops::Add;
+use std::sync::atomic::AtomicU64;
+use std::sync::{Arc, Mutex, RwLock};
 use std::vec;
-use std::{cell::RefCell, rc::Rc};
 
 use chrono::{prelude::*, Duration};
 
@@ -10,105 +12,355 @@ use fake::{Fake, Faker};
 use rand::prelude::ThreadRng;
 use rand::Rng;
 
+use super::context_persistence::ContextPersistence;
+use super::id_allocator::IdAllocator;
+use super::journal_record::JournalRecord;
+
 use crate::context::Context;
 use crate::entity_impl::event_impl::EventImpl;
 use crate::entity_impl::expulsion_impl::ExpulsionImpl;
 use crate::entity_impl::feed_impl::FeedImpl;
+use crate::entity_impl::measurement_impl::MeasurementImpl;
 use crate::entity_impl::person_impl::PersonImpl;
+use crate::error::OstError;
 use crate::event::{Event, EventType};
 use crate::event_base::EventBase;
 use crate::event_key::{EventType as ost_EventKey, OstEventKey};
 use crate::expulsion::{Expulsion, ExpulsionDegree};
 use crate::feed::Feed;
+use crate::integrity::{self, IntegrityIssue};
+use crate::measurement::{Measurement, Measures};
+use crate::nursing::NursingSession;
+use crate::offline::{ReplayId, ReplayMark};
 use crate::person::Person;
+use crate::query::{EventQuery, QueryItem, QueryResult};
 
 pub struct ContextMonolithicImpl {
     pub target_file: String,
-    pub persons: Vec<Rc<RefCell<PersonImpl>>>,
+    pub persons: Vec<Arc<RwLock<PersonImpl>>>,
     pub feeds: Vec<FeedImpl>,
     pub expulsions: Vec<ExpulsionImpl>,
     pub events: Vec<EventImpl>,
-    pub persist_function: fn(&ContextMonolithicImpl) -> Result<(), String>,
+    pub measurements: Vec<MeasurementImpl>,
+    pub id_allocator: IdAllocator,
+    /// Persons made up on load for entries referencing a missing one, never persisted
+    pub placeholder_person_ids: HashSet<u32>,
+    /// Per client
+    pub replay_marks: HashMap<String, ReplayMark>,
+    /// The snapshot's `ContextPersistence::journal_generation`, bumped while compacting
+    pub journal_generation: AtomicU64,
+    /// Only `Some` for persistences that store single mutations instead of the whole monolith.
+    pub pending_records: Option<Mutex<Vec<JournalRecord>>>,
+    pub persist_function: fn(&ContextMonolithicImpl) -> Result<(), OstError>,
+    pub compact_function: fn(&ContextMonolithicImpl) -> Result<(), OstError>,
 }
 
 impl Context for ContextMonolithicImpl {
-    fn purge_all_data(&mut self) -> Result<(), String> {
+    fn purge_all_data(&mut self) -> Result<(), OstError> {
+        self.measurements.clear();
         self.events.clear();
         self.expulsions.clear();
         self.feeds.clear();
         self.persons.clear();
+        self.placeholder_person_ids.clear();
 
+        self.record(JournalRecord::PurgeAllData);
         (self.persist_function)(self)?;
         Ok(())
     }
 
-    fn purge_all_events(&mut self) -> Result<(), String> {
+    fn purge_all_events(&mut self) -> Result<(), OstError> {
+        self.measurements.clear();
         self.events.clear();
         self.expulsions.clear();
         self.feeds.clear();
 
+        self.record(JournalRecord::PurgeAllEvents);
         (self.persist_function)(self)?;
         Ok(())
     }
 
+    fn compact_storage(&mut self) -> Result<(), OstError> {
+        (self.compact_function)(self)
+    }
+
+    fn check_integrity(&self) -> Result<Vec<IntegrityIssue>, OstError> {
+        Ok(integrity::check(
+            &ContextPersistence::from_monolith(self),
+            Utc::now(),
+        ))
+    }
+
+    fn repair_integrity(&mut self) -> Result<Vec<IntegrityIssue>, OstError> {
+        let mut persistence = ContextPersistence::from_monolith(self);
+        let issues = integrity::repair(&mut persistence, Utc::now());
+        if issues.is_empty() {
+            return Ok(issues);
+        }
+
+        let repaired = persistence.into_monolith(&self.target_file, self.persist_function);
+        self.persons = repaired.persons;
+        self.feeds = repaired.feeds;
+        self.expulsions = repaired.expulsions;
+        self.events = repaired.events;
+        self.measurements = repaired.measurements;
+        self.id_allocator = repaired.id_allocator;
+        self.placeholder_person_ids = repaired.placeholder_person_ids;
+        self.sort_collection_by_time_stamp();
+
+        // Ids may have changed, single records can't describe that
+        (self.compact_function)(self)?;
+        Ok(issues)
+    }
+
+    fn query(&self, query: &EventQuery) -> Result<QueryResult, OstError> {
+        let mut items = vec![];
+        self.feeds
+            .iter()
+            .filter(|f| query.matches(ost_EventKey::Feed, &f.person.read().unwrap(), &f.time_stamp))
+            .for_each(|f| items.push(QueryItem::Feed(Box::new(f.clone()))));
+        self.expulsions
+            .iter()
+            .filter(|e| {
+                query.matches(
+                    ost_EventKey::Expulsion,
+                    &e.person.read().unwrap(),
+                    &e.time_stamp,
+                )
+            })
+            .for_each(|e| items.push(QueryItem::Expulsion(Box::new(e.clone()))));
+        self.events
+            .iter()
+            .filter(|e| {
+                query.matches(
+                    ost_EventKey::Event,
+                    &e.person.read().unwrap(),
+                    &e.time_stamp,
+                )
+            })
+            .for_each(|e| items.push(QueryItem::Event(Box::new(e.clone()))));
+        self.measurements
+            .iter()
+            .filter(|m| {
+                query.matches(
+                    ost_EventKey::Measurement,
+                    &m.person.read().unwrap(),
+                    &m.time_stamp,
+                )
+            })
+            .for_each(|m| items.push(QueryItem::Measurement(Box::new(m.clone()))));
+        Ok(query.page(items))
+    }
+
     fn persons(&self) -> Vec<Box<dyn Person>> {
         let mut output: Vec<Box<dyn Person>> = vec![];
         self.persons.iter().for_each(|p| {
-            let nu_p: Box<dyn Person> = Box::new(p.borrow().clone());
+            let nu_p: Box<dyn Person> = Box::new(p.read().unwrap().clone());
             output.push(nu_p);
         });
         output
     }
 
-    fn add_person(&mut self, name: &str) -> Result<Box<dyn Person>, String> {
-        if let Some(_existing) = self.persons.iter().find(|p| p.borrow().name() == name) {
-            return Err(format!("Person with name {} already exists", name));
+    fn add_person(&mut self, name: &str) -> Result<Box<dyn Person>, OstError> {
+        if let Some(_existing) = self
+            .persons
+            .iter()
+            .find(|p| p.read().unwrap().name() == name)
+        {
+            return Err(OstError::DuplicateName(format!(
+                "Person with name {} already exists",
+                name
+            )));
         }
 
-        let p = PersonImpl::new(self.persons.len() as u32, name);
-        self.persons.push(Rc::new(RefCell::new(p.clone())));
+        let p = PersonImpl::new(self.id_allocator.next_person_id(), name);
+        self.persons.push(Arc::new(RwLock::new(p.clone())));
+        self.record(JournalRecord::PersonUpserted(p.clone()));
         self.sort_collection_by_time_stamp();
         (self.persist_function)(self)?;
         Ok(Box::new(p))
     }
 
-    fn add_fake_persons(&mut self, count: u32) -> Result<(), String> {
+    fn add_fake_persons(&mut self, count: u32) -> Result<(), OstError> {
         for _i in 0..count {
             let first_name: String = Name(EN).fake();
-            let p = PersonImpl::new(self.persons.len() as u32, first_name.as_str());
-            self.persons.push(Rc::new(RefCell::new(p.clone())));
+            let p = PersonImpl::new(self.id_allocator.next_person_id(), first_name.as_str());
+            self.persons.push(Arc::new(RwLock::new(p.clone())));
+            self.record(JournalRecord::PersonUpserted(p));
         }
         self.sort_collection_by_time_stamp();
         (self.persist_function)(self)?;
         Ok(())
     }
 
-    fn modify_person(&mut self, person: &Box<dyn Person>) -> Result<(), String> {
+    fn modify_person(&mut self, person: &Box<dyn Person>) -> Result<(), OstError> {
         let person_impl_id: u32 = PersonImpl::from_person(person).id();
 
         if let Some(existing_person) = self
             .persons
             .iter_mut()
-            .find(|p| p.borrow().id() == person_impl_id)
+            .find(|p| p.read().unwrap().id() == person_impl_id)
         {
-            let original_name = existing_person.borrow().name().to_string();
-            existing_person
-                .borrow_mut()
-                .set_is_active(person.is_active());
+            let original_name = existing_person.read().unwrap().name().to_string();
+            {
+                let mut existing_person = existing_person.write().unwrap();
+                existing_person.set_is_active(person.is_active());
+                existing_person.set_birth_date(person.birth_date());
+                existing_person.set_sex(person.sex());
+                existing_person.set_birth_weight(person.birth_weight());
+                existing_person.set_colour(person.colour());
+            }
 
             if original_name != person.name() {
-                existing_person.borrow_mut().set_name(person.name());
+                existing_person.write().unwrap().set_name(person.name());
             }
+            let updated = existing_person.read().unwrap().clone();
+            // Once modified a placeholder is a person like any other
+            self.placeholder_person_ids.remove(&updated.id);
+            self.record(JournalRecord::PersonUpserted(updated));
         } else {
-            return Err(format!("No person with name {} found", person.name()));
+            return Err(OstError::NotFound(format!(
+                "No person with name {} found",
+                person.name()
+            )));
+        }
+        (self.persist_function)(self)?;
+        Ok(())
+    }
+
+    fn remove_person(&mut self, person: Box<dyn Person>, cascade: bool) -> Result<(), OstError> {
+        let person_id = person.key().id;
+        let position = match self
+            .persons
+            .iter()
+            .position(|p| p.read().unwrap().id() == person_id)
+        {
+            Some(position) => position,
+            None => {
+                return Err(OstError::NotFound(format!(
+                    "No person with name {} found",
+                    person.name()
+                )))
+            }
+        };
+
+        let entry_count = self.entry_count_by(person_id);
+        if entry_count > 0 && !cascade {
+            return Err(OstError::Validation(format!(
+                "{} still has {} entries, merge or remove them first",
+                person.name(),
+                entry_count
+            )));
+        }
+
+        self.feeds
+            .retain(|f| f.person.read().unwrap().id() != person_id);
+        self.expulsions
+            .retain(|e| e.person.read().unwrap().id() != person_id);
+        self.events
+            .retain(|e| e.person.read().unwrap().id() != person_id);
+        self.measurements
+            .retain(|m| m.person.read().unwrap().id() != person_id);
+        self.persons.remove(position);
+        self.placeholder_person_ids.remove(&person_id);
+
+        self.record(JournalRecord::PersonRemoved(person_id));
+        (self.persist_function)(self)?;
+        Ok(())
+    }
+
+    fn merge_persons(
+        &mut self,
+        from: Box<dyn Person>,
+        into: &Box<dyn Person>,
+    ) -> Result<(), OstError> {
+        let from_id = from.key().id;
+        let into_id = into.key().id;
+        if from_id == into_id {
+            return Err(OstError::Validation(format!(
+                "Can't merge {} into itself",
+                from.name()
+            )));
+        }
+
+        let from_position = match self
+            .persons
+            .iter()
+            .position(|p| p.read().unwrap().id() == from_id)
+        {
+            Some(position) => position,
+            None => {
+                return Err(OstError::NotFound(format!(
+                    "No person with name {} found",
+                    from.name()
+                )))
+            }
+        };
+        let into_person = match self
+            .persons
+            .iter()
+            .find(|p| p.read().unwrap().id() == into_id)
+        {
+            Some(p) => p.clone(),
+            None => {
+                return Err(OstError::NotFound(format!(
+                    "No person with name {} found",
+                    into.name()
+                )))
+            }
+        };
+
+        let mut records = vec![];
+        for feed in self
+            .feeds
+            .iter_mut()
+            .filter(|f| f.person.read().unwrap().id() == from_id)
+        {
+            feed.person = into_person.clone();
+            records.push(JournalRecord::FeedUpserted(feed.to_persistence()));
+        }
+        for expulsion in self
+            .expulsions
+            .iter_mut()
+            .filter(|e| e.person.read().unwrap().id() == from_id)
+        {
+            expulsion.person = into_person.clone();
+            records.push(JournalRecord::ExpulsionUpserted(expulsion.to_persistence()));
         }
+        for event in self
+            .events
+            .iter_mut()
+            .filter(|e| e.person.read().unwrap().id() == from_id)
+        {
+            event.person = into_person.clone();
+            records.push(JournalRecord::EventUpserted(event.to_persistence()));
+        }
+        for measurement in self
+            .measurements
+            .iter_mut()
+            .filter(|m| m.person.read().unwrap().id() == from_id)
+        {
+            measurement.person = into_person.clone();
+            records.push(JournalRecord::MeasurementUpserted(
+                measurement.to_persistence(),
+            ));
+        }
+        self.persons.remove(from_position);
+        self.placeholder_person_ids.remove(&from_id);
+        records.push(JournalRecord::PersonRemoved(from_id));
+
+        records.into_iter().for_each(|record| self.record(record));
         (self.persist_function)(self)?;
         Ok(())
     }
 
     fn get_person_by_key(&self, key: crate::person_key::OstPersonKey) -> Option<Box<dyn Person>> {
-        match self.persons.iter().find(|p| p.borrow().id() == key.id) {
-            Some(p) => Some(Box::new(p.borrow().clone())),
+        match self
+            .persons
+            .iter()
+            .find(|p| p.read().unwrap().id() == key.id)
+        {
+            Some(p) => Some(Box::new(p.read().unwrap().clone())),
             None => None,
         }
     }
@@ -125,16 +377,10 @@ impl Context for ContextMonolithicImpl {
     fn feedings_by(&self, person: &Box<dyn Person>) -> Vec<Box<dyn Feed>> {
         let person_impl_id: u32 = PersonImpl::from_person(person).id();
 
-        let _existing_p = self
-            .persons
-            .iter()
-            .find(|p| p.borrow().id() == person_impl_id)
-            .expect("this person should exist");
-
         let mut output: Vec<Box<dyn Feed>> = vec![];
         self.feeds
             .iter()
-            .filter(|p| p.person.borrow().id() == person_impl_id)
+            .filter(|p| p.person.read().unwrap().id() == person_impl_id)
             .for_each(|f| {
                 let nu_feed: Box<dyn Feed> = Box::new(f.clone());
                 output.push(nu_feed);
@@ -142,36 +388,56 @@ impl Context for ContextMonolithicImpl {
         output
     }
 
-    fn add_feeding(
+    fn add_feeding_at(
         &mut self,
         person: &Box<dyn Person>,
         breast_milk: u32,
         formula: u32,
         solids: u32,
-    ) -> Result<Box<dyn Feed>, String> {
-        let person_impl_id: u32 = PersonImpl::from_person(person).id();
-
-        let existing_p = self
-            .persons
-            .iter()
-            .find(|p| p.borrow().id() == person_impl_id)
-            .expect("this person should exist");
-
-        let f = FeedImpl::new(
-            existing_p.clone(),
-            self.feeds.len() as u32,
+        time_stamp: DateTime<Utc>,
+        replay_id: Option<&ReplayId>,
+    ) -> Result<Box<dyn Feed>, OstError> {
+        let existing_p = self.existing_person(person.as_ref())?;
+
+        let mut f = FeedImpl::new(
+            existing_p,
+            self.id_allocator.next_feed_id(),
             breast_milk,
             formula,
             solids,
         );
+        f.time_stamp = time_stamp;
 
         self.feeds.push(f.clone());
+        self.record(JournalRecord::FeedUpserted(f.to_persistence()));
+        self.mark_replayed(replay_id, f.key());
         self.sort_collection_by_time_stamp();
         (self.persist_function)(self)?;
         Ok(Box::new(f))
     }
 
-    fn add_fake_feedings(&mut self, count: u32) -> Result<(), String> {
+    fn add_nursing_replayed(
+        &mut self,
+        person: &Box<dyn Person>,
+        nursing: NursingSession,
+        replay_id: Option<&ReplayId>,
+    ) -> Result<Box<dyn Feed>, OstError> {
+        nursing.validate()?;
+        let existing_p = self.existing_person(person.as_ref())?;
+
+        let mut f = FeedImpl::new(existing_p, self.id_allocator.next_feed_id(), 0, 0, 0);
+        f.time_stamp = nursing.start().unwrap_or(f.time_stamp);
+        f.nursing = Some(nursing);
+
+        self.feeds.push(f.clone());
+        self.record(JournalRecord::FeedUpserted(f.to_persistence()));
+        self.mark_replayed(replay_id, f.key());
+        self.sort_collection_by_time_stamp();
+        (self.persist_function)(self)?;
+        Ok(Box::new(f))
+    }
+
+    fn add_fake_feedings(&mut self, count: u32) -> Result<(), OstError> {
         assert!(
             !self.persons.is_empty(),
             "You can't add fake feedings without creating persons first!"
@@ -185,7 +451,7 @@ impl Context for ContextMonolithicImpl {
                 .expect("getting rand person, this person should exist ?");
             let mut f = FeedImpl::new(
                 existing_person.clone(),
-                self.feeds.len() as u32,
+                self.id_allocator.next_feed_id(),
                 rng.gen_range(0..150),
                 rng.gen_range(0..150),
                 rng.gen_range(0..150),
@@ -193,15 +459,19 @@ impl Context for ContextMonolithicImpl {
 
             f.time_stamp = ContextMonolithicImpl::random_time_stamp_in_the_past(&mut rng);
 
-            self.feeds.push(f.clone());
+            self.record(JournalRecord::FeedUpserted(f.to_persistence()));
+            self.feeds.push(f);
         }
         self.sort_collection_by_time_stamp();
         (self.persist_function)(self)?;
         Ok(())
     }
 
-    fn modify_feeding(&mut self, feed: &Box<dyn Feed>) -> Result<(), String> {
+    fn modify_feeding(&mut self, feed: &Box<dyn Feed>) -> Result<(), OstError> {
         let feed_impl_id: u32 = FeedImpl::from_feed(feed).id();
+        if let Some(nursing) = feed.nursing() {
+            nursing.validate()?;
+        }
 
         if let Some(existing_feed) = self.feeds.iter_mut().find(|f| f.id() == feed_impl_id) {
             existing_feed.modify_feed(
@@ -210,19 +480,22 @@ impl Context for ContextMonolithicImpl {
                 feed.solids(),
                 *feed.time_stamp(),
             );
+            existing_feed.modify_nursing(feed.nursing());
+            let updated = existing_feed.to_persistence();
+            self.record(JournalRecord::FeedUpserted(updated));
         } else {
-            return Err(format!(
+            return Err(OstError::NotFound(format!(
                 "Feeding not found: {} {}",
                 feed.person_name(),
                 feed.time_stamp(),
-            ));
+            )));
         }
         self.sort_collection_by_time_stamp();
         (self.persist_function)(self)?;
         Ok(())
     }
 
-    fn remove_feeding(&mut self, feed: Box<dyn Feed>) -> Result<(), String> {
+    fn remove_feeding(&mut self, feed: Box<dyn Feed>) -> Result<(), OstError> {
         let feed_id_to_remove: u32 = FeedImpl::from_feed(&feed).id();
 
         if let Some(pos_to_remove) = self
@@ -231,12 +504,13 @@ impl Context for ContextMonolithicImpl {
             .position(|feed| feed.id() == feed_id_to_remove)
         {
             let _ = self.feeds.remove(pos_to_remove);
+            self.record(JournalRecord::FeedRemoved(feed_id_to_remove));
         } else {
-            return Err(format!(
+            return Err(OstError::NotFound(format!(
                 "Feeding not found: {} {}",
                 feed.person_name(),
                 feed.time_stamp(),
-            ));
+            )));
         }
         self.sort_collection_by_time_stamp();
         (self.persist_function)(self)?;
@@ -255,16 +529,10 @@ impl Context for ContextMonolithicImpl {
     fn expulsions_by(&self, person: &Box<dyn Person>) -> Vec<Box<dyn Expulsion>> {
         let person_impl_id: u32 = PersonImpl::from_person(person).id();
 
-        let _existing_p = self
-            .persons
-            .iter()
-            .find(|p| p.borrow().id() == person_impl_id)
-            .expect("this person should exist");
-
         let mut output: Vec<Box<dyn Expulsion>> = vec![];
         self.expulsions
             .iter()
-            .filter(|p| p.person.borrow().id() == person_impl_id)
+            .filter(|p| p.person.read().unwrap().id() == person_impl_id)
             .for_each(|e| {
                 let nu_feed: Box<dyn Expulsion> = Box::new(e.clone());
                 output.push(nu_feed);
@@ -272,29 +540,28 @@ impl Context for ContextMonolithicImpl {
         output
     }
 
-    fn add_expulsion(
+    fn add_expulsion_at(
         &mut self,
         person: &Box<dyn Person>,
         degree: ExpulsionDegree,
-    ) -> Result<Box<dyn Expulsion>, String> {
-        let person_impl_id: u32 = PersonImpl::from_person(person).id();
+        time_stamp: DateTime<Utc>,
+        replay_id: Option<&ReplayId>,
+    ) -> Result<Box<dyn Expulsion>, OstError> {
+        let existing_p = self.existing_person(person.as_ref())?;
 
-        let existing_p = self
-            .persons
-            .iter()
-            .find(|p| p.borrow().id() == person_impl_id)
-            .expect("this person should exist");
-
-        let expulsion =
-            ExpulsionImpl::new(existing_p.clone(), self.expulsions.len() as u32, degree);
+        let mut expulsion =
+            ExpulsionImpl::new(existing_p, self.id_allocator.next_expulsion_id(), degree);
+        expulsion.time_stamp = time_stamp;
 
         self.expulsions.push(expulsion.clone());
+        self.record(JournalRecord::ExpulsionUpserted(expulsion.to_persistence()));
+        self.mark_replayed(replay_id, expulsion.key());
         self.sort_collection_by_time_stamp();
         (self.persist_function)(self)?;
         Ok(Box::new(expulsion))
     }
 
-    fn add_fake_expulsions(&mut self, count: u32) -> Result<(), String> {
+    fn add_fake_expulsions(&mut self, count: u32) -> Result<(), OstError> {
         assert!(
             !self.persons.is_empty(),
             "You can't add fake feedings without creating persons first!"
@@ -308,19 +575,20 @@ impl Context for ContextMonolithicImpl {
 
             let mut expulsion = ExpulsionImpl::new(
                 existing_person.clone(),
-                self.expulsions.len() as u32,
+                self.id_allocator.next_expulsion_id(),
                 Faker.fake::<ExpulsionDegree>(),
             );
 
             expulsion.time_stamp = ContextMonolithicImpl::random_time_stamp_in_the_past(&mut rng);
-            self.expulsions.push(expulsion.clone());
+            self.record(JournalRecord::ExpulsionUpserted(expulsion.to_persistence()));
+            self.expulsions.push(expulsion);
         }
         self.sort_collection_by_time_stamp();
         (self.persist_function)(self)?;
         Ok(())
     }
 
-    fn remove_expulsion(&mut self, expulsion: Box<dyn Expulsion>) -> Result<(), String> {
+    fn remove_expulsion(&mut self, expulsion: Box<dyn Expulsion>) -> Result<(), OstError> {
         let expulsion_impl_id: u32 = ExpulsionImpl::from_expulsion(&expulsion).id();
 
         if let Some(pos_to_remove) = self
@@ -329,19 +597,20 @@ impl Context for ContextMonolithicImpl {
             .position(|feed| feed.id() == expulsion_impl_id)
         {
             let _ = self.expulsions.remove(pos_to_remove);
+            self.record(JournalRecord::ExpulsionRemoved(expulsion_impl_id));
         } else {
-            return Err(format!(
+            return Err(OstError::NotFound(format!(
                 "Expulsion not found: {} {}",
                 expulsion.person_name(),
                 expulsion.time_stamp(),
-            ));
+            )));
         }
         self.sort_collection_by_time_stamp();
         (self.persist_function)(self)?;
         Ok(())
     }
 
-    fn modify_expulsion(&mut self, expulsion: &Box<dyn Expulsion>) -> Result<(), String> {
+    fn modify_expulsion(&mut self, expulsion: &Box<dyn Expulsion>) -> Result<(), OstError> {
         let expulsion_impl_id: u32 = ExpulsionImpl::from_expulsion(expulsion).id();
 
         if let Some(existing_expulsion) = self
@@ -350,12 +619,14 @@ impl Context for ContextMonolithicImpl {
             .find(|exp| exp.id() == expulsion_impl_id)
         {
             existing_expulsion.modify_expulsion(expulsion.degree(), *expulsion.time_stamp());
+            let updated = existing_expulsion.to_persistence();
+            self.record(JournalRecord::ExpulsionUpserted(updated));
         } else {
-            return Err(format!(
+            return Err(OstError::NotFound(format!(
                 "Expulsion not found: {} {}",
                 expulsion.person_name(),
                 expulsion.time_stamp(),
-            ));
+            )));
         }
         self.sort_collection_by_time_stamp();
         (self.persist_function)(self)?;
@@ -374,16 +645,10 @@ impl Context for ContextMonolithicImpl {
     fn events_by(&self, person: &Box<dyn Person>) -> Vec<Box<dyn Event>> {
         let person_impl_id: u32 = PersonImpl::from_person(person).id();
 
-        let _existing_p = self
-            .persons
-            .iter()
-            .find(|p| p.borrow().id() == person_impl_id)
-            .expect("this person should exist");
-
         let mut output: Vec<Box<dyn Event>> = vec![];
         self.events
             .iter()
-            .filter(|p| p.person.borrow().id() == person_impl_id)
+            .filter(|p| p.person.read().unwrap().id() == person_impl_id)
             .for_each(|e| {
                 let nu_feed: Box<dyn Event> = Box::new(e.clone());
                 output.push(nu_feed);
@@ -391,27 +656,26 @@ impl Context for ContextMonolithicImpl {
         output
     }
 
-    fn add_event(
+    fn add_event_at(
         &mut self,
         person: &Box<dyn Person>,
         event_type: crate::event::EventType,
-    ) -> Result<Box<dyn crate::event::Event>, String> {
-        let person_impl_id: u32 = PersonImpl::from_person(person).id();
+        time_stamp: DateTime<Utc>,
+        replay_id: Option<&ReplayId>,
+    ) -> Result<Box<dyn crate::event::Event>, OstError> {
+        let existing_p = self.existing_person(person.as_ref())?;
 
-        let existing_p = self
-            .persons
-            .iter()
-            .find(|p| p.borrow().id() == person_impl_id)
-            .expect("this person should exist");
-
-        let event = EventImpl::new(existing_p.clone(), self.events.len() as u32, event_type);
+        let mut event = EventImpl::new(existing_p, self.id_allocator.next_event_id(), event_type);
+        event.time_stamp = time_stamp;
         self.events.push(event.clone());
+        self.record(JournalRecord::EventUpserted(event.to_persistence()));
+        self.mark_replayed(replay_id, event.key());
         self.sort_collection_by_time_stamp();
         (self.persist_function)(self)?;
         Ok(Box::new(event))
     }
 
-    fn add_fake_events(&mut self, count: u32) -> Result<(), String> {
+    fn add_fake_events(&mut self, count: u32) -> Result<(), OstError> {
         assert!(
             !self.persons.is_empty(),
             "You can't add fake feedings without creating persons first!"
@@ -425,10 +689,11 @@ impl Context for ContextMonolithicImpl {
 
             let mut event = EventImpl::new(
                 existing_person.clone(),
-                self.events.len() as u32,
+                self.id_allocator.next_event_id(),
                 Faker.fake::<EventType>(),
             );
             event.time_stamp = ContextMonolithicImpl::random_time_stamp_in_the_past(&mut rng);
+            self.record(JournalRecord::EventUpserted(event.to_persistence()));
             self.events.push(event);
         }
         self.sort_collection_by_time_stamp();
@@ -436,7 +701,7 @@ impl Context for ContextMonolithicImpl {
         Ok(())
     }
 
-    fn modify_event(&mut self, event: &Box<dyn Event>) -> Result<(), String> {
+    fn modify_event(&mut self, event: &Box<dyn Event>) -> Result<(), OstError> {
         let event_impl_id: u32 = EventImpl::from_event(event).id();
         if let Some(existing_event) = self
             .events
@@ -444,19 +709,21 @@ impl Context for ContextMonolithicImpl {
             .find(|event| event.id() == event_impl_id)
         {
             existing_event.modify_event(*event.time_stamp(), event.event());
+            let updated = existing_event.to_persistence();
+            self.record(JournalRecord::EventUpserted(updated));
         } else {
-            return Err(format!(
+            return Err(OstError::NotFound(format!(
                 "Event not found: {} {}",
                 event.person_name(),
                 event.time_stamp(),
-            ));
+            )));
         }
         self.sort_collection_by_time_stamp();
         (self.persist_function)(self)?;
         Ok(())
     }
 
-    fn remove_event(&mut self, event: Box<dyn Event>) -> Result<(), String> {
+    fn remove_event(&mut self, event: Box<dyn Event>) -> Result<(), OstError> {
         let event_impl_id: u32 = EventImpl::from_event(&event).id();
         if let Some(index_existing_event) = self
             .events
@@ -464,12 +731,13 @@ impl Context for ContextMonolithicImpl {
             .position(|event| event.id() == event_impl_id)
         {
             self.events.remove(index_existing_event);
+            self.record(JournalRecord::EventRemoved(event_impl_id));
         } else {
-            return Err(format!(
+            return Err(OstError::NotFound(format!(
                 "Event not found: {} {}",
                 event.person_name(),
                 event.time_stamp(),
-            ));
+            )));
         }
         self.sort_collection_by_time_stamp();
         (self.persist_function)(self)?;
@@ -493,6 +761,11 @@ impl Context for ContextMonolithicImpl {
                     return Some(Box::new(event.clone()) as Box<dyn EventBase>);
                 }
             }
+            ost_EventKey::Measurement => {
+                if let Some(measurement) = self.measurements.iter().find(|m| m.id() == key.id) {
+                    return Some(Box::new(measurement.clone()) as Box<dyn EventBase>);
+                }
+            }
         }
         None
     }
@@ -526,10 +799,212 @@ impl Context for ContextMonolithicImpl {
         }
         None
     }
+
+    fn measurements(&self) -> Vec<Box<dyn Measurement>> {
+        self.measurements
+            .iter()
+            .map(|m| Box::new(m.clone()) as Box<dyn Measurement>)
+            .collect()
+    }
+
+    fn measurements_by(&self, person: &Box<dyn Person>) -> Vec<Box<dyn Measurement>> {
+        let person_id = person.key().id;
+        self.measurements
+            .iter()
+            .filter(|m| m.person.read().unwrap().id() == person_id)
+            .map(|m| Box::new(m.clone()) as Box<dyn Measurement>)
+            .collect()
+    }
+
+    fn add_measurement_at(
+        &mut self,
+        person: &Box<dyn Person>,
+        measures: Measures,
+        time_stamp: DateTime<Utc>,
+        replay_id: Option<&ReplayId>,
+    ) -> Result<Box<dyn Measurement>, OstError> {
+        measures.validate()?;
+        let existing_p = self.existing_person(person.as_ref())?;
+
+        let mut measurement = MeasurementImpl::new(
+            existing_p,
+            self.id_allocator.next_measurement_id(),
+            measures,
+        );
+        measurement.time_stamp = time_stamp;
+        self.measurements.push(measurement.clone());
+        self.record(JournalRecord::MeasurementUpserted(
+            measurement.to_persistence(),
+        ));
+        self.mark_replayed(replay_id, measurement.key());
+        self.sort_collection_by_time_stamp();
+        (self.persist_function)(self)?;
+        Ok(Box::new(measurement))
+    }
+
+    fn add_fake_measurements(&mut self, count: u32) -> Result<(), OstError> {
+        assert!(
+            !self.persons.is_empty(),
+            "You can't add fake measurements without creating persons first!"
+        );
+        let mut rng = rand::thread_rng();
+        for _i in 0..count {
+            let existing_person = self
+                .persons
+                .get(rng.gen_range(0..self.persons.len() as usize))
+                .expect("getting rand person, this person should exist ?");
+
+            let mut measurement = MeasurementImpl::new(
+                existing_person.clone(),
+                self.id_allocator.next_measurement_id(),
+                ContextMonolithicImpl::random_measures(&mut rng),
+            );
+            measurement.time_stamp = ContextMonolithicImpl::random_time_stamp_in_the_past(&mut rng);
+            self.record(JournalRecord::MeasurementUpserted(
+                measurement.to_persistence(),
+            ));
+            self.measurements.push(measurement);
+        }
+        self.sort_collection_by_time_stamp();
+        (self.persist_function)(self)?;
+        Ok(())
+    }
+
+    fn modify_measurement(&mut self, measurement: &Box<dyn Measurement>) -> Result<(), OstError> {
+        measurement.measures().validate()?;
+        let measurement_impl_id = measurement.key().id;
+        if let Some(existing_measurement) = self
+            .measurements
+            .iter_mut()
+            .find(|m| m.id() == measurement_impl_id)
+        {
+            existing_measurement
+                .modify_measurement(measurement.measures(), *measurement.time_stamp());
+            let updated = existing_measurement.to_persistence();
+            self.record(JournalRecord::MeasurementUpserted(updated));
+        } else {
+            return Err(OstError::NotFound(format!(
+                "Measurement not found: {} {}",
+                measurement.person_name(),
+                measurement.time_stamp(),
+            )));
+        }
+        self.sort_collection_by_time_stamp();
+        (self.persist_function)(self)?;
+        Ok(())
+    }
+
+    fn remove_measurement(&mut self, measurement: Box<dyn Measurement>) -> Result<(), OstError> {
+        let measurement_impl_id = measurement.key().id;
+        if let Some(position) = self
+            .measurements
+            .iter()
+            .position(|m| m.id() == measurement_impl_id)
+        {
+            self.measurements.remove(position);
+            self.record(JournalRecord::MeasurementRemoved(measurement_impl_id));
+        } else {
+            return Err(OstError::NotFound(format!(
+                "Measurement not found: {} {}",
+                measurement.person_name(),
+                measurement.time_stamp(),
+            )));
+        }
+        (self.persist_function)(self)?;
+        Ok(())
+    }
+
+    fn get_measurement_by_key(&self, key: &OstEventKey) -> Option<Box<dyn Measurement>> {
+        if key.t != ost_EventKey::Measurement {
+            return None;
+        }
+        self.measurements
+            .iter()
+            .find(|m| m.id() == key.id)
+            .map(|m| Box::new(m.clone()) as Box<dyn Measurement>)
+    }
+
+    fn replay_mark(&self, client: &str) -> Option<ReplayMark> {
+        self.replay_marks.get(client).cloned()
+    }
 }
 
 impl ContextMonolithicImpl {
-    fn random_time_stamp_in_the_past(rng: &mut ThreadRng) -> DateTime<Utc> {
+    fn existing_person(&self, person: &dyn Person) -> Result<Arc<RwLock<PersonImpl>>, OstError> {
+        let person_id = person.key().id;
+        match self
+            .persons
+            .iter()
+            .find(|p| p.read().unwrap().id() == person_id)
+        {
+            Some(p) => Ok(p.clone()),
+            None => Err(OstError::NotFound(format!(
+                "No person with name {} found",
+                person.name()
+            ))),
+        }
+    }
+
+    /// Moves the id counters past every id in use and gives a fresh id to every
+    /// person, feed, expulsion, event or measurement that shares its id with an earlier one.
+    /// Entries keep pointing to the first person with their person id.
+    /// Returns how many got a new id.
+    pub fn repair_duplicated_ids(&mut self) -> u32 {
+        self.id_allocator.skip_past(
+            self.persons.iter().map(|p| p.read().unwrap().id()).max(),
+            self.feeds.iter().map(|f| f.id).max(),
+            self.expulsions.iter().map(|e| e.id).max(),
+            self.events.iter().map(|e| e.id).max(),
+            self.measurements.iter().map(|m| m.id).max(),
+        );
+
+        let mut repaired = 0;
+
+        let mut seen = HashSet::new();
+        for person in self.persons.iter() {
+            let mut person = person.write().unwrap();
+            if !seen.insert(person.id) {
+                person.id = self.id_allocator.next_person_id();
+                repaired += 1;
+            }
+        }
+
+        let mut seen = HashSet::new();
+        for feed in self.feeds.iter_mut() {
+            if !seen.insert(feed.id) {
+                feed.id = self.id_allocator.next_feed_id();
+                repaired += 1;
+            }
+        }
+
+        let mut seen = HashSet::new();
+        for expulsion in self.expulsions.iter_mut() {
+            if !seen.insert(expulsion.id) {
+                expulsion.id = self.id_allocator.next_expulsion_id();
+                repaired += 1;
+            }
+        }
+
+        let mut seen = HashSet::new();
+        for event in self.events.iter_mut() {
+            if !seen.insert(event.id) {
+                event.id = self.id_allocator.next_event_id();
+                repaired += 1;
+            }
+        }
+
+        let mut seen = HashSet::new();
+        for measurement in self.measurements.iter_mut() {
+            if !seen.insert(measurement.id) {
+                measurement.id = self.id_allocator.next_measurement_id();
+                repaired += 1;
+            }
+        }
+
+        repaired
+    }
+
+    pub(crate) fn random_time_stamp_in_the_past(rng: &mut ThreadRng) -> DateTime<Utc> {
         let time = Utc::now();
         let days_before = rng.gen_range(0..365);
         let duration_days = Duration::days(-days_before);
@@ -538,11 +1013,67 @@ impl ContextMonolithicImpl {
         time.add(duration_days).add(shift_hours).add(shift_minutes)
     }
 
-    fn sort_collection_by_time_stamp(&mut self) {
+    /// Somewhere between a newborn and a toddler, not every value measured every time
+    pub(crate) fn random_measures(rng: &mut ThreadRng) -> Measures {
+        let growth: f64 = rng.gen_range(0.0..1.0);
+        Measures {
+            weight: Some(2500 + (growth * 9500.0) as u32),
+            length: rng
+                .gen_bool(0.7)
+                .then(|| ((46.0 + growth * 34.0) * 10.0).round() / 10.0),
+            head_circumference: rng
+                .gen_bool(0.5)
+                .then(|| ((33.0 + growth * 14.0) * 10.0).round() / 10.0),
+        }
+    }
+
+    fn entry_count_by(&self, person_id: u32) -> usize {
+        self.feeds
+            .iter()
+            .filter(|f| f.person.read().unwrap().id() == person_id)
+            .count()
+            + self
+                .expulsions
+                .iter()
+                .filter(|e| e.person.read().unwrap().id() == person_id)
+                .count()
+            + self
+                .events
+                .iter()
+                .filter(|e| e.person.read().unwrap().id() == person_id)
+                .count()
+            + self
+                .measurements
+                .iter()
+                .filter(|m| m.person.read().unwrap().id() == person_id)
+                .count()
+    }
+
+    fn mark_replayed(&mut self, replay_id: Option<&ReplayId>, key: OstEventKey) {
+        if let Some(replay_id) = replay_id {
+            let mark = ReplayMark {
+                id: replay_id.id,
+                key,
+            };
+            self.replay_marks
+                .insert(replay_id.client.clone(), mark.clone());
+            self.record(JournalRecord::ReplayMarked(replay_id.client.clone(), mark));
+        }
+    }
+
+    fn record(&self, record: JournalRecord) {
+        if let Some(pending_records) = &self.pending_records {
+            pending_records.lock().unwrap().push(record);
+        }
+    }
+
+    pub(crate) fn sort_collection_by_time_stamp(&mut self) {
         self.feeds.sort_by(|a, b| b.time_stamp.cmp(&a.time_stamp));
         self.expulsions
             .sort_by(|a, b| b.time_stamp.cmp(&a.time_stamp));
         self.events.sort_by(|a, b| b.time_stamp.cmp(&a.time_stamp));
+        self.measurements
+            .sort_by(|a, b| b.time_stamp.cmp(&a.time_stamp));
     }
 }
 
@@ -0,0 +1,243 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+use serde_derive::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::context_monolithic_impl::ContextMonolithicImpl;
+use super::id_allocator::IdAllocator;
+
+use crate::entity_impl::event_impl::EventImpl;
+use crate::entity_impl::expulsion_impl::ExpulsionImpl;
+use crate::entity_impl::feed_impl::FeedImpl;
+use crate::entity_impl::measurement_impl::MeasurementImpl;
+use crate::entity_impl::person_impl::PersonImpl;
+
+use crate::integrity::add_missing_persons;
+
+use crate::entity_impl::event_impl::EventPersistence;
+use crate::entity_impl::expulsion_impl::ExpulsionPersistence;
+use crate::entity_impl::feed_impl::FeedPersistence;
+use crate::entity_impl::measurement_impl::MeasurementPersistence;
+use crate::error::OstError;
+use crate::offline::ReplayMark;
+
+/// Version of the documents written by `ContextPersistence`.
+/// Bumping it requires a new entry in `MIGRATORS` and a new sample in `test_data/schema`.
+pub const CURRENT_SCHEMA_VERSION: u32 = 3;
+
+/// Documents written before `schema_version` existed
+const UNVERSIONED_SCHEMA_VERSION: u32 = 1;
+
+/// `MIGRATORS[n]` upgrades a version `n + 1` document to version `n + 2`.
+const MIGRATORS: [fn(Value) -> Result<Value, OstError>; (CURRENT_SCHEMA_VERSION - 1) as usize] =
+    [migrate_v1_to_v2, migrate_v2_to_v3];
+
+/// The on disk model shared by every persistence of `ContextMonolithicImpl`
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ContextPersistence {
+    pub schema_version: u32,
+    pub persons: Vec<PersonImpl>,
+    pub feeds: Vec<FeedPersistence>,
+    pub expulsions: Vec<ExpulsionPersistence>,
+    pub events: Vec<EventPersistence>,
+    pub measurements: Vec<MeasurementPersistence>,
+    pub next_ids: IdAllocator,
+    /// Bumped by every compaction of a journaled monolith, the journal records which
+    /// snapshot it belongs to. Left out while it's 0.
+    #[serde(default, skip_serializing_if = "is_first_generation")]
+    pub journal_generation: u64,
+    /// The latest outbox entry each client replayed, kept so replays stay idempotent
+    /// across restarts
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub replay_marks: HashMap<String, ReplayMark>,
+}
+
+fn is_first_generation(generation: &u64) -> bool {
+    *generation == 0
+}
+
+impl ContextPersistence {
+    pub fn from_monolith(monolith: &ContextMonolithicImpl) -> Self {
+        Self {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            persons: monolith
+                .persons
+                .iter()
+                .map(|p| p.read().unwrap().clone())
+                .filter(|p| !monolith.placeholder_person_ids.contains(&p.id))
+                .collect(),
+            feeds: monolith.feeds.iter().map(|f| f.to_persistence()).collect(),
+            expulsions: monolith
+                .expulsions
+                .iter()
+                .map(|e| e.to_persistence())
+                .collect(),
+            events: monolith.events.iter().map(|e| e.to_persistence()).collect(),
+            measurements: monolith
+                .measurements
+                .iter()
+                .map(|m| m.to_persistence())
+                .collect(),
+            next_ids: monolith.id_allocator.clone(),
+            journal_generation: monolith.journal_generation.load(Ordering::SeqCst),
+            replay_marks: monolith.replay_marks.clone(),
+        }
+    }
+
+    /// Upgrades documents of any older schema version before deserializing them.
+    pub fn from_value(mut document: Value) -> Result<Self, OstError> {
+        let version = schema_version(&document)?;
+        if version > CURRENT_SCHEMA_VERSION {
+            return Err(OstError::Serialization(format!(
+                "Schema version {} is newer than the supported {}",
+                version, CURRENT_SCHEMA_VERSION
+            )));
+        }
+
+        for migrator in MIGRATORS.iter().skip((version - 1) as usize) {
+            document = migrator(document)?;
+        }
+
+        match serde_json::from_value(document) {
+            Ok(persistence) => Ok(persistence),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    pub fn from_json(payload: &str) -> Result<Self, OstError> {
+        match serde_json::from_str(payload) {
+            Ok(document) => Self::from_value(document),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Entries pointing to a missing person get an inactive placeholder instead of failing.
+    /// Placeholders aren't persisted back, `check_integrity` keeps reporting the dangling
+    /// references until they are repaired.
+    pub fn into_monolith(
+        mut self,
+        target_file: &str,
+        persist_function: fn(&ContextMonolithicImpl) -> Result<(), OstError>,
+    ) -> ContextMonolithicImpl {
+        let placeholder_person_ids: HashSet<u32> =
+            add_missing_persons(&mut self).into_iter().collect();
+        if !placeholder_person_ids.is_empty() {
+            log::warn!(
+                "{} references missing persons {:?}, loaded them as placeholders",
+                target_file,
+                placeholder_person_ids
+            );
+        }
+
+        let persons: Vec<Arc<RwLock<PersonImpl>>> = self
+            .persons
+            .into_iter()
+            .map(|p| Arc::new(RwLock::new(p)))
+            .collect();
+
+        let feeds = self
+            .feeds
+            .iter()
+            .map(|f| FeedImpl::from_persistence(f, &persons))
+            .collect();
+
+        let expulsions = self
+            .expulsions
+            .iter()
+            .map(|e| ExpulsionImpl::from_persistence(e, &persons))
+            .collect();
+
+        let events = self
+            .events
+            .iter()
+            .map(|event| EventImpl::from_persistence(event, &persons))
+            .collect();
+
+        let measurements = self
+            .measurements
+            .iter()
+            .map(|m| MeasurementImpl::from_persistence(m, &persons))
+            .collect();
+
+        ContextMonolithicImpl {
+            target_file: target_file.to_string(),
+            persons,
+            feeds,
+            expulsions,
+            events,
+            measurements,
+            id_allocator: self.next_ids,
+            placeholder_person_ids,
+            journal_generation: AtomicU64::new(self.journal_generation),
+            replay_marks: self.replay_marks,
+            pending_records: None,
+            persist_function,
+            compact_function: persist_function,
+        }
+    }
+}
+
+impl Default for ContextPersistence {
+    fn default() -> Self {
+        Self {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            persons: vec![],
+            feeds: vec![],
+            expulsions: vec![],
+            events: vec![],
+            measurements: vec![],
+            next_ids: IdAllocator::default(),
+            journal_generation: 0,
+            replay_marks: HashMap::new(),
+        }
+    }
+}
+
+fn schema_version(document: &Value) -> Result<u32, OstError> {
+    match document.get("schema_version") {
+        None => Ok(UNVERSIONED_SCHEMA_VERSION),
+        Some(version) => match version.as_u64() {
+            Some(version) if version >= 1 => Ok(version as u32),
+            _ => Err(OstError::Serialization(format!(
+                "Invalid schema version: {}",
+                version
+            ))),
+        },
+    }
+}
+
+/// Version 2 persists the id allocator next to the entities
+fn migrate_v1_to_v2(mut document: Value) -> Result<Value, OstError> {
+    let object = match document.as_object_mut() {
+        Some(object) => object,
+        None => {
+            return Err(OstError::Serialization(
+                "Persistence document is not an object".to_string(),
+            ))
+        }
+    };
+    if !object.contains_key("next_ids") {
+        object.insert("next_ids".to_string(), json!(IdAllocator::default()));
+    }
+    object.insert("schema_version".to_string(), json!(2));
+    Ok(document)
+}
+
+/// Version 3 adds measurements
+fn migrate_v2_to_v3(mut document: Value) -> Result<Value, OstError> {
+    let object = match document.as_object_mut() {
+        Some(object) => object,
+        None => {
+            return Err(OstError::Serialization(
+                "Persistence document is not an object".to_string(),
+            ))
+        }
+    };
+    if !object.contains_key("measurements") {
+        object.insert("measurements".to_string(), json!([]));
+    }
+    object.insert("schema_version".to_string(), json!(3));
+    Ok(document)
+}
@@ -1,17 +1,12 @@
 use super::context_monolithic_impl::ContextMonolithicImpl;
+use super::context_persistence::ContextPersistence;
+use crate::error::OstError;
 
-pub fn new_monolith() -> Result<ContextMonolithicImpl, String> {
-    let nu_monolith = ContextMonolithicImpl {
-        target_file: "executing in memory".to_string(),
-        persons: vec![],
-        feeds: vec![],
-        expulsions: vec![],
-        events: vec![],
-        persist_function: persist,
-    };
+pub fn new_monolith() -> Result<ContextMonolithicImpl, OstError> {
+    let nu_monolith = ContextPersistence::default().into_monolith("executing in memory", persist);
     Ok(nu_monolith)
 }
 
-pub fn persist(_: &ContextMonolithicImpl) -> Result<(), String> {
+pub fn persist(_: &ContextMonolithicImpl) -> Result<(), OstError> {
     Ok(())
 }
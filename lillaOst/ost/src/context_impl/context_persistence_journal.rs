@@ -0,0 +1,298 @@
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex, RwLock};
+
+use super::context_monolithic_impl::ContextMonolithicImpl;
+use super::context_persistence_single_file;
+use super::journal_record::JournalRecord;
+
+use crate::entity_impl::event_impl::EventImpl;
+use crate::entity_impl::expulsion_impl::ExpulsionImpl;
+use crate::entity_impl::feed_impl::FeedImpl;
+use crate::entity_impl::measurement_impl::MeasurementImpl;
+use crate::error::OstError;
+
+/// Once the journal grows past this size it gets folded into the snapshot.
+pub const COMPACTION_THRESHOLD_BYTES: u64 = 1024 * 1024;
+
+/// The snapshot lives in `path_to_file`, in the same format `context_persistence_single_file`
+/// uses, and every mutation since the last compaction is appended to `<path_to_file>.journal`.
+pub fn new_monolith(path_to_file: &str) -> Result<ContextMonolithicImpl, OstError> {
+    let mut monolith = context_persistence_single_file::new_monolith(path_to_file)?;
+    monolith.pending_records = Some(Mutex::new(vec![]));
+    monolith.persist_function = persist;
+    monolith.compact_function = compact;
+
+    let rewrite_journal = replay(&mut monolith)?;
+
+    if rewrite_journal
+        || monolith.repair_duplicated_ids() > 0
+        || journal_len(path_to_file) > COMPACTION_THRESHOLD_BYTES
+    {
+        compact(&monolith)?;
+    }
+    Ok(monolith)
+}
+
+/// Appends the pending records to the journal. They stay pending until they are synced,
+/// a failed append is cut off the journal again and retried with the next mutation.
+pub fn persist(monolith: &ContextMonolithicImpl) -> Result<(), OstError> {
+    let pending_records = match &monolith.pending_records {
+        Some(pending_records) => pending_records,
+        None => return Ok(()),
+    };
+    let mut records = pending_records.lock().unwrap();
+    if records.is_empty() {
+        return Ok(());
+    }
+
+    let mut lines = vec![];
+    let header = match journal_len(&monolith.target_file) {
+        0 => Some(JournalRecord::Generation(
+            monolith.journal_generation.load(Ordering::SeqCst),
+        )),
+        _ => None,
+    };
+    for record in header.iter().chain(records.iter()) {
+        if let Err(err) = serde_json::to_writer(&mut lines, record) {
+            return Err(err.into());
+        }
+        lines.push(b'\n');
+    }
+
+    let mut journal_file = match OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(journal_path(&monolith.target_file))
+    {
+        Ok(file) => file,
+        Err(err) => return Err(err.into()),
+    };
+    let appended_at = match journal_file.metadata() {
+        Ok(metadata) => metadata.len(),
+        Err(err) => return Err(err.into()),
+    };
+
+    if let Err(err) = journal_file
+        .write_all(&lines)
+        .and_then(|_| journal_file.sync_data())
+    {
+        let _ignore = journal_file.set_len(appended_at);
+        return Err(err.into());
+    }
+    records.clear();
+    drop(records);
+
+    if journal_len(&monolith.target_file) > COMPACTION_THRESHOLD_BYTES {
+        compact(monolith)?;
+    }
+    Ok(())
+}
+
+/// Writes the whole monolith as the new snapshot, one generation up, and empties the journal.
+/// Records still in the journal after a crash in between belong to the previous generation,
+/// `replay` skips them.
+pub fn compact(monolith: &ContextMonolithicImpl) -> Result<(), OstError> {
+    let generation = monolith.journal_generation.fetch_add(1, Ordering::SeqCst);
+    if let Err(err) = context_persistence_single_file::persist(monolith) {
+        monolith
+            .journal_generation
+            .store(generation, Ordering::SeqCst);
+        return Err(err);
+    }
+    // Part of the snapshot now
+    if let Some(pending_records) = &monolith.pending_records {
+        pending_records.lock().unwrap().clear();
+    }
+
+    let journal = journal_path(&monolith.target_file);
+    if Path::new(&journal).exists() && std::fs::remove_file(&journal).is_err() {
+        // Whatever gets appended next must not count as part of the stale generation
+        let header = JournalRecord::Generation(generation + 1);
+        let mut line = serde_json::to_vec(&header)?;
+        line.push(b'\n');
+        let appended =
+            OpenOptions::new()
+                .append(true)
+                .open(&journal)
+                .and_then(|mut journal_file| {
+                    journal_file.write_all(&line)?;
+                    journal_file.sync_data()
+                });
+        if let Err(err) = appended {
+            return Err(err.into());
+        }
+    }
+    Ok(())
+}
+
+fn journal_path(path_to_file: &str) -> String {
+    format!("{}.journal", path_to_file)
+}
+
+fn journal_len(path_to_file: &str) -> u64 {
+    match std::fs::metadata(journal_path(path_to_file)) {
+        Ok(metadata) => metadata.len(),
+        Err(_) => 0,
+    }
+}
+
+/// Applies the records that belong to the snapshot's generation.
+/// Returns whether the journal should be rewritten, because it holds stale or torn records.
+fn replay(monolith: &mut ContextMonolithicImpl) -> Result<bool, OstError> {
+    let journal_file = match File::open(journal_path(&monolith.target_file)) {
+        Ok(file) => file,
+        Err(_) => return Ok(false),
+    };
+
+    let lines: Vec<String> = match BufReader::new(journal_file).lines().collect() {
+        Ok(lines) => lines,
+        Err(err) => return Err(err.into()),
+    };
+
+    let snapshot_generation = monolith.journal_generation.load(Ordering::SeqCst);
+    // Journals without a header predate generations
+    let mut generation = 0;
+    let mut rewrite_journal = false;
+    for (index, line) in lines.iter().enumerate() {
+        let applied = match serde_json::from_str::<JournalRecord>(line) {
+            Ok(JournalRecord::Generation(header)) => {
+                generation = header;
+                Ok(())
+            }
+            // Folded into the snapshot by a compaction that didn't get to remove the journal
+            Ok(_) if generation < snapshot_generation => {
+                rewrite_journal = true;
+                Ok(())
+            }
+            Ok(_) if generation > snapshot_generation => Err(OstError::Serialization(format!(
+                "Generation {} is newer than the snapshot's {}",
+                generation, snapshot_generation
+            ))),
+            Ok(record) => apply(monolith, record),
+            // A process killed halfway through an append leaves a torn last line
+            Err(_) if index + 1 == lines.len() => {
+                rewrite_journal = true;
+                break;
+            }
+            Err(err) => Err(err.into()),
+        };
+        if let Err(err) = applied {
+            return Err(OstError::Serialization(format!(
+                "Journal line {}: {}",
+                index + 1,
+                err
+            )));
+        }
+    }
+
+    monolith.sort_collection_by_time_stamp();
+    Ok(rewrite_journal)
+}
+
+/// Entries can only be replayed for persons the monolith holds
+fn person_exists(monolith: &ContextMonolithicImpl, person_id: u32) -> Result<(), OstError> {
+    match monolith
+        .persons
+        .iter()
+        .any(|p| p.read().unwrap().id() == person_id)
+    {
+        true => Ok(()),
+        false => Err(OstError::NotFound(format!("Person {}", person_id))),
+    }
+}
+
+fn apply(monolith: &mut ContextMonolithicImpl, record: JournalRecord) -> Result<(), OstError> {
+    match record {
+        JournalRecord::Generation(_) => {}
+        JournalRecord::PurgeAllData => {
+            monolith.measurements.clear();
+            monolith.events.clear();
+            monolith.expulsions.clear();
+            monolith.feeds.clear();
+            monolith.persons.clear();
+            monolith.placeholder_person_ids.clear();
+        }
+        JournalRecord::PurgeAllEvents => {
+            monolith.measurements.clear();
+            monolith.events.clear();
+            monolith.expulsions.clear();
+            monolith.feeds.clear();
+        }
+        JournalRecord::PersonUpserted(person) => {
+            monolith
+                .id_allocator
+                .skip_past(Some(person.id()), None, None, None, None);
+            monolith.placeholder_person_ids.remove(&person.id());
+            match monolith
+                .persons
+                .iter()
+                .find(|p| p.read().unwrap().id() == person.id())
+            {
+                Some(existing) => *existing.write().unwrap() = person,
+                None => monolith.persons.push(Arc::new(RwLock::new(person))),
+            }
+        }
+        JournalRecord::PersonRemoved(id) => {
+            monolith
+                .feeds
+                .retain(|f| f.person.read().unwrap().id() != id);
+            monolith
+                .expulsions
+                .retain(|e| e.person.read().unwrap().id() != id);
+            monolith
+                .events
+                .retain(|e| e.person.read().unwrap().id() != id);
+            monolith
+                .measurements
+                .retain(|m| m.person.read().unwrap().id() != id);
+            monolith.persons.retain(|p| p.read().unwrap().id() != id);
+        }
+        JournalRecord::FeedUpserted(persistence) => {
+            person_exists(monolith, persistence.person_id)?;
+            monolith
+                .id_allocator
+                .skip_past(None, Some(persistence.id), None, None, None);
+            let feed = FeedImpl::from_persistence(&persistence, &monolith.persons);
+            monolith.feeds.retain(|f| f.id() != feed.id());
+            monolith.feeds.push(feed);
+        }
+        JournalRecord::FeedRemoved(id) => monolith.feeds.retain(|f| f.id() != id),
+        JournalRecord::ExpulsionUpserted(persistence) => {
+            person_exists(monolith, persistence.person_id)?;
+            monolith
+                .id_allocator
+                .skip_past(None, None, Some(persistence.id), None, None);
+            let expulsion = ExpulsionImpl::from_persistence(&persistence, &monolith.persons);
+            monolith.expulsions.retain(|e| e.id() != expulsion.id());
+            monolith.expulsions.push(expulsion);
+        }
+        JournalRecord::ExpulsionRemoved(id) => monolith.expulsions.retain(|e| e.id() != id),
+        JournalRecord::EventUpserted(persistence) => {
+            person_exists(monolith, persistence.person_id)?;
+            monolith
+                .id_allocator
+                .skip_past(None, None, None, Some(persistence.id), None);
+            let event = EventImpl::from_persistence(&persistence, &monolith.persons);
+            monolith.events.retain(|e| e.id() != event.id());
+            monolith.events.push(event);
+        }
+        JournalRecord::EventRemoved(id) => monolith.events.retain(|e| e.id() != id),
+        JournalRecord::MeasurementUpserted(persistence) => {
+            person_exists(monolith, persistence.person_id)?;
+            monolith
+                .id_allocator
+                .skip_past(None, None, None, None, Some(persistence.id));
+            let measurement = MeasurementImpl::from_persistence(&persistence, &monolith.persons);
+            monolith.measurements.retain(|m| m.id() != measurement.id());
+            monolith.measurements.push(measurement);
+        }
+        JournalRecord::MeasurementRemoved(id) => monolith.measurements.retain(|m| m.id() != id),
+        JournalRecord::ReplayMarked(client, mark) => {
+            monolith.replay_marks.insert(client, mark);
+        }
+    }
+    Ok(())
+}
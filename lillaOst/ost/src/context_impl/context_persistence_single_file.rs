@@ -1,146 +1,166 @@
 use std::fs::File;
-use std::fs::OpenOptions;
 use std::io::{BufReader, BufWriter};
 use std::path::Path;
-use std::vec;
-use std::{cell::RefCell, rc::Rc};
 
-use serde_derive::{Deserialize, Serialize};
-use serde_json::to_writer_pretty;
+use serde_json::{to_writer_pretty, Value};
 
 use super::context_monolithic_impl::ContextMonolithicImpl;
+use super::context_persistence::ContextPersistence;
+use crate::error::OstError;
 
-use crate::entity_impl::event_impl::EventImpl;
-use crate::entity_impl::expulsion_impl::ExpulsionImpl;
-use crate::entity_impl::feed_impl::FeedImpl;
-use crate::entity_impl::person_impl::PersonImpl;
-
-use crate::entity_impl::event_impl::EventPersistence;
-use crate::entity_impl::expulsion_impl::ExpulsionPersistence;
-use crate::entity_impl::feed_impl::FeedPersistence;
-
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
-struct ContextPersistence {
-    persons: Vec<PersonImpl>,
-    feeds: Vec<FeedPersistence>,
-    expulsions: Vec<ExpulsionPersistence>,
-    events: Vec<EventPersistence>,
-}
-
-pub fn new_monolith(path_to_file: &str) -> Result<ContextMonolithicImpl, String> {
+pub fn new_monolith(path_to_file: &str) -> Result<ContextMonolithicImpl, OstError> {
     let target_file = Path::new(path_to_file);
     if !target_file.exists() {
-        let nu_monolith = ContextMonolithicImpl {
-            target_file: path_to_file.to_string(),
-            persons: vec![],
-            feeds: vec![],
-            expulsions: vec![],
-            events: vec![],
-            persist_function: persist,
-        };
+        let nu_monolith = ContextPersistence::default().into_monolith(path_to_file, persist);
         persist(&nu_monolith)?;
         Ok(nu_monolith)
     } else {
-        let persistence: ContextPersistence = match File::open(target_file) {
-            Ok(opened_file) => {
-                let reader = BufReader::new(opened_file);
-                match serde_json::from_reader(reader) {
-                    Ok(deserialized) => deserialized,
-                    Err(err) => return Err(err.to_string()),
-                }
-            }
-            Err(err) => return Err(err.to_string()),
+        let mut monolith = match load_monolith(path_to_file) {
+            Ok(monolith) => monolith,
+            Err(err) => recover_monolith(path_to_file, err)?,
         };
-
-        let mut persons: Vec<Rc<RefCell<PersonImpl>>> = vec![];
-        persistence
-            .persons
-            .iter()
-            .for_each(|p| persons.push(Rc::new(RefCell::new(p.clone()))));
-
-        let feeds = persistence
-            .feeds
-            .iter()
-            .map(|f| FeedImpl::from_persistence(f, &persons))
-            .collect();
-
-        let expulsions = persistence
-            .expulsions
-            .iter()
-            .map(|e| ExpulsionImpl::from_persistence(e, &persons))
-            .collect();
-
-        let events = persistence
-            .events
-            .iter()
-            .map(|event| EventImpl::from_persistence(event, &persons))
-            .collect();
-
-        Ok(ContextMonolithicImpl {
-            target_file: path_to_file.to_string(),
-            persons,
-            feeds,
-            expulsions,
-            events,
-            persist_function: persist,
-        })
+        if monolith.repair_duplicated_ids() > 0 {
+            persist(&monolith)?;
+        }
+        Ok(monolith)
     }
 }
 
-pub fn persist(monolith: &ContextMonolithicImpl) -> Result<(), String> {
-    if std::path::Path::new(&monolith.target_file).exists() {
-        match std::fs::remove_file(&monolith.target_file) {
-            Ok(_) => {}
-            Err(err) => return Err(err.to_string()),
+/// A target file that can't be read was most likely cut in half by an older,
+/// non atomic, write. Falls back to the newest complete copy left behind by `persist`
+/// and keeps the broken file around as `<target>.broken`.
+fn recover_monolith(
+    path_to_file: &str,
+    load_error: OstError,
+) -> Result<ContextMonolithicImpl, OstError> {
+    // Complete json that doesn't load, e.g. from a newer schema version, isn't ours to replace
+    if let Ok(payload) = std::fs::read_to_string(path_to_file) {
+        if serde_json::from_str::<Value>(&payload).is_ok() {
+            return Err(load_error);
         }
     }
 
-    let target_file = match OpenOptions::new()
-        .write(true)
-        .create(true)
-        .open(&monolith.target_file)
+    let candidates = [temp_path(path_to_file), backup_path(path_to_file)];
+    let mut recovered = match candidates
+        .iter()
+        .filter(|candidate| Path::new(candidate).exists())
+        .find_map(|candidate| load_monolith(candidate).ok())
     {
-        Ok(file) => file,
-        Err(err) => return Err(err.to_string()),
+        Some(recovered) => recovered,
+        None => return Err(load_error),
     };
 
-    let writer = BufWriter::new(target_file);
+    if let Err(err) = std::fs::rename(path_to_file, format!("{}.broken", path_to_file)) {
+        return Err(err.into());
+    }
 
-    let persons_to_persist = monolith
-        .persons
-        .iter()
-        .map(|f| f.borrow().clone())
-        .collect();
+    recovered.target_file = path_to_file.to_string();
+    persist(&recovered)?;
+    Ok(recovered)
+}
 
-    let feeds_to_persist = monolith.feeds.iter().map(|f| f.to_persistence()).collect();
+pub(crate) fn load_monolith(path_to_file: &str) -> Result<ContextMonolithicImpl, OstError> {
+    let document: Value = match File::open(path_to_file) {
+        Ok(opened_file) => {
+            let reader = BufReader::new(opened_file);
+            match serde_json::from_reader(reader) {
+                Ok(deserialized) => deserialized,
+                Err(err) => return Err(err.into()),
+            }
+        }
+        Err(err) => return Err(err.into()),
+    };
 
-    let expulsions_to_persist = monolith
-        .expulsions
-        .iter()
-        .map(|e| e.to_persistence())
-        .collect();
+    Ok(ContextPersistence::from_value(document)?.into_monolith(path_to_file, persist))
+}
 
-    let events_to_persist = monolith.events.iter().map(|e| e.to_persistence()).collect();
+/// Writes to `<target>.tmp`, syncs it to disk and only then renames it over the target,
+/// so the target file is always either the previous or the new complete version.
+/// The previous version stays around as `<target>.bak`.
+pub fn persist(monolith: &ContextMonolithicImpl) -> Result<(), OstError> {
+    let to_persistence = ContextPersistence::from_monolith(monolith);
+
+    let temp_file_path = temp_path(&monolith.target_file);
+    let temp_file = match File::create(&temp_file_path) {
+        Ok(file) => file,
+        Err(err) => return Err(err.into()),
+    };
+
+    let mut writer = BufWriter::new(temp_file);
+    if let Err(err) = to_writer_pretty(&mut writer, &to_persistence) {
+        return Err(err.into());
+    }
 
-    let to_persistence = ContextPersistence {
-        persons: persons_to_persist,
-        feeds: feeds_to_persist,
-        expulsions: expulsions_to_persist,
-        events: events_to_persist,
+    let temp_file = match writer.into_inner() {
+        Ok(file) => file,
+        Err(err) => return Err(err.into_error().into()),
     };
+    if let Err(err) = temp_file.sync_all() {
+        return Err(err.into());
+    }
+
+    let target_file = Path::new(&monolith.target_file);
+    if target_file.exists() {
+        keep_last_good_copy(&monolith.target_file)?;
+    }
 
-    match to_writer_pretty(writer, &to_persistence) {
-        Ok(_) => Ok(()),
-        Err(err) => Err(err.to_string()),
+    if let Err(err) = std::fs::rename(&temp_file_path, target_file) {
+        return Err(err.into());
     }
+
+    sync_parent_directory(target_file);
+    Ok(())
 }
 
+fn temp_path(path_to_file: &str) -> String {
+    format!("{}.tmp", path_to_file)
+}
+
+fn backup_path(path_to_file: &str) -> String {
+    format!("{}.bak", path_to_file)
+}
+
+fn keep_last_good_copy(path_to_file: &str) -> Result<(), OstError> {
+    let backup = backup_path(path_to_file);
+    let _ignore = std::fs::remove_file(&backup);
+    if std::fs::hard_link(path_to_file, &backup).is_err() {
+        if let Err(err) = std::fs::copy(path_to_file, &backup) {
+            return Err(err.into());
+        }
+    }
+    Ok(())
+}
+
+/// Makes the rename itself durable. Only meaningful, and only possible, on unix.
+#[cfg(unix)]
+fn sync_parent_directory(target_file: &Path) {
+    if let Some(parent) = target_file.parent() {
+        let parent = if parent.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            parent
+        };
+        if let Ok(directory) = File::open(parent) {
+            let _ignore = directory.sync_all();
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn sync_parent_directory(_: &Path) {}
+
 /// Allows the creation of a monolithic context that saves to a different file
 pub fn from_file(
     path_origin_file: &str,
     path_destination_file: &str,
-) -> Result<ContextMonolithicImpl, String> {
-    let mut temp_monolith = new_monolith(path_origin_file)?;
+) -> Result<ContextMonolithicImpl, OstError> {
+    let mut temp_monolith = if Path::new(path_origin_file).exists() {
+        let mut loaded = load_monolith(path_origin_file)?;
+        loaded.repair_duplicated_ids();
+        loaded
+    } else {
+        new_monolith(path_origin_file)?
+    };
     temp_monolith.target_file = path_destination_file.to_string();
     persist(&temp_monolith)?;
     Ok(temp_monolith)
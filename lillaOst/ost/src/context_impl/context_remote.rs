@@ -8,23 +8,31 @@ use crate::context::Context;
 use crate::entity_impl::event_impl::EventPersistence;
 use crate::entity_impl::expulsion_impl::ExpulsionPersistence;
 use crate::entity_impl::feed_impl::FeedPersistence;
+use crate::entity_impl::measurement_impl::MeasurementPersistence;
 use crate::entity_impl::person_impl::PersonImpl;
 use crate::entity_impl::remote_event_impl::RemoteEventImpl;
 use crate::entity_impl::remote_expulsion_impl::RemoteExpulsionImpl;
 use crate::entity_impl::remote_feed_impl::RemoteFeedImpl;
+use crate::entity_impl::remote_measurement_impl::RemoteMeasurementImpl;
+use crate::error::OstError;
 use crate::event::{Event, EventType};
 use crate::event_key::{EventType as ost_EventKey, OstEventKey};
 use crate::expulsion::{Expulsion, ExpulsionDegree};
 use crate::feed::Feed;
+use crate::integrity::IntegrityIssue;
+use crate::measurement::{Measurement, Measures};
+use crate::nursing::NursingSession;
+use crate::offline::{ReplayId, ReplayMark};
 use crate::person::{deserialize as person_deserialize, Person};
 use crate::person_key::OstPersonKey;
+use crate::query::{EventQuery, QueryResult};
 
 pub fn new_monolith(
     remote_endpoint: &str,
     get_call: fn(&str) -> Result<String, String>,
     post_call: fn(&str, String) -> Result<String, String>,
     post_empty_call: fn(&str) -> Result<String, String>,
-) -> Result<RemoteMonolithicContextImpl, String> {
+) -> Result<RemoteMonolithicContextImpl, OstError> {
     Ok(RemoteMonolithicContextImpl {
         remote_endpoint: remote_endpoint.to_string(),
         get_call,
@@ -43,6 +51,18 @@ struct ArgFakeCount {
     pub count: u32,
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct ArgRemovePerson {
+    pub person_key: OstPersonKey,
+    pub cascade: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ArgMergePersons {
+    pub from: OstPersonKey,
+    pub into: OstPersonKey,
+}
+
 #[derive(Serialize, Deserialize)]
 struct ArgEventKey {
     pub event_key: OstEventKey,
@@ -52,12 +72,31 @@ struct ArgEventKey {
 pub struct ArgAddEvent {
     pub person_key: OstPersonKey,
     pub new_event: EventType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_stamp: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replay_id: Option<ReplayId>,
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct ArgAddExpulsion {
     pub person_key: OstPersonKey,
     pub expulsion_degree: ExpulsionDegree,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_stamp: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replay_id: Option<ReplayId>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ArgAddMeasurement {
+    pub person_key: OstPersonKey,
+    #[serde(flatten)]
+    pub measures: Measures,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_stamp: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replay_id: Option<ReplayId>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -66,6 +105,18 @@ pub struct ArgAddFeeding {
     pub breast_milk: u32,
     pub formula: u32,
     pub solids: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_stamp: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replay_id: Option<ReplayId>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ArgAddNursing {
+    pub person_key: OstPersonKey,
+    pub nursing: NursingSession,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replay_id: Option<ReplayId>,
 }
 
 // This is the contact from the UI
@@ -77,22 +128,52 @@ pub struct RemoteMonolithicContextImpl {
 }
 
 impl Context for RemoteMonolithicContextImpl {
-    fn purge_all_data(&mut self) -> Result<(), String> {
-        let _ = match (self.post_empty_call)(self.build_api_url("api/admin/reset").as_str()) {
-            Ok(_) => {}
-            Err(e) => return Err(e),
-        };
-        Ok(())
+    fn purge_all_data(&mut self) -> Result<(), OstError> {
+        match (self.post_empty_call)(self.build_api_url("api/admin/reset").as_str()) {
+            Ok(o) => unpack_response(&o),
+            Err(e) => Err(OstError::Transport(e)),
+        }
     }
 
-    fn purge_all_events(&mut self) -> Result<(), String> {
-        let _ =
-            match (self.post_empty_call)(self.build_api_url("api/admin/purge-all-events").as_str())
-            {
-                Ok(_) => {}
-                Err(e) => return Err(e),
+    fn purge_all_events(&mut self) -> Result<(), OstError> {
+        match (self.post_empty_call)(self.build_api_url("api/admin/purge-all-events").as_str()) {
+            Ok(o) => unpack_response(&o),
+            Err(e) => Err(OstError::Transport(e)),
+        }
+    }
+
+    fn compact_storage(&mut self) -> Result<(), OstError> {
+        Err(OstError::Validation(
+            "Storage compaction is not available through the remote context".to_string(),
+        ))
+    }
+
+    fn check_integrity(&self) -> Result<Vec<IntegrityIssue>, OstError> {
+        match (self.get_call)(self.build_api_url("api/admin/integrity").as_str()) {
+            Ok(o) => unpack_response(&o),
+            Err(e) => Err(OstError::Transport(e)),
+        }
+    }
+
+    fn repair_integrity(&mut self) -> Result<Vec<IntegrityIssue>, OstError> {
+        match (self.post_empty_call)(self.build_api_url("api/admin/integrity/repair").as_str()) {
+            Ok(o) => unpack_response(&o),
+            Err(e) => Err(OstError::Transport(e)),
+        }
+    }
+
+    fn query(&self, query: &EventQuery) -> Result<QueryResult, OstError> {
+        let url = format!("api/query?{}", query.to_query_string());
+        let remote_call_result: Result<String, OstError> =
+            match (self.get_call)(self.build_api_url(&url).as_str()) {
+                Ok(o) => unpack_response(&o),
+                Err(e) => return Err(OstError::Transport(e)),
             };
-        Ok(())
+
+        match remote_call_result {
+            Ok(payload) => QueryResult::deserialize(&payload, &self.fetch_persons_impl()),
+            Err(e) => Err(e),
+        }
     }
 
     fn get_base_event_by_key(
@@ -112,6 +193,10 @@ impl Context for RemoteMonolithicContextImpl {
                 Some(o) => Some(o),
                 None => None,
             },
+            ost_EventKey::Measurement => match self.get_measurement_by_key(key) {
+                Some(o) => Some(o),
+                None => None,
+            },
         }
     }
 
@@ -119,15 +204,15 @@ impl Context for RemoteMonolithicContextImpl {
         self.fetch_persons()
     }
 
-    fn add_person(&mut self, name: &str) -> Result<Box<dyn crate::person::Person>, String> {
+    fn add_person(&mut self, name: &str) -> Result<Box<dyn crate::person::Person>, OstError> {
         let post_message = serde_json::to_string(&ArgAddNameCommand {
             name: name.to_string(),
         })
         .unwrap();
-        let remote_call_result: Result<String, String> =
+        let remote_call_result: Result<String, OstError> =
             match (self.post_call)(self.build_api_url("api/persons").as_str(), post_message) {
-                Ok(o) => serde_json::from_str(&o).unwrap(),
-                Err(e) => return Err(e),
+                Ok(o) => unpack_response(&o),
+                Err(e) => return Err(OstError::Transport(e)),
             };
 
         match remote_call_result {
@@ -136,12 +221,12 @@ impl Context for RemoteMonolithicContextImpl {
         }
     }
 
-    fn add_fake_persons(&mut self, count: u32) -> Result<(), String> {
+    fn add_fake_persons(&mut self, count: u32) -> Result<(), OstError> {
         let post_message = serde_json::to_string(&ArgFakeCount { count }).unwrap();
         self.post_call_to("api/persons/add-fake-count", post_message)
     }
 
-    fn modify_person(&mut self, person: &Box<dyn crate::person::Person>) -> Result<(), String> {
+    fn modify_person(&mut self, person: &Box<dyn crate::person::Person>) -> Result<(), OstError> {
         #[derive(Serialize)]
         struct ArgAModifyPerson {
             pub person_key: OstPersonKey,
@@ -154,10 +239,10 @@ impl Context for RemoteMonolithicContextImpl {
         })
         .unwrap();
 
-        let remote_call_result: Result<(), String> =
+        let remote_call_result: Result<(), OstError> =
             match (self.post_call)(self.build_api_url("api/person").as_str(), message) {
-                Ok(o) => serde_json::from_str(&o).unwrap(),
-                Err(e) => return Err(e),
+                Ok(o) => unpack_response(&o),
+                Err(e) => return Err(OstError::Transport(e)),
             };
 
         match remote_call_result {
@@ -166,6 +251,28 @@ impl Context for RemoteMonolithicContextImpl {
         }
     }
 
+    fn remove_person(&mut self, person: Box<dyn Person>, cascade: bool) -> Result<(), OstError> {
+        let message = serde_json::to_string(&ArgRemovePerson {
+            person_key: person.key(),
+            cascade,
+        })
+        .unwrap();
+        self.post_call_to("api/persons/remove", message)
+    }
+
+    fn merge_persons(
+        &mut self,
+        from: Box<dyn Person>,
+        into: &Box<dyn Person>,
+    ) -> Result<(), OstError> {
+        let message = serde_json::to_string(&ArgMergePersons {
+            from: from.key(),
+            into: into.key(),
+        })
+        .unwrap();
+        self.post_call_to("api/persons/merge", message)
+    }
+
     fn get_person_by_key(&self, key: crate::person_key::OstPersonKey) -> Option<Box<dyn Person>> {
         self.fetch_person_by_key(key)
     }
@@ -198,6 +305,7 @@ impl Context for RemoteMonolithicContextImpl {
                     breast_milk: feed_persistence.breast_milk,
                     formula: feed_persistence.formula,
                     solids: feed_persistence.solids,
+                    nursing: feed_persistence.nursing.clone(),
                     time_stamp: feed_persistence.time_stamp,
                     person: target_person.clone(),
                 }))
@@ -205,24 +313,28 @@ impl Context for RemoteMonolithicContextImpl {
         output
     }
 
-    fn add_feeding(
+    fn add_feeding_at(
         &mut self,
         person: &Box<dyn crate::person::Person>,
         breast_milk: u32,
         formula: u32,
         solids: u32,
-    ) -> Result<Box<dyn crate::feed::Feed>, String> {
+        time_stamp: DateTime<Utc>,
+        replay_id: Option<&ReplayId>,
+    ) -> Result<Box<dyn crate::feed::Feed>, OstError> {
         let post_message = serde_json::to_string(&ArgAddFeeding {
             person_key: person.key(),
             breast_milk,
             formula,
             solids,
+            time_stamp: Some(time_stamp),
+            replay_id: replay_id.cloned(),
         })
         .unwrap();
         match (self.post_call)(&self.build_api_url("api/feedings/add"), post_message) {
             Ok(server_message) => {
-                let unpacked_server_message: Result<String, String> =
-                    serde_json::from_str(&server_message).unwrap();
+                let unpacked_server_message: Result<String, OstError> =
+                    unpack_response(&server_message);
                 match unpacked_server_message {
                     Ok(server_response) => {
                         let de_serialized_feedings: FeedPersistence =
@@ -239,23 +351,60 @@ impl Context for RemoteMonolithicContextImpl {
                                 breast_milk: de_serialized_feedings.breast_milk,
                                 formula: de_serialized_feedings.formula,
                                 solids: de_serialized_feedings.solids,
+                                nursing: de_serialized_feedings.nursing.clone(),
                             })),
-                            None => Err(format!("Person {:#?} not found!", person.key())),
+                            None => Err(OstError::NotFound(format!(
+                                "Person {:#?} not found!",
+                                person.key()
+                            ))),
                         }
                     }
                     Err(e) => Err(e),
                 }
             }
-            Err(e) => Err(e),
+            Err(e) => Err(OstError::Transport(e)),
         }
     }
 
-    fn add_fake_feedings(&mut self, count: u32) -> Result<(), String> {
+    fn add_nursing_replayed(
+        &mut self,
+        person: &Box<dyn Person>,
+        nursing: NursingSession,
+        replay_id: Option<&ReplayId>,
+    ) -> Result<Box<dyn Feed>, OstError> {
+        let post_message = serde_json::to_string(&ArgAddNursing {
+            person_key: person.key(),
+            nursing,
+            replay_id: replay_id.cloned(),
+        })
+        .unwrap();
+        let server_response: String = match (self.post_call)(
+            &self.build_api_url("api/feedings/add-nursing"),
+            post_message,
+        ) {
+            Ok(o) => unpack_response(&o)?,
+            Err(e) => return Err(OstError::Transport(e)),
+        };
+        let persisted: FeedPersistence = serde_json::from_str(&server_response)?;
+        match self
+            .fetch_persons_impl()
+            .into_iter()
+            .find(|p| p.id() == persisted.person_id)
+        {
+            Some(p) => Ok(Box::new(RemoteFeedImpl::from_persistence(persisted, p))),
+            None => Err(OstError::NotFound(format!(
+                "Person {:#?} not found!",
+                person.key()
+            ))),
+        }
+    }
+
+    fn add_fake_feedings(&mut self, count: u32) -> Result<(), OstError> {
         let post_message = serde_json::to_string(&ArgFakeCount { count }).unwrap();
         self.post_call_to("api/feedings/add-fake-count", post_message)
     }
 
-    fn modify_feeding(&mut self, feed: &Box<dyn crate::feed::Feed>) -> Result<(), String> {
+    fn modify_feeding(&mut self, feed: &Box<dyn crate::feed::Feed>) -> Result<(), OstError> {
         #[derive(Serialize)]
         struct ArgAModifyFeeding {
             pub event_key: OstEventKey,
@@ -263,6 +412,7 @@ impl Context for RemoteMonolithicContextImpl {
             pub breast_milk: u32,
             pub formula: u32,
             pub solids: u32,
+            pub nursing: Option<NursingSession>,
         }
 
         let message = serde_json::to_string(&ArgAModifyFeeding {
@@ -271,13 +421,14 @@ impl Context for RemoteMonolithicContextImpl {
             breast_milk: feed.breast_milk(),
             formula: feed.formula(),
             solids: feed.solids(),
+            nursing: feed.nursing(),
         })
         .unwrap();
 
-        let remote_call_result: Result<(), String> =
+        let remote_call_result: Result<(), OstError> =
             match (self.post_call)(self.build_api_url("api/feed").as_str(), message) {
-                Ok(_) => Ok(()), // Server is returning a ()
-                Err(e) => return Err(e),
+                Ok(o) => unpack_response(&o),
+                Err(e) => return Err(OstError::Transport(e)),
             };
 
         match remote_call_result {
@@ -286,7 +437,7 @@ impl Context for RemoteMonolithicContextImpl {
         }
     }
 
-    fn remove_feeding(&mut self, feed: Box<dyn crate::feed::Feed>) -> Result<(), String> {
+    fn remove_feeding(&mut self, feed: Box<dyn crate::feed::Feed>) -> Result<(), OstError> {
         let event_key = serde_json::to_string(&ArgEventKey {
             event_key: feed.key(),
         })
@@ -323,6 +474,7 @@ impl Context for RemoteMonolithicContextImpl {
                                 breast_milk: event_persistence.breast_milk,
                                 formula: event_persistence.formula,
                                 solids: event_persistence.solids,
+                                nursing: event_persistence.nursing.clone(),
                             })),
                             None => None,
                         }
@@ -364,20 +516,24 @@ impl Context for RemoteMonolithicContextImpl {
         output
     }
 
-    fn add_expulsion(
+    fn add_expulsion_at(
         &mut self,
         person: &Box<dyn crate::person::Person>,
         degree: crate::expulsion::ExpulsionDegree,
-    ) -> Result<Box<dyn crate::expulsion::Expulsion>, String> {
+        time_stamp: DateTime<Utc>,
+        replay_id: Option<&ReplayId>,
+    ) -> Result<Box<dyn crate::expulsion::Expulsion>, OstError> {
         let post_message = serde_json::to_string(&ArgAddExpulsion {
             person_key: person.key(),
             expulsion_degree: degree,
+            time_stamp: Some(time_stamp),
+            replay_id: replay_id.cloned(),
         })
         .unwrap();
         match (self.post_call)(&self.build_api_url("api/expulsions/add"), post_message) {
             Ok(server_message) => {
-                let unpacked_server_message: Result<String, String> =
-                    serde_json::from_str(&server_message).unwrap();
+                let unpacked_server_message: Result<String, OstError> =
+                    unpack_response(&server_message);
                 match unpacked_server_message {
                     Ok(server_response) => {
                         let de_serialized_expulsion: ExpulsionPersistence =
@@ -393,17 +549,20 @@ impl Context for RemoteMonolithicContextImpl {
                                 person: p.clone(),
                                 degree: de_serialized_expulsion.degree,
                             })),
-                            None => Err(format!("Person {:#?} not found!", person.key())),
+                            None => Err(OstError::NotFound(format!(
+                                "Person {:#?} not found!",
+                                person.key()
+                            ))),
                         }
                     }
                     Err(e) => Err(e),
                 }
             }
-            Err(e) => Err(e),
+            Err(e) => Err(OstError::Transport(e)),
         }
     }
 
-    fn add_fake_expulsions(&mut self, count: u32) -> Result<(), String> {
+    fn add_fake_expulsions(&mut self, count: u32) -> Result<(), OstError> {
         let post_message = serde_json::to_string(&ArgFakeCount { count }).unwrap();
         self.post_call_to("api/expulsions/add-fake-count", post_message)
     }
@@ -411,7 +570,7 @@ impl Context for RemoteMonolithicContextImpl {
     fn modify_expulsion(
         &mut self,
         expulsion: &Box<dyn crate::expulsion::Expulsion>,
-    ) -> Result<(), String> {
+    ) -> Result<(), OstError> {
         #[derive(Serialize)]
         struct ArgModifyExpulsion {
             pub event_key: OstEventKey,
@@ -426,10 +585,10 @@ impl Context for RemoteMonolithicContextImpl {
         })
         .unwrap();
 
-        let remote_call_result: Result<(), String> =
+        let remote_call_result: Result<(), OstError> =
             match (self.post_call)(self.build_api_url("api/expulsion").as_str(), message) {
-                Ok(_) => Ok(()), // Server is returning a ()
-                Err(e) => return Err(e),
+                Ok(o) => unpack_response(&o),
+                Err(e) => return Err(OstError::Transport(e)),
             };
 
         match remote_call_result {
@@ -441,7 +600,7 @@ impl Context for RemoteMonolithicContextImpl {
     fn remove_expulsion(
         &mut self,
         expulsion: Box<dyn crate::expulsion::Expulsion>,
-    ) -> Result<(), String> {
+    ) -> Result<(), OstError> {
         let event_key = serde_json::to_string(&ArgEventKey {
             event_key: expulsion.key(),
         })
@@ -517,20 +676,24 @@ impl Context for RemoteMonolithicContextImpl {
         output
     }
 
-    fn add_event(
+    fn add_event_at(
         &mut self,
         person: &Box<dyn crate::person::Person>,
         event_type: crate::event::EventType,
-    ) -> Result<Box<dyn crate::event::Event>, String> {
+        time_stamp: DateTime<Utc>,
+        replay_id: Option<&ReplayId>,
+    ) -> Result<Box<dyn crate::event::Event>, OstError> {
         let post_message = serde_json::to_string(&ArgAddEvent {
             person_key: person.key(),
             new_event: event_type,
+            time_stamp: Some(time_stamp),
+            replay_id: replay_id.cloned(),
         })
         .unwrap();
         match (self.post_call)(&self.build_api_url("api/events/add"), post_message) {
             Ok(server_message) => {
-                let unpacked_server_message: Result<String, String> =
-                    serde_json::from_str(&server_message).unwrap();
+                let unpacked_server_message: Result<String, OstError> =
+                    unpack_response(&server_message);
                 match unpacked_server_message {
                     Ok(server_response) => {
                         let de_serialized_event: EventPersistence =
@@ -546,22 +709,25 @@ impl Context for RemoteMonolithicContextImpl {
                                 person: p.clone(),
                                 event: de_serialized_event.event,
                             })),
-                            None => Err(format!("Person {:#?} not found!", person.key())),
+                            None => Err(OstError::NotFound(format!(
+                                "Person {:#?} not found!",
+                                person.key()
+                            ))),
                         }
                     }
                     Err(e) => Err(e),
                 }
             }
-            Err(e) => Err(e),
+            Err(e) => Err(OstError::Transport(e)),
         }
     }
 
-    fn add_fake_events(&mut self, count: u32) -> Result<(), String> {
+    fn add_fake_events(&mut self, count: u32) -> Result<(), OstError> {
         let post_message = serde_json::to_string(&ArgFakeCount { count }).unwrap();
         self.post_call_to("api/events/add-fake-count", post_message)
     }
 
-    fn modify_event(&mut self, event: &Box<dyn crate::event::Event>) -> Result<(), String> {
+    fn modify_event(&mut self, event: &Box<dyn crate::event::Event>) -> Result<(), OstError> {
         #[derive(Serialize)]
         struct ArgAModifyEvent {
             pub event_key: OstEventKey,
@@ -576,10 +742,10 @@ impl Context for RemoteMonolithicContextImpl {
         })
         .unwrap();
 
-        let remote_call_result: Result<(), String> =
+        let remote_call_result: Result<(), OstError> =
             match (self.post_call)(self.build_api_url("api/event").as_str(), message) {
-                Ok(_) => Ok(()), // Server is returning a ()
-                Err(e) => return Err(e),
+                Ok(o) => unpack_response(&o),
+                Err(e) => return Err(OstError::Transport(e)),
             };
 
         match remote_call_result {
@@ -588,7 +754,7 @@ impl Context for RemoteMonolithicContextImpl {
         }
     }
 
-    fn remove_event(&mut self, event: Box<dyn crate::event::Event>) -> Result<(), String> {
+    fn remove_event(&mut self, event: Box<dyn crate::event::Event>) -> Result<(), OstError> {
         let event_key = serde_json::to_string(&ArgEventKey {
             event_key: event.key(),
         })
@@ -633,6 +799,126 @@ impl Context for RemoteMonolithicContextImpl {
             Err(_) => None,
         }
     }
+
+    fn measurements(&self) -> Vec<Box<dyn Measurement>> {
+        let persons = self.fetch_persons_impl();
+        self.fetch_measurements_persistence()
+            .into_iter()
+            .filter_map(|persisted| {
+                let person = persons.iter().find(|p| p.id == persisted.person_id)?;
+                Some(Box::new(RemoteMeasurementImpl::from_persistence(
+                    persisted,
+                    person.clone(),
+                )) as Box<dyn Measurement>)
+            })
+            .collect()
+    }
+
+    fn measurements_by(&self, person: &Box<dyn Person>) -> Vec<Box<dyn Measurement>> {
+        let person_id = person.key().id;
+        self.measurements()
+            .into_iter()
+            .filter(|m| m.person_key().id == person_id)
+            .collect()
+    }
+
+    fn add_measurement_at(
+        &mut self,
+        person: &Box<dyn Person>,
+        measures: Measures,
+        time_stamp: DateTime<Utc>,
+        replay_id: Option<&ReplayId>,
+    ) -> Result<Box<dyn Measurement>, OstError> {
+        let post_message = serde_json::to_string(&ArgAddMeasurement {
+            person_key: person.key(),
+            measures,
+            time_stamp: Some(time_stamp),
+            replay_id: replay_id.cloned(),
+        })
+        .unwrap();
+        let server_response: String =
+            match (self.post_call)(&self.build_api_url("api/measurements/add"), post_message) {
+                Ok(o) => unpack_response(&o)?,
+                Err(e) => return Err(OstError::Transport(e)),
+            };
+        let persisted: MeasurementPersistence = serde_json::from_str(&server_response)?;
+        match self
+            .fetch_persons_impl()
+            .into_iter()
+            .find(|p| p.id() == persisted.person_id)
+        {
+            Some(p) => Ok(Box::new(RemoteMeasurementImpl::from_persistence(
+                persisted, p,
+            ))),
+            None => Err(OstError::NotFound(format!(
+                "Person {:#?} not found!",
+                person.key()
+            ))),
+        }
+    }
+
+    fn add_fake_measurements(&mut self, count: u32) -> Result<(), OstError> {
+        let post_message = serde_json::to_string(&ArgFakeCount { count }).unwrap();
+        self.post_call_to("api/measurements/add-fake-count", post_message)
+    }
+
+    fn modify_measurement(&mut self, measurement: &Box<dyn Measurement>) -> Result<(), OstError> {
+        #[derive(Serialize)]
+        struct ArgModifyMeasurement {
+            pub event_key: OstEventKey,
+            pub time_stamp: DateTime<Utc>,
+            #[serde(flatten)]
+            pub measures: Measures,
+        }
+
+        let message = serde_json::to_string(&ArgModifyMeasurement {
+            event_key: measurement.key(),
+            time_stamp: *measurement.time_stamp(),
+            measures: measurement.measures(),
+        })
+        .unwrap();
+        self.post_call_to("api/measurement", message)
+    }
+
+    fn remove_measurement(&mut self, measurement: Box<dyn Measurement>) -> Result<(), OstError> {
+        let event_key = serde_json::to_string(&ArgEventKey {
+            event_key: measurement.key(),
+        })
+        .unwrap();
+        self.post_call_to("api/measurements/remove", event_key)
+    }
+
+    fn get_measurement_by_key(&self, key: &OstEventKey) -> Option<Box<dyn Measurement>> {
+        let event_key = serde_json::to_string(&ArgEventKey { event_key: *key }).unwrap();
+
+        let payload: Option<String> =
+            match (self.post_call)(self.build_api_url("api/measurements").as_str(), event_key) {
+                Ok(o) => serde_json::from_str(&o).ok()?,
+                Err(_) => return None,
+            };
+        let persisted: MeasurementPersistence = serde_json::from_str(&payload?).ok()?;
+        let person = self
+            .fetch_persons_impl()
+            .into_iter()
+            .find(|p| p.id() == persisted.person_id)?;
+        Some(Box::new(RemoteMeasurementImpl::from_persistence(
+            persisted, person,
+        )))
+    }
+
+    /// The backend keeps the marks, none are shared with clients
+    fn replay_mark(&self, _client: &str) -> Option<ReplayMark> {
+        None
+    }
+}
+
+/// The v1 api answers with a json `Result` of a string error, a payload that doesn't parse is a
+/// `Serialization` error
+fn unpack_response<T: serde::de::DeserializeOwned>(payload: &str) -> Result<T, OstError> {
+    match serde_json::from_str::<Result<T, String>>(payload) {
+        Ok(result) => result.map_err(|message| OstError::from_message(&message)),
+        Err(err) => Err(err.into()),
+    }
 }
 
 impl RemoteMonolithicContextImpl {
@@ -640,11 +926,11 @@ impl RemoteMonolithicContextImpl {
         format!("{}/{}", self.remote_endpoint, api_path)
     }
 
-    fn post_call_to(&self, api_path: &str, payload: String) -> Result<(), String> {
-        let remote_call_result: Result<(), String> =
+    fn post_call_to(&self, api_path: &str, payload: String) -> Result<(), OstError> {
+        let remote_call_result: Result<(), OstError> =
             match (self.post_call)(self.build_api_url(api_path).as_str(), payload) {
-                Ok(o) => serde_json::from_str(&o).unwrap(),
-                Err(e) => return Err(e),
+                Ok(o) => unpack_response(&o),
+                Err(e) => return Err(OstError::Transport(e)),
             };
         remote_call_result
     }
@@ -722,6 +1008,7 @@ impl RemoteMonolithicContextImpl {
                         breast_milk: feed_persistence.breast_milk,
                         formula: feed_persistence.formula,
                         solids: feed_persistence.solids,
+                        nursing: feed_persistence.nursing.clone(),
                         time_stamp: feed_persistence.time_stamp,
                         person: person_impl,
                     }
@@ -835,4 +1122,19 @@ impl RemoteMonolithicContextImpl {
             vec![]
         }
     }
+
+    fn fetch_measurements_persistence(&self) -> Vec<MeasurementPersistence> {
+        if let Ok(serialized_measurements) =
+            (self.get_call)(self.build_api_url("api/measurements").as_str())
+        {
+            let vec_of_serialized_measurements: Vec<String> =
+                serde_json::from_str(&serialized_measurements).unwrap();
+            vec_of_serialized_measurements
+                .iter()
+                .map(|s| serde_json::from_str(s).unwrap())
+                .collect()
+        } else {
+            vec![]
+        }
+    }
 }
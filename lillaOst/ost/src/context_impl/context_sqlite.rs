@@ -0,0 +1,1438 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, RwLock};
+
+use chrono::prelude::*;
+
+use fake::faker::name::raw::Name;
+use fake::locales::EN;
+use fake::{Fake, Faker};
+use rand::Rng;
+
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Row, ToSql};
+
+use super::context_monolithic_impl::ContextMonolithicImpl;
+use super::context_persistence::ContextPersistence;
+use super::context_persistence_single_file::load_monolith;
+
+use crate::context::Context;
+use crate::entity_impl::event_impl::{EventImpl, EventPersistence};
+use crate::entity_impl::expulsion_impl::{ExpulsionImpl, ExpulsionPersistence};
+use crate::entity_impl::feed_impl::{FeedImpl, FeedPersistence};
+use crate::entity_impl::measurement_impl::{MeasurementImpl, MeasurementPersistence};
+use crate::entity_impl::person_impl::PersonImpl;
+use crate::error::OstError;
+use crate::event::{Event, EventType};
+use crate::event_base::EventBase;
+use crate::event_key::{EventType as ost_EventKey, OstEventKey};
+use crate::expulsion::{Expulsion, ExpulsionDegree};
+use crate::feed::Feed;
+use crate::integrity::{self, IntegrityIssue};
+use crate::measurement::{Measurement, Measures};
+use crate::nursing::NursingSession;
+use crate::offline::{ReplayId, ReplayMark};
+use crate::person::Person;
+use crate::person_key::OstPersonKey;
+use crate::query::{EventQuery, QueryItem, QueryResult, SortOrder};
+
+/// `AUTOINCREMENT` keeps SQLite from handing out the id of a removed row again.
+static SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS persons (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        name TEXT NOT NULL,
+        is_active INTEGER NOT NULL,
+        birth_date TEXT,
+        sex TEXT,
+        birth_weight INTEGER,
+        colour TEXT
+    );
+    CREATE TABLE IF NOT EXISTS feeds (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        person_id INTEGER NOT NULL REFERENCES persons(id),
+        breast_milk INTEGER NOT NULL,
+        formula INTEGER NOT NULL,
+        solids INTEGER NOT NULL,
+        time_stamp TEXT NOT NULL,
+        nursing TEXT
+    );
+    CREATE TABLE IF NOT EXISTS expulsions (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        person_id INTEGER NOT NULL REFERENCES persons(id),
+        degree TEXT NOT NULL,
+        time_stamp TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS events (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        person_id INTEGER NOT NULL REFERENCES persons(id),
+        event TEXT NOT NULL,
+        time_stamp TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS measurements (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        person_id INTEGER NOT NULL REFERENCES persons(id),
+        weight INTEGER,
+        length REAL,
+        head_circumference REAL,
+        time_stamp TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS replay_marks (
+        client TEXT PRIMARY KEY,
+        mark TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS feeds_time_stamp ON feeds(time_stamp);
+    CREATE INDEX IF NOT EXISTS feeds_person ON feeds(person_id, time_stamp);
+    CREATE INDEX IF NOT EXISTS expulsions_time_stamp ON expulsions(time_stamp);
+    CREATE INDEX IF NOT EXISTS expulsions_person ON expulsions(person_id, time_stamp);
+    CREATE INDEX IF NOT EXISTS events_time_stamp ON events(time_stamp);
+    CREATE INDEX IF NOT EXISTS events_person ON events(person_id, time_stamp);
+    CREATE INDEX IF NOT EXISTS measurements_time_stamp ON measurements(time_stamp);
+    CREATE INDEX IF NOT EXISTS measurements_person ON measurements(person_id, time_stamp);
+";
+
+/// Added after the first release, older databases get them when opened
+static ADDED_COLUMNS: [(&str, &str, &str); 5] = [
+    ("persons", "birth_date", "TEXT"),
+    ("persons", "sex", "TEXT"),
+    ("persons", "birth_weight", "INTEGER"),
+    ("persons", "colour", "TEXT"),
+    ("feeds", "nursing", "TEXT"),
+];
+
+/// `Connection` is `Send` but not `Sync`, readers take turns on the mutex
+pub struct SqliteContextImpl {
+    pub connection: Mutex<Connection>,
+}
+
+pub fn new_context(path_to_database: &str) -> Result<SqliteContextImpl, OstError> {
+    let connection = Connection::open(path_to_database).map_err(OstError::from)?;
+    connection
+        .execute_batch("PRAGMA foreign_keys = ON;")
+        .map_err(OstError::from)?;
+    connection.execute_batch(SCHEMA).map_err(OstError::from)?;
+    add_missing_columns(&connection)?;
+    Ok(SqliteContextImpl {
+        connection: Mutex::new(connection),
+    })
+}
+
+fn add_missing_columns(connection: &Connection) -> Result<(), OstError> {
+    for (table, column, column_type) in ADDED_COLUMNS.iter() {
+        let existing: Vec<String> = connection
+            .prepare(&format!("SELECT name FROM pragma_table_info('{}')", table))
+            .and_then(|mut statement| {
+                statement
+                    .query_map([], |row| row.get(0))?
+                    .collect::<rusqlite::Result<Vec<String>>>()
+            })
+            .map_err(OstError::from)?;
+        if existing.iter().any(|e| e == column) {
+            continue;
+        }
+        connection
+            .execute_batch(&format!(
+                "ALTER TABLE {} ADD COLUMN {} {}",
+                table, column, column_type
+            ))
+            .map_err(OstError::from)?;
+    }
+    Ok(())
+}
+
+/// Creates `path_to_database` with the contents of a single file monolith.
+/// Entity ids and id counters are kept, so keys handed out before the switch stay valid.
+/// Monoliths with entries of missing persons need to be repaired first.
+pub fn from_monolith_file(
+    path_to_monolith: &str,
+    path_to_database: &str,
+) -> Result<SqliteContextImpl, OstError> {
+    let mut monolith = load_monolith(path_to_monolith)?;
+    monolith.repair_duplicated_ids();
+    if !monolith.placeholder_person_ids.is_empty() {
+        return Err(OstError::Validation(format!(
+            "{} has entries of missing persons {:?}, repair it before importing",
+            path_to_monolith, monolith.placeholder_person_ids
+        )));
+    }
+
+    let mut context = new_context(path_to_database)?;
+    if !context.query_persons("", [])?.is_empty() {
+        return Err(OstError::Validation(format!(
+            "Database {} is not empty",
+            path_to_database
+        )));
+    }
+    context.import_monolith(&monolith)?;
+    Ok(context)
+}
+
+impl SqliteContextImpl {
+    fn import_monolith(&mut self, monolith: &ContextMonolithicImpl) -> Result<(), OstError> {
+        let transaction = self
+            .connection_mut()
+            .transaction()
+            .map_err(OstError::from)?;
+        for person in monolith.persons.iter() {
+            let person = person.read().unwrap();
+            transaction
+                .execute(
+                    "INSERT INTO persons (id, name, is_active, birth_date, sex, birth_weight, colour) \
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    params![
+                        person.id,
+                        person.name,
+                        person.is_active,
+                        person.birth_date,
+                        to_optional_column(&person.sex)?,
+                        person.birth_weight,
+                        person.colour
+                    ],
+                )
+                .map_err(OstError::from)?;
+        }
+        for feed in monolith.feeds.iter() {
+            transaction
+                .execute(
+                    "INSERT INTO feeds (id, person_id, breast_milk, formula, solids, time_stamp, nursing) \
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    params![
+                        feed.id,
+                        feed.person.read().unwrap().id,
+                        feed.breast_milk,
+                        feed.formula,
+                        feed.solids,
+                        feed.time_stamp,
+                        to_optional_column(&feed.nursing)?
+                    ],
+                )
+                .map_err(OstError::from)?;
+        }
+        for expulsion in monolith.expulsions.iter() {
+            transaction
+                .execute(
+                    "INSERT INTO expulsions (id, person_id, degree, time_stamp) \
+                     VALUES (?1, ?2, ?3, ?4)",
+                    params![
+                        expulsion.id,
+                        expulsion.person.read().unwrap().id,
+                        to_column(&expulsion.degree)?,
+                        expulsion.time_stamp
+                    ],
+                )
+                .map_err(OstError::from)?;
+        }
+        for event in monolith.events.iter() {
+            transaction
+                .execute(
+                    "INSERT INTO events (id, person_id, event, time_stamp) VALUES (?1, ?2, ?3, ?4)",
+                    params![
+                        event.id,
+                        event.person.read().unwrap().id,
+                        to_column(&event.event)?,
+                        event.time_stamp
+                    ],
+                )
+                .map_err(OstError::from)?;
+        }
+        for measurement in monolith.measurements.iter() {
+            transaction
+                .execute(
+                    "INSERT INTO measurements \
+                     (id, person_id, weight, length, head_circumference, time_stamp) \
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![
+                        measurement.id,
+                        measurement.person.read().unwrap().id,
+                        measurement.measures.weight,
+                        measurement.measures.length,
+                        measurement.measures.head_circumference,
+                        measurement.time_stamp
+                    ],
+                )
+                .map_err(OstError::from)?;
+        }
+
+        for (client, mark) in monolith.replay_marks.iter() {
+            transaction
+                .execute(
+                    "INSERT INTO replay_marks (client, mark) VALUES (?1, ?2)",
+                    params![client, to_column(mark)?],
+                )
+                .map_err(OstError::from)?;
+        }
+
+        // Ids of removed entities were handed out too, AUTOINCREMENT continues after them
+        let allocator = &monolith.id_allocator;
+        for (table, next_id) in [
+            ("persons", allocator.next_person_id),
+            ("feeds", allocator.next_feed_id),
+            ("expulsions", allocator.next_expulsion_id),
+            ("events", allocator.next_event_id),
+            ("measurements", allocator.next_measurement_id),
+        ] {
+            if next_id == 0 {
+                continue;
+            }
+            transaction
+                .execute(
+                    "INSERT INTO sqlite_sequence (name, seq) SELECT ?1, 0 \
+                     WHERE NOT EXISTS (SELECT 1 FROM sqlite_sequence WHERE name = ?1)",
+                    params![table],
+                )
+                .map_err(OstError::from)?;
+            transaction
+                .execute(
+                    "UPDATE sqlite_sequence SET seq = max(seq, ?2) WHERE name = ?1",
+                    params![table, next_id - 1],
+                )
+                .map_err(OstError::from)?;
+        }
+        transaction.commit().map_err(OstError::from)
+    }
+
+    /// Inserts one entry and, with a `replay_id`, moves the client's replay mark to it in
+    /// the same transaction
+    fn insert_entry(
+        &mut self,
+        kind: ost_EventKey,
+        insert: &str,
+        values: &[&dyn ToSql],
+        replay_id: Option<&ReplayId>,
+    ) -> Result<u32, OstError> {
+        let transaction = self
+            .connection_mut()
+            .transaction()
+            .map_err(OstError::from)?;
+        transaction
+            .execute(insert, values)
+            .map_err(OstError::from)?;
+        let id = transaction.last_insert_rowid() as u32;
+        if let Some(replay_id) = replay_id {
+            let mark = ReplayMark {
+                id: replay_id.id,
+                key: OstEventKey { t: kind, id },
+            };
+            transaction
+                .execute(
+                    "INSERT INTO replay_marks (client, mark) VALUES (?1, ?2) \
+                     ON CONFLICT(client) DO UPDATE SET mark = excluded.mark",
+                    params![replay_id.client, to_column(&mark)?],
+                )
+                .map_err(OstError::from)?;
+        }
+        transaction.commit().map_err(OstError::from)?;
+        Ok(id)
+    }
+
+    /// A panic while the lock was held leaves the connection itself usable
+    fn connection(&self) -> MutexGuard<'_, Connection> {
+        self.connection
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn connection_mut(&mut self) -> &mut Connection {
+        self.connection
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn person_impls(&self) -> Result<Vec<Arc<RwLock<PersonImpl>>>, OstError> {
+        Ok(self
+            .query_persons("", [])?
+            .into_iter()
+            .map(|p| Arc::new(RwLock::new(p)))
+            .collect())
+    }
+
+    fn query_persons<P: rusqlite::Params>(
+        &self,
+        filter: &str,
+        params: P,
+    ) -> Result<Vec<PersonImpl>, OstError> {
+        let sql = format!(
+            "SELECT id, name, is_active, birth_date, sex, birth_weight, colour \
+             FROM persons {} ORDER BY id",
+            filter
+        );
+        let connection = self.connection();
+        let mut statement = connection.prepare(&sql)?;
+        let rows = statement
+            .query_map(params, |row| {
+                Ok(PersonImpl {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    is_active: row.get(2)?,
+                    birth_date: row.get(3)?,
+                    sex: from_optional_column(row, 4)?,
+                    birth_weight: row.get(5)?,
+                    colour: row.get(6)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows)
+    }
+
+    fn query_feeds<P: rusqlite::Params>(
+        &self,
+        filter: &str,
+        params: P,
+    ) -> Result<Vec<FeedImpl>, OstError> {
+        let persons = self.person_impls()?;
+        let sql = format!(
+            "SELECT id, person_id, breast_milk, formula, solids, time_stamp, nursing \
+             FROM feeds {} ORDER BY time_stamp DESC, id DESC",
+            filter
+        );
+        let connection = self.connection();
+        let mut statement = connection.prepare(&sql)?;
+        let rows = statement
+            .query_map(params, |row| {
+                Ok(FeedPersistence {
+                    id: row.get(0)?,
+                    person_id: row.get(1)?,
+                    breast_milk: row.get(2)?,
+                    formula: row.get(3)?,
+                    solids: row.get(4)?,
+                    time_stamp: row.get(5)?,
+                    nursing: from_optional_column(row, 6)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows
+            .iter()
+            .map(|f| FeedImpl::from_persistence(f, &persons))
+            .collect())
+    }
+
+    fn query_expulsions<P: rusqlite::Params>(
+        &self,
+        filter: &str,
+        params: P,
+    ) -> Result<Vec<ExpulsionImpl>, OstError> {
+        let persons = self.person_impls()?;
+        let sql = format!(
+            "SELECT id, person_id, degree, time_stamp \
+             FROM expulsions {} ORDER BY time_stamp DESC, id DESC",
+            filter
+        );
+        let connection = self.connection();
+        let mut statement = connection.prepare(&sql)?;
+        let rows = statement
+            .query_map(params, |row| {
+                Ok(ExpulsionPersistence {
+                    id: row.get(0)?,
+                    person_id: row.get(1)?,
+                    degree: from_column(row, 2)?,
+                    time_stamp: row.get(3)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows
+            .iter()
+            .map(|e| ExpulsionImpl::from_persistence(e, &persons))
+            .collect())
+    }
+
+    fn query_events<P: rusqlite::Params>(
+        &self,
+        filter: &str,
+        params: P,
+    ) -> Result<Vec<EventImpl>, OstError> {
+        let persons = self.person_impls()?;
+        let sql = format!(
+            "SELECT id, person_id, event, time_stamp \
+             FROM events {} ORDER BY time_stamp DESC, id DESC",
+            filter
+        );
+        let connection = self.connection();
+        let mut statement = connection.prepare(&sql)?;
+        let rows = statement
+            .query_map(params, |row| {
+                Ok(EventPersistence {
+                    id: row.get(0)?,
+                    person_id: row.get(1)?,
+                    event: from_column(row, 2)?,
+                    time_stamp: row.get(3)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows
+            .iter()
+            .map(|e| EventImpl::from_persistence(e, &persons))
+            .collect())
+    }
+
+    fn query_measurements<P: rusqlite::Params>(
+        &self,
+        filter: &str,
+        params: P,
+    ) -> Result<Vec<MeasurementImpl>, OstError> {
+        let persons = self.person_impls()?;
+        let sql = format!(
+            "SELECT id, person_id, weight, length, head_circumference, time_stamp \
+             FROM measurements {} ORDER BY time_stamp DESC, id DESC",
+            filter
+        );
+        let connection = self.connection();
+        let mut statement = connection.prepare(&sql)?;
+        let rows = statement
+            .query_map(params, |row| {
+                Ok(MeasurementPersistence {
+                    id: row.get(0)?,
+                    person_id: row.get(1)?,
+                    measures: Measures {
+                        weight: row.get(2)?,
+                        length: row.get(3)?,
+                        head_circumference: row.get(4)?,
+                    },
+                    time_stamp: row.get(5)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows
+            .iter()
+            .map(|m| MeasurementImpl::from_persistence(m, &persons))
+            .collect())
+    }
+
+    fn to_persistence(&self) -> Result<ContextPersistence, OstError> {
+        Ok(ContextPersistence {
+            persons: self.query_persons("", [])?,
+            feeds: self
+                .query_feeds("", [])?
+                .iter()
+                .map(|f| f.to_persistence())
+                .collect(),
+            expulsions: self
+                .query_expulsions("", [])?
+                .iter()
+                .map(|e| e.to_persistence())
+                .collect(),
+            events: self
+                .query_events("", [])?
+                .iter()
+                .map(|e| e.to_persistence())
+                .collect(),
+            measurements: self
+                .query_measurements("", [])?
+                .iter()
+                .map(|m| m.to_persistence())
+                .collect(),
+            ..ContextPersistence::default()
+        })
+    }
+
+    fn existing_person(&self, person: &dyn Person) -> Result<Arc<RwLock<PersonImpl>>, OstError> {
+        let person_id = person.key().id;
+        match self
+            .person_impls()?
+            .into_iter()
+            .find(|p| p.read().unwrap().id() == person_id)
+        {
+            Some(p) => Ok(p),
+            None => Err(OstError::NotFound(format!(
+                "No person with name {} found",
+                person.name()
+            ))),
+        }
+    }
+
+    fn random_person_ids(&self, count: u32) -> Result<Vec<u32>, OstError> {
+        let persons = self.person_impls()?;
+        if persons.is_empty() {
+            return Err(OstError::Validation(
+                "You can't add fake entries without creating persons first".to_string(),
+            ));
+        }
+        let mut rng = rand::thread_rng();
+        Ok((0..count)
+            .map(|_| {
+                persons[rng.gen_range(0..persons.len())]
+                    .read()
+                    .unwrap()
+                    .id()
+            })
+            .collect())
+    }
+
+    fn execute_on_existing_row<P: rusqlite::Params>(
+        &self,
+        sql: &str,
+        params: P,
+        not_found: String,
+    ) -> Result<(), OstError> {
+        match self.connection().execute(sql, params) {
+            Ok(0) => Err(OstError::NotFound(not_found)),
+            Ok(_) => Ok(()),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+/// Enums with payload, `EventType::Medicine(String)` and friends, are stored as json
+fn to_column<T: serde::Serialize>(value: &T) -> Result<String, OstError> {
+    serde_json::to_string(value).map_err(OstError::from)
+}
+
+fn to_optional_column<T: serde::Serialize>(value: &Option<T>) -> Result<Option<String>, OstError> {
+    value.as_ref().map(to_column).transpose()
+}
+
+fn from_optional_column<T: serde::de::DeserializeOwned>(
+    row: &Row,
+    index: usize,
+) -> rusqlite::Result<Option<T>> {
+    match row.get::<_, Option<String>>(index)? {
+        Some(_) => from_column(row, index).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// The reads the trait can't fail on log the error and come back empty
+fn or_logged<T: Default>(result: Result<T, OstError>) -> T {
+    result.unwrap_or_else(|err| {
+        log::error!("Reading from SQLite failed: {}", err);
+        T::default()
+    })
+}
+
+fn from_column<T: serde::de::DeserializeOwned>(row: &Row, index: usize) -> rusqlite::Result<T> {
+    let payload: String = row.get(index)?;
+    serde_json::from_str(&payload).map_err(|err| {
+        rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, Box::new(err))
+    })
+}
+
+impl Context for SqliteContextImpl {
+    fn purge_all_data(&mut self) -> Result<(), OstError> {
+        self.connection()
+            .execute_batch(
+                "DELETE FROM measurements; DELETE FROM events; DELETE FROM expulsions; \
+                 DELETE FROM feeds; DELETE FROM persons;",
+            )
+            .map_err(OstError::from)
+    }
+
+    fn purge_all_events(&mut self) -> Result<(), OstError> {
+        self.connection()
+            .execute_batch(
+                "DELETE FROM measurements; DELETE FROM events; DELETE FROM expulsions; \
+                 DELETE FROM feeds;",
+            )
+            .map_err(OstError::from)
+    }
+
+    fn compact_storage(&mut self) -> Result<(), OstError> {
+        self.connection()
+            .execute_batch("VACUUM;")
+            .map_err(OstError::from)
+    }
+
+    fn check_integrity(&self) -> Result<Vec<IntegrityIssue>, OstError> {
+        Ok(integrity::check(&self.to_persistence()?, Utc::now()))
+    }
+
+    /// Ids and person references are enforced by the schema, leaving names and time stamps
+    fn repair_integrity(&mut self) -> Result<Vec<IntegrityIssue>, OstError> {
+        let original = self.to_persistence()?;
+        let mut repaired = original.clone();
+        let issues = integrity::repair(&mut repaired, Utc::now());
+        if issues.is_empty() {
+            return Ok(issues);
+        }
+
+        let transaction = self
+            .connection_mut()
+            .transaction()
+            .map_err(OstError::from)?;
+        for (before, after) in original.persons.iter().zip(repaired.persons.iter()) {
+            if before.name != after.name {
+                transaction
+                    .execute(
+                        "UPDATE persons SET name = ?1 WHERE id = ?2",
+                        params![after.name, after.id],
+                    )
+                    .map_err(OstError::from)?;
+            }
+        }
+        for (before, after) in original.feeds.iter().zip(repaired.feeds.iter()) {
+            if before.time_stamp != after.time_stamp {
+                transaction
+                    .execute(
+                        "UPDATE feeds SET time_stamp = ?1 WHERE id = ?2",
+                        params![after.time_stamp, after.id],
+                    )
+                    .map_err(OstError::from)?;
+            }
+        }
+        for (before, after) in original.expulsions.iter().zip(repaired.expulsions.iter()) {
+            if before.time_stamp != after.time_stamp {
+                transaction
+                    .execute(
+                        "UPDATE expulsions SET time_stamp = ?1 WHERE id = ?2",
+                        params![after.time_stamp, after.id],
+                    )
+                    .map_err(OstError::from)?;
+            }
+        }
+        for (before, after) in original.events.iter().zip(repaired.events.iter()) {
+            if before.time_stamp != after.time_stamp {
+                transaction
+                    .execute(
+                        "UPDATE events SET time_stamp = ?1 WHERE id = ?2",
+                        params![after.time_stamp, after.id],
+                    )
+                    .map_err(OstError::from)?;
+            }
+        }
+        for (before, after) in original
+            .measurements
+            .iter()
+            .zip(repaired.measurements.iter())
+        {
+            if before.time_stamp != after.time_stamp {
+                transaction
+                    .execute(
+                        "UPDATE measurements SET time_stamp = ?1 WHERE id = ?2",
+                        params![after.time_stamp, after.id],
+                    )
+                    .map_err(OstError::from)?;
+            }
+        }
+        transaction.commit().map_err(OstError::from)?;
+        Ok(issues)
+    }
+
+    fn get_base_event_by_key(&self, key: &OstEventKey) -> Option<Box<dyn EventBase>> {
+        match key.t {
+            ost_EventKey::Expulsion => self
+                .get_expulsion_by_key(key)
+                .map(|o| o as Box<dyn EventBase>),
+            ost_EventKey::Event => self.get_event_by_key(key).map(|o| o as Box<dyn EventBase>),
+            ost_EventKey::Feed => self
+                .get_feeding_by_key(key)
+                .map(|o| o as Box<dyn EventBase>),
+            ost_EventKey::Measurement => self
+                .get_measurement_by_key(key)
+                .map(|o| o as Box<dyn EventBase>),
+        }
+    }
+
+    /// Person and time range are filtered by SQLite, sorting and paging mix the tables
+    fn query(&self, query: &EventQuery) -> Result<QueryResult, OstError> {
+        let mut conditions: Vec<String> = vec![];
+        let mut values: Vec<Box<dyn ToSql>> = vec![];
+        if let Some(person) = query.person {
+            values.push(Box::new(person.id));
+            conditions.push(format!("person_id = ?{}", values.len()));
+        }
+        if let Some(from) = query.from {
+            values.push(Box::new(from));
+            conditions.push(format!("time_stamp >= ?{}", values.len()));
+        }
+        if let Some(to) = query.to {
+            values.push(Box::new(to));
+            conditions.push(format!("time_stamp < ?{}", values.len()));
+        }
+        if query.active_persons_only {
+            conditions
+                .push("person_id IN (SELECT id FROM persons WHERE is_active = 1)".to_string());
+        }
+        let filter = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+
+        // Only the keys of the requested page leave SQLite, in the order of `EventQuery::page`
+        let matches = [
+            (ost_EventKey::Feed, "feeds"),
+            (ost_EventKey::Expulsion, "expulsions"),
+            (ost_EventKey::Event, "events"),
+            (ost_EventKey::Measurement, "measurements"),
+        ]
+        .iter()
+        .filter(|(kind, _)| query.includes_kind(*kind))
+        .map(|(kind, table)| {
+            format!(
+                "SELECT '{}' AS kind, id, time_stamp FROM {} {}",
+                kind, table, filter
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(" UNION ALL ");
+        let direction = match query.sort {
+            SortOrder::NewestFirst => "DESC",
+            SortOrder::OldestFirst => "ASC",
+        };
+
+        let (total, page) = {
+            let connection = self.connection();
+            let total: i64 = connection.query_row(
+                &format!("SELECT COUNT(*) FROM ({})", matches),
+                params_from_iter(values.iter()),
+                |row| row.get(0),
+            )?;
+
+            // A negative limit is no limit to SQLite
+            values.push(Box::new(query.limit.map_or(-1, |limit| limit as i64)));
+            values.push(Box::new(query.offset as i64));
+            let sql = format!(
+                "SELECT kind, id FROM ({}) ORDER BY time_stamp {d}, kind {d}, id {d} \
+                 LIMIT ?{} OFFSET ?{}",
+                matches,
+                values.len() - 1,
+                values.len(),
+                d = direction
+            );
+            let mut statement = connection.prepare(&sql)?;
+            let page = statement
+                .query_map(params_from_iter(values.iter()), |row| {
+                    Ok((row.get::<_, String>(0)?, row.get::<_, u32>(1)?))
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            (total as usize, page)
+        };
+
+        let positions: HashMap<(String, u32), usize> = page
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(position, key)| (key, position))
+            .collect();
+        let ids_of = |kind: ost_EventKey| {
+            let ids: Vec<String> = page
+                .iter()
+                .filter(|(k, _)| *k == kind.to_string())
+                .map(|(_, id)| id.to_string())
+                .collect();
+            format!("WHERE id IN ({})", ids.join(","))
+        };
+
+        let mut items = vec![];
+        self.query_feeds(&ids_of(ost_EventKey::Feed), [])?
+            .into_iter()
+            .for_each(|f| items.push(QueryItem::Feed(Box::new(f))));
+        self.query_expulsions(&ids_of(ost_EventKey::Expulsion), [])?
+            .into_iter()
+            .for_each(|e| items.push(QueryItem::Expulsion(Box::new(e))));
+        self.query_events(&ids_of(ost_EventKey::Event), [])?
+            .into_iter()
+            .for_each(|e| items.push(QueryItem::Event(Box::new(e))));
+        self.query_measurements(&ids_of(ost_EventKey::Measurement), [])?
+            .into_iter()
+            .for_each(|m| items.push(QueryItem::Measurement(Box::new(m))));
+        items.sort_by_key(|item| {
+            let key = item.event_base().key();
+            positions[&(key.t.to_string(), key.id)]
+        });
+        Ok(QueryResult { items, total })
+    }
+
+    fn persons(&self) -> Vec<Box<dyn Person>> {
+        or_logged(self.query_persons("", []))
+            .into_iter()
+            .map(|p| Box::new(p) as Box<dyn Person>)
+            .collect()
+    }
+
+    fn add_person(&mut self, name: &str) -> Result<Box<dyn Person>, OstError> {
+        let existing: Option<u32> = self
+            .connection()
+            .query_row("SELECT id FROM persons WHERE name = ?1", [name], |row| {
+                row.get(0)
+            })
+            .optional()
+            .map_err(OstError::from)?;
+        if existing.is_some() {
+            return Err(OstError::DuplicateName(format!(
+                "Person with name {} already exists",
+                name
+            )));
+        }
+
+        self.connection()
+            .execute(
+                "INSERT INTO persons (name, is_active) VALUES (?1, ?2)",
+                params![name, true],
+            )
+            .map_err(OstError::from)?;
+        let id = self.connection().last_insert_rowid() as u32;
+        Ok(Box::new(PersonImpl::new(id, name)))
+    }
+
+    fn add_fake_persons(&mut self, count: u32) -> Result<(), OstError> {
+        let transaction = self
+            .connection_mut()
+            .transaction()
+            .map_err(OstError::from)?;
+        for _i in 0..count {
+            let first_name: String = Name(EN).fake();
+            transaction
+                .execute(
+                    "INSERT INTO persons (name, is_active) VALUES (?1, ?2)",
+                    params![first_name, true],
+                )
+                .map_err(OstError::from)?;
+        }
+        transaction.commit().map_err(OstError::from)
+    }
+
+    fn modify_person(&mut self, person: &Box<dyn Person>) -> Result<(), OstError> {
+        self.execute_on_existing_row(
+            "UPDATE persons SET name = ?1, is_active = ?2, birth_date = ?3, sex = ?4, \
+             birth_weight = ?5, colour = ?6 WHERE id = ?7",
+            params![
+                person.name(),
+                person.is_active(),
+                person.birth_date(),
+                to_optional_column(&person.sex())?,
+                person.birth_weight(),
+                person.colour(),
+                person.key().id
+            ],
+            format!("No person with name {} found", person.name()),
+        )
+    }
+
+    fn remove_person(&mut self, person: Box<dyn Person>, cascade: bool) -> Result<(), OstError> {
+        let person_id = self.existing_person(person.as_ref())?.read().unwrap().id();
+
+        let transaction = self
+            .connection_mut()
+            .transaction()
+            .map_err(OstError::from)?;
+        let mut entry_count = 0;
+        for table in ["feeds", "expulsions", "events", "measurements"] {
+            entry_count += if cascade {
+                transaction.execute(
+                    &format!("DELETE FROM {} WHERE person_id = ?1", table),
+                    [person_id],
+                )
+            } else {
+                transaction.query_row(
+                    &format!("SELECT COUNT(*) FROM {} WHERE person_id = ?1", table),
+                    [person_id],
+                    |row| row.get::<_, usize>(0),
+                )
+            }
+            .map_err(OstError::from)?;
+        }
+        if entry_count > 0 && !cascade {
+            return Err(OstError::Validation(format!(
+                "{} still has {} entries, merge or remove them first",
+                person.name(),
+                entry_count
+            )));
+        }
+        transaction
+            .execute("DELETE FROM persons WHERE id = ?1", [person_id])
+            .map_err(OstError::from)?;
+        transaction.commit().map_err(OstError::from)
+    }
+
+    fn merge_persons(
+        &mut self,
+        from: Box<dyn Person>,
+        into: &Box<dyn Person>,
+    ) -> Result<(), OstError> {
+        let from_id = self.existing_person(from.as_ref())?.read().unwrap().id();
+        let into_id = self.existing_person(into.as_ref())?.read().unwrap().id();
+        if from_id == into_id {
+            return Err(OstError::Validation(format!(
+                "Can't merge {} into itself",
+                from.name()
+            )));
+        }
+
+        let transaction = self
+            .connection_mut()
+            .transaction()
+            .map_err(OstError::from)?;
+        for table in ["feeds", "expulsions", "events", "measurements"] {
+            transaction
+                .execute(
+                    &format!("UPDATE {} SET person_id = ?1 WHERE person_id = ?2", table),
+                    [into_id, from_id],
+                )
+                .map_err(OstError::from)?;
+        }
+        transaction
+            .execute("DELETE FROM persons WHERE id = ?1", [from_id])
+            .map_err(OstError::from)?;
+        transaction.commit().map_err(OstError::from)
+    }
+
+    fn get_person_by_key(&self, key: OstPersonKey) -> Option<Box<dyn Person>> {
+        or_logged(
+            self.query_persons("WHERE id = ?1", [key.id])
+                .map(|mut found| found.pop()),
+        )
+        .map(|p| Box::new(p) as Box<dyn Person>)
+    }
+
+    fn feedings(&self) -> Vec<Box<dyn Feed>> {
+        or_logged(self.query_feeds("", []))
+            .into_iter()
+            .map(|f| Box::new(f) as Box<dyn Feed>)
+            .collect()
+    }
+
+    fn feedings_by(&self, person: &Box<dyn Person>) -> Vec<Box<dyn Feed>> {
+        or_logged(self.query_feeds("WHERE person_id = ?1", [person.key().id]))
+            .into_iter()
+            .map(|f| Box::new(f) as Box<dyn Feed>)
+            .collect()
+    }
+
+    fn add_feeding_at(
+        &mut self,
+        person: &Box<dyn Person>,
+        breast_milk: u32,
+        formula: u32,
+        solids: u32,
+        time_stamp: DateTime<Utc>,
+        replay_id: Option<&ReplayId>,
+    ) -> Result<Box<dyn Feed>, OstError> {
+        let existing_p = self.existing_person(person.as_ref())?;
+        let id = self.insert_entry(
+            ost_EventKey::Feed,
+            "INSERT INTO feeds (person_id, breast_milk, formula, solids, time_stamp) \
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                existing_p.read().unwrap().id(),
+                breast_milk,
+                formula,
+                solids,
+                time_stamp
+            ],
+            replay_id,
+        )?;
+
+        let mut f = FeedImpl::new(existing_p, id, breast_milk, formula, solids);
+        f.time_stamp = time_stamp;
+        Ok(Box::new(f))
+    }
+
+    fn add_nursing_replayed(
+        &mut self,
+        person: &Box<dyn Person>,
+        nursing: NursingSession,
+        replay_id: Option<&ReplayId>,
+    ) -> Result<Box<dyn Feed>, OstError> {
+        nursing.validate()?;
+        let existing_p = self.existing_person(person.as_ref())?;
+        let time_stamp = nursing.start().unwrap_or_else(Utc::now);
+        let id = self.insert_entry(
+            ost_EventKey::Feed,
+            "INSERT INTO feeds (person_id, breast_milk, formula, solids, time_stamp, nursing) \
+             VALUES (?1, 0, 0, 0, ?2, ?3)",
+            params![
+                existing_p.read().unwrap().id(),
+                time_stamp,
+                to_optional_column(&Some(&nursing))?
+            ],
+            replay_id,
+        )?;
+
+        let mut f = FeedImpl::new(existing_p, id, 0, 0, 0);
+        f.time_stamp = time_stamp;
+        f.nursing = Some(nursing);
+        Ok(Box::new(f))
+    }
+
+    fn add_fake_feedings(&mut self, count: u32) -> Result<(), OstError> {
+        let person_ids = self.random_person_ids(count)?;
+        let mut rng = rand::thread_rng();
+        let transaction = self
+            .connection_mut()
+            .transaction()
+            .map_err(OstError::from)?;
+        for person_id in person_ids {
+            transaction
+                .execute(
+                    "INSERT INTO feeds (person_id, breast_milk, formula, solids, time_stamp) \
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![
+                        person_id,
+                        rng.gen_range(0..150),
+                        rng.gen_range(0..150),
+                        rng.gen_range(0..150),
+                        ContextMonolithicImpl::random_time_stamp_in_the_past(&mut rng)
+                    ],
+                )
+                .map_err(OstError::from)?;
+        }
+        transaction.commit().map_err(OstError::from)
+    }
+
+    fn modify_feeding(&mut self, feed: &Box<dyn Feed>) -> Result<(), OstError> {
+        if let Some(nursing) = feed.nursing() {
+            nursing.validate()?;
+        }
+        self.execute_on_existing_row(
+            "UPDATE feeds SET breast_milk = ?1, formula = ?2, solids = ?3, time_stamp = ?4, \
+             nursing = ?5 WHERE id = ?6",
+            params![
+                feed.breast_milk(),
+                feed.formula(),
+                feed.solids(),
+                feed.time_stamp(),
+                to_optional_column(&feed.nursing())?,
+                feed.key().id
+            ],
+            format!(
+                "Feeding not found: {} {}",
+                feed.person_name(),
+                feed.time_stamp()
+            ),
+        )
+    }
+
+    fn remove_feeding(&mut self, feed: Box<dyn Feed>) -> Result<(), OstError> {
+        self.execute_on_existing_row(
+            "DELETE FROM feeds WHERE id = ?1",
+            [feed.key().id],
+            format!(
+                "Feeding not found: {} {}",
+                feed.person_name(),
+                feed.time_stamp()
+            ),
+        )
+    }
+
+    fn get_feeding_by_key(&self, key: &OstEventKey) -> Option<Box<dyn Feed>> {
+        if key.t != ost_EventKey::Feed {
+            return None;
+        }
+        or_logged(
+            self.query_feeds("WHERE id = ?1", [key.id])
+                .map(|mut found| found.pop()),
+        )
+        .map(|f| Box::new(f) as Box<dyn Feed>)
+    }
+
+    fn expulsions(&self) -> Vec<Box<dyn Expulsion>> {
+        or_logged(self.query_expulsions("", []))
+            .into_iter()
+            .map(|e| Box::new(e) as Box<dyn Expulsion>)
+            .collect()
+    }
+
+    fn expulsions_by(&self, person: &Box<dyn Person>) -> Vec<Box<dyn Expulsion>> {
+        or_logged(self.query_expulsions("WHERE person_id = ?1", [person.key().id]))
+            .into_iter()
+            .map(|e| Box::new(e) as Box<dyn Expulsion>)
+            .collect()
+    }
+
+    fn add_expulsion_at(
+        &mut self,
+        person: &Box<dyn Person>,
+        degree: ExpulsionDegree,
+        time_stamp: DateTime<Utc>,
+        replay_id: Option<&ReplayId>,
+    ) -> Result<Box<dyn Expulsion>, OstError> {
+        let existing_p = self.existing_person(person.as_ref())?;
+        let id = self.insert_entry(
+            ost_EventKey::Expulsion,
+            "INSERT INTO expulsions (person_id, degree, time_stamp) VALUES (?1, ?2, ?3)",
+            params![
+                existing_p.read().unwrap().id(),
+                to_column(&degree)?,
+                time_stamp
+            ],
+            replay_id,
+        )?;
+
+        let mut expulsion = ExpulsionImpl::new(existing_p, id, degree);
+        expulsion.time_stamp = time_stamp;
+        Ok(Box::new(expulsion))
+    }
+
+    fn add_fake_expulsions(&mut self, count: u32) -> Result<(), OstError> {
+        let person_ids = self.random_person_ids(count)?;
+        let mut rng = rand::thread_rng();
+        let transaction = self
+            .connection_mut()
+            .transaction()
+            .map_err(OstError::from)?;
+        for person_id in person_ids {
+            transaction
+                .execute(
+                    "INSERT INTO expulsions (person_id, degree, time_stamp) VALUES (?1, ?2, ?3)",
+                    params![
+                        person_id,
+                        to_column(&Faker.fake::<ExpulsionDegree>())?,
+                        ContextMonolithicImpl::random_time_stamp_in_the_past(&mut rng)
+                    ],
+                )
+                .map_err(OstError::from)?;
+        }
+        transaction.commit().map_err(OstError::from)
+    }
+
+    fn modify_expulsion(&mut self, expulsion: &Box<dyn Expulsion>) -> Result<(), OstError> {
+        self.execute_on_existing_row(
+            "UPDATE expulsions SET degree = ?1, time_stamp = ?2 WHERE id = ?3",
+            params![
+                to_column(&expulsion.degree())?,
+                expulsion.time_stamp(),
+                expulsion.key().id
+            ],
+            format!(
+                "Expulsion not found: {} {}",
+                expulsion.person_name(),
+                expulsion.time_stamp()
+            ),
+        )
+    }
+
+    fn remove_expulsion(&mut self, expulsion: Box<dyn Expulsion>) -> Result<(), OstError> {
+        self.execute_on_existing_row(
+            "DELETE FROM expulsions WHERE id = ?1",
+            [expulsion.key().id],
+            format!(
+                "Expulsion not found: {} {}",
+                expulsion.person_name(),
+                expulsion.time_stamp()
+            ),
+        )
+    }
+
+    fn get_expulsion_by_key(&self, key: &OstEventKey) -> Option<Box<dyn Expulsion>> {
+        if key.t != ost_EventKey::Expulsion {
+            return None;
+        }
+        or_logged(
+            self.query_expulsions("WHERE id = ?1", [key.id])
+                .map(|mut found| found.pop()),
+        )
+        .map(|e| Box::new(e) as Box<dyn Expulsion>)
+    }
+
+    fn events(&self) -> Vec<Box<dyn Event>> {
+        or_logged(self.query_events("", []))
+            .into_iter()
+            .map(|e| Box::new(e) as Box<dyn Event>)
+            .collect()
+    }
+
+    fn events_by(&self, person: &Box<dyn Person>) -> Vec<Box<dyn Event>> {
+        or_logged(self.query_events("WHERE person_id = ?1", [person.key().id]))
+            .into_iter()
+            .map(|e| Box::new(e) as Box<dyn Event>)
+            .collect()
+    }
+
+    fn add_event_at(
+        &mut self,
+        person: &Box<dyn Person>,
+        event_type: EventType,
+        time_stamp: DateTime<Utc>,
+        replay_id: Option<&ReplayId>,
+    ) -> Result<Box<dyn Event>, OstError> {
+        let existing_p = self.existing_person(person.as_ref())?;
+        let id = self.insert_entry(
+            ost_EventKey::Event,
+            "INSERT INTO events (person_id, event, time_stamp) VALUES (?1, ?2, ?3)",
+            params![
+                existing_p.read().unwrap().id(),
+                to_column(&event_type)?,
+                time_stamp
+            ],
+            replay_id,
+        )?;
+
+        let mut event = EventImpl::new(existing_p, id, event_type);
+        event.time_stamp = time_stamp;
+        Ok(Box::new(event))
+    }
+
+    fn add_fake_events(&mut self, count: u32) -> Result<(), OstError> {
+        let person_ids = self.random_person_ids(count)?;
+        let mut rng = rand::thread_rng();
+        let transaction = self
+            .connection_mut()
+            .transaction()
+            .map_err(OstError::from)?;
+        for person_id in person_ids {
+            transaction
+                .execute(
+                    "INSERT INTO events (person_id, event, time_stamp) VALUES (?1, ?2, ?3)",
+                    params![
+                        person_id,
+                        to_column(&Faker.fake::<EventType>())?,
+                        ContextMonolithicImpl::random_time_stamp_in_the_past(&mut rng)
+                    ],
+                )
+                .map_err(OstError::from)?;
+        }
+        transaction.commit().map_err(OstError::from)
+    }
+
+    fn modify_event(&mut self, event: &Box<dyn Event>) -> Result<(), OstError> {
+        self.execute_on_existing_row(
+            "UPDATE events SET event = ?1, time_stamp = ?2 WHERE id = ?3",
+            params![
+                to_column(&event.event())?,
+                event.time_stamp(),
+                event.key().id
+            ],
+            format!(
+                "Event not found: {} {}",
+                event.person_name(),
+                event.time_stamp()
+            ),
+        )
+    }
+
+    fn remove_event(&mut self, event: Box<dyn Event>) -> Result<(), OstError> {
+        self.execute_on_existing_row(
+            "DELETE FROM events WHERE id = ?1",
+            [event.key().id],
+            format!(
+                "Event not found: {} {}",
+                event.person_name(),
+                event.time_stamp()
+            ),
+        )
+    }
+
+    fn get_event_by_key(&self, key: &OstEventKey) -> Option<Box<dyn Event>> {
+        if key.t != ost_EventKey::Event {
+            return None;
+        }
+        or_logged(
+            self.query_events("WHERE id = ?1", [key.id])
+                .map(|mut found| found.pop()),
+        )
+        .map(|e| Box::new(e) as Box<dyn Event>)
+    }
+
+    fn measurements(&self) -> Vec<Box<dyn Measurement>> {
+        or_logged(self.query_measurements("", []))
+            .into_iter()
+            .map(|m| Box::new(m) as Box<dyn Measurement>)
+            .collect()
+    }
+
+    fn measurements_by(&self, person: &Box<dyn Person>) -> Vec<Box<dyn Measurement>> {
+        or_logged(self.query_measurements("WHERE person_id = ?1", [person.key().id]))
+            .into_iter()
+            .map(|m| Box::new(m) as Box<dyn Measurement>)
+            .collect()
+    }
+
+    fn add_measurement_at(
+        &mut self,
+        person: &Box<dyn Person>,
+        measures: Measures,
+        time_stamp: DateTime<Utc>,
+        replay_id: Option<&ReplayId>,
+    ) -> Result<Box<dyn Measurement>, OstError> {
+        measures.validate()?;
+        let existing_p = self.existing_person(person.as_ref())?;
+        let id = self.insert_entry(
+            ost_EventKey::Measurement,
+            "INSERT INTO measurements \
+             (person_id, weight, length, head_circumference, time_stamp) \
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                existing_p.read().unwrap().id(),
+                measures.weight,
+                measures.length,
+                measures.head_circumference,
+                time_stamp
+            ],
+            replay_id,
+        )?;
+
+        let mut measurement = MeasurementImpl::new(existing_p, id, measures);
+        measurement.time_stamp = time_stamp;
+        Ok(Box::new(measurement))
+    }
+
+    fn add_fake_measurements(&mut self, count: u32) -> Result<(), OstError> {
+        let person_ids = self.random_person_ids(count)?;
+        let mut rng = rand::thread_rng();
+        let transaction = self
+            .connection_mut()
+            .transaction()
+            .map_err(OstError::from)?;
+        for person_id in person_ids {
+            let measures = ContextMonolithicImpl::random_measures(&mut rng);
+            transaction
+                .execute(
+                    "INSERT INTO measurements \
+                     (person_id, weight, length, head_circumference, time_stamp) \
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![
+                        person_id,
+                        measures.weight,
+                        measures.length,
+                        measures.head_circumference,
+                        ContextMonolithicImpl::random_time_stamp_in_the_past(&mut rng)
+                    ],
+                )
+                .map_err(OstError::from)?;
+        }
+        transaction.commit().map_err(OstError::from)
+    }
+
+    fn modify_measurement(&mut self, measurement: &Box<dyn Measurement>) -> Result<(), OstError> {
+        let measures = measurement.measures();
+        measures.validate()?;
+        self.execute_on_existing_row(
+            "UPDATE measurements SET weight = ?1, length = ?2, head_circumference = ?3, \
+             time_stamp = ?4 WHERE id = ?5",
+            params![
+                measures.weight,
+                measures.length,
+                measures.head_circumference,
+                measurement.time_stamp(),
+                measurement.key().id
+            ],
+            format!(
+                "Measurement not found: {} {}",
+                measurement.person_name(),
+                measurement.time_stamp()
+            ),
+        )
+    }
+
+    fn remove_measurement(&mut self, measurement: Box<dyn Measurement>) -> Result<(), OstError> {
+        self.execute_on_existing_row(
+            "DELETE FROM measurements WHERE id = ?1",
+            [measurement.key().id],
+            format!(
+                "Measurement not found: {} {}",
+                measurement.person_name(),
+                measurement.time_stamp()
+            ),
+        )
+    }
+
+    fn get_measurement_by_key(&self, key: &OstEventKey) -> Option<Box<dyn Measurement>> {
+        if key.t != ost_EventKey::Measurement {
+            return None;
+        }
+        or_logged(
+            self.query_measurements("WHERE id = ?1", [key.id])
+                .map(|mut found| found.pop()),
+        )
+        .map(|m| Box::new(m) as Box<dyn Measurement>)
+    }
+
+    fn replay_mark(&self, client: &str) -> Option<ReplayMark> {
+        or_logged(
+            self.connection()
+                .query_row(
+                    "SELECT mark FROM replay_marks WHERE client = ?1",
+                    [client],
+                    |row| from_column(row, 0),
+                )
+                .optional()
+                .map_err(OstError::from),
+        )
+    }
+}
@@ -0,0 +1,94 @@
+use serde_derive::{Deserialize, Serialize};
+
+/// Hands out ids per collection. Counters only ever move forward, so an id that
+/// was used once is never handed out again, not even after the entity got removed.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct IdAllocator {
+    pub next_person_id: u32,
+    pub next_feed_id: u32,
+    pub next_expulsion_id: u32,
+    pub next_event_id: u32,
+    /// Missing in allocators persisted before measurements existed
+    #[serde(default)]
+    pub next_measurement_id: u32,
+}
+
+impl IdAllocator {
+    pub fn next_person_id(&mut self) -> u32 {
+        Self::take(&mut self.next_person_id)
+    }
+
+    pub fn next_feed_id(&mut self) -> u32 {
+        Self::take(&mut self.next_feed_id)
+    }
+
+    pub fn next_expulsion_id(&mut self) -> u32 {
+        Self::take(&mut self.next_expulsion_id)
+    }
+
+    pub fn next_event_id(&mut self) -> u32 {
+        Self::take(&mut self.next_event_id)
+    }
+
+    pub fn next_measurement_id(&mut self) -> u32 {
+        Self::take(&mut self.next_measurement_id)
+    }
+
+    /// Moves every counter past the highest id already in use.
+    /// Needed when loading files written before the allocator was persisted.
+    pub fn skip_past(
+        &mut self,
+        max_person_id: Option<u32>,
+        max_feed_id: Option<u32>,
+        max_expulsion_id: Option<u32>,
+        max_event_id: Option<u32>,
+        max_measurement_id: Option<u32>,
+    ) {
+        Self::bump(&mut self.next_person_id, max_person_id);
+        Self::bump(&mut self.next_feed_id, max_feed_id);
+        Self::bump(&mut self.next_expulsion_id, max_expulsion_id);
+        Self::bump(&mut self.next_event_id, max_event_id);
+        Self::bump(&mut self.next_measurement_id, max_measurement_id);
+    }
+
+    fn take(counter: &mut u32) -> u32 {
+        let id = *counter;
+        *counter += 1;
+        id
+    }
+
+    fn bump(counter: &mut u32, max_in_use: Option<u32>) {
+        if let Some(max_in_use) = max_in_use {
+            if *counter <= max_in_use {
+                *counter = max_in_use + 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IdAllocator;
+
+    #[test]
+    fn ids_only_count_up() {
+        let mut allocator = IdAllocator::default();
+        assert_eq!(allocator.next_feed_id(), 0);
+        assert_eq!(allocator.next_feed_id(), 1);
+        assert_eq!(allocator.next_event_id(), 0);
+    }
+
+    #[test]
+    fn skip_past_never_moves_backwards() {
+        let mut allocator = IdAllocator {
+            next_feed_id: 40,
+            ..Default::default()
+        };
+        allocator.skip_past(Some(3), Some(10), None, Some(7), Some(0));
+        assert_eq!(allocator.next_person_id, 4);
+        assert_eq!(allocator.next_feed_id, 40);
+        assert_eq!(allocator.next_expulsion_id, 0);
+        assert_eq!(allocator.next_event_id, 8);
+        assert_eq!(allocator.next_measurement_id, 1);
+    }
+}
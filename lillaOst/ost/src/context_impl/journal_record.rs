@@ -0,0 +1,32 @@
+use serde_derive::{Deserialize, Serialize};
+
+use crate::entity_impl::event_impl::EventPersistence;
+use crate::entity_impl::expulsion_impl::ExpulsionPersistence;
+use crate::entity_impl::feed_impl::FeedPersistence;
+use crate::entity_impl::measurement_impl::MeasurementPersistence;
+use crate::entity_impl::person_impl::PersonImpl;
+use crate::offline::ReplayMark;
+
+/// One mutation of a monolith. Records carry the full entity state and only get replayed
+/// on top of the snapshot generation they were written after.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum JournalRecord {
+    /// First line of every journal, the `journal_generation` of the snapshot it goes on top of.
+    /// Journals written before it existed go on top of generation 0.
+    Generation(u64),
+    PurgeAllData,
+    PurgeAllEvents,
+    PersonUpserted(PersonImpl),
+    /// Also drops whatever entries still point to the person
+    PersonRemoved(u32),
+    FeedUpserted(FeedPersistence),
+    FeedRemoved(u32),
+    ExpulsionUpserted(ExpulsionPersistence),
+    ExpulsionRemoved(u32),
+    EventUpserted(EventPersistence),
+    EventRemoved(u32),
+    MeasurementUpserted(MeasurementPersistence),
+    MeasurementRemoved(u32),
+    /// Client and mark, written right after the entry it points to
+    ReplayMarked(String, ReplayMark),
+}
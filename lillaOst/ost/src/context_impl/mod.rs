@@ -1,7 +1,13 @@
 pub mod context_monolithic_impl;
+pub mod context_persistence;
 pub mod context_persistence_in_memory;
+pub mod context_persistence_journal;
 pub mod context_persistence_json_string;
 pub mod context_persistence_local_storage;
 pub mod context_persistence_single_file;
 pub mod context_redis;
 pub mod context_remote;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod context_sqlite;
+pub mod id_allocator;
+pub mod journal_record;
@@ -1,23 +1,36 @@
 use std::boxed::Box;
+use std::future::Future;
+use std::pin::Pin;
 use std::vec;
 
+use async_trait::async_trait;
 use chrono::prelude::*;
 
 use serde_derive::{Deserialize, Serialize};
 
+use crate::context_async::AsyncContext;
+use crate::context_impl::context_persistence::ContextPersistence;
 use crate::entity_impl::event_impl::EventPersistence;
 use crate::entity_impl::expulsion_impl::ExpulsionPersistence;
 use crate::entity_impl::feed_impl::FeedPersistence;
+use crate::entity_impl::measurement_impl::MeasurementPersistence;
 use crate::entity_impl::person_impl::PersonImpl;
 use crate::entity_impl::remote_event_impl::RemoteEventImpl;
 use crate::entity_impl::remote_expulsion_impl::RemoteExpulsionImpl;
 use crate::entity_impl::remote_feed_impl::RemoteFeedImpl;
+use crate::entity_impl::remote_measurement_impl::RemoteMeasurementImpl;
+use crate::error::OstError;
 use crate::event::{Event, EventType};
 use crate::event_key::{EventType as ost_EventKey, OstEventKey};
 use crate::expulsion::{Expulsion, ExpulsionDegree};
 use crate::feed::Feed;
+use crate::measurement::{Measurement, Measures};
+use crate::nursing::NursingSession;
+use crate::offline::{Mutation, PendingMutation, ReplayId, Target};
 use crate::person::{deserialize as person_deserialize, Person};
 use crate::person_key::OstPersonKey;
+use crate::query::{EventQuery, QueryItem, QueryResult};
+use crate::role::Role;
 
 #[derive(Serialize, Deserialize)]
 struct ArgAddNameCommand {
@@ -29,6 +42,18 @@ struct ArgFakeCount {
     pub count: u32,
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct ArgRemovePerson {
+    pub person_key: OstPersonKey,
+    pub cascade: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ArgMergePersons {
+    pub from: OstPersonKey,
+    pub into: OstPersonKey,
+}
+
 #[derive(Serialize, Deserialize)]
 struct ArgEventKey {
     pub event_key: OstEventKey,
@@ -38,12 +63,33 @@ struct ArgEventKey {
 pub struct ArgAddEvent {
     pub person_key: OstPersonKey,
     pub new_event: EventType,
+    /// Only entries replayed from the outbox carry one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_stamp: Option<DateTime<Utc>>,
+    /// Lets the backend apply a replayed entry only once
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replay_id: Option<ReplayId>,
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct ArgAddExpulsion {
     pub person_key: OstPersonKey,
     pub expulsion_degree: ExpulsionDegree,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_stamp: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replay_id: Option<ReplayId>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ArgAddMeasurement {
+    pub person_key: OstPersonKey,
+    #[serde(flatten)]
+    pub measures: Measures,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_stamp: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replay_id: Option<ReplayId>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -52,30 +98,191 @@ pub struct ArgAddFeeding {
     pub breast_milk: u32,
     pub formula: u32,
     pub solids: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_stamp: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replay_id: Option<ReplayId>,
+}
+
+/// No time stamp, the session knows when it started
+#[derive(Serialize, Deserialize)]
+pub struct ArgAddNursing {
+    pub person_key: OstPersonKey,
+    pub nursing: NursingSession,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replay_id: Option<ReplayId>,
+}
+
+#[derive(Serialize)]
+struct ArgAModifyFeeding {
+    pub event_key: OstEventKey,
+    pub time_stamp: DateTime<Utc>,
+    pub breast_milk: u32,
+    pub formula: u32,
+    pub solids: u32,
+    pub nursing: Option<NursingSession>,
+}
+
+#[derive(Serialize)]
+struct ArgModifyExpulsion {
+    pub event_key: OstEventKey,
+    pub time_stamp: DateTime<Utc>,
+    pub expulsion_degree: ExpulsionDegree,
+}
+
+#[derive(Serialize)]
+struct ArgAModifyEvent {
+    pub event_key: OstEventKey,
+    pub time_stamp: DateTime<Utc>,
+    pub event_payload: EventType,
+}
+
+#[derive(Serialize)]
+struct ArgModifyMeasurement {
+    pub event_key: OstEventKey,
+    pub time_stamp: DateTime<Utc>,
+    #[serde(flatten)]
+    pub measures: Measures,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ArgCredentials {
+    pub name: String,
+    pub password: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SessionToken {
+    pub token: String,
+    pub role: Role,
+}
+
+/// Where the backend lives, paths resolve against the page while `base_url` is empty
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Endpoint {
+    /// Like `https://ost.example.com`, for a frontend hosted apart from the backend
+    pub base_url: String,
+    /// Sent with every call, next to the session's authorization
+    pub headers: Vec<(String, String)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Method {
+    Get,
+    Post,
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct ApiRequest {
+    pub method: Method,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ApiResponse {
+    pub status: u16,
+    pub body: String,
+}
+
+/// Only a backend out of reach is an error, any status makes a response
+pub type TransportFuture = Pin<Box<dyn Future<Output = Result<ApiResponse, OstError>>>>;
+
 // This is the contact from the UI
-#[derive(Clone, PartialEq)]
-pub struct AsyncRemoteMonolith {}
+#[derive(Clone)]
+pub struct AsyncRemoteMonolith {
+    pub endpoint: Endpoint,
+    /// Sends the requests, `fetch` unless a test stubs it
+    pub transport: fn(ApiRequest) -> TransportFuture,
+}
+
+impl Default for AsyncRemoteMonolith {
+    fn default() -> Self {
+        Self::new(Endpoint::default(), fetch)
+    }
+}
 
 impl AsyncRemoteMonolith {
-    pub async fn purge_all_data(&self) -> Result<(), String> {
-        let _ = match post_message("api/admin/reset", None).await {
-            Ok(_) => {}
-            Err(e) => return Err(e),
-        };
-        Ok(())
+    pub fn new(endpoint: Endpoint, transport: fn(ApiRequest) -> TransportFuture) -> Self {
+        Self {
+            endpoint,
+            transport,
+        }
     }
 
-    pub async fn purge_all_events(&self) -> Result<(), String> {
-        let _ = match post_message("api/admin/purge-all-events", None).await {
-            Ok(_) => {}
-            Err(e) => return Err(e),
+    /// Keeps the session token and role in local storage, later calls send the token along
+    pub async fn login(&self, name: &str, password: &str) -> Result<(), OstError> {
+        let payload = serde_json::to_string(&ArgCredentials {
+            name: name.to_string(),
+            password: password.to_string(),
+        })?;
+        let response = self.post_message("api/auth/login", Some(payload)).await?;
+        let session: SessionToken = serde_json::from_str(&response)?;
+        LocalStorage::set(household_key(SESSION_ROLE_KEY), session.role)
+            .and_then(|_| LocalStorage::set(household_key(SESSION_TOKEN_KEY), session.token))
+            .map_err(|err| OstError::PersistenceIo(err.to_string()))
+    }
+
+    pub async fn logout(&self) -> Result<(), OstError> {
+        let result = self.post_message("api/auth/logout", None).await;
+        forget_session();
+        result.map(|_| ())
+    }
+
+    pub fn is_logged_in(&self) -> bool {
+        session_token().is_some()
+    }
+
+    /// The WebSocket streaming the backend's changes, none without a session.
+    /// Sockets can't carry headers, the token goes in the query.
+    pub fn changes_url(&self) -> Option<String> {
+        let token = session_token()?;
+        let url = self.api_url("api/changes");
+        if !self.endpoint.base_url.is_empty() {
+            // `http` turns into `ws` and `https` into `wss`
+            return Some(format!(
+                "{}?access_token={}",
+                url.replacen("http", "ws", 1),
+                token
+            ));
+        }
+
+        let location = gloo_utils::window().location();
+        let scheme = match location.protocol().ok()?.as_str() {
+            "https:" => "wss:",
+            _ => "ws:",
         };
-        Ok(())
+        Some(format!(
+            "{}//{}/{}?access_token={}",
+            scheme,
+            location.host().ok()?,
+            url.trim_start_matches('/'),
+            token
+        ))
+    }
+
+    /// The role the backend granted at login, the UI hides what it can't use
+    pub fn role(&self) -> Option<Role> {
+        session_token().and(LocalStorage::get(household_key(SESSION_ROLE_KEY)).ok())
     }
+}
 
-    pub async fn get_base_event_by_key(
+#[async_trait(?Send)]
+impl AsyncContext for AsyncRemoteMonolith {
+    async fn purge_all_data(&self) -> Result<(), OstError> {
+        let response = self.post_message("api/admin/reset", None).await?;
+        unpack_response(&response)
+    }
+
+    async fn purge_all_events(&self) -> Result<(), OstError> {
+        let response = self
+            .post_message("api/admin/purge-all-events", None)
+            .await?;
+        unpack_response(&response)
+    }
+
+    async fn get_base_event_by_key(
         &self,
         key: &crate::event_key::OstEventKey,
     ) -> Option<Box<dyn crate::event_base::EventBase>> {
@@ -92,21 +299,39 @@ impl AsyncRemoteMonolith {
                 Some(o) => Some(o),
                 None => None,
             },
+            ost_EventKey::Measurement => match self.get_measurement_by_key(key).await {
+                Some(o) => Some(o),
+                None => None,
+            },
         }
     }
 
-    pub async fn persons(&self) -> Vec<Box<dyn crate::person::Person>> {
+    /// Only the requested page travels over the wire
+    async fn query(&self, query: &EventQuery) -> Result<QueryResult, OstError> {
+        let url = format!("api/query?{}", query.to_query_string());
+        let remote_call_result: Result<String, OstError> = match self.get_string(&url).await {
+            Ok(o) => unpack_response(&o),
+            Err(e) => return Err(e),
+        };
+
+        match remote_call_result {
+            Ok(payload) => QueryResult::deserialize(&payload, &self.fetch_persons_impl().await),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn persons(&self) -> Vec<Box<dyn crate::person::Person>> {
         self.fetch_persons().await
     }
 
-    pub async fn add_person(&self, name: &str) -> Result<Box<dyn crate::person::Person>, String> {
+    async fn add_person(&self, name: &str) -> Result<Box<dyn crate::person::Person>, OstError> {
         let payload = serde_json::to_string(&ArgAddNameCommand {
             name: name.to_string(),
         })
         .unwrap();
-        let remote_call_result: Result<String, String> =
-            match post_message("api/persons", Some(payload)).await {
-                Ok(o) => serde_json::from_str(&o).unwrap(),
+        let remote_call_result: Result<String, OstError> =
+            match self.post_message("api/persons", Some(payload)).await {
+                Ok(o) => unpack_response(&o),
                 Err(e) => return Err(e),
             };
 
@@ -116,18 +341,18 @@ impl AsyncRemoteMonolith {
         }
     }
 
-    pub async fn add_fake_persons(&self, count: u32) -> Result<(), String> {
+    async fn add_fake_persons(&self, count: u32) -> Result<(), OstError> {
         let payload = serde_json::to_string(&ArgFakeCount { count }).unwrap();
-        match post_message("api/persons/add-fake-count", Some(payload)).await {
-            Ok(_) => Ok(()),
+        match self
+            .post_message("api/persons/add-fake-count", Some(payload))
+            .await
+        {
+            Ok(o) => unpack_response(&o),
             Err(e) => Err(e),
         }
     }
 
-    pub async fn modify_person(
-        &self,
-        person: &Box<dyn crate::person::Person>,
-    ) -> Result<(), String> {
+    async fn modify_person(&self, person: &Box<dyn crate::person::Person>) -> Result<(), OstError> {
         #[derive(Serialize)]
         struct ArgAModifyPerson {
             pub person_key: OstPersonKey,
@@ -140,9 +365,9 @@ impl AsyncRemoteMonolith {
         })
         .unwrap();
 
-        let remote_call_result: Result<(), String> =
-            match post_message("api/person", Some(message)).await {
-                Ok(o) => serde_json::from_str(&o).unwrap(),
+        let remote_call_result: Result<(), OstError> =
+            match self.post_message("api/person", Some(message)).await {
+                Ok(o) => unpack_response(&o),
                 Err(e) => return Err(e),
             };
 
@@ -152,62 +377,89 @@ impl AsyncRemoteMonolith {
         }
     }
 
-    pub async fn get_person_by_key(
+    async fn remove_person(
+        &self,
+        person: Box<dyn crate::person::Person>,
+        cascade: bool,
+    ) -> Result<(), OstError> {
+        let message = serde_json::to_string(&ArgRemovePerson {
+            person_key: person.key(),
+            cascade,
+        })
+        .unwrap();
+
+        match self.post_message("api/persons/remove", Some(message)).await {
+            Ok(o) => unpack_response(&o),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn merge_persons(
+        &self,
+        from: Box<dyn crate::person::Person>,
+        into: &Box<dyn crate::person::Person>,
+    ) -> Result<(), OstError> {
+        let message = serde_json::to_string(&ArgMergePersons {
+            from: from.key(),
+            into: into.key(),
+        })
+        .unwrap();
+
+        match self.post_message("api/persons/merge", Some(message)).await {
+            Ok(o) => unpack_response(&o),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn get_person_by_key(
         &self,
         key: crate::person_key::OstPersonKey,
     ) -> Option<Box<dyn Person>> {
         self.fetch_person_by_key(key).await
     }
 
-    pub async fn feedings_by(
+    async fn feedings_by(
         &self,
         person: &Box<dyn crate::person::Person>,
     ) -> Vec<Box<dyn crate::feed::Feed>> {
-        let person_impl_id: u32 = PersonImpl::from_person(person).id();
-
-        let persons = self.fetch_persons_impl().await;
-
-        let target_person = match persons.iter().find(|p| p.id() == person_impl_id) {
-            Some(p) => p.clone(),
-            None => return vec![],
+        let query = EventQuery {
+            person: Some(person.key()),
+            kinds: vec![ost_EventKey::Feed],
+            ..EventQuery::default()
         };
+        match self.query(&query).await {
+            Ok(result) => result
+                .items
+                .into_iter()
+                .filter_map(|item| match item {
+                    QueryItem::Feed(feed) => Some(feed),
+                    _ => None,
+                })
+                .collect(),
+            Err(_) => vec![],
+        }
+    }
 
-        let mut output: Vec<Box<dyn Feed>> = vec![];
-        self.fetch_feedings_persistence()
-            .await
-            .iter()
-            .filter(|f| f.person_id == person_impl_id)
-            .for_each(|feed_persistence| {
-                output.push(Box::new(RemoteFeedImpl {
-                    id: feed_persistence.id,
-                    breast_milk: feed_persistence.breast_milk,
-                    formula: feed_persistence.formula,
-                    solids: feed_persistence.solids,
-                    time_stamp: feed_persistence.time_stamp,
-                    person: target_person.clone(),
-                }))
-            });
-        output
-    }
-
-    pub async fn add_feeding(
+    async fn add_feeding(
         &self,
         person: &Box<dyn crate::person::Person>,
         breast_milk: u32,
         formula: u32,
         solids: u32,
-    ) -> Result<Box<dyn crate::feed::Feed>, String> {
+    ) -> Result<Box<dyn crate::feed::Feed>, OstError> {
         let payload = serde_json::to_string(&ArgAddFeeding {
             person_key: person.key(),
             breast_milk,
             formula,
             solids,
+            time_stamp: None,
+            replay_id: None,
         })
         .unwrap();
-        match post_message("api/feedings/add", Some(payload)).await {
+        match self.post_message("api/feedings/add", Some(payload)).await {
             Ok(server_message) => {
-                let unpacked_server_message: Result<String, String> =
-                    serde_json::from_str(&server_message).unwrap();
+                let unpacked_server_message: Result<String, OstError> =
+                    unpack_response(&server_message);
                 match unpacked_server_message {
                     Ok(server_response) => {
                         let de_serialized_feedings: FeedPersistence =
@@ -225,8 +477,12 @@ impl AsyncRemoteMonolith {
                                 breast_milk: de_serialized_feedings.breast_milk,
                                 formula: de_serialized_feedings.formula,
                                 solids: de_serialized_feedings.solids,
+                                nursing: de_serialized_feedings.nursing.clone(),
                             })),
-                            None => Err(format!("Person {:#?} not found!", person.key())),
+                            None => Err(OstError::NotFound(format!(
+                                "Person {:#?} not found!",
+                                person.key()
+                            ))),
                         }
                     }
                     Err(e) => Err(e),
@@ -236,36 +492,61 @@ impl AsyncRemoteMonolith {
         }
     }
 
-    pub async fn add_fake_feedings(&self, count: u32) -> Result<(), String> {
-        let payload = serde_json::to_string(&ArgFakeCount { count }).unwrap();
-        match post_message("api/feedings/add-fake-count", Some(payload)).await {
-            Ok(_) => Ok(()),
-            Err(e) => Err(e),
+    async fn add_nursing(
+        &self,
+        person: &Box<dyn Person>,
+        nursing: NursingSession,
+    ) -> Result<Box<dyn Feed>, OstError> {
+        let payload = serde_json::to_string(&ArgAddNursing {
+            person_key: person.key(),
+            nursing,
+            replay_id: None,
+        })
+        .unwrap();
+        let response = self
+            .post_message("api/feedings/add-nursing", Some(payload))
+            .await?;
+        let server_response: String = unpack_response(&response)?;
+        let persisted: FeedPersistence = serde_json::from_str(&server_response)?;
+        match self
+            .fetch_persons_impl()
+            .await
+            .into_iter()
+            .find(|p| p.id() == persisted.person_id)
+        {
+            Some(p) => Ok(Box::new(RemoteFeedImpl::from_persistence(persisted, p))),
+            None => Err(OstError::NotFound(format!(
+                "Person {:#?} not found!",
+                person.key()
+            ))),
         }
     }
 
-    pub async fn modify_feeding(&self, feed: &Box<dyn crate::feed::Feed>) -> Result<(), String> {
-        #[derive(Serialize)]
-        struct ArgAModifyFeeding {
-            pub event_key: OstEventKey,
-            pub time_stamp: DateTime<Utc>,
-            pub breast_milk: u32,
-            pub formula: u32,
-            pub solids: u32,
+    async fn add_fake_feedings(&self, count: u32) -> Result<(), OstError> {
+        let payload = serde_json::to_string(&ArgFakeCount { count }).unwrap();
+        match self
+            .post_message("api/feedings/add-fake-count", Some(payload))
+            .await
+        {
+            Ok(o) => unpack_response(&o),
+            Err(e) => Err(e),
         }
+    }
 
+    async fn modify_feeding(&self, feed: &Box<dyn crate::feed::Feed>) -> Result<(), OstError> {
         let message = serde_json::to_string(&ArgAModifyFeeding {
             event_key: feed.key(),
             time_stamp: feed.time_stamp().clone(),
             breast_milk: feed.breast_milk(),
             formula: feed.formula(),
             solids: feed.solids(),
+            nursing: feed.nursing(),
         })
         .unwrap();
 
-        let remote_call_result: Result<(), String> =
-            match post_message("api/feed", Some(message)).await {
-                Ok(_) => Ok(()), // Server is returning a ()
+        let remote_call_result: Result<(), OstError> =
+            match self.post_message("api/feed", Some(message)).await {
+                Ok(o) => unpack_response(&o),
                 Err(e) => return Err(e),
             };
 
@@ -275,18 +556,21 @@ impl AsyncRemoteMonolith {
         }
     }
 
-    pub async fn remove_feeding(&self, feed: Box<dyn crate::feed::Feed>) -> Result<(), String> {
+    async fn remove_feeding(&self, feed: Box<dyn crate::feed::Feed>) -> Result<(), OstError> {
         let event_key = serde_json::to_string(&ArgEventKey {
             event_key: feed.key(),
         })
         .unwrap();
-        match post_message("api/feedings/remove", Some(event_key)).await {
-            Ok(_) => Ok(()),
+        match self
+            .post_message("api/feedings/remove", Some(event_key))
+            .await
+        {
+            Ok(o) => unpack_response(&o),
             Err(e) => Err(e),
         }
     }
 
-    pub async fn get_feeding_by_key(
+    async fn get_feeding_by_key(
         &self,
         key: &crate::event_key::OstEventKey,
     ) -> Option<Box<dyn crate::feed::Feed>> {
@@ -295,7 +579,7 @@ impl AsyncRemoteMonolith {
         })
         .unwrap();
 
-        match post_message("api/feedings", Some(event_key)).await {
+        match self.post_message("api/feedings", Some(event_key)).await {
             Ok(o) => {
                 let is_some: Option<String> = serde_json::from_str(&o).unwrap();
                 match is_some {
@@ -315,6 +599,7 @@ impl AsyncRemoteMonolith {
                                 breast_milk: event_persistence.breast_milk,
                                 formula: event_persistence.formula,
                                 solids: event_persistence.solids,
+                                nursing: event_persistence.nursing.clone(),
                             })),
                             None => None,
                         }
@@ -326,54 +611,49 @@ impl AsyncRemoteMonolith {
         }
     }
 
-    pub async fn expulsions(&self) -> Vec<Box<dyn crate::expulsion::Expulsion>> {
+    async fn expulsions(&self) -> Vec<Box<dyn crate::expulsion::Expulsion>> {
         let p = self.fetch_persons_impl().await;
         self.fetch_expulsions(&p).await
     }
 
-    pub async fn expulsions_by(
+    async fn expulsions_by(
         &self,
         person: &Box<dyn crate::person::Person>,
     ) -> Vec<Box<dyn Expulsion>> {
-        let person_impl_id: u32 = PersonImpl::from_person(person).id();
-
-        let persons = self.fetch_persons_impl().await;
-
-        let target_person = match persons.iter().find(|p| p.id() == person_impl_id) {
-            Some(p) => p.clone(),
-            None => return vec![],
+        let query = EventQuery {
+            person: Some(person.key()),
+            kinds: vec![ost_EventKey::Expulsion],
+            ..EventQuery::default()
         };
+        match self.query(&query).await {
+            Ok(result) => result
+                .items
+                .into_iter()
+                .filter_map(|item| match item {
+                    QueryItem::Expulsion(expulsion) => Some(expulsion),
+                    _ => None,
+                })
+                .collect(),
+            Err(_) => vec![],
+        }
+    }
 
-        let mut output: Vec<Box<dyn Expulsion>> = vec![];
-        self.fetch_expulsions_persistence()
-            .await
-            .iter()
-            .filter(|f| f.person_id == person_impl_id)
-            .for_each(|expulsion_persistence| {
-                output.push(Box::new(RemoteExpulsionImpl {
-                    id: expulsion_persistence.id,
-                    time_stamp: expulsion_persistence.time_stamp,
-                    person: target_person.clone(),
-                    degree: expulsion_persistence.degree.clone(),
-                }))
-            });
-        output
-    }
-
-    pub async fn add_expulsion(
+    async fn add_expulsion(
         &self,
         person: &Box<dyn crate::person::Person>,
         degree: crate::expulsion::ExpulsionDegree,
-    ) -> Result<Box<dyn crate::expulsion::Expulsion>, String> {
+    ) -> Result<Box<dyn crate::expulsion::Expulsion>, OstError> {
         let payload = serde_json::to_string(&ArgAddExpulsion {
             person_key: person.key(),
             expulsion_degree: degree,
+            time_stamp: None,
+            replay_id: None,
         })
         .unwrap();
-        match post_message("api/expulsions/add", Some(payload)).await {
+        match self.post_message("api/expulsions/add", Some(payload)).await {
             Ok(server_message) => {
-                let unpacked_server_message: Result<String, String> =
-                    serde_json::from_str(&server_message).unwrap();
+                let unpacked_server_message: Result<String, OstError> =
+                    unpack_response(&server_message);
                 match unpacked_server_message {
                     Ok(server_response) => {
                         let de_serialized_expulsion: ExpulsionPersistence =
@@ -390,7 +670,10 @@ impl AsyncRemoteMonolith {
                                 person: p.clone(),
                                 degree: de_serialized_expulsion.degree,
                             })),
-                            None => Err(format!("Person {:#?} not found!", person.key())),
+                            None => Err(OstError::NotFound(format!(
+                                "Person {:#?} not found!",
+                                person.key()
+                            ))),
                         }
                     }
                     Err(e) => Err(e),
@@ -400,25 +683,21 @@ impl AsyncRemoteMonolith {
         }
     }
 
-    pub async fn add_fake_expulsions(&self, count: u32) -> Result<(), String> {
+    async fn add_fake_expulsions(&self, count: u32) -> Result<(), OstError> {
         let payload = serde_json::to_string(&ArgFakeCount { count }).unwrap();
-        match post_message("api/expulsions/add-fake-count", Some(payload)).await {
-            Ok(_) => Ok(()),
+        match self
+            .post_message("api/expulsions/add-fake-count", Some(payload))
+            .await
+        {
+            Ok(o) => unpack_response(&o),
             Err(e) => Err(e),
         }
     }
 
-    pub async fn modify_expulsion(
+    async fn modify_expulsion(
         &self,
         expulsion: &Box<dyn crate::expulsion::Expulsion>,
-    ) -> Result<(), String> {
-        #[derive(Serialize)]
-        struct ArgModifyExpulsion {
-            pub event_key: OstEventKey,
-            pub time_stamp: DateTime<Utc>,
-            pub expulsion_degree: ExpulsionDegree,
-        }
-
+    ) -> Result<(), OstError> {
         let message = serde_json::to_string(&ArgModifyExpulsion {
             event_key: expulsion.key(),
             time_stamp: expulsion.time_stamp().clone(),
@@ -426,9 +705,9 @@ impl AsyncRemoteMonolith {
         })
         .unwrap();
 
-        let remote_call_result: Result<(), String> =
-            match post_message("api/expulsion", Some(message)).await {
-                Ok(_) => Ok(()), // Server is returning a ()
+        let remote_call_result: Result<(), OstError> =
+            match self.post_message("api/expulsion", Some(message)).await {
+                Ok(o) => unpack_response(&o),
                 Err(e) => return Err(e),
             };
 
@@ -438,21 +717,24 @@ impl AsyncRemoteMonolith {
         }
     }
 
-    pub async fn remove_expulsion(
+    async fn remove_expulsion(
         &self,
         expulsion: Box<dyn crate::expulsion::Expulsion>,
-    ) -> Result<(), String> {
+    ) -> Result<(), OstError> {
         let event_key = serde_json::to_string(&ArgEventKey {
             event_key: expulsion.key(),
         })
         .unwrap();
-        match post_message("api/expulsions/remove", Some(event_key)).await {
-            Ok(_) => Ok(()),
+        match self
+            .post_message("api/expulsions/remove", Some(event_key))
+            .await
+        {
+            Ok(o) => unpack_response(&o),
             Err(e) => Err(e),
         }
     }
 
-    pub async fn get_expulsion_by_key(
+    async fn get_expulsion_by_key(
         &self,
         key: &crate::event_key::OstEventKey,
     ) -> Option<Box<dyn crate::expulsion::Expulsion>> {
@@ -461,7 +743,7 @@ impl AsyncRemoteMonolith {
         })
         .unwrap();
 
-        match post_message("api/expulsions", Some(event_key)).await {
+        match self.post_message("api/expulsions", Some(event_key)).await {
             Ok(o) => {
                 let is_some: Option<String> = serde_json::from_str(&o).unwrap();
                 match is_some {
@@ -490,51 +772,46 @@ impl AsyncRemoteMonolith {
         }
     }
 
-    pub async fn events(&self) -> Vec<Box<dyn crate::event::Event>> {
+    async fn events(&self) -> Vec<Box<dyn crate::event::Event>> {
         let p = self.fetch_persons_impl().await;
         self.fetch_events(&p).await
     }
 
-    pub async fn events_by(&self, person: &Box<dyn crate::person::Person>) -> Vec<Box<dyn Event>> {
-        let person_impl_id: u32 = PersonImpl::from_person(person).id();
-
-        let persons = self.fetch_persons_impl().await;
-
-        let target_person = match persons.iter().find(|p| p.id() == person_impl_id) {
-            Some(p) => p.clone(),
-            None => return vec![],
+    async fn events_by(&self, person: &Box<dyn crate::person::Person>) -> Vec<Box<dyn Event>> {
+        let query = EventQuery {
+            person: Some(person.key()),
+            kinds: vec![ost_EventKey::Event],
+            ..EventQuery::default()
         };
+        match self.query(&query).await {
+            Ok(result) => result
+                .items
+                .into_iter()
+                .filter_map(|item| match item {
+                    QueryItem::Event(event) => Some(event),
+                    _ => None,
+                })
+                .collect(),
+            Err(_) => vec![],
+        }
+    }
 
-        let mut output: Vec<Box<dyn Event>> = vec![];
-        self.fetch_events_persistence()
-            .await
-            .iter()
-            .filter(|f| f.person_id == person_impl_id)
-            .for_each(|event_persistence| {
-                output.push(Box::new(RemoteEventImpl {
-                    id: event_persistence.id,
-                    time_stamp: event_persistence.time_stamp,
-                    person: target_person.clone(),
-                    event: event_persistence.event.clone(),
-                }))
-            });
-        output
-    }
-
-    pub async fn add_event(
+    async fn add_event(
         &self,
         person: &Box<dyn crate::person::Person>,
         event_type: crate::event::EventType,
-    ) -> Result<Box<dyn crate::event::Event>, String> {
+    ) -> Result<Box<dyn crate::event::Event>, OstError> {
         let payload = serde_json::to_string(&ArgAddEvent {
             person_key: person.key(),
             new_event: event_type,
+            time_stamp: None,
+            replay_id: None,
         })
         .unwrap();
-        match post_message("api/events/add", Some(payload)).await {
+        match self.post_message("api/events/add", Some(payload)).await {
             Ok(server_message) => {
-                let unpacked_server_message: Result<String, String> =
-                    serde_json::from_str(&server_message).unwrap();
+                let unpacked_server_message: Result<String, OstError> =
+                    unpack_response(&server_message);
                 match unpacked_server_message {
                     Ok(server_response) => {
                         let de_serialized_event: EventPersistence =
@@ -551,7 +828,10 @@ impl AsyncRemoteMonolith {
                                 person: p.clone(),
                                 event: de_serialized_event.event,
                             })),
-                            None => Err(format!("Person {:#?} not found!", person.key())),
+                            None => Err(OstError::NotFound(format!(
+                                "Person {:#?} not found!",
+                                person.key()
+                            ))),
                         }
                     }
                     Err(e) => Err(e),
@@ -561,22 +841,18 @@ impl AsyncRemoteMonolith {
         }
     }
 
-    pub async fn add_fake_events(&self, count: u32) -> Result<(), String> {
+    async fn add_fake_events(&self, count: u32) -> Result<(), OstError> {
         let payload = serde_json::to_string(&ArgFakeCount { count }).unwrap();
-        match post_message("api/events/add-fake-count", Some(payload)).await {
-            Ok(_) => Ok(()),
+        match self
+            .post_message("api/events/add-fake-count", Some(payload))
+            .await
+        {
+            Ok(o) => unpack_response(&o),
             Err(e) => Err(e),
         }
     }
 
-    pub async fn modify_event(&self, event: &Box<dyn crate::event::Event>) -> Result<(), String> {
-        #[derive(Serialize)]
-        struct ArgAModifyEvent {
-            pub event_key: OstEventKey,
-            pub time_stamp: DateTime<Utc>,
-            pub event_payload: EventType,
-        }
-
+    async fn modify_event(&self, event: &Box<dyn crate::event::Event>) -> Result<(), OstError> {
         let message = serde_json::to_string(&ArgAModifyEvent {
             event_key: event.key(),
             time_stamp: event.time_stamp().clone(),
@@ -584,9 +860,9 @@ impl AsyncRemoteMonolith {
         })
         .unwrap();
 
-        let remote_call_result: Result<(), String> =
-            match post_message("api/event", Some(message)).await {
-                Ok(_) => Ok(()), // Server is returning a ()
+        let remote_call_result: Result<(), OstError> =
+            match self.post_message("api/event", Some(message)).await {
+                Ok(o) => unpack_response(&o),
                 Err(e) => return Err(e),
             };
 
@@ -596,18 +872,21 @@ impl AsyncRemoteMonolith {
         }
     }
 
-    pub async fn remove_event(&self, event: Box<dyn crate::event::Event>) -> Result<(), String> {
+    async fn remove_event(&self, event: Box<dyn crate::event::Event>) -> Result<(), OstError> {
         let event_key = serde_json::to_string(&ArgEventKey {
             event_key: event.key(),
         })
         .unwrap();
-        match post_message("api/events/remove", Some(event_key)).await {
-            Ok(_) => Ok(()),
+        match self
+            .post_message("api/events/remove", Some(event_key))
+            .await
+        {
+            Ok(o) => unpack_response(&o),
             Err(e) => Err(e),
         }
     }
 
-    pub async fn get_event_by_key(
+    async fn get_event_by_key(
         &self,
         key: &crate::event_key::OstEventKey,
     ) -> Option<Box<dyn crate::event::Event>> {
@@ -616,7 +895,7 @@ impl AsyncRemoteMonolith {
         })
         .unwrap();
 
-        match post_message("api/events", Some(event_key)).await {
+        match self.post_message("api/events", Some(event_key)).await {
             Ok(o) => {
                 let is_some: Option<String> = serde_json::from_str(&o).unwrap();
                 match is_some {
@@ -645,10 +924,290 @@ impl AsyncRemoteMonolith {
         }
     }
 
-    pub async fn feedings(&self) -> Vec<Box<dyn crate::feed::Feed>> {
+    async fn feedings(&self) -> Vec<Box<dyn crate::feed::Feed>> {
         let p = self.fetch_persons_impl().await;
         self.fetch_feedings(&p).await
     }
+
+    async fn measurements(&self) -> Vec<Box<dyn Measurement>> {
+        let persons = self.fetch_persons_impl().await;
+        self.fetch_measurements_persistence()
+            .await
+            .into_iter()
+            .filter_map(|persisted| {
+                let person = persons.iter().find(|p| p.id == persisted.person_id)?;
+                Some(Box::new(RemoteMeasurementImpl::from_persistence(
+                    persisted,
+                    person.clone(),
+                )) as Box<dyn Measurement>)
+            })
+            .collect()
+    }
+
+    async fn measurements_by(&self, person: &Box<dyn Person>) -> Vec<Box<dyn Measurement>> {
+        let query = EventQuery {
+            person: Some(person.key()),
+            kinds: vec![ost_EventKey::Measurement],
+            ..EventQuery::default()
+        };
+        match self.query(&query).await {
+            Ok(result) => result
+                .items
+                .into_iter()
+                .filter_map(|item| match item {
+                    QueryItem::Measurement(measurement) => Some(measurement),
+                    _ => None,
+                })
+                .collect(),
+            Err(_) => vec![],
+        }
+    }
+
+    async fn add_measurement(
+        &self,
+        person: &Box<dyn Person>,
+        measures: Measures,
+    ) -> Result<Box<dyn Measurement>, OstError> {
+        let payload = serde_json::to_string(&ArgAddMeasurement {
+            person_key: person.key(),
+            measures,
+            time_stamp: None,
+            replay_id: None,
+        })
+        .unwrap();
+        let response = self
+            .post_message("api/measurements/add", Some(payload))
+            .await?;
+        let server_response: String = unpack_response(&response)?;
+        let persisted: MeasurementPersistence = serde_json::from_str(&server_response)?;
+        match self
+            .fetch_persons_impl()
+            .await
+            .into_iter()
+            .find(|p| p.id() == persisted.person_id)
+        {
+            Some(p) => Ok(Box::new(RemoteMeasurementImpl::from_persistence(
+                persisted, p,
+            ))),
+            None => Err(OstError::NotFound(format!(
+                "Person {:#?} not found!",
+                person.key()
+            ))),
+        }
+    }
+
+    async fn add_fake_measurements(&self, count: u32) -> Result<(), OstError> {
+        let payload = serde_json::to_string(&ArgFakeCount { count }).unwrap();
+        let response = self
+            .post_message("api/measurements/add-fake-count", Some(payload))
+            .await?;
+        unpack_response(&response)
+    }
+
+    async fn modify_measurement(&self, measurement: &Box<dyn Measurement>) -> Result<(), OstError> {
+        let message = serde_json::to_string(&ArgModifyMeasurement {
+            event_key: measurement.key(),
+            time_stamp: *measurement.time_stamp(),
+            measures: measurement.measures(),
+        })
+        .unwrap();
+        let response = self.post_message("api/measurement", Some(message)).await?;
+        unpack_response(&response)
+    }
+
+    async fn remove_measurement(&self, measurement: Box<dyn Measurement>) -> Result<(), OstError> {
+        let event_key = serde_json::to_string(&ArgEventKey {
+            event_key: measurement.key(),
+        })
+        .unwrap();
+        let response = self
+            .post_message("api/measurements/remove", Some(event_key))
+            .await?;
+        unpack_response(&response)
+    }
+
+    async fn get_measurement_by_key(&self, key: &OstEventKey) -> Option<Box<dyn Measurement>> {
+        let event_key = serde_json::to_string(&ArgEventKey { event_key: *key }).unwrap();
+        let response = self
+            .post_message("api/measurements", Some(event_key))
+            .await
+            .ok()?;
+        let payload: Option<String> = serde_json::from_str(&response).ok()?;
+        let persisted: MeasurementPersistence = serde_json::from_str(&payload?).ok()?;
+        let person = self
+            .fetch_persons_impl()
+            .await
+            .into_iter()
+            .find(|p| p.id() == persisted.person_id)?;
+        Some(Box::new(RemoteMeasurementImpl::from_persistence(
+            persisted, person,
+        )))
+    }
+}
+
+impl AsyncRemoteMonolith {
+    /// Sends a change recorded offline, additions keep the time they were recorded at.
+    /// Returns the key the backend saved an addition as.
+    pub async fn replay(&self, pending: &PendingMutation) -> Result<Option<OstEventKey>, OstError> {
+        let key = match pending.mutation.target() {
+            Some(Target::Saved(key)) => key,
+            Some(Target::Queued(id)) => {
+                return Err(OstError::NotFound(format!("Entry {} was never saved", id)))
+            }
+            None => return self.replay_addition(pending).await.map(Some),
+        };
+        let event_key = || serde_json::to_string(&ArgEventKey { event_key: key });
+        let (url, payload) = match &pending.mutation {
+            Mutation::ModifyFeeding {
+                breast_milk,
+                formula,
+                solids,
+                nursing,
+                time_stamp,
+                ..
+            } => (
+                "api/feed",
+                serde_json::to_string(&ArgAModifyFeeding {
+                    event_key: key,
+                    time_stamp: *time_stamp,
+                    breast_milk: *breast_milk,
+                    formula: *formula,
+                    solids: *solids,
+                    nursing: nursing.clone(),
+                })?,
+            ),
+            Mutation::RemoveFeeding { .. } => ("api/feedings/remove", event_key()?),
+            Mutation::ModifyExpulsion {
+                degree, time_stamp, ..
+            } => (
+                "api/expulsion",
+                serde_json::to_string(&ArgModifyExpulsion {
+                    event_key: key,
+                    time_stamp: *time_stamp,
+                    expulsion_degree: degree.clone(),
+                })?,
+            ),
+            Mutation::RemoveExpulsion { .. } => ("api/expulsions/remove", event_key()?),
+            Mutation::ModifyEvent {
+                event, time_stamp, ..
+            } => (
+                "api/event",
+                serde_json::to_string(&ArgAModifyEvent {
+                    event_key: key,
+                    time_stamp: *time_stamp,
+                    event_payload: event.clone(),
+                })?,
+            ),
+            Mutation::RemoveEvent { .. } => ("api/events/remove", event_key()?),
+            Mutation::ModifyMeasurement {
+                measures,
+                time_stamp,
+                ..
+            } => (
+                "api/measurement",
+                serde_json::to_string(&ArgModifyMeasurement {
+                    event_key: key,
+                    time_stamp: *time_stamp,
+                    measures: *measures,
+                })?,
+            ),
+            Mutation::RemoveMeasurement { .. } => ("api/measurements/remove", event_key()?),
+            _ => {
+                return Err(OstError::Validation(
+                    "Not a modification or removal".to_string(),
+                ))
+            }
+        };
+        let response = self.post_message(url, Some(payload)).await?;
+        unpack_response::<()>(&response).map(|_| None)
+    }
+
+    async fn replay_addition(&self, pending: &PendingMutation) -> Result<OstEventKey, OstError> {
+        #[derive(Deserialize)]
+        struct Saved {
+            id: u32,
+        }
+
+        let time_stamp = Some(pending.time_stamp);
+        let (url, payload) = match &pending.mutation {
+            Mutation::AddFeeding {
+                person_key,
+                breast_milk,
+                formula,
+                solids,
+            } => (
+                "api/feedings/add",
+                serde_json::to_string(&ArgAddFeeding {
+                    person_key: *person_key,
+                    breast_milk: *breast_milk,
+                    formula: *formula,
+                    solids: *solids,
+                    time_stamp,
+                    replay_id: pending.replay_id(),
+                })?,
+            ),
+            Mutation::AddNursing {
+                person_key,
+                nursing,
+            } => (
+                "api/feedings/add-nursing",
+                serde_json::to_string(&ArgAddNursing {
+                    person_key: *person_key,
+                    nursing: nursing.clone(),
+                    replay_id: pending.replay_id(),
+                })?,
+            ),
+            Mutation::AddExpulsion { person_key, degree } => (
+                "api/expulsions/add",
+                serde_json::to_string(&ArgAddExpulsion {
+                    person_key: *person_key,
+                    expulsion_degree: degree.clone(),
+                    time_stamp,
+                    replay_id: pending.replay_id(),
+                })?,
+            ),
+            Mutation::AddEvent { person_key, event } => (
+                "api/events/add",
+                serde_json::to_string(&ArgAddEvent {
+                    person_key: *person_key,
+                    new_event: event.clone(),
+                    time_stamp,
+                    replay_id: pending.replay_id(),
+                })?,
+            ),
+            Mutation::AddMeasurement {
+                person_key,
+                measures,
+            } => (
+                "api/measurements/add",
+                serde_json::to_string(&ArgAddMeasurement {
+                    person_key: *person_key,
+                    measures: *measures,
+                    time_stamp,
+                    replay_id: pending.replay_id(),
+                })?,
+            ),
+            _ => return Err(OstError::Validation("Not an addition".to_string())),
+        };
+        let response = self.post_message(url, Some(payload)).await?;
+        let saved: Saved = serde_json::from_str(&unpack_response::<String>(&response)?)?;
+        Ok(OstEventKey {
+            t: pending.mutation.entry_type(),
+            id: saved.id,
+        })
+    }
+
+    /// Everything the backend holds, failing when it can't be reached
+    pub(crate) async fn snapshot(&self) -> Result<ContextPersistence, OstError> {
+        Ok(ContextPersistence {
+            persons: self.fetch_v2("api/v2/persons").await?,
+            feeds: self.fetch_v2("api/v2/feedings").await?,
+            expulsions: self.fetch_v2("api/v2/expulsions").await?,
+            events: self.fetch_v2("api/v2/events").await?,
+            measurements: self.fetch_v2("api/v2/measurements").await?,
+            ..ContextPersistence::default()
+        })
+    }
 }
 
 impl AsyncRemoteMonolith {
@@ -656,54 +1215,23 @@ impl AsyncRemoteMonolith {
         &self,
         key: crate::person_key::OstPersonKey,
     ) -> Option<Box<dyn Person>> {
-        if let Ok(serialized_persons) = get_string("api/persons").await {
-            let vec_of_serialized_persons: Vec<String> =
-                serde_json::from_str(&serialized_persons).unwrap();
-
-            #[allow(clippy::needless_collect)]
-            let available_persons: Vec<PersonImpl> = vec_of_serialized_persons
-                .iter()
-                .map(|s| serde_json::from_str(s).unwrap())
-                .collect();
-
-            match available_persons.iter().find(|f| f.id() == key.id) {
-                Some(p) => Some(Box::new(p.clone())),
-                None => None,
-            }
-        } else {
-            None
-        }
+        self.fetch_persons_impl()
+            .await
+            .into_iter()
+            .find(|f| f.id() == key.id)
+            .map(|p| Box::new(p) as Box<dyn Person>)
     }
 
     async fn fetch_persons(&self) -> Vec<Box<dyn Person>> {
-        if let Ok(serialized_persons) = get_string("api/persons").await {
-            let vec_of_serialized_persons: Vec<String> =
-                serde_json::from_str(&serialized_persons).unwrap();
-
-            #[allow(clippy::needless_collect)]
-            let reconstructed: Vec<Box<dyn Person>> = vec_of_serialized_persons
-                .iter()
-                .map(|s| person_deserialize(s).unwrap())
-                .collect();
-            reconstructed
-        } else {
-            vec![]
-        }
+        self.fetch_persons_impl()
+            .await
+            .into_iter()
+            .map(|p| Box::new(p) as Box<dyn Person>)
+            .collect()
     }
 
     async fn fetch_persons_impl(&self) -> Vec<PersonImpl> {
-        if let Ok(serialized_persons) = get_string("api/persons").await {
-            let vec_of_serialized_persons: Vec<String> =
-                serde_json::from_str(&serialized_persons).unwrap();
-
-            let reconstructed: Vec<PersonImpl> = vec_of_serialized_persons
-                .iter()
-                .map(|s| serde_json::from_str(s).unwrap())
-                .collect();
-            reconstructed
-        } else {
-            vec![]
-        }
+        self.fetch_v2_list("api/v2/persons").await
     }
 
     async fn fetch_feedings(&self, persons: &[PersonImpl]) -> Vec<Box<dyn Feed>> {
@@ -725,6 +1253,7 @@ impl AsyncRemoteMonolith {
                         breast_milk: feed_persistence.breast_milk,
                         formula: feed_persistence.formula,
                         solids: feed_persistence.solids,
+                        nursing: feed_persistence.nursing.clone(),
                         time_stamp: feed_persistence.time_stamp,
                         person: person_impl,
                     }
@@ -737,17 +1266,7 @@ impl AsyncRemoteMonolith {
     }
 
     async fn fetch_feedings_persistence(&self) -> Vec<FeedPersistence> {
-        if let Ok(serialized_feedings) = get_string("api/feedings").await {
-            let vec_of_serialized_feedings: Vec<String> =
-                serde_json::from_str(&serialized_feedings).unwrap();
-            let deserialized_feedings: Vec<FeedPersistence> = vec_of_serialized_feedings
-                .iter()
-                .map(|s| serde_json::from_str(s).unwrap())
-                .collect();
-            deserialized_feedings
-        } else {
-            vec![]
-        }
+        self.fetch_v2_list("api/v2/feedings").await
     }
 
     async fn fetch_expulsions(&self, persons: &[PersonImpl]) -> Vec<Box<dyn Expulsion>> {
@@ -779,17 +1298,11 @@ impl AsyncRemoteMonolith {
     }
 
     async fn fetch_expulsions_persistence(&self) -> Vec<ExpulsionPersistence> {
-        if let Ok(serialized_feedings) = get_string("api/expulsions").await {
-            let vec_of_serialized_expulsions: Vec<String> =
-                serde_json::from_str(&serialized_feedings).unwrap();
-            let deserialized_expulsions: Vec<ExpulsionPersistence> = vec_of_serialized_expulsions
-                .iter()
-                .map(|s| serde_json::from_str(s).unwrap())
-                .collect();
-            deserialized_expulsions
-        } else {
-            vec![]
-        }
+        self.fetch_v2_list("api/v2/expulsions").await
+    }
+
+    async fn fetch_measurements_persistence(&self) -> Vec<MeasurementPersistence> {
+        self.fetch_v2_list("api/v2/measurements").await
     }
 
     async fn fetch_events(&self, persons: &[PersonImpl]) -> Vec<Box<dyn Event>> {
@@ -821,129 +1334,196 @@ impl AsyncRemoteMonolith {
     }
 
     async fn fetch_events_persistence(&self) -> Vec<EventPersistence> {
-        if let Ok(serialized_feedings) = get_string("api/events").await {
-            let vec_of_serialized_events: Vec<String> =
-                serde_json::from_str(&serialized_feedings).unwrap();
-            let deserialized_events: Vec<EventPersistence> = vec_of_serialized_events
-                .iter()
-                .map(|s| serde_json::from_str(s).unwrap())
-                .collect();
-            deserialized_events
-        } else {
-            vec![]
-        }
+        self.fetch_v2_list("api/v2/events").await
     }
 }
 
+use gloo_storage::{LocalStorage, Storage};
 use wasm_bindgen::{JsCast, JsValue};
 use wasm_bindgen_futures::JsFuture;
 use web_sys::{Request, RequestInit, RequestMode, Response};
 
-/// Consult the following for an example of the fetch api by the team behind web_sys:
-/// https://rustwasm.github.io/wasm-bindgen/examples/fetch.html
-pub async fn get_string(url: &'static str) -> Result<String, String> {
-    let mut opts = RequestInit::new();
-    opts.method("GET");
-    opts.mode(RequestMode::Cors);
+const SESSION_TOKEN_KEY: &str = "ost_session_token";
+const SESSION_ROLE_KEY: &str = "ost_session_role";
 
-    let request = match Request::new_with_str_and_init(url, &opts) {
-        Ok(r) => r,
-        Err(e) => {
-            return Err(e
-                .as_string()
-                .unwrap_or_else(|| "Unknown error constructing request".to_string()))
+impl AsyncRemoteMonolith {
+    /// Api calls go to the household the page was loaded from, on the endpoint's host if it has one
+    fn api_url(&self, url: &str) -> String {
+        let path = match household() {
+            Some(id) => format!("/h/{}/{}", id, url),
+            None => url.to_string(),
+        };
+        match self.endpoint.base_url.is_empty() {
+            true => path,
+            false => format!(
+                "{}/{}",
+                self.endpoint.base_url.trim_end_matches('/'),
+                path.trim_start_matches('/')
+            ),
         }
-    };
+    }
 
-    let window = gloo_utils::window();
-    let resp_value = match JsFuture::from(window.fetch_with_request(&request)).await {
-        Ok(o) => o,
-        Err(e) => {
-            return Err(e
-                .as_string()
-                .unwrap_or_else(|| "Unknown error calling window fetch with request".to_string()))
+    pub async fn get_string(&self, url: &str) -> Result<String, OstError> {
+        self.call(Method::Get, url, None).await
+    }
+
+    pub async fn post_message(
+        &self,
+        url: &str,
+        payload: Option<String>,
+    ) -> Result<String, OstError> {
+        self.call(Method::Post, url, payload).await
+    }
+
+    /// Sends the endpoint's headers and the session along, and checks the answer for a lost session
+    async fn call(
+        &self,
+        method: Method,
+        url: &str,
+        body: Option<String>,
+    ) -> Result<String, OstError> {
+        let mut headers = self.endpoint.headers.clone();
+        if body.is_some() {
+            headers.push(("content-type".to_string(), "application/json".to_string()));
+        }
+        if let Some(token) = session_token() {
+            headers.push(("authorization".to_string(), format!("Bearer {}", token)));
         }
-    };
 
-    let resp: Response = resp_value.dyn_into().unwrap();
+        let response = (self.transport)(ApiRequest {
+            method,
+            url: self.api_url(url),
+            headers,
+            body,
+        })
+        .await?;
+        check_session(&response)?;
+        Ok(response.body)
+    }
 
-    match resp.text() {
-        Ok(o) => {
-            let text = match JsFuture::from(o).await {
-                Ok(t) => t,
-                Err(e) => {
-                    return Err(e
-                        .as_string()
-                        .unwrap_or_else(|| "Unknown error unpacking response".to_string()))
-                }
-            };
-            Ok(text.as_string().unwrap())
+    /// `/api/v2` sends entities as plain json, an unreachable backend reads as empty
+    async fn fetch_v2_list<T: serde::de::DeserializeOwned>(&self, url: &str) -> Vec<T> {
+        self.fetch_v2(url).await.unwrap_or_default()
+    }
+
+    async fn fetch_v2<T: serde::de::DeserializeOwned>(
+        &self,
+        url: &str,
+    ) -> Result<Vec<T>, OstError> {
+        let payload = self.get_string(url).await?;
+        Ok(serde_json::from_str(&payload)?)
+    }
+}
+
+/// The household in `/h/{household}/...`, none when the app is served from the root.
+/// Native builds have no page, their calls go to the root.
+pub fn household() -> Option<String> {
+    if !cfg!(target_arch = "wasm32") {
+        return None;
+    }
+    let path = gloo_utils::window().location().pathname().ok()?;
+    let id = path.strip_prefix("/h/")?.split('/').next()?;
+    (!id.is_empty()).then(|| id.to_string())
+}
+
+/// Households share the origin and so the local storage, each keeps its own session and data
+pub(crate) fn household_key(key: &str) -> String {
+    match household() {
+        Some(id) => format!("{}@{}", key, id),
+        None => key.to_string(),
+    }
+}
+
+/// Native builds have no local storage and so no session
+fn session_token() -> Option<String> {
+    if !cfg!(target_arch = "wasm32") {
+        return None;
+    }
+    LocalStorage::get(household_key(SESSION_TOKEN_KEY)).ok()
+}
+
+fn forget_session() {
+    if !cfg!(target_arch = "wasm32") {
+        return;
+    }
+    LocalStorage::delete(household_key(SESSION_TOKEN_KEY));
+    LocalStorage::delete(household_key(SESSION_ROLE_KEY));
+}
+
+/// A 401 means the session is gone, the token is dropped so the UI asks for a login again.
+/// A 403 is the role falling short, both carry a bare `OstError`.
+/// A proxy answering for a backend that is down counts as unreachable.
+fn check_session(response: &ApiResponse) -> Result<(), OstError> {
+    match response.status {
+        502..=504 => Err(OstError::Transport(format!(
+            "Backend unreachable, status {}",
+            response.status
+        ))),
+        401 => {
+            forget_session();
+            Err(serde_json::from_str(&response.body)
+                .unwrap_or_else(|_| OstError::Unauthorized("Log in to use the api".to_string())))
         }
-        Err(e) => Err(e
-            .as_string()
-            .unwrap_or_else(|| "Unknown error unpacking response".to_string())),
+        403 => Err(serde_json::from_str(&response.body)
+            .unwrap_or_else(|_| OstError::Forbidden("Not allowed for this role".to_string()))),
+        _ => Ok(()),
     }
 }
 
+fn transport_error(err: JsValue, fallback: &str) -> OstError {
+    OstError::Transport(err.as_string().unwrap_or_else(|| fallback.to_string()))
+}
+
+/// The v1 api answers with a json `Result` of a string error, a payload that doesn't parse is a
+/// `Serialization` error
+fn unpack_response<T: serde::de::DeserializeOwned>(payload: &str) -> Result<T, OstError> {
+    match serde_json::from_str::<Result<T, String>>(payload) {
+        Ok(result) => result.map_err(|message| OstError::from_message(&message)),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// The browser's fetch api, the transport of `AsyncRemoteMonolith::default()`
+pub fn fetch(request: ApiRequest) -> TransportFuture {
+    Box::pin(fetch_request(request))
+}
+
 /// Consult the following for an example of the fetch api by the team behind web_sys:
 /// https://rustwasm.github.io/wasm-bindgen/examples/fetch.html
-pub async fn post_message(url: &'static str, payload: Option<String>) -> Result<String, String> {
+async fn fetch_request(api_request: ApiRequest) -> Result<ApiResponse, OstError> {
     let mut opts = RequestInit::new();
-    opts.method("POST");
+    opts.method(match api_request.method {
+        Method::Get => "GET",
+        Method::Post => "POST",
+    });
     opts.mode(RequestMode::Cors);
-
-    let has_payload = payload.is_some();
-
-    if has_payload {
-        opts.body(Some(&JsValue::from_str(&payload.unwrap())));
+    if let Some(body) = &api_request.body {
+        opts.body(Some(&JsValue::from_str(body)));
     }
 
-    let request = match Request::new_with_str_and_init(url, &opts) {
-        Ok(r) => r,
-        Err(e) => {
-            return Err(e
-                .as_string()
-                .unwrap_or_else(|| "Unknown error constructing request".to_string()))
-        }
-    };
-
-    if has_payload {
-        match request.headers().set("content-type", "application/json") {
-            Ok(_) => {}
-            Err(e) => {
-                return Err(e
-                    .as_string()
-                    .unwrap_or_else(|| "Can't modify headers".to_string()))
-            }
-        }
+    let request = Request::new_with_str_and_init(&api_request.url, &opts)
+        .map_err(|e| transport_error(e, "Unknown error constructing request"))?;
+    for (name, value) in &api_request.headers {
+        request
+            .headers()
+            .set(name, value)
+            .map_err(|e| transport_error(e, "Can't modify headers"))?;
     }
 
     let window = gloo_utils::window();
-    let resp_value = match JsFuture::from(window.fetch_with_request(&request)).await {
-        Ok(o) => o,
-        Err(e) => {
-            return Err(e
-                .as_string()
-                .unwrap_or_else(|| "Unknown error calling window fetch with request".to_string()))
-        }
-    };
-
+    let resp_value = JsFuture::from(window.fetch_with_request(&request))
+        .await
+        .map_err(|e| transport_error(e, "Unknown error calling window fetch with request"))?;
     let resp: Response = resp_value.dyn_into().unwrap();
 
-    match resp.text() {
-        Ok(o) => {
-            let text = match JsFuture::from(o).await {
-                Ok(t) => t,
-                Err(e) => {
-                    return Err(e
-                        .as_string()
-                        .unwrap_or_else(|| "Unknown error unpacking response".to_string()))
-                }
-            };
-            Ok(text.as_string().unwrap())
-        }
-        Err(e) => Err(e
-            .as_string()
-            .unwrap_or_else(|| "Unknown error unpacking response".to_string())),
-    }
+    let text = resp
+        .text()
+        .map_err(|e| transport_error(e, "Unknown error unpacking response"))?;
+    let text = JsFuture::from(text)
+        .await
+        .map_err(|e| transport_error(e, "Unknown error unpacking response"))?;
+    Ok(ApiResponse {
+        status: resp.status(),
+        body: text.as_string().unwrap_or_default(),
+    })
 }
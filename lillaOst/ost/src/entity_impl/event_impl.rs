@@ -1,4 +1,4 @@
-use std::{cell::RefCell, rc::Rc};
+use std::sync::{Arc, RwLock};
 
 use chrono::prelude::*;
 
@@ -9,6 +9,7 @@ use crate::event::{Event, EventType};
 use crate::event_base::EventBase;
 use crate::event_key::{EventType as context_EventType, OstEventKey};
 use crate::person::Person;
+use crate::person_key::OstPersonKey;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct EventPersistence {
@@ -22,7 +23,7 @@ pub struct EventPersistence {
 pub struct EventImpl {
     pub id: u32,
     pub time_stamp: DateTime<Utc>,
-    pub person: Rc<RefCell<PersonImpl>>,
+    pub person: Arc<RwLock<PersonImpl>>,
     pub event: EventType,
 }
 
@@ -47,11 +48,11 @@ impl Event for EventImpl {
 
 impl EventBase for EventImpl {
     fn person_name(&self) -> String {
-        self.person.borrow().name().to_string()
+        self.person.read().unwrap().name().to_string()
     }
 
     fn is_person_active(&self) -> bool {
-        self.person.borrow().is_active()
+        self.person.read().unwrap().is_active()
     }
 
     fn time_stamp(&self) -> &DateTime<Utc> {
@@ -69,6 +70,10 @@ impl EventBase for EventImpl {
         }
     }
 
+    fn person_key(&self) -> OstPersonKey {
+        self.person.read().unwrap().key()
+    }
+
     fn key(&self) -> OstEventKey {
         OstEventKey {
             t: context_EventType::Event,
@@ -78,7 +83,7 @@ impl EventBase for EventImpl {
 }
 
 impl EventImpl {
-    pub fn new(person: Rc<RefCell<PersonImpl>>, id: u32, event: EventType) -> Self {
+    pub fn new(person: Arc<RwLock<PersonImpl>>, id: u32, event: EventType) -> Self {
         EventImpl {
             id,
             time_stamp: Utc::now(),
@@ -89,13 +94,13 @@ impl EventImpl {
 
     pub fn from_persistence(
         persisted: &EventPersistence,
-        persons: &[Rc<RefCell<PersonImpl>>],
+        persons: &[Arc<RwLock<PersonImpl>>],
     ) -> Self {
         let existing_person = persons
         .iter()
         .find(|p| {
             let unpacked_p = &***p;
-            unpacked_p.borrow().id() == persisted.person_id
+            unpacked_p.read().unwrap().id() == persisted.person_id
         })
         .unwrap_or_else(|| panic!("broken reference persisted feeding points to person {} that can't be found in provided persons", persisted.person_id));
         EventImpl {
@@ -108,7 +113,7 @@ impl EventImpl {
 
     pub fn to_persistence(&self) -> EventPersistence {
         let person = &*self.person;
-        let person_id = person.borrow().id();
+        let person_id = person.read().unwrap().id();
         EventPersistence {
             id: self.id,
             time_stamp: self.time_stamp,
@@ -1,4 +1,4 @@
-use std::{cell::RefCell, rc::Rc};
+use std::sync::{Arc, RwLock};
 
 use chrono::prelude::*;
 
@@ -9,6 +9,7 @@ use crate::event_base::EventBase;
 use crate::event_key::{EventType, OstEventKey};
 use crate::expulsion::{Expulsion, ExpulsionDegree};
 use crate::person::Person;
+use crate::person_key::OstPersonKey;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ExpulsionPersistence {
@@ -23,7 +24,7 @@ pub struct ExpulsionImpl {
     pub id: u32,
     pub degree: ExpulsionDegree,
     pub time_stamp: DateTime<Utc>,
-    pub person: Rc<RefCell<PersonImpl>>,
+    pub person: Arc<RwLock<PersonImpl>>,
 }
 
 impl Expulsion for ExpulsionImpl {
@@ -47,7 +48,7 @@ impl Expulsion for ExpulsionImpl {
 
 impl EventBase for ExpulsionImpl {
     fn person_name(&self) -> String {
-        self.person.borrow().name().to_string()
+        self.person.read().unwrap().name().to_string()
     }
 
     fn time_stamp(&self) -> &DateTime<Utc> {
@@ -59,7 +60,11 @@ impl EventBase for ExpulsionImpl {
     }
 
     fn is_person_active(&self) -> bool {
-        self.person.borrow().is_active()
+        self.person.read().unwrap().is_active()
+    }
+
+    fn person_key(&self) -> OstPersonKey {
+        self.person.read().unwrap().key()
     }
 
     fn key(&self) -> OstEventKey {
@@ -71,7 +76,7 @@ impl EventBase for ExpulsionImpl {
 }
 
 impl ExpulsionImpl {
-    pub fn new(person: Rc<RefCell<PersonImpl>>, id: u32, degree: ExpulsionDegree) -> Self {
+    pub fn new(person: Arc<RwLock<PersonImpl>>, id: u32, degree: ExpulsionDegree) -> Self {
         ExpulsionImpl {
             id,
             degree,
@@ -82,13 +87,13 @@ impl ExpulsionImpl {
 
     pub fn from_persistence(
         persisted: &ExpulsionPersistence,
-        persons: &[Rc<RefCell<PersonImpl>>],
+        persons: &[Arc<RwLock<PersonImpl>>],
     ) -> Self {
         let existing_person = persons
         .iter()
         .find(|p| {
             let unpacked_p = &***p;
-            unpacked_p.borrow().id() == persisted.person_id
+            unpacked_p.read().unwrap().id() == persisted.person_id
         })
         .unwrap_or_else(|| panic!("broken reference persisted feeding points to person {} that can't be found in provided persons", persisted.person_id));
 
@@ -102,7 +107,7 @@ impl ExpulsionImpl {
 
     pub fn to_persistence(&self) -> ExpulsionPersistence {
         let person = &*self.person;
-        let person_id = person.borrow().id();
+        let person_id = person.read().unwrap().id();
 
         ExpulsionPersistence {
             id: self.id,
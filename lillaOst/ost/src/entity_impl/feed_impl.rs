@@ -1,10 +1,11 @@
-use std::{cell::RefCell, rc::Rc};
+use std::sync::{Arc, RwLock};
 
 use chrono::prelude::*;
 use serde_derive::{Deserialize, Serialize};
 
 use crate::{
-    event_base::EventBase, event_key::EventType, event_key::OstEventKey, feed::Feed, person::Person,
+    event_base::EventBase, event_key::EventType, event_key::OstEventKey, feed::Feed,
+    nursing::NursingSession, person::Person, person_key::OstPersonKey,
 };
 
 use super::person_impl::PersonImpl;
@@ -17,6 +18,8 @@ pub struct FeedPersistence {
     pub solids: u32,
     pub time_stamp: DateTime<Utc>,
     pub person_id: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nursing: Option<NursingSession>,
 }
 
 #[derive(Clone, Debug)]
@@ -26,7 +29,8 @@ pub struct FeedImpl {
     pub formula: u32,
     pub solids: u32,
     pub time_stamp: DateTime<Utc>,
-    pub person: Rc<RefCell<PersonImpl>>,
+    pub person: Arc<RwLock<PersonImpl>>,
+    pub nursing: Option<NursingSession>,
 }
 
 impl Feed for FeedImpl {
@@ -59,6 +63,14 @@ impl Feed for FeedImpl {
         self.time_stamp = time_stamp;
     }
 
+    fn nursing(&self) -> Option<NursingSession> {
+        self.nursing.clone()
+    }
+
+    fn modify_nursing(&mut self, nursing: Option<NursingSession>) {
+        self.nursing = nursing;
+    }
+
     fn serialize(&self) -> String {
         serde_json::to_string(&self.to_persistence()).unwrap()
     }
@@ -66,7 +78,7 @@ impl Feed for FeedImpl {
 
 impl EventBase for FeedImpl {
     fn person_name(&self) -> String {
-        self.person.borrow().name().to_string()
+        self.person.read().unwrap().name().to_string()
     }
 
     fn time_stamp(&self) -> &DateTime<Utc> {
@@ -74,14 +86,22 @@ impl EventBase for FeedImpl {
     }
 
     fn summary(&self) -> String {
-        format!(
+        let amounts = format!(
             "BM: {} F: {} Solids: {}",
             self.breast_milk, self.formula, self.solids
-        )
+        );
+        match &self.nursing {
+            Some(nursing) => format!("Nursing: {} {}", nursing, amounts),
+            None => amounts,
+        }
     }
 
     fn is_person_active(&self) -> bool {
-        self.person.borrow().is_active()
+        self.person.read().unwrap().is_active()
+    }
+
+    fn person_key(&self) -> OstPersonKey {
+        self.person.read().unwrap().key()
     }
 
     fn key(&self) -> OstEventKey {
@@ -94,7 +114,7 @@ impl EventBase for FeedImpl {
 
 impl FeedImpl {
     pub fn new(
-        person: Rc<RefCell<PersonImpl>>,
+        person: Arc<RwLock<PersonImpl>>,
         id: u32,
         breast_milk: u32,
         formula: u32,
@@ -107,18 +127,19 @@ impl FeedImpl {
             time_stamp: Utc::now(),
             person,
             id,
+            nursing: None,
         }
     }
 
     pub fn from_persistence(
         persisted: &FeedPersistence,
-        persons: &[Rc<RefCell<PersonImpl>>],
+        persons: &[Arc<RwLock<PersonImpl>>],
     ) -> Self {
         let existing_person = persons
         .iter()
         .find(|p| {
             let unpacked_p = &***p;
-            unpacked_p.borrow().id() == persisted.person_id
+            unpacked_p.read().unwrap().id() == persisted.person_id
         })
         .expect(format!("broken reference persisted feeding points to person {} that can't be found in provided persons", persisted.person_id ).as_str());
 
@@ -129,12 +150,13 @@ impl FeedImpl {
             time_stamp: persisted.time_stamp,
             person: existing_person.clone(),
             id: persisted.id,
+            nursing: persisted.nursing.clone(),
         }
     }
 
     pub fn to_persistence(&self) -> FeedPersistence {
         let person = &*self.person;
-        let person_id = person.borrow().id();
+        let person_id = person.read().unwrap().id();
 
         FeedPersistence {
             breast_milk: self.breast_milk,
@@ -143,6 +165,7 @@ impl FeedImpl {
             time_stamp: self.time_stamp,
             person_id: person_id,
             id: self.id,
+            nursing: self.nursing.clone(),
         }
     }
 
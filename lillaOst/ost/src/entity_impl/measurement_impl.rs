@@ -0,0 +1,137 @@
+use std::sync::{Arc, RwLock};
+
+use chrono::prelude::*;
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::entity_impl::person_impl::PersonImpl;
+use crate::event_base::EventBase;
+use crate::event_key::{EventType, OstEventKey};
+use crate::measurement::{Measurement, Measures};
+use crate::person::Person;
+use crate::person_key::OstPersonKey;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MeasurementPersistence {
+    pub id: u32,
+    #[serde(flatten)]
+    pub measures: Measures,
+    pub time_stamp: DateTime<Utc>,
+    pub person_id: u32,
+}
+
+#[derive(Clone, Debug)]
+pub struct MeasurementImpl {
+    pub id: u32,
+    pub measures: Measures,
+    pub time_stamp: DateTime<Utc>,
+    pub person: Arc<RwLock<PersonImpl>>,
+}
+
+impl Measurement for MeasurementImpl {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn measures(&self) -> Measures {
+        self.measures
+    }
+
+    fn modify_measurement(&mut self, measures: Measures, time_stamp: DateTime<Utc>) {
+        self.measures = measures;
+        self.time_stamp = time_stamp;
+    }
+
+    fn serialize(&self) -> String {
+        serde_json::to_string(&self.to_persistence()).unwrap()
+    }
+}
+
+impl EventBase for MeasurementImpl {
+    fn person_name(&self) -> String {
+        self.person.read().unwrap().name().to_string()
+    }
+
+    fn time_stamp(&self) -> &DateTime<Utc> {
+        &self.time_stamp
+    }
+
+    fn summary(&self) -> String {
+        self.measures.to_string()
+    }
+
+    fn is_person_active(&self) -> bool {
+        self.person.read().unwrap().is_active()
+    }
+
+    fn person_key(&self) -> OstPersonKey {
+        self.person.read().unwrap().key()
+    }
+
+    fn key(&self) -> OstEventKey {
+        OstEventKey {
+            t: EventType::Measurement,
+            id: self.id,
+        }
+    }
+}
+
+impl MeasurementImpl {
+    pub fn new(person: Arc<RwLock<PersonImpl>>, id: u32, measures: Measures) -> Self {
+        MeasurementImpl {
+            id,
+            measures,
+            time_stamp: Utc::now(),
+            person,
+        }
+    }
+
+    pub fn from_persistence(
+        persisted: &MeasurementPersistence,
+        persons: &[Arc<RwLock<PersonImpl>>],
+    ) -> Self {
+        let existing_person = persons
+        .iter()
+        .find(|p| p.read().unwrap().id() == persisted.person_id)
+        .unwrap_or_else(|| panic!("broken reference persisted measurement points to person {} that can't be found in provided persons", persisted.person_id));
+
+        MeasurementImpl {
+            id: persisted.id,
+            measures: persisted.measures,
+            time_stamp: persisted.time_stamp,
+            person: existing_person.clone(),
+        }
+    }
+
+    pub fn to_persistence(&self) -> MeasurementPersistence {
+        MeasurementPersistence {
+            id: self.id,
+            measures: self.measures,
+            time_stamp: self.time_stamp,
+            person_id: self.person.read().unwrap().id(),
+        }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MeasurementPersistence;
+
+    #[test]
+    fn measures_are_persisted_next_to_the_id() {
+        let persisted: MeasurementPersistence = serde_json::from_str(
+            r#"{"id":3,"weight":4200,"time_stamp":"2022-01-20T10:00:00Z","person_id":0}"#,
+        )
+        .unwrap();
+        assert_eq!(persisted.measures.weight, Some(4200));
+        assert_eq!(persisted.measures.length, None);
+
+        let serialized = serde_json::to_string(&persisted).unwrap();
+        assert!(serialized.contains(r#""weight":4200"#));
+        assert!(!serialized.contains("length"));
+    }
+}
@@ -1,7 +1,9 @@
 pub mod event_impl;
 pub mod expulsion_impl;
 pub mod feed_impl;
+pub mod measurement_impl;
 pub mod person_impl;
 pub mod remote_event_impl;
 pub mod remote_expulsion_impl;
 pub mod remote_feed_impl;
+pub mod remote_measurement_impl;
@@ -1,8 +1,9 @@
 use std::any::Any;
 
+use chrono::prelude::*;
 use serde_derive::{Deserialize, Serialize};
 
-use crate::person::Person;
+use crate::person::{Person, Sex};
 use crate::person_key::OstPersonKey;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -10,6 +11,14 @@ pub struct PersonImpl {
     pub id: u32,
     pub name: String,
     pub is_active: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub birth_date: Option<NaiveDate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sex: Option<Sex>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub birth_weight: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub colour: Option<String>,
 }
 
 impl PersonImpl {
@@ -18,6 +27,10 @@ impl PersonImpl {
             id,
             name: name.to_string(),
             is_active: true,
+            birth_date: None,
+            sex: None,
+            birth_weight: None,
+            colour: None,
         }
     }
 
@@ -56,6 +69,38 @@ impl Person for PersonImpl {
         self.name = name.to_string();
     }
 
+    fn birth_date(&self) -> Option<NaiveDate> {
+        self.birth_date
+    }
+
+    fn set_birth_date(&mut self, birth_date: Option<NaiveDate>) {
+        self.birth_date = birth_date;
+    }
+
+    fn sex(&self) -> Option<Sex> {
+        self.sex
+    }
+
+    fn set_sex(&mut self, sex: Option<Sex>) {
+        self.sex = sex;
+    }
+
+    fn birth_weight(&self) -> Option<u32> {
+        self.birth_weight
+    }
+
+    fn set_birth_weight(&mut self, birth_weight: Option<u32>) {
+        self.birth_weight = birth_weight;
+    }
+
+    fn colour(&self) -> Option<&str> {
+        self.colour.as_deref()
+    }
+
+    fn set_colour(&mut self, colour: Option<&str>) {
+        self.colour = colour.map(str::to_string);
+    }
+
     fn serialize(&self) -> String {
         serde_json::to_string(self).unwrap()
     }
@@ -92,4 +137,11 @@ mod tests {
         let rebuilt_once = deserialize(&serialized_p_once).unwrap();
         assert_eq!(p_once.name(), rebuilt_once.name());
     }
+
+    #[test]
+    fn persons_without_a_profile_still_load() {
+        let rebuilt = deserialize(r#"{"id":3,"name":"old","is_active":true}"#).unwrap();
+        assert_eq!(rebuilt.birth_date(), None);
+        assert_eq!(rebuilt.colour(), None);
+    }
 }
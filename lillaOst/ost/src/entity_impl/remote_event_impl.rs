@@ -4,6 +4,7 @@ use crate::entity_impl::person_impl::PersonImpl;
 use crate::event::{Event, EventType};
 use crate::event_base::EventBase;
 use crate::event_key::{EventType as context_EventType, OstEventKey};
+use crate::person_key::OstPersonKey;
 
 #[derive(Clone, Debug)]
 pub struct RemoteEventImpl {
@@ -56,6 +57,10 @@ impl EventBase for RemoteEventImpl {
         }
     }
 
+    fn person_key(&self) -> OstPersonKey {
+        OstPersonKey { id: self.person.id }
+    }
+
     fn key(&self) -> OstEventKey {
         OstEventKey {
             t: context_EventType::Event,
@@ -4,6 +4,7 @@ use crate::entity_impl::person_impl::PersonImpl;
 use crate::event_base::EventBase;
 use crate::event_key::{EventType, OstEventKey};
 use crate::expulsion::{Expulsion, ExpulsionDegree};
+use crate::person_key::OstPersonKey;
 
 #[derive(Clone, Debug)]
 pub struct RemoteExpulsionImpl {
@@ -49,6 +50,10 @@ impl EventBase for RemoteExpulsionImpl {
         self.person.is_active
     }
 
+    fn person_key(&self) -> OstPersonKey {
+        OstPersonKey { id: self.person.id }
+    }
+
     fn key(&self) -> OstEventKey {
         OstEventKey {
             t: EventType::Expulsion,
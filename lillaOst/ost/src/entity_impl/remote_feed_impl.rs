@@ -2,9 +2,12 @@ use chrono::prelude::*;
 
 use super::person_impl::PersonImpl;
 
+use crate::entity_impl::feed_impl::FeedPersistence;
 use crate::event_base::EventBase;
 use crate::event_key::{EventType, OstEventKey};
 use crate::feed::Feed;
+use crate::nursing::NursingSession;
+use crate::person_key::OstPersonKey;
 
 #[derive(Clone, Debug)]
 pub struct RemoteFeedImpl {
@@ -14,6 +17,7 @@ pub struct RemoteFeedImpl {
     pub solids: u32,
     pub time_stamp: DateTime<Utc>,
     pub person: PersonImpl,
+    pub nursing: Option<NursingSession>,
 }
 
 impl Feed for RemoteFeedImpl {
@@ -46,6 +50,14 @@ impl Feed for RemoteFeedImpl {
         self.time_stamp = time_stamp;
     }
 
+    fn nursing(&self) -> Option<NursingSession> {
+        self.nursing.clone()
+    }
+
+    fn modify_nursing(&mut self, nursing: Option<NursingSession>) {
+        self.nursing = nursing;
+    }
+
     fn serialize(&self) -> String {
         panic!("Serializing remote feed impl is not supported ... yet (??)")
     }
@@ -61,16 +73,24 @@ impl EventBase for RemoteFeedImpl {
     }
 
     fn summary(&self) -> String {
-        format!(
+        let amounts = format!(
             "BM: {} F: {} Solids: {}",
             self.breast_milk, self.formula, self.solids
-        )
+        );
+        match &self.nursing {
+            Some(nursing) => format!("Nursing: {} {}", nursing, amounts),
+            None => amounts,
+        }
     }
 
     fn is_person_active(&self) -> bool {
         self.person.is_active
     }
 
+    fn person_key(&self) -> OstPersonKey {
+        OstPersonKey { id: self.person.id }
+    }
+
     fn key(&self) -> OstEventKey {
         OstEventKey {
             t: EventType::Feed,
@@ -78,3 +98,17 @@ impl EventBase for RemoteFeedImpl {
         }
     }
 }
+
+impl RemoteFeedImpl {
+    pub fn from_persistence(persisted: FeedPersistence, person: PersonImpl) -> Self {
+        RemoteFeedImpl {
+            id: persisted.id,
+            breast_milk: persisted.breast_milk,
+            formula: persisted.formula,
+            solids: persisted.solids,
+            time_stamp: persisted.time_stamp,
+            person,
+            nursing: persisted.nursing,
+        }
+    }
+}
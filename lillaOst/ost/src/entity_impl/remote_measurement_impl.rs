@@ -0,0 +1,81 @@
+use chrono::prelude::*;
+
+use crate::entity_impl::measurement_impl::MeasurementPersistence;
+use crate::entity_impl::person_impl::PersonImpl;
+use crate::event_base::EventBase;
+use crate::event_key::{EventType, OstEventKey};
+use crate::measurement::{Measurement, Measures};
+use crate::person_key::OstPersonKey;
+
+#[derive(Clone, Debug)]
+pub struct RemoteMeasurementImpl {
+    pub id: u32,
+    pub measures: Measures,
+    pub time_stamp: DateTime<Utc>,
+    pub person: PersonImpl,
+}
+
+impl Measurement for RemoteMeasurementImpl {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn measures(&self) -> Measures {
+        self.measures
+    }
+
+    fn modify_measurement(&mut self, measures: Measures, time_stamp: DateTime<Utc>) {
+        self.measures = measures;
+        self.time_stamp = time_stamp;
+    }
+
+    fn serialize(&self) -> String {
+        serde_json::to_string(&MeasurementPersistence {
+            id: self.id,
+            measures: self.measures,
+            time_stamp: self.time_stamp,
+            person_id: self.person.id,
+        })
+        .unwrap()
+    }
+}
+
+impl EventBase for RemoteMeasurementImpl {
+    fn person_name(&self) -> String {
+        self.person.name.clone()
+    }
+
+    fn time_stamp(&self) -> &DateTime<Utc> {
+        &self.time_stamp
+    }
+
+    fn summary(&self) -> String {
+        self.measures.to_string()
+    }
+
+    fn is_person_active(&self) -> bool {
+        self.person.is_active
+    }
+
+    fn person_key(&self) -> OstPersonKey {
+        OstPersonKey { id: self.person.id }
+    }
+
+    fn key(&self) -> OstEventKey {
+        OstEventKey {
+            t: EventType::Measurement,
+            id: self.id,
+        }
+    }
+}
+
+impl RemoteMeasurementImpl {
+    pub fn from_persistence(persisted: MeasurementPersistence, person: PersonImpl) -> Self {
+        RemoteMeasurementImpl {
+            id: persisted.id,
+            measures: persisted.measures,
+            time_stamp: persisted.time_stamp,
+            person,
+        }
+    }
+}
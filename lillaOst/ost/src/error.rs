@@ -0,0 +1,92 @@
+use std::fmt;
+
+use serde_derive::{Deserialize, Serialize};
+
+/// Everything that can go wrong in a `Context`, the payload is a human readable message.
+/// It travels as json between the backend and the remote contexts.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OstError {
+    NotFound(String),
+    DuplicateName(String),
+    Validation(String),
+    PersistenceIo(String),
+    Serialization(String),
+    Transport(String),
+    Unauthorized(String),
+    Forbidden(String),
+}
+
+impl OstError {
+    pub fn message(&self) -> &str {
+        match self {
+            OstError::NotFound(message)
+            | OstError::DuplicateName(message)
+            | OstError::Validation(message)
+            | OstError::PersistenceIo(message)
+            | OstError::Serialization(message)
+            | OstError::Transport(message)
+            | OstError::Unauthorized(message)
+            | OstError::Forbidden(message) => message,
+        }
+    }
+
+    /// The reverse of `Display`, the v1 api sends its errors as plain strings.
+    /// A message without a known prefix is kept whole as a `Serialization` error.
+    pub fn from_message(message: &str) -> Self {
+        let (kind, rest) = match message.split_once(": ") {
+            Some(split) => split,
+            None => return OstError::Serialization(message.to_string()),
+        };
+        let rest = rest.to_string();
+        match kind {
+            "Not found" => OstError::NotFound(rest),
+            "Duplicate name" => OstError::DuplicateName(rest),
+            "Invalid request" => OstError::Validation(rest),
+            "Persistence error" => OstError::PersistenceIo(rest),
+            "Serialization error" => OstError::Serialization(rest),
+            "Transport error" => OstError::Transport(rest),
+            "Unauthorized" => OstError::Unauthorized(rest),
+            "Forbidden" => OstError::Forbidden(rest),
+            _ => OstError::Serialization(message.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for OstError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OstError::NotFound(message) => write!(f, "Not found: {}", message),
+            OstError::DuplicateName(message) => write!(f, "Duplicate name: {}", message),
+            OstError::Validation(message) => write!(f, "Invalid request: {}", message),
+            OstError::PersistenceIo(message) => write!(f, "Persistence error: {}", message),
+            OstError::Serialization(message) => write!(f, "Serialization error: {}", message),
+            OstError::Transport(message) => write!(f, "Transport error: {}", message),
+            OstError::Unauthorized(message) => write!(f, "Unauthorized: {}", message),
+            OstError::Forbidden(message) => write!(f, "Forbidden: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for OstError {}
+
+impl From<std::io::Error> for OstError {
+    fn from(err: std::io::Error) -> Self {
+        OstError::PersistenceIo(err.to_string())
+    }
+}
+
+impl From<serde_json::Error> for OstError {
+    fn from(err: serde_json::Error) -> Self {
+        OstError::Serialization(err.to_string())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl From<rusqlite::Error> for OstError {
+    fn from(err: rusqlite::Error) -> Self {
+        match err {
+            rusqlite::Error::QueryReturnedNoRows => OstError::NotFound(err.to_string()),
+            _ => OstError::PersistenceIo(err.to_string()),
+        }
+    }
+}
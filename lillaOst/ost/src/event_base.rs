@@ -1,12 +1,15 @@
 use chrono::prelude::*;
 
 use crate::event_key::OstEventKey;
+use crate::person_key::OstPersonKey;
 
 pub trait EventBase {
     fn person_name(&self) -> String;
 
     fn is_person_active(&self) -> bool;
 
+    fn person_key(&self) -> OstPersonKey;
+
     fn time_stamp(&self) -> &DateTime<Utc>;
 
     fn summary(&self) -> String;
@@ -7,6 +7,7 @@ pub enum EventType {
     Event,
     Expulsion,
     Feed,
+    Measurement,
 }
 
 impl fmt::Display for EventType {
@@ -15,6 +16,7 @@ impl fmt::Display for EventType {
             EventType::Event => write!(f, "{}", r#"Event"#),
             EventType::Expulsion => write!(f, "{}", r#"Expulsion"#),
             EventType::Feed => write!(f, "{}", r#"Feed"#),
+            EventType::Measurement => write!(f, "{}", r#"Measurement"#),
         }
     }
 }
@@ -2,6 +2,7 @@ use chrono::prelude::*;
 use std::any::Any;
 
 use crate::event_base::EventBase;
+use crate::nursing::NursingSession;
 
 pub trait Feed: EventBase {
     fn breast_milk(&self) -> u32;
@@ -16,6 +17,11 @@ pub trait Feed: EventBase {
         time_stamp: DateTime<Utc>,
     );
 
+    /// The time at the breast, none for bottles and solids
+    fn nursing(&self) -> Option<NursingSession>;
+
+    fn modify_nursing(&mut self, nursing: Option<NursingSession>);
+
     // https://bennetthardwick.com/rust/downcast-trait-object/
     fn as_any(&self) -> &dyn Any;
 
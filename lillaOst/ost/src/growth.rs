@@ -0,0 +1,260 @@
+//! WHO Child Growth Standards for the first year, the LMS parameters of
+//! https://www.who.int/tools/child-growth-standards/standards at every completed month.
+//! Ages in between are interpolated linearly, past 12 months there is no answer.
+
+use std::fmt;
+
+use crate::measurement::Measures;
+use crate::person::{Age, Sex};
+
+/// Average length of a month in days, the WHO tables use the same
+const DAYS_PER_MONTH: f64 = 30.4375;
+
+/// The z-scores of the 3rd, 15th, 50th, 85th and 97th percentile, the lines of a growth chart
+pub const CHART_Z_SCORES: [f64; 5] = [-1.881, -1.036, 0.0, 1.036, 1.881];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GrowthIndicator {
+    /// In kilograms
+    WeightForAge,
+    /// In centimetres
+    LengthForAge,
+    /// In centimetres
+    HeadCircumferenceForAge,
+}
+
+impl GrowthIndicator {
+    pub const ALL: [GrowthIndicator; 3] = [
+        GrowthIndicator::WeightForAge,
+        GrowthIndicator::LengthForAge,
+        GrowthIndicator::HeadCircumferenceForAge,
+    ];
+
+    /// The measured value in the table's unit, weights are kept in grams
+    pub fn value_of(&self, measures: &Measures) -> Option<f64> {
+        match self {
+            GrowthIndicator::WeightForAge => measures.weight.map(|grams| grams as f64 / 1000.0),
+            GrowthIndicator::LengthForAge => measures.length,
+            GrowthIndicator::HeadCircumferenceForAge => measures.head_circumference,
+        }
+    }
+
+    fn table(&self, sex: Sex) -> &'static [(f64, f64, f64); 13] {
+        match (self, sex) {
+            (GrowthIndicator::WeightForAge, Sex::Male) => &WEIGHT_FOR_AGE_BOYS,
+            (GrowthIndicator::WeightForAge, Sex::Female) => &WEIGHT_FOR_AGE_GIRLS,
+            (GrowthIndicator::LengthForAge, Sex::Male) => &LENGTH_FOR_AGE_BOYS,
+            (GrowthIndicator::LengthForAge, Sex::Female) => &LENGTH_FOR_AGE_GIRLS,
+            (GrowthIndicator::HeadCircumferenceForAge, Sex::Male) => &HEAD_FOR_AGE_BOYS,
+            (GrowthIndicator::HeadCircumferenceForAge, Sex::Female) => &HEAD_FOR_AGE_GIRLS,
+        }
+    }
+}
+
+impl fmt::Display for GrowthIndicator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GrowthIndicator::WeightForAge => write!(f, "Weight"),
+            GrowthIndicator::LengthForAge => write!(f, "Length"),
+            GrowthIndicator::HeadCircumferenceForAge => write!(f, "Head circumference"),
+        }
+    }
+}
+
+/// Box-Cox power, median and coefficient of variation at one age
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Lms {
+    pub l: f64,
+    pub m: f64,
+    pub s: f64,
+}
+
+impl Lms {
+    pub fn z_score(&self, value: f64) -> f64 {
+        if self.l == 0.0 {
+            (value / self.m).ln() / self.s
+        } else {
+            ((value / self.m).powf(self.l) - 1.0) / (self.l * self.s)
+        }
+    }
+
+    /// The value that has the given z-score, used to draw the percentile lines
+    pub fn value_at(&self, z_score: f64) -> f64 {
+        if self.l == 0.0 {
+            self.m * (self.s * z_score).exp()
+        } else {
+            self.m * (1.0 + self.l * self.s * z_score).powf(1.0 / self.l)
+        }
+    }
+}
+
+/// None past the first year
+pub fn lms(indicator: GrowthIndicator, sex: Sex, age: Age) -> Option<Lms> {
+    let months = age.days as f64 / DAYS_PER_MONTH;
+    let table = indicator.table(sex);
+    let lower = months.floor() as usize;
+    if lower + 1 >= table.len() {
+        return None;
+    }
+    let fraction = months - lower as f64;
+    let (l0, m0, s0) = table[lower];
+    let (l1, m1, s1) = table[lower + 1];
+    Some(Lms {
+        l: l0 + (l1 - l0) * fraction,
+        m: m0 + (m1 - m0) * fraction,
+        s: s0 + (s1 - s0) * fraction,
+    })
+}
+
+pub fn z_score(indicator: GrowthIndicator, sex: Sex, age: Age, value: f64) -> Option<f64> {
+    lms(indicator, sex, age).map(|lms| lms.z_score(value))
+}
+
+/// Share of children below the z-score, from 0 to 100
+pub fn percentile(z_score: f64) -> f64 {
+    100.0 * 0.5 * (1.0 + erf(z_score / std::f64::consts::SQRT_2))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Assessment {
+    pub indicator: GrowthIndicator,
+    pub z_score: f64,
+    pub percentile: f64,
+}
+
+impl fmt::Display for Assessment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} P{:.0} (z {:+.2})",
+            self.indicator, self.percentile, self.z_score
+        )
+    }
+}
+
+/// One assessment for every value that was measured, none past the first year
+pub fn assess(sex: Sex, age: Age, measures: &Measures) -> Vec<Assessment> {
+    GrowthIndicator::ALL
+        .iter()
+        .filter_map(|indicator| {
+            let z_score = z_score(*indicator, sex, age, indicator.value_of(measures)?)?;
+            Some(Assessment {
+                indicator: *indicator,
+                z_score,
+                percentile: percentile(z_score),
+            })
+        })
+        .collect()
+}
+
+/// Abramowitz and Stegun 7.1.26, good to 1.5e-7
+fn erf(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.3275911 * x.abs());
+    let polynomial = t
+        * (0.254829592
+            + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    let y = 1.0 - polynomial * (-x * x).exp();
+    if x < 0.0 {
+        -y
+    } else {
+        y
+    }
+}
+
+// (L, M, S) for months 0 to 12
+
+const WEIGHT_FOR_AGE_BOYS: [(f64, f64, f64); 13] = [
+    (0.3487, 3.3464, 0.14602),
+    (0.2297, 4.4709, 0.13395),
+    (0.1970, 5.5675, 0.12385),
+    (0.1738, 6.3762, 0.11727),
+    (0.1553, 7.0023, 0.11316),
+    (0.1395, 7.5105, 0.11080),
+    (0.1257, 7.9340, 0.10958),
+    (0.1134, 8.2970, 0.10902),
+    (0.1021, 8.6151, 0.10882),
+    (0.0917, 8.9014, 0.10881),
+    (0.0820, 9.1649, 0.10891),
+    (0.0730, 9.4122, 0.10906),
+    (0.0644, 9.6479, 0.10925),
+];
+
+const WEIGHT_FOR_AGE_GIRLS: [(f64, f64, f64); 13] = [
+    (0.3809, 3.2322, 0.14171),
+    (0.1714, 4.1873, 0.13724),
+    (0.0962, 5.1282, 0.13000),
+    (0.0402, 5.8458, 0.12619),
+    (-0.0050, 6.4237, 0.12402),
+    (-0.0430, 6.8985, 0.12274),
+    (-0.0756, 7.2970, 0.12204),
+    (-0.1039, 7.6422, 0.12178),
+    (-0.1288, 7.9487, 0.12181),
+    (-0.1507, 8.2254, 0.12199),
+    (-0.1700, 8.4800, 0.12223),
+    (-0.1872, 8.7192, 0.12247),
+    (-0.2024, 8.9481, 0.12268),
+];
+
+const LENGTH_FOR_AGE_BOYS: [(f64, f64, f64); 13] = [
+    (1.0, 49.8842, 0.03795),
+    (1.0, 54.7244, 0.03557),
+    (1.0, 58.4249, 0.03424),
+    (1.0, 61.4292, 0.03328),
+    (1.0, 63.8860, 0.03257),
+    (1.0, 65.9026, 0.03204),
+    (1.0, 67.6236, 0.03165),
+    (1.0, 69.1645, 0.03139),
+    (1.0, 70.5994, 0.03124),
+    (1.0, 71.9687, 0.03117),
+    (1.0, 73.2812, 0.03118),
+    (1.0, 74.5388, 0.03125),
+    (1.0, 75.7488, 0.03137),
+];
+
+const LENGTH_FOR_AGE_GIRLS: [(f64, f64, f64); 13] = [
+    (1.0, 49.1477, 0.03790),
+    (1.0, 53.6872, 0.03640),
+    (1.0, 57.0673, 0.03568),
+    (1.0, 59.8029, 0.03520),
+    (1.0, 62.0899, 0.03486),
+    (1.0, 64.0301, 0.03463),
+    (1.0, 65.7311, 0.03448),
+    (1.0, 67.2873, 0.03441),
+    (1.0, 68.7498, 0.03440),
+    (1.0, 70.1435, 0.03444),
+    (1.0, 71.4818, 0.03452),
+    (1.0, 72.7710, 0.03464),
+    (1.0, 74.0150, 0.03479),
+];
+
+const HEAD_FOR_AGE_BOYS: [(f64, f64, f64); 13] = [
+    (1.0, 34.4618, 0.03686),
+    (1.0, 37.2759, 0.03133),
+    (1.0, 39.1285, 0.02997),
+    (1.0, 40.5135, 0.02918),
+    (1.0, 41.6317, 0.02868),
+    (1.0, 42.5576, 0.02837),
+    (1.0, 43.3306, 0.02817),
+    (1.0, 43.9803, 0.02804),
+    (1.0, 44.5300, 0.02796),
+    (1.0, 44.9998, 0.02792),
+    (1.0, 45.4051, 0.02790),
+    (1.0, 45.7573, 0.02789),
+    (1.0, 46.0661, 0.02789),
+];
+
+const HEAD_FOR_AGE_GIRLS: [(f64, f64, f64); 13] = [
+    (1.0, 33.8787, 0.03496),
+    (1.0, 36.5463, 0.03210),
+    (1.0, 38.2521, 0.03168),
+    (1.0, 39.5328, 0.03140),
+    (1.0, 40.5817, 0.03119),
+    (1.0, 41.4590, 0.03102),
+    (1.0, 42.1995, 0.03087),
+    (1.0, 42.8290, 0.03075),
+    (1.0, 43.3671, 0.03063),
+    (1.0, 43.8300, 0.03053),
+    (1.0, 44.2319, 0.03044),
+    (1.0, 44.5844, 0.03035),
+    (1.0, 44.9227, 0.03027),
+];
@@ -0,0 +1,323 @@
+use std::collections::{BTreeSet, HashSet};
+
+use chrono::{prelude::*, Duration};
+use serde_derive::{Deserialize, Serialize};
+
+use crate::context_impl::context_persistence::ContextPersistence;
+use crate::context_impl::context_persistence_single_file::persist;
+use crate::entity_impl::person_impl::PersonImpl;
+use crate::error::OstError;
+use crate::event_key::{EventType, OstEventKey};
+
+/// How far ahead of the clock a time stamp can be before it counts as an issue
+pub const FUTURE_TOLERANCE_HOURS: i64 = 24;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum IntegrityIssue {
+    DanglingPersonReference {
+        key: OstEventKey,
+        person_id: u32,
+    },
+    DuplicatePersonId {
+        person_id: u32,
+    },
+    DuplicateId {
+        key: OstEventKey,
+    },
+    FutureTimeStamp {
+        key: OstEventKey,
+        time_stamp: DateTime<Utc>,
+    },
+    DuplicatePersonName {
+        name: String,
+        person_ids: Vec<u32>,
+    },
+}
+
+/// Reports every issue found in a persistence document, whatever its schema version
+pub fn check_document(payload: &str) -> Result<Vec<IntegrityIssue>, OstError> {
+    let persistence = ContextPersistence::from_json(payload)?;
+    Ok(check(&persistence, Utc::now()))
+}
+
+/// Returns the repaired document next to the issues that got repaired
+pub fn repair_document(payload: &str) -> Result<(String, Vec<IntegrityIssue>), OstError> {
+    let mut persistence = ContextPersistence::from_json(payload)?;
+    let issues = repair(&mut persistence, Utc::now());
+    match serde_json::to_string_pretty(&persistence) {
+        Ok(repaired) => Ok((repaired, issues)),
+        Err(err) => Err(err.into()),
+    }
+}
+
+pub fn check_file(path_to_file: &str) -> Result<Vec<IntegrityIssue>, OstError> {
+    match std::fs::read_to_string(path_to_file) {
+        Ok(payload) => check_document(&payload),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Repairs a monolith file in place, the previous version is kept as `<file>.bak`
+pub fn repair_file(path_to_file: &str) -> Result<Vec<IntegrityIssue>, OstError> {
+    let payload = match std::fs::read_to_string(path_to_file) {
+        Ok(payload) => payload,
+        Err(err) => return Err(err.into()),
+    };
+    let mut persistence = ContextPersistence::from_json(&payload)?;
+    let issues = repair(&mut persistence, Utc::now());
+    if !issues.is_empty() {
+        persist(&persistence.into_monolith(path_to_file, persist))?;
+    }
+    Ok(issues)
+}
+
+pub(crate) fn check(persistence: &ContextPersistence, now: DateTime<Utc>) -> Vec<IntegrityIssue> {
+    let mut issues = vec![];
+
+    let mut person_ids = HashSet::new();
+    for person in persistence.persons.iter() {
+        if !person_ids.insert(person.id) {
+            issues.push(IntegrityIssue::DuplicatePersonId {
+                person_id: person.id,
+            });
+        }
+    }
+
+    let entries = entries(persistence);
+    for kind in [
+        EventType::Feed,
+        EventType::Expulsion,
+        EventType::Event,
+        EventType::Measurement,
+    ] {
+        let mut ids = HashSet::new();
+        for (key, _, _) in entries.iter().filter(|(key, _, _)| key.t == kind) {
+            if !ids.insert(key.id) {
+                issues.push(IntegrityIssue::DuplicateId { key: *key });
+            }
+        }
+    }
+
+    for (key, person_id, _) in entries.iter() {
+        if !person_ids.contains(person_id) {
+            issues.push(IntegrityIssue::DanglingPersonReference {
+                key: *key,
+                person_id: *person_id,
+            });
+        }
+    }
+
+    let limit = now + Duration::hours(FUTURE_TOLERANCE_HOURS);
+    for (key, _, time_stamp) in entries.iter() {
+        if *time_stamp > limit {
+            issues.push(IntegrityIssue::FutureTimeStamp {
+                key: *key,
+                time_stamp: *time_stamp,
+            });
+        }
+    }
+
+    // Spelled as the first person with it
+    let mut names: Vec<&str> = vec![];
+    for person in persistence.persons.iter() {
+        if !names
+            .iter()
+            .any(|name| folded(name) == folded(&person.name))
+        {
+            names.push(person.name.as_str());
+        }
+    }
+    for name in names {
+        let person_ids: Vec<u32> = persistence
+            .persons
+            .iter()
+            .filter(|p| folded(&p.name) == folded(name))
+            .map(|p| p.id)
+            .collect();
+        if person_ids.len() > 1 {
+            issues.push(IntegrityIssue::DuplicatePersonName {
+                name: name.to_string(),
+                person_ids,
+            });
+        }
+    }
+
+    issues
+}
+
+/// Fixes every issue `check` reports and returns them:
+/// * entries pointing to a missing person get an inactive placeholder person
+/// * repeated ids, of persons or entries, are replaced by fresh ones
+/// * time stamps in the future are moved to `now`
+/// * repeated person names get a numeric suffix
+pub(crate) fn repair(
+    persistence: &mut ContextPersistence,
+    now: DateTime<Utc>,
+) -> Vec<IntegrityIssue> {
+    let issues = check(persistence, now);
+    if issues.is_empty() {
+        return issues;
+    }
+
+    persistence.next_ids.skip_past(
+        persistence.persons.iter().map(|p| p.id).max(),
+        persistence.feeds.iter().map(|f| f.id).max(),
+        persistence.expulsions.iter().map(|e| e.id).max(),
+        persistence.events.iter().map(|e| e.id).max(),
+        persistence.measurements.iter().map(|m| m.id).max(),
+    );
+
+    // Placeholders first, so re-keyed persons can't take over a dangling reference
+    let _placeholders = add_missing_persons(persistence);
+
+    let mut person_ids = HashSet::new();
+    for person in persistence.persons.iter_mut() {
+        if !person_ids.insert(person.id) {
+            person.id = persistence.next_ids.next_person_id();
+        }
+    }
+
+    let mut seen = HashSet::new();
+    for feed in persistence.feeds.iter_mut() {
+        if !seen.insert(feed.id) {
+            feed.id = persistence.next_ids.next_feed_id();
+        }
+    }
+    let mut seen = HashSet::new();
+    for expulsion in persistence.expulsions.iter_mut() {
+        if !seen.insert(expulsion.id) {
+            expulsion.id = persistence.next_ids.next_expulsion_id();
+        }
+    }
+    let mut seen = HashSet::new();
+    for event in persistence.events.iter_mut() {
+        if !seen.insert(event.id) {
+            event.id = persistence.next_ids.next_event_id();
+        }
+    }
+    let mut seen = HashSet::new();
+    for measurement in persistence.measurements.iter_mut() {
+        if !seen.insert(measurement.id) {
+            measurement.id = persistence.next_ids.next_measurement_id();
+        }
+    }
+
+    let limit = now + Duration::hours(FUTURE_TOLERANCE_HOURS);
+    persistence
+        .feeds
+        .iter_mut()
+        .filter(|f| f.time_stamp > limit)
+        .for_each(|f| f.time_stamp = now);
+    persistence
+        .expulsions
+        .iter_mut()
+        .filter(|e| e.time_stamp > limit)
+        .for_each(|e| e.time_stamp = now);
+    persistence
+        .events
+        .iter_mut()
+        .filter(|e| e.time_stamp > limit)
+        .for_each(|e| e.time_stamp = now);
+    persistence
+        .measurements
+        .iter_mut()
+        .filter(|m| m.time_stamp > limit)
+        .for_each(|m| m.time_stamp = now);
+
+    let mut names: HashSet<String> = HashSet::new();
+    let all_names: HashSet<String> = persistence
+        .persons
+        .iter()
+        .map(|p| folded(&p.name))
+        .collect();
+    for person in persistence.persons.iter_mut() {
+        if names.insert(folded(&person.name)) {
+            continue;
+        }
+        let name = person.name.trim();
+        let mut suffix = 2;
+        let mut nu_name = format!("{} ({})", name, suffix);
+        while names.contains(&folded(&nu_name)) || all_names.contains(&folded(&nu_name)) {
+            suffix += 1;
+            nu_name = format!("{} ({})", name, suffix);
+        }
+        names.insert(folded(&nu_name));
+        person.name = nu_name;
+    }
+
+    issues
+}
+
+/// Names differing only in case or surrounding blanks are the same name
+fn folded(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+/// Adds an inactive placeholder for every person id referenced but not persisted.
+/// Returns the ids of the placeholders added.
+pub(crate) fn add_missing_persons(persistence: &mut ContextPersistence) -> Vec<u32> {
+    let person_ids: HashSet<u32> = persistence.persons.iter().map(|p| p.id).collect();
+    let missing: BTreeSet<u32> = entries(persistence)
+        .into_iter()
+        .map(|(_, person_id, _)| person_id)
+        .filter(|person_id| !person_ids.contains(person_id))
+        .collect();
+
+    for person_id in missing.iter() {
+        let mut placeholder = PersonImpl::new(*person_id, &format!("Unknown person {}", person_id));
+        placeholder.is_active = false;
+        persistence.persons.push(placeholder);
+    }
+    persistence
+        .next_ids
+        .skip_past(missing.iter().max().copied(), None, None, None, None);
+    missing.into_iter().collect()
+}
+
+fn entries(persistence: &ContextPersistence) -> Vec<(OstEventKey, u32, DateTime<Utc>)> {
+    let feeds = persistence.feeds.iter().map(|f| {
+        (
+            OstEventKey {
+                t: EventType::Feed,
+                id: f.id,
+            },
+            f.person_id,
+            f.time_stamp,
+        )
+    });
+    let expulsions = persistence.expulsions.iter().map(|e| {
+        (
+            OstEventKey {
+                t: EventType::Expulsion,
+                id: e.id,
+            },
+            e.person_id,
+            e.time_stamp,
+        )
+    });
+    let events = persistence.events.iter().map(|e| {
+        (
+            OstEventKey {
+                t: EventType::Event,
+                id: e.id,
+            },
+            e.person_id,
+            e.time_stamp,
+        )
+    });
+    let measurements = persistence.measurements.iter().map(|m| {
+        (
+            OstEventKey {
+                t: EventType::Measurement,
+                id: m.id,
+            },
+            m.person_id,
+            m.time_stamp,
+        )
+    });
+    feeds
+        .chain(expulsions)
+        .chain(events)
+        .chain(measurements)
+        .collect()
+}
@@ -1,15 +1,25 @@
 #![feature(trait_upcasting)]
 #![allow(incomplete_features)]
 
+pub mod change;
 pub mod communications;
 pub mod context;
+pub mod context_async;
 mod context_impl;
 pub mod context_remote_async;
 mod entity_impl;
+pub mod error;
 pub mod event;
 pub mod event_base;
 pub mod event_key;
 pub mod expulsion;
 pub mod feed;
+pub mod growth;
+pub mod integrity;
+pub mod measurement;
+pub mod nursing;
+pub mod offline;
 pub mod person;
 pub mod person_key;
+pub mod query;
+pub mod role;
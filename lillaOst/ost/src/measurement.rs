@@ -0,0 +1,77 @@
+use std::any::Any;
+use std::fmt;
+
+use chrono::prelude::*;
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::error::OstError;
+use crate::event_base::EventBase;
+
+/// One visit to the scale and the tape, whatever wasn't measured stays `None`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Measures {
+    /// In grams
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<u32>,
+    /// In centimetres
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub length: Option<f64>,
+    /// In centimetres
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub head_circumference: Option<f64>,
+}
+
+impl Measures {
+    pub fn is_empty(&self) -> bool {
+        self.weight.is_none() && self.length.is_none() && self.head_circumference.is_none()
+    }
+
+    /// At least one value, and no length that isn't a positive number
+    pub fn validate(&self) -> Result<(), OstError> {
+        if self.is_empty() {
+            return Err(OstError::Validation(
+                "A measurement needs a weight, a length or a head circumference".to_string(),
+            ));
+        }
+        if self.weight == Some(0) {
+            return Err(OstError::Validation("A weight can't be zero".to_string()));
+        }
+        for centimetres in [self.length, self.head_circumference].into_iter().flatten() {
+            if !centimetres.is_finite() || centimetres <= 0.0 {
+                return Err(OstError::Validation(format!(
+                    "{} isn't a length in centimetres",
+                    centimetres
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Measures {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = vec![];
+        if let Some(weight) = self.weight {
+            parts.push(format!("{} g", weight));
+        }
+        if let Some(length) = self.length {
+            parts.push(format!("{:.1} cm", length));
+        }
+        if let Some(head_circumference) = self.head_circumference {
+            parts.push(format!("head {:.1} cm", head_circumference));
+        }
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+pub trait Measurement: EventBase {
+    fn measures(&self) -> Measures;
+
+    fn modify_measurement(&mut self, measures: Measures, time_stamp: DateTime<Utc>);
+
+    // https://bennetthardwick.com/rust/downcast-trait-object/
+    fn as_any(&self) -> &dyn Any;
+
+    fn serialize(&self) -> String;
+}
@@ -0,0 +1,188 @@
+//! Time at the breast, kept on the feed it belongs to.
+//! A session is a list of stretches on one side each, sides can alternate more than once.
+
+use std::fmt;
+
+use chrono::prelude::*;
+use chrono::Duration;
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::error::OstError;
+use crate::person_key::OstPersonKey;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BreastSide {
+    Left,
+    Right,
+}
+
+impl BreastSide {
+    pub fn other(&self) -> BreastSide {
+        match self {
+            BreastSide::Left => BreastSide::Right,
+            BreastSide::Right => BreastSide::Left,
+        }
+    }
+}
+
+impl fmt::Display for BreastSide {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BreastSide::Left => write!(f, "Left"),
+            BreastSide::Right => write!(f, "Right"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NursingStretch {
+    pub side: BreastSide,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl NursingStretch {
+    pub fn duration(&self) -> Duration {
+        self.end - self.start
+    }
+}
+
+/// Stretches oldest first
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NursingSession {
+    pub stretches: Vec<NursingStretch>,
+}
+
+impl NursingSession {
+    pub fn start(&self) -> Option<DateTime<Utc>> {
+        self.stretches.first().map(|s| s.start)
+    }
+
+    pub fn end(&self) -> Option<DateTime<Utc>> {
+        self.stretches.last().map(|s| s.end)
+    }
+
+    pub fn first_side(&self) -> Option<BreastSide> {
+        self.stretches.first().map(|s| s.side)
+    }
+
+    pub fn duration_on(&self, side: BreastSide) -> Duration {
+        self.stretches
+            .iter()
+            .filter(|s| s.side == side)
+            .fold(Duration::zero(), |total, s| total + s.duration())
+    }
+
+    /// The side that got less time, it's the fuller one. On a tie the one the session didn't start on.
+    pub fn suggested_next_side(&self) -> Option<BreastSide> {
+        let first_side = self.first_side()?;
+        let left = self.duration_on(BreastSide::Left);
+        let right = self.duration_on(BreastSide::Right);
+        Some(match left.cmp(&right) {
+            std::cmp::Ordering::Less => BreastSide::Left,
+            std::cmp::Ordering::Greater => BreastSide::Right,
+            std::cmp::Ordering::Equal => first_side.other(),
+        })
+    }
+
+    /// At least one stretch, none ending before it starts or overlapping the one before
+    pub fn validate(&self) -> Result<(), OstError> {
+        if self.stretches.is_empty() {
+            return Err(OstError::Validation(
+                "A nursing session needs at least one side".to_string(),
+            ));
+        }
+        let mut previous_end: Option<DateTime<Utc>> = None;
+        for stretch in self.stretches.iter() {
+            if stretch.end < stretch.start {
+                return Err(OstError::Validation(format!(
+                    "The {} side ends before it starts",
+                    stretch.side
+                )));
+            }
+            if previous_end.is_some_and(|end| stretch.start < end) {
+                return Err(OstError::Validation(
+                    "Nursing stretches overlap or are out of order".to_string(),
+                ));
+            }
+            previous_end = Some(stretch.end);
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for NursingSession {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "L {}m R {}m",
+            self.duration_on(BreastSide::Left).num_minutes(),
+            self.duration_on(BreastSide::Right).num_minutes()
+        )
+    }
+}
+
+/// The side to start on after the most recent of `sessions`, none without any
+pub fn next_side(sessions: impl IntoIterator<Item = NursingSession>) -> Option<BreastSide> {
+    sessions
+        .into_iter()
+        .filter(|s| !s.stretches.is_empty())
+        .max_by_key(|s| s.end())?
+        .suggested_next_side()
+}
+
+/// A session still going on, what the timer keeps so it survives a reload
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunningNursing {
+    pub person_key: OstPersonKey,
+    pub completed: Vec<NursingStretch>,
+    pub side: BreastSide,
+    pub since: DateTime<Utc>,
+}
+
+impl RunningNursing {
+    pub fn start(person_key: OstPersonKey, side: BreastSide, now: DateTime<Utc>) -> Self {
+        Self {
+            person_key,
+            completed: vec![],
+            side,
+            since: now,
+        }
+    }
+
+    /// Closes the current stretch and carries on with the other side
+    pub fn switch_side(&mut self, now: DateTime<Utc>) {
+        self.completed.push(NursingStretch {
+            side: self.side,
+            start: self.since,
+            end: now.max(self.since),
+        });
+        self.side = self.side.other();
+        self.since = now.max(self.since);
+    }
+
+    /// Time on `side` so far, the current stretch included
+    pub fn elapsed_on(&self, side: BreastSide, now: DateTime<Utc>) -> Duration {
+        let completed = self
+            .completed
+            .iter()
+            .filter(|s| s.side == side)
+            .fold(Duration::zero(), |total, s| total + s.duration());
+        match side == self.side {
+            true => completed + (now - self.since).max(Duration::zero()),
+            false => completed,
+        }
+    }
+
+    pub fn finish(mut self, now: DateTime<Utc>) -> NursingSession {
+        self.completed.push(NursingStretch {
+            side: self.side,
+            start: self.since,
+            end: now.max(self.since),
+        });
+        NursingSession {
+            stretches: self.completed,
+        }
+    }
+}
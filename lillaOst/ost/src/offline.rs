@@ -0,0 +1,465 @@
+//! Entries recorded while the backend is out of reach wait in an outbox kept in local storage.
+//! Meanwhile the last copy of the backend's data, the replica, stands in for it.
+
+use chrono::prelude::*;
+use gloo_storage::{LocalStorage, Storage};
+use serde_derive::{Deserialize, Serialize};
+
+use crate::context::Context;
+use crate::context_impl::context_persistence_in_memory::persist as keep_in_memory;
+use crate::context_impl::context_persistence_local_storage::{load_document, store_document};
+use crate::context_remote_async::{household_key, AsyncRemoteMonolith};
+use crate::error::OstError;
+use crate::event::EventType;
+use crate::event_key::{EventType as EntryType, OstEventKey};
+use crate::expulsion::ExpulsionDegree;
+use crate::measurement::Measures;
+use crate::nursing::{NursingSession, RunningNursing};
+use crate::person_key::OstPersonKey;
+
+const OUTBOX_KEY: &str = "ost_outbox";
+const REPLICA_KEY: &str = "ost_replica";
+const NURSING_KEY: &str = "ost_running_nursing";
+
+/// The entry a modification or removal is about
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Target {
+    Saved(OstEventKey),
+    /// Added offline, the outbox id of the addition until the backend saved it
+    Queued(u32),
+}
+
+/// A change the backend hasn't seen yet
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Mutation {
+    AddFeeding {
+        person_key: OstPersonKey,
+        breast_milk: u32,
+        formula: u32,
+        solids: u32,
+    },
+    AddNursing {
+        person_key: OstPersonKey,
+        nursing: NursingSession,
+    },
+    AddExpulsion {
+        person_key: OstPersonKey,
+        degree: ExpulsionDegree,
+    },
+    AddEvent {
+        person_key: OstPersonKey,
+        event: EventType,
+    },
+    AddMeasurement {
+        person_key: OstPersonKey,
+        measures: Measures,
+    },
+    /// Replaces the amounts, the nursing session and the time like `modify_feeding`
+    ModifyFeeding {
+        target: Target,
+        breast_milk: u32,
+        formula: u32,
+        solids: u32,
+        nursing: Option<NursingSession>,
+        time_stamp: DateTime<Utc>,
+    },
+    RemoveFeeding {
+        target: Target,
+    },
+    ModifyExpulsion {
+        target: Target,
+        degree: ExpulsionDegree,
+        time_stamp: DateTime<Utc>,
+    },
+    RemoveExpulsion {
+        target: Target,
+    },
+    ModifyEvent {
+        target: Target,
+        event: EventType,
+        time_stamp: DateTime<Utc>,
+    },
+    RemoveEvent {
+        target: Target,
+    },
+    ModifyMeasurement {
+        target: Target,
+        measures: Measures,
+        time_stamp: DateTime<Utc>,
+    },
+    RemoveMeasurement {
+        target: Target,
+    },
+}
+
+impl Mutation {
+    /// The person an addition is for, modifications and removals name their entry instead
+    pub fn person_key(&self) -> Option<OstPersonKey> {
+        match self {
+            Mutation::AddFeeding { person_key, .. }
+            | Mutation::AddNursing { person_key, .. }
+            | Mutation::AddExpulsion { person_key, .. }
+            | Mutation::AddEvent { person_key, .. }
+            | Mutation::AddMeasurement { person_key, .. } => Some(*person_key),
+            _ => None,
+        }
+    }
+
+    /// The entry a modification or removal is about, none for additions
+    pub fn target(&self) -> Option<Target> {
+        match self {
+            Mutation::ModifyFeeding { target, .. }
+            | Mutation::RemoveFeeding { target }
+            | Mutation::ModifyExpulsion { target, .. }
+            | Mutation::RemoveExpulsion { target }
+            | Mutation::ModifyEvent { target, .. }
+            | Mutation::RemoveEvent { target }
+            | Mutation::ModifyMeasurement { target, .. }
+            | Mutation::RemoveMeasurement { target } => Some(*target),
+            _ => None,
+        }
+    }
+
+    fn target_mut(&mut self) -> Option<&mut Target> {
+        match self {
+            Mutation::ModifyFeeding { target, .. }
+            | Mutation::RemoveFeeding { target }
+            | Mutation::ModifyExpulsion { target, .. }
+            | Mutation::RemoveExpulsion { target }
+            | Mutation::ModifyEvent { target, .. }
+            | Mutation::RemoveEvent { target }
+            | Mutation::ModifyMeasurement { target, .. }
+            | Mutation::RemoveMeasurement { target } => Some(target),
+            _ => None,
+        }
+    }
+
+    pub fn entry_type(&self) -> EntryType {
+        match self {
+            Mutation::AddFeeding { .. }
+            | Mutation::AddNursing { .. }
+            | Mutation::ModifyFeeding { .. }
+            | Mutation::RemoveFeeding { .. } => EntryType::Feed,
+            Mutation::AddExpulsion { .. }
+            | Mutation::ModifyExpulsion { .. }
+            | Mutation::RemoveExpulsion { .. } => EntryType::Expulsion,
+            Mutation::AddEvent { .. }
+            | Mutation::ModifyEvent { .. }
+            | Mutation::RemoveEvent { .. } => EntryType::Event,
+            Mutation::AddMeasurement { .. }
+            | Mutation::ModifyMeasurement { .. }
+            | Mutation::RemoveMeasurement { .. } => EntryType::Measurement,
+        }
+    }
+}
+
+/// Names one outbox entry across devices, the backend applies each only once
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ReplayId {
+    pub client: String,
+    pub id: u32,
+}
+
+/// The latest entry a client replayed. Outboxes replay oldest first,
+/// so every id of the client up to `id` was applied before.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplayMark {
+    pub id: u32,
+    pub key: OstEventKey,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PendingMutation {
+    pub id: u32,
+    /// The outbox it was queued in, empty for entries queued before it had one
+    #[serde(default)]
+    pub client: String,
+    /// When it was recorded, the backend keeps it as the time of the entry
+    pub time_stamp: DateTime<Utc>,
+    pub mutation: Mutation,
+}
+
+impl PendingMutation {
+    /// `None` for entries without a client, their ids aren't unique across devices
+    pub fn replay_id(&self) -> Option<ReplayId> {
+        if self.client.is_empty() {
+            None
+        } else {
+            Some(ReplayId {
+                client: self.client.clone(),
+                id: self.id,
+            })
+        }
+    }
+}
+
+/// An addition waiting in the outbox and the key the replica gave it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QueuedEntry {
+    pub id: u32,
+    pub key: OstEventKey,
+}
+
+/// Oldest first, the order they are replayed in
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Outbox {
+    /// Random, picked on the first push
+    #[serde(default)]
+    client: String,
+    next_id: u32,
+    pending: Vec<PendingMutation>,
+}
+
+impl Outbox {
+    pub fn push(&mut self, mutation: Mutation, time_stamp: DateTime<Utc>) -> u32 {
+        if self.client.is_empty() {
+            self.client = format!("{:032x}", rand::random::<u128>());
+        }
+        let id = self.next_id;
+        self.next_id += 1;
+        self.pending.push(PendingMutation {
+            id,
+            client: self.client.clone(),
+            time_stamp,
+            mutation,
+        });
+        id
+    }
+
+    pub fn oldest(&self) -> Option<&PendingMutation> {
+        self.pending.first()
+    }
+
+    pub fn pending(&self) -> &[PendingMutation] {
+        &self.pending
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// The backend saved the addition `id` as `key`, what was queued about it goes there now
+    pub fn resolve(&mut self, id: u32, key: OstEventKey) {
+        for pending in self.pending.iter_mut() {
+            if let Some(target) = pending.mutation.target_mut() {
+                if *target == Target::Queued(id) {
+                    *target = Target::Saved(key);
+                }
+            }
+        }
+    }
+
+    /// Drops `id` once the backend took it, or refused it for good
+    pub fn confirm(&mut self, id: u32) -> bool {
+        let before = self.pending.len();
+        self.pending.retain(|p| p.id != id);
+        self.pending.len() != before
+    }
+
+    /// Applies the pending mutations to `ost`, additions at the time they were recorded, and
+    /// returns the additions still there. Mutations about a person or entry `ost` doesn't know
+    /// are left out.
+    pub fn apply_to(&self, ost: &mut dyn Context) -> Vec<QueuedEntry> {
+        let mut queued: Vec<QueuedEntry> = vec![];
+        for pending in self.pending.iter() {
+            let applied = match pending.mutation.target() {
+                Some(target) => resolved(target, &queued)
+                    .and_then(|key| change(ost, &pending.mutation, &key))
+                    .map(|_| None),
+                None => add(ost, pending).map(Some),
+            };
+            if let Ok(Some(key)) = applied {
+                queued.push(QueuedEntry {
+                    id: pending.id,
+                    key,
+                });
+            }
+        }
+        queued.retain(|q| holds(ost, &q.key));
+        queued
+    }
+}
+
+fn resolved(target: Target, queued: &[QueuedEntry]) -> Result<OstEventKey, OstError> {
+    match target {
+        Target::Saved(key) => Ok(key),
+        Target::Queued(id) => queued
+            .iter()
+            .find(|q| q.id == id)
+            .map(|q| q.key)
+            .ok_or_else(|| OstError::NotFound(format!("Queued entry {} not found", id))),
+    }
+}
+
+fn add(ost: &mut dyn Context, pending: &PendingMutation) -> Result<OstEventKey, OstError> {
+    let person = pending
+        .mutation
+        .person_key()
+        .and_then(|person_key| ost.get_person_by_key(person_key))
+        .ok_or_else(|| OstError::NotFound("Person not found".to_string()))?;
+    let time_stamp = pending.time_stamp;
+
+    match &pending.mutation {
+        Mutation::AddFeeding {
+            breast_milk,
+            formula,
+            solids,
+            ..
+        } => {
+            let feed =
+                ost.add_feeding_at(&person, *breast_milk, *formula, *solids, time_stamp, None)?;
+            Ok(feed.key())
+        }
+        // Timed by the session itself, not by when it was queued
+        Mutation::AddNursing { nursing, .. } => {
+            let feed = ost.add_nursing(&person, nursing.clone())?;
+            Ok(feed.key())
+        }
+        Mutation::AddExpulsion { degree, .. } => {
+            let expulsion = ost.add_expulsion_at(&person, degree.clone(), time_stamp, None)?;
+            Ok(expulsion.key())
+        }
+        Mutation::AddEvent { event, .. } => {
+            let added = ost.add_event_at(&person, event.clone(), time_stamp, None)?;
+            Ok(added.key())
+        }
+        Mutation::AddMeasurement { measures, .. } => {
+            let measurement = ost.add_measurement_at(&person, *measures, time_stamp, None)?;
+            Ok(measurement.key())
+        }
+        _ => Err(OstError::Validation("Not an addition".to_string())),
+    }
+}
+
+/// Modifies or removes the entry `key`
+fn change(ost: &mut dyn Context, mutation: &Mutation, key: &OstEventKey) -> Result<(), OstError> {
+    let missing = || OstError::NotFound(format!("{} {} not found", key.t, key.id));
+    match mutation {
+        Mutation::ModifyFeeding {
+            breast_milk,
+            formula,
+            solids,
+            nursing,
+            time_stamp,
+            ..
+        } => {
+            let mut feed = ost.get_feeding_by_key(key).ok_or_else(missing)?;
+            feed.modify_feed(*breast_milk, *formula, *solids, *time_stamp);
+            feed.modify_nursing(nursing.clone());
+            ost.modify_feeding(&feed)
+        }
+        Mutation::RemoveFeeding { .. } => {
+            let feed = ost.get_feeding_by_key(key).ok_or_else(missing)?;
+            ost.remove_feeding(feed)
+        }
+        Mutation::ModifyExpulsion {
+            degree, time_stamp, ..
+        } => {
+            let mut expulsion = ost.get_expulsion_by_key(key).ok_or_else(missing)?;
+            expulsion.modify_expulsion(degree.clone(), *time_stamp);
+            ost.modify_expulsion(&expulsion)
+        }
+        Mutation::RemoveExpulsion { .. } => {
+            let expulsion = ost.get_expulsion_by_key(key).ok_or_else(missing)?;
+            ost.remove_expulsion(expulsion)
+        }
+        Mutation::ModifyEvent {
+            event, time_stamp, ..
+        } => {
+            let mut modified = ost.get_event_by_key(key).ok_or_else(missing)?;
+            modified.modify_event(*time_stamp, event.clone());
+            ost.modify_event(&modified)
+        }
+        Mutation::RemoveEvent { .. } => {
+            let event = ost.get_event_by_key(key).ok_or_else(missing)?;
+            ost.remove_event(event)
+        }
+        Mutation::ModifyMeasurement {
+            measures,
+            time_stamp,
+            ..
+        } => {
+            let mut measurement = ost.get_measurement_by_key(key).ok_or_else(missing)?;
+            measurement.modify_measurement(*measures, *time_stamp);
+            ost.modify_measurement(&measurement)
+        }
+        Mutation::RemoveMeasurement { .. } => {
+            let measurement = ost.get_measurement_by_key(key).ok_or_else(missing)?;
+            ost.remove_measurement(measurement)
+        }
+        _ => Err(OstError::Validation(
+            "Not a modification or removal".to_string(),
+        )),
+    }
+}
+
+fn holds(ost: &dyn Context, key: &OstEventKey) -> bool {
+    match key.t {
+        EntryType::Feed => ost.get_feeding_by_key(key).is_some(),
+        EntryType::Expulsion => ost.get_expulsion_by_key(key).is_some(),
+        EntryType::Event => ost.get_event_by_key(key).is_some(),
+        EntryType::Measurement => ost.get_measurement_by_key(key).is_some(),
+    }
+}
+
+pub fn load_outbox() -> Outbox {
+    LocalStorage::get(household_key(OUTBOX_KEY)).unwrap_or_default()
+}
+
+pub fn store_outbox(outbox: &Outbox) -> Result<(), OstError> {
+    LocalStorage::set(household_key(OUTBOX_KEY), outbox)
+        .map_err(|err| OstError::PersistenceIo(err.to_string()))
+}
+
+/// The nursing timers still running, one per person at most
+pub fn load_running_nursing() -> Vec<RunningNursing> {
+    LocalStorage::get(household_key(NURSING_KEY)).unwrap_or_default()
+}
+
+/// Replaces the person's running timer, `None` drops it
+pub fn store_running_nursing(
+    person_key: OstPersonKey,
+    running: Option<&RunningNursing>,
+) -> Result<(), OstError> {
+    let mut all = load_running_nursing();
+    all.retain(|r| r.person_key != person_key);
+    all.extend(running.cloned());
+    LocalStorage::set(household_key(NURSING_KEY), all)
+        .map_err(|err| OstError::PersistenceIo(err.to_string()))
+}
+
+pub struct Replica {
+    pub context: Box<dyn Context>,
+    /// The additions still waiting in the outbox
+    pub pending: Vec<QueuedEntry>,
+    /// False when the backend didn't answer and the local copy was used
+    pub is_online: bool,
+}
+
+/// The backend's data, or the copy kept the last time it answered, with the outbox on top.
+/// Only an unreachable backend falls back to the copy, other errors are returned.
+pub async fn load_replica(remote: &AsyncRemoteMonolith) -> Result<Replica, OstError> {
+    let storage_key = household_key(REPLICA_KEY);
+    let (document, is_online) = match remote.snapshot().await {
+        Ok(document) => {
+            let _ignore = store_document(&storage_key, &document);
+            (document, true)
+        }
+        Err(OstError::Transport(_)) => (load_document(&storage_key).unwrap_or_default(), false),
+        Err(err) => return Err(err),
+    };
+
+    let mut context = document.into_monolith(&storage_key, keep_in_memory);
+    // The outbox entries must not take the ids of the backend's entries
+    context.repair_duplicated_ids();
+    let pending = load_outbox().apply_to(&mut context);
+    Ok(Replica {
+        context: Box::new(context),
+        pending,
+        is_online,
+    })
+}
@@ -1,5 +1,8 @@
 use std::{any::Any, fmt::Debug};
 
+use chrono::prelude::*;
+use serde_derive::{Deserialize, Serialize};
+
 use crate::person_key::OstPersonKey;
 
 use crate::entity_impl::person_impl::PersonImpl;
@@ -13,6 +16,30 @@ pub trait Person: Debug {
 
     fn set_name(&mut self, name: &str);
 
+    fn birth_date(&self) -> Option<NaiveDate>;
+
+    fn set_birth_date(&mut self, birth_date: Option<NaiveDate>);
+
+    fn sex(&self) -> Option<Sex>;
+
+    fn set_sex(&mut self, sex: Option<Sex>);
+
+    /// In grams
+    fn birth_weight(&self) -> Option<u32>;
+
+    fn set_birth_weight(&mut self, birth_weight: Option<u32>);
+
+    /// A css colour like `#3273dc`, tells the person apart in lists and charts
+    fn colour(&self) -> Option<&str>;
+
+    fn set_colour(&mut self, colour: Option<&str>);
+
+    /// None without a birth date, or on a day before it
+    fn age_on(&self, date: NaiveDate) -> Option<Age> {
+        let days = date.signed_duration_since(self.birth_date()?).num_days();
+        u32::try_from(days).ok().map(|days| Age { days })
+    }
+
     // https://bennetthardwick.com/rust/downcast-trait-object/
     fn as_any(&self) -> &dyn Any;
 
@@ -21,6 +48,31 @@ pub trait Person: Debug {
     fn key(&self) -> OstPersonKey;
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Sex {
+    Female,
+    Male,
+}
+
+/// Time since birth, day 0 is the birth date
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Age {
+    pub days: u32,
+}
+
+impl Age {
+    /// Completed weeks, a baby is in week 0 until it's seven days old
+    pub fn weeks(&self) -> u32 {
+        self.days / 7
+    }
+}
+
+impl std::fmt::Display for Age {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "week {}", self.weeks())
+    }
+}
+
 pub fn deserialize(json: &str) -> Result<Box<dyn Person>, String> {
     let deserialized_person: PersonImpl = match serde_json::from_str(json) {
         Ok(p) => p,
@@ -0,0 +1,305 @@
+use chrono::prelude::*;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::entity_impl::event_impl::EventPersistence;
+use crate::entity_impl::expulsion_impl::ExpulsionPersistence;
+use crate::entity_impl::feed_impl::FeedPersistence;
+use crate::entity_impl::measurement_impl::MeasurementPersistence;
+use crate::entity_impl::person_impl::PersonImpl;
+use crate::entity_impl::remote_event_impl::RemoteEventImpl;
+use crate::entity_impl::remote_expulsion_impl::RemoteExpulsionImpl;
+use crate::entity_impl::remote_feed_impl::RemoteFeedImpl;
+use crate::entity_impl::remote_measurement_impl::RemoteMeasurementImpl;
+use crate::error::OstError;
+use crate::event::Event;
+use crate::event_base::EventBase;
+use crate::event_key::EventType;
+use crate::expulsion::Expulsion;
+use crate::feed::Feed;
+use crate::measurement::Measurement;
+use crate::person_key::OstPersonKey;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SortOrder {
+    #[default]
+    NewestFirst,
+    OldestFirst,
+}
+
+/// Every field narrows the result, the default query matches everything.
+/// `from` is inclusive and `to` exclusive.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct EventQuery {
+    pub person: Option<OstPersonKey>,
+    /// Empty means every kind
+    pub kinds: Vec<EventType>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub active_persons_only: bool,
+    pub sort: SortOrder,
+    pub offset: usize,
+    pub limit: Option<usize>,
+}
+
+/// The flat shape of `EventQuery` used in urls, `kinds` is a comma separated list.
+#[derive(Default, Serialize, Deserialize)]
+struct EventQueryParameters {
+    person: Option<u32>,
+    kinds: Option<String>,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    active_persons_only: Option<bool>,
+    sort: Option<SortOrder>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+}
+
+impl EventQuery {
+    pub fn to_query_string(&self) -> String {
+        let kinds: Vec<String> = self.kinds.iter().map(|k| k.to_string()).collect();
+        let parameters = EventQueryParameters {
+            person: self.person.map(|p| p.id),
+            kinds: if kinds.is_empty() {
+                None
+            } else {
+                Some(kinds.join(","))
+            },
+            from: self.from,
+            to: self.to,
+            active_persons_only: Some(self.active_persons_only),
+            sort: Some(self.sort),
+            offset: Some(self.offset),
+            limit: self.limit,
+        };
+        serde_urlencoded::to_string(&parameters).unwrap()
+    }
+
+    pub fn from_query_string(query_string: &str) -> Result<Self, OstError> {
+        let parameters: EventQueryParameters = match serde_urlencoded::from_str(query_string) {
+            Ok(parameters) => parameters,
+            Err(err) => return Err(OstError::Validation(err.to_string())),
+        };
+
+        let mut kinds = vec![];
+        for kind in parameters
+            .kinds
+            .unwrap_or_default()
+            .split(',')
+            .filter(|k| !k.is_empty())
+        {
+            kinds.push(match kind {
+                "Event" => EventType::Event,
+                "Expulsion" => EventType::Expulsion,
+                "Feed" => EventType::Feed,
+                "Measurement" => EventType::Measurement,
+                unknown => return Err(OstError::Validation(format!("Unknown kind: {}", unknown))),
+            });
+        }
+
+        Ok(Self {
+            person: parameters.person.map(|id| OstPersonKey { id }),
+            kinds,
+            from: parameters.from,
+            to: parameters.to,
+            active_persons_only: parameters.active_persons_only.unwrap_or_default(),
+            sort: parameters.sort.unwrap_or_default(),
+            offset: parameters.offset.unwrap_or_default(),
+            limit: parameters.limit,
+        })
+    }
+
+    pub fn includes_kind(&self, kind: EventType) -> bool {
+        self.kinds.is_empty() || self.kinds.contains(&kind)
+    }
+
+    pub(crate) fn matches(
+        &self,
+        kind: EventType,
+        person: &PersonImpl,
+        time_stamp: &DateTime<Utc>,
+    ) -> bool {
+        if !self.includes_kind(kind) || (self.active_persons_only && !person.is_active) {
+            return false;
+        }
+        if let Some(p) = self.person {
+            if p.id != person.id {
+                return false;
+            }
+        }
+        if let Some(from) = self.from {
+            if *time_stamp < from {
+                return false;
+            }
+        }
+        if let Some(to) = self.to {
+            if *time_stamp >= to {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Sorts the already filtered `items` and keeps the requested page
+    pub(crate) fn page(&self, mut items: Vec<QueryItem>) -> QueryResult {
+        let total = items.len();
+        items.sort_by(|a, b| {
+            let a = a.event_base();
+            let b = b.event_base();
+            (a.time_stamp(), a.key().t.to_string(), a.key().id).cmp(&(
+                b.time_stamp(),
+                b.key().t.to_string(),
+                b.key().id,
+            ))
+        });
+        if self.sort == SortOrder::NewestFirst {
+            items.reverse();
+        }
+
+        let items = items
+            .into_iter()
+            .skip(self.offset)
+            .take(self.limit.unwrap_or(usize::MAX))
+            .collect();
+        QueryResult { items, total }
+    }
+}
+
+pub enum QueryItem {
+    Feed(Box<dyn Feed>),
+    Expulsion(Box<dyn Expulsion>),
+    Event(Box<dyn Event>),
+    Measurement(Box<dyn Measurement>),
+}
+
+impl QueryItem {
+    pub fn event_base(&self) -> &dyn EventBase {
+        match self {
+            QueryItem::Feed(feed) => feed.as_ref(),
+            QueryItem::Expulsion(expulsion) => expulsion.as_ref(),
+            QueryItem::Event(event) => event.as_ref(),
+            QueryItem::Measurement(measurement) => measurement.as_ref(),
+        }
+    }
+
+    pub fn into_event_base(self) -> Box<dyn EventBase> {
+        match self {
+            QueryItem::Feed(feed) => feed,
+            QueryItem::Expulsion(expulsion) => expulsion,
+            QueryItem::Event(event) => event,
+            QueryItem::Measurement(measurement) => measurement,
+        }
+    }
+}
+
+/// One page of a query, `total` counts every match regardless of `offset` and `limit`
+pub struct QueryResult {
+    pub items: Vec<QueryItem>,
+    pub total: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+struct QueryResultPayload {
+    items: Vec<(EventType, String)>,
+    total: usize,
+}
+
+impl QueryResult {
+    pub fn serialize(&self) -> String {
+        let items = self
+            .items
+            .iter()
+            .map(|item| match item {
+                QueryItem::Feed(feed) => (EventType::Feed, feed.serialize()),
+                QueryItem::Expulsion(expulsion) => (EventType::Expulsion, expulsion.serialize()),
+                QueryItem::Event(event) => (EventType::Event, event.serialize()),
+                QueryItem::Measurement(measurement) => {
+                    (EventType::Measurement, measurement.serialize())
+                }
+            })
+            .collect();
+        serde_json::to_string(&QueryResultPayload {
+            items,
+            total: self.total,
+        })
+        .unwrap()
+    }
+
+    /// Rebuilds the items of a serialized result as remote entities
+    pub(crate) fn deserialize(payload: &str, persons: &[PersonImpl]) -> Result<Self, OstError> {
+        let payload: QueryResultPayload = match serde_json::from_str(payload) {
+            Ok(payload) => payload,
+            Err(err) => return Err(err.into()),
+        };
+
+        let find_person = |person_id: u32| match persons.iter().find(|p| p.id == person_id) {
+            Some(p) => Ok(p.clone()),
+            None => Err(OstError::NotFound(format!(
+                "No person with id {} found",
+                person_id
+            ))),
+        };
+
+        let mut items = vec![];
+        for (kind, serialized) in payload.items.iter() {
+            let item = match kind {
+                EventType::Feed => {
+                    let feed: FeedPersistence = match serde_json::from_str(serialized) {
+                        Ok(feed) => feed,
+                        Err(err) => return Err(err.into()),
+                    };
+                    QueryItem::Feed(Box::new(RemoteFeedImpl {
+                        id: feed.id,
+                        breast_milk: feed.breast_milk,
+                        formula: feed.formula,
+                        solids: feed.solids,
+                        nursing: feed.nursing.clone(),
+                        time_stamp: feed.time_stamp,
+                        person: find_person(feed.person_id)?,
+                    }))
+                }
+                EventType::Expulsion => {
+                    let expulsion: ExpulsionPersistence = match serde_json::from_str(serialized) {
+                        Ok(expulsion) => expulsion,
+                        Err(err) => return Err(err.into()),
+                    };
+                    QueryItem::Expulsion(Box::new(RemoteExpulsionImpl {
+                        id: expulsion.id,
+                        degree: expulsion.degree,
+                        time_stamp: expulsion.time_stamp,
+                        person: find_person(expulsion.person_id)?,
+                    }))
+                }
+                EventType::Event => {
+                    let event: EventPersistence = match serde_json::from_str(serialized) {
+                        Ok(event) => event,
+                        Err(err) => return Err(err.into()),
+                    };
+                    QueryItem::Event(Box::new(RemoteEventImpl {
+                        id: event.id,
+                        time_stamp: event.time_stamp,
+                        person: find_person(event.person_id)?,
+                        event: event.event,
+                    }))
+                }
+                EventType::Measurement => {
+                    let measurement: MeasurementPersistence = match serde_json::from_str(serialized)
+                    {
+                        Ok(measurement) => measurement,
+                        Err(err) => return Err(err.into()),
+                    };
+                    let person = find_person(measurement.person_id)?;
+                    QueryItem::Measurement(Box::new(RemoteMeasurementImpl::from_persistence(
+                        measurement,
+                        person,
+                    )))
+                }
+            };
+            items.push(item);
+        }
+
+        Ok(Self {
+            items,
+            total: payload.total,
+        })
+    }
+}
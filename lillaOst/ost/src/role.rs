@@ -0,0 +1,24 @@
+use serde_derive::{Deserialize, Serialize};
+
+/// What an account may do, each role includes everything the previous one can
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Role {
+    /// Reads everything, changes nothing
+    Viewer,
+    /// Logs and edits feedings, expulsions and events
+    Caregiver,
+    /// Manages persons, accounts and the admin operations.
+    /// Accounts from before roles existed had full access.
+    #[default]
+    Owner,
+}
+
+impl Role {
+    pub fn can_log(&self) -> bool {
+        *self >= Role::Caregiver
+    }
+
+    pub fn can_manage(&self) -> bool {
+        *self >= Role::Owner
+    }
+}
@@ -0,0 +1,59 @@
+use std::path::Path;
+
+use ost::context::construct_monolith;
+
+fn remove_all(mono_file: &str) {
+    for suffix in ["", ".tmp", ".bak", ".broken"] {
+        let _ignore_fail = std::fs::remove_file(format!("{}{}", mono_file, suffix));
+    }
+}
+
+#[test]
+fn persisting_leaves_no_temporary_file_behind() {
+    let mono_file: &str = "./test_output/atomic_persistence_00.json";
+    remove_all(mono_file);
+    {
+        let mut monolith_context = construct_monolith(mono_file).unwrap();
+        let _ = monolith_context.add_person("Zardoz").unwrap();
+    }
+    assert!(Path::new(mono_file).exists());
+    assert!(Path::new(&format!("{}.bak", mono_file)).exists());
+    assert!(!Path::new(&format!("{}.tmp", mono_file)).exists());
+    remove_all(mono_file);
+}
+
+#[test]
+fn half_written_file_recovers_from_last_good_copy() {
+    let mono_file: &str = "./test_output/atomic_persistence_01.json";
+    remove_all(mono_file);
+    {
+        let mut monolith_context = construct_monolith(mono_file).unwrap();
+        let zardoz = monolith_context.add_person("Zardoz").unwrap();
+        let _ = monolith_context.add_feeding(&zardoz, 1, 1, 1).unwrap();
+        let _ = monolith_context.add_feeding(&zardoz, 2, 2, 2).unwrap();
+    }
+    {
+        let payload = std::fs::read_to_string(mono_file).unwrap();
+        std::fs::write(mono_file, &payload[..payload.len() / 2]).unwrap();
+    }
+    {
+        let monolith_context = construct_monolith(mono_file).unwrap();
+        assert_eq!(monolith_context.persons().len(), 1);
+        assert_eq!(monolith_context.feedings().len(), 1);
+        assert!(Path::new(&format!("{}.broken", mono_file)).exists());
+    }
+    {
+        let monolith_context = construct_monolith(mono_file).unwrap();
+        assert_eq!(monolith_context.feedings().len(), 1);
+    }
+    remove_all(mono_file);
+}
+
+#[test]
+fn half_written_file_without_copies_is_an_error() {
+    let mono_file: &str = "./test_output/atomic_persistence_02.json";
+    remove_all(mono_file);
+    std::fs::write(mono_file, "{ \"persons\": [").unwrap();
+    assert!(construct_monolith(mono_file).is_err());
+    remove_all(mono_file);
+}
@@ -0,0 +1,51 @@
+use std::sync::{Arc, RwLock};
+use std::thread;
+
+use ost::context::{construct_monolith_in_memory, construct_sqlite, Context};
+
+fn read_from_threads(context: Box<dyn Context>) {
+    let context = Arc::new(RwLock::new(context));
+    {
+        let mut ost = context.write().unwrap();
+        let zardoz = ost.add_person("Zardoz").unwrap();
+        ost.add_feeding(&zardoz, 1, 2, 3).unwrap();
+    }
+
+    let readers: Vec<_> = (0..4)
+        .map(|_| {
+            let context = context.clone();
+            thread::spawn(move || {
+                let ost = context.read().unwrap();
+                (ost.persons().len(), ost.feedings()[0].person_name())
+            })
+        })
+        .collect();
+    for reader in readers {
+        assert_eq!(reader.join().unwrap(), (1, "Zardoz".to_string()));
+    }
+
+    let writer = {
+        let context = context.clone();
+        thread::spawn(move || {
+            let mut ost = context.write().unwrap();
+            let mut zardoz = ost.persons().remove(0);
+            zardoz.set_name("Zed");
+            ost.modify_person(&zardoz).unwrap();
+        })
+    };
+    writer.join().unwrap();
+    assert_eq!(context.read().unwrap().feedings()[0].person_name(), "Zed");
+}
+
+#[test]
+fn monolith_can_be_shared_between_threads() {
+    read_from_threads(construct_monolith_in_memory().unwrap());
+}
+
+#[test]
+fn sqlite_can_be_shared_between_threads() {
+    let db_file: &str = "./test_output/concurrent_reads_00.sqlite";
+    let _ignore_fail = std::fs::remove_file(db_file);
+    read_from_threads(construct_sqlite(db_file).unwrap());
+    let _ignore_fail = std::fs::remove_file(db_file);
+}
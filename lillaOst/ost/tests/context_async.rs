@@ -0,0 +1,44 @@
+use ost::context::construct_monolith_in_memory;
+use ost::context_async::{AsyncAdapter, AsyncContext};
+use ost::event::EventType;
+use ost::expulsion::ExpulsionDegree;
+
+#[tokio::test]
+async fn adapter_reaches_the_wrapped_context() {
+    let ost: Box<dyn AsyncContext> =
+        Box::new(AsyncAdapter::new(construct_monolith_in_memory().unwrap()));
+    let person = ost.add_person("Tiny").await.unwrap();
+
+    let feed = ost.add_feeding(&person, 60, 0, 0).await.unwrap();
+    ost.add_expulsion(&person, ExpulsionDegree::Pee)
+        .await
+        .unwrap();
+    ost.add_event(&person, EventType::Note("Hiccups".to_string()))
+        .await
+        .unwrap();
+
+    assert_eq!(ost.persons().await.len(), 1);
+    assert_eq!(ost.feedings_by(&person).await.len(), 1);
+    assert_eq!(ost.expulsions().await.len(), 1);
+    assert_eq!(ost.events().await.len(), 1);
+    assert_eq!(
+        ost.get_feeding_by_key(&feed.key())
+            .await
+            .unwrap()
+            .breast_milk(),
+        60
+    );
+
+    ost.remove_person(person, true).await.unwrap();
+    assert!(ost.persons().await.is_empty());
+    assert!(ost.feedings().await.is_empty());
+}
+
+#[tokio::test]
+async fn adapter_hands_back_the_context() {
+    let adapter = AsyncAdapter::new(construct_monolith_in_memory().unwrap());
+    adapter.add_fake_persons(2).await.unwrap();
+
+    let ost = adapter.into_inner();
+    assert_eq!(ost.persons().len(), 2);
+}
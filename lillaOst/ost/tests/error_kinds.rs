@@ -0,0 +1,73 @@
+use ost::context::{construct_monolith, construct_monolith_in_memory, construct_sqlite, Context};
+use ost::error::OstError;
+use ost::event::EventType;
+use ost::expulsion::ExpulsionDegree;
+
+fn assert_error_kinds(context: &mut dyn Context) {
+    let zardoz = context.add_person("Zardoz").unwrap();
+    let _ = context.add_feeding(&zardoz, 1, 1, 1).unwrap();
+
+    assert!(matches!(
+        context.add_person("Zardoz"),
+        Err(OstError::DuplicateName(_))
+    ));
+    assert!(matches!(
+        context.merge_persons(context.get_person_by_key(zardoz.key()).unwrap(), &zardoz),
+        Err(OstError::Validation(_))
+    ));
+    assert!(matches!(
+        context.remove_person(context.get_person_by_key(zardoz.key()).unwrap(), false),
+        Err(OstError::Validation(_))
+    ));
+
+    let removed = context.add_person("Removed").unwrap();
+    let removed_copy = context.get_person_by_key(removed.key()).unwrap();
+    context.remove_person(removed, false).unwrap();
+    assert!(matches!(
+        context.add_feeding(&removed_copy, 1, 1, 1),
+        Err(OstError::NotFound(_))
+    ));
+    assert!(matches!(
+        context.add_expulsion(&removed_copy, ExpulsionDegree::Poopies),
+        Err(OstError::NotFound(_))
+    ));
+    assert!(matches!(
+        context.add_event(&removed_copy, EventType::Temperature(37.5)),
+        Err(OstError::NotFound(_))
+    ));
+    assert!(context.feedings_by(&removed_copy).is_empty());
+    assert!(context.expulsions_by(&removed_copy).is_empty());
+    assert!(context.events_by(&removed_copy).is_empty());
+    assert!(matches!(
+        context.remove_person(removed_copy, false),
+        Err(OstError::NotFound(_))
+    ));
+}
+
+#[test]
+fn monolith_reports_typed_errors() {
+    let mut context = construct_monolith_in_memory().unwrap();
+    assert_error_kinds(context.as_mut());
+}
+
+#[test]
+fn sqlite_reports_typed_errors() {
+    let db_file: &str = "./test_output/error_kinds_00.sqlite";
+    let _ignore_fail = std::fs::remove_file(db_file);
+    {
+        let mut context = construct_sqlite(db_file).unwrap();
+        assert_error_kinds(context.as_mut());
+    }
+    let _ignore_fail = std::fs::remove_file(db_file);
+}
+
+#[test]
+fn unreadable_persistence_is_a_serialization_error() {
+    let mono_file: &str = "./test_output/error_kinds_01.json";
+    std::fs::write(mono_file, r#"{"schema_version": "two"}"#).unwrap();
+    assert!(matches!(
+        construct_monolith(mono_file),
+        Err(OstError::Serialization(_))
+    ));
+    let _ignore_fail = std::fs::remove_file(mono_file);
+}
@@ -0,0 +1,126 @@
+use std::collections::HashSet;
+
+use ost::context::{construct_monolith, construct_monolith_from_file};
+
+static MONOLITHDUPLICATEDIDS: &str = "./test_data/monolith_duplicated_ids.json";
+
+#[test]
+fn removed_ids_are_never_handed_out_again() {
+    let mono_file: &str = "./test_output/id_allocation_00.json";
+    let _ignore_fail = std::fs::remove_file(mono_file);
+    {
+        let mut monolith_context = construct_monolith(mono_file).unwrap();
+        let zardoz = monolith_context.add_person("Zardoz").unwrap();
+        let _ = monolith_context.add_feeding(&zardoz, 1, 1, 1).unwrap();
+        let _ = monolith_context.add_feeding(&zardoz, 2, 2, 2).unwrap();
+
+        let mut feeds = monolith_context.feedings();
+        let first = feeds.remove(
+            feeds
+                .iter()
+                .position(|feed| feed.breast_milk() == 1)
+                .unwrap(),
+        );
+        monolith_context.remove_feeding(first).unwrap();
+        let _ = monolith_context.add_feeding(&zardoz, 3, 3, 3).unwrap();
+
+        let feeds = monolith_context.feedings();
+        let keys: HashSet<u32> = feeds.iter().map(|f| f.key().id).collect();
+        assert_eq!(keys.len(), 2, "two feedings share the same id");
+
+        let third = feeds.iter().find(|f| f.breast_milk() == 3).unwrap();
+        let retrieved = monolith_context.get_feeding_by_key(&third.key()).unwrap();
+        assert_eq!(retrieved.breast_milk(), 3);
+    }
+    let _ignore_fail = std::fs::remove_file(mono_file);
+}
+
+#[test]
+fn id_counters_persist_between_executions() {
+    let mono_file: &str = "./test_output/id_allocation_01.json";
+    let _ignore_fail = std::fs::remove_file(mono_file);
+    {
+        let mut monolith_context = construct_monolith(mono_file).unwrap();
+        let zardoz = monolith_context.add_person("Zardoz").unwrap();
+        let _ = monolith_context.add_feeding(&zardoz, 1, 1, 1).unwrap();
+        let _ = monolith_context.add_feeding(&zardoz, 2, 2, 2).unwrap();
+        let feeds = monolith_context.feedings();
+        for feed in feeds {
+            monolith_context.remove_feeding(feed).unwrap();
+        }
+    }
+    {
+        let mut monolith_context = construct_monolith(mono_file).unwrap();
+        let zardoz = monolith_context.persons().pop().unwrap();
+        let feed = monolith_context.add_feeding(&zardoz, 3, 3, 3).unwrap();
+        assert_eq!(feed.key().id, 2);
+    }
+    let _ignore_fail = std::fs::remove_file(mono_file);
+}
+
+#[test]
+fn duplicated_ids_are_repaired_on_load() {
+    let mono_file: &str = "./test_output/id_allocation_02.json";
+    let _ignore_fail = std::fs::remove_file(mono_file);
+    {
+        let monolith_context =
+            construct_monolith_from_file(MONOLITHDUPLICATEDIDS, mono_file).unwrap();
+
+        let feed_ids: HashSet<u32> = monolith_context
+            .feedings()
+            .iter()
+            .map(|f| f.key().id)
+            .collect();
+        assert_eq!(feed_ids.len(), 3);
+
+        let expulsion_ids: HashSet<u32> = monolith_context
+            .expulsions()
+            .iter()
+            .map(|e| e.key().id)
+            .collect();
+        assert_eq!(expulsion_ids.len(), 2);
+
+        let event_ids: HashSet<u32> = monolith_context
+            .events()
+            .iter()
+            .map(|e| e.key().id)
+            .collect();
+        assert_eq!(event_ids.len(), 2);
+    }
+    {
+        let mut monolith_context = construct_monolith(mono_file).unwrap();
+        let zardoz = monolith_context.persons().pop().unwrap();
+        let feed = monolith_context.add_feeding(&zardoz, 4, 4, 4).unwrap();
+        let feed_ids: HashSet<u32> = monolith_context
+            .feedings()
+            .iter()
+            .map(|f| f.key().id)
+            .collect();
+        assert_eq!(feed_ids.len(), 4);
+        assert!(feed.key().id > 2);
+    }
+    let _ignore_fail = std::fs::remove_file(mono_file);
+}
+
+#[test]
+fn duplicated_person_ids_are_repaired_on_load() {
+    let mono_file: &str = "./test_output/id_allocation_03.json";
+    let _ignore_fail = std::fs::remove_file(mono_file);
+    {
+        let monolith_context =
+            construct_monolith_from_file("./test_data/monolith_broken_integrity.json", mono_file)
+                .unwrap();
+        let persons = monolith_context.persons();
+        let person_ids: HashSet<u32> = persons.iter().map(|p| p.key().id).collect();
+        assert_eq!(person_ids.len(), persons.len());
+
+        // The event of person 1 stays with the first person persisted with that id
+        let event = monolith_context.events().pop().unwrap();
+        assert_eq!(event.person_key().id, 1);
+        assert_eq!(event.person_name(), "Zardoz");
+        let zed = persons.iter().find(|p| p.name() == "Zed").unwrap();
+        assert!(zed.key().id > 1);
+    }
+    let _ignore_fail = std::fs::remove_file(mono_file);
+    let _ignore_fail = std::fs::remove_file(format!("{}.bak", mono_file));
+}
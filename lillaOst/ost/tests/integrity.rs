@@ -0,0 +1,142 @@
+use ost::context::{construct_monolith, construct_monolith_from_file};
+use ost::event_key::{EventType, OstEventKey};
+use ost::integrity::{check_document, check_file, repair_document, repair_file, IntegrityIssue};
+
+static MONOLITHBROKENINTEGRITY: &str = "./test_data/monolith_broken_integrity.json";
+
+#[test]
+fn check_reports_every_issue() {
+    let issues = check_file(MONOLITHBROKENINTEGRITY).unwrap();
+
+    let feed_1 = OstEventKey {
+        t: EventType::Feed,
+        id: 1,
+    };
+    assert!(issues.contains(&IntegrityIssue::DuplicatePersonId { person_id: 1 }));
+    assert!(issues.contains(&IntegrityIssue::DuplicateId { key: feed_1 }));
+    assert!(issues.contains(&IntegrityIssue::DanglingPersonReference {
+        key: feed_1,
+        person_id: 7
+    }));
+    assert!(issues.contains(&IntegrityIssue::DuplicatePersonName {
+        name: "Zardoz".to_string(),
+        person_ids: vec![0, 1]
+    }));
+    assert!(issues.iter().any(|issue| matches!(
+        issue,
+        IntegrityIssue::FutureTimeStamp {
+            key: OstEventKey {
+                t: EventType::Expulsion,
+                id: 0
+            },
+            ..
+        }
+    )));
+    assert_eq!(issues.len(), 5);
+}
+
+#[test]
+fn repaired_document_has_no_issues() {
+    let payload = std::fs::read_to_string(MONOLITHBROKENINTEGRITY).unwrap();
+    let (repaired, issues) = repair_document(&payload).unwrap();
+    assert_eq!(issues.len(), 5);
+    assert!(check_document(&repaired).unwrap().is_empty());
+
+    let (_, issues) = repair_document(&repaired).unwrap();
+    assert!(issues.is_empty());
+}
+
+#[test]
+fn names_differing_in_case_or_blanks_are_duplicates() {
+    let payload = r#"{
+        "persons": [
+            { "id": 0, "name": "Ada", "is_active": true },
+            { "id": 1, "name": " ada ", "is_active": true },
+            { "id": 2, "name": "ADA", "is_active": true },
+            { "id": 3, "name": "Ada (2)", "is_active": true }
+        ],
+        "feeds": [],
+        "expulsions": [],
+        "events": []
+    }"#;
+    assert_eq!(
+        check_document(payload).unwrap(),
+        vec![IntegrityIssue::DuplicatePersonName {
+            name: "Ada".to_string(),
+            person_ids: vec![0, 1, 2]
+        }]
+    );
+
+    let (repaired, _) = repair_document(payload).unwrap();
+    assert!(check_document(&repaired).unwrap().is_empty());
+    let repaired: serde_json::Value = serde_json::from_str(&repaired).unwrap();
+    let names: Vec<&str> = repaired["persons"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| p["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["Ada", "ada (3)", "ADA (4)", "Ada (2)"]);
+}
+
+#[test]
+fn repair_file_keeps_the_data() {
+    let mono_file: &str = "./test_output/integrity_00.json";
+    let _ignore_fail = std::fs::remove_file(mono_file);
+    std::fs::copy(MONOLITHBROKENINTEGRITY, mono_file).unwrap();
+
+    assert_eq!(repair_file(mono_file).unwrap().len(), 5);
+    assert!(check_file(mono_file).unwrap().is_empty());
+    {
+        let monolith_context = construct_monolith(mono_file).unwrap();
+        let mut names: Vec<String> = monolith_context
+            .persons()
+            .iter()
+            .map(|p| p.name().to_string())
+            .collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec!["Unknown person 7", "Zardoz", "Zardoz (2)", "Zed"]
+        );
+        assert_eq!(monolith_context.feedings().len(), 2);
+        assert_eq!(monolith_context.expulsions().len(), 1);
+        assert_eq!(monolith_context.events().len(), 1);
+    }
+    let _ignore_fail = std::fs::remove_file(mono_file);
+    let _ignore_fail = std::fs::remove_file(format!("{}.bak", mono_file));
+}
+
+#[test]
+fn dangling_references_do_not_prevent_loading() {
+    let mono_file: &str = "./test_output/integrity_01.json";
+    let _ignore_fail = std::fs::remove_file(mono_file);
+    {
+        let mut monolith_context =
+            construct_monolith_from_file(MONOLITHBROKENINTEGRITY, mono_file).unwrap();
+        let placeholder = monolith_context
+            .persons()
+            .into_iter()
+            .find(|p| p.name() == "Unknown person 7")
+            .unwrap();
+        assert!(!placeholder.is_active());
+
+        // The placeholder only stands in, the reference is still reported
+        assert!(monolith_context
+            .check_integrity()
+            .unwrap()
+            .iter()
+            .any(|issue| matches!(
+                issue,
+                IntegrityIssue::DanglingPersonReference { person_id: 7, .. }
+            )));
+        assert!(!monolith_context.repair_integrity().unwrap().is_empty());
+        assert!(monolith_context.check_integrity().unwrap().is_empty());
+    }
+    {
+        let monolith_context = construct_monolith(mono_file).unwrap();
+        assert!(monolith_context.check_integrity().unwrap().is_empty());
+    }
+    let _ignore_fail = std::fs::remove_file(mono_file);
+    let _ignore_fail = std::fs::remove_file(format!("{}.bak", mono_file));
+}
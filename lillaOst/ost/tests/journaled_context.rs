@@ -0,0 +1,222 @@
+use std::path::Path;
+
+use chrono::{prelude::*, Duration};
+
+use ost::context::{construct_monolith, construct_monolith_journaled};
+use ost::offline::{ReplayId, ReplayMark};
+
+fn remove_all(mono_file: &str) {
+    for suffix in ["", ".journal", ".tmp", ".bak"] {
+        let _ignore_fail = std::fs::remove_file(format!("{}{}", mono_file, suffix));
+    }
+}
+
+#[test]
+fn mutations_are_replayed_from_the_journal() {
+    let mono_file: &str = "./test_output/journaled_context_00.json";
+    remove_all(mono_file);
+    {
+        let mut monolith_context = construct_monolith_journaled(mono_file).unwrap();
+        let zardoz = monolith_context.add_person("Zardoz").unwrap();
+        let _ = monolith_context.add_feeding(&zardoz, 1, 1, 1).unwrap();
+        let _ = monolith_context.add_feeding(&zardoz, 2, 2, 2).unwrap();
+        let _ = monolith_context.add_feeding(&zardoz, 3, 3, 3).unwrap();
+
+        let feeds = monolith_context.feedings();
+        let to_remove = feeds.into_iter().find(|f| f.breast_milk() == 2).unwrap();
+        monolith_context.remove_feeding(to_remove).unwrap();
+    }
+    assert!(Path::new(&format!("{}.journal", mono_file)).exists());
+    {
+        let snapshot = construct_monolith(mono_file).unwrap();
+        assert!(snapshot.feedings().is_empty(), "the snapshot got rewritten");
+    }
+    {
+        let monolith_context = construct_monolith_journaled(mono_file).unwrap();
+        assert_eq!(monolith_context.persons().len(), 1);
+        let feeds = monolith_context.feedings();
+        assert_eq!(feeds.len(), 2);
+        assert!(feeds.iter().all(|f| f.breast_milk() != 2));
+    }
+    remove_all(mono_file);
+}
+
+#[test]
+fn compaction_folds_the_journal_into_the_snapshot() {
+    let mono_file: &str = "./test_output/journaled_context_01.json";
+    remove_all(mono_file);
+    {
+        let mut monolith_context = construct_monolith_journaled(mono_file).unwrap();
+        monolith_context.add_fake_persons(2).unwrap();
+        monolith_context.add_fake_feedings(20).unwrap();
+        monolith_context.add_fake_expulsions(20).unwrap();
+        monolith_context.add_fake_events(20).unwrap();
+        monolith_context.compact_storage().unwrap();
+    }
+    assert!(!Path::new(&format!("{}.journal", mono_file)).exists());
+    {
+        let snapshot = construct_monolith(mono_file).unwrap();
+        assert_eq!(snapshot.feedings().len(), 20);
+        assert_eq!(snapshot.expulsions().len(), 20);
+        assert_eq!(snapshot.events().len(), 20);
+    }
+    {
+        let mut monolith_context = construct_monolith_journaled(mono_file).unwrap();
+        let person = monolith_context.persons().pop().unwrap();
+        let feed = monolith_context.add_feeding(&person, 1, 1, 1).unwrap();
+        assert_eq!(feed.key().id, 20);
+    }
+    remove_all(mono_file);
+}
+
+#[test]
+fn torn_last_record_is_ignored() {
+    let mono_file: &str = "./test_output/journaled_context_02.json";
+    remove_all(mono_file);
+    {
+        let mut monolith_context = construct_monolith_journaled(mono_file).unwrap();
+        let zardoz = monolith_context.add_person("Zardoz").unwrap();
+        let _ = monolith_context.add_feeding(&zardoz, 1, 1, 1).unwrap();
+    }
+    {
+        let journal = format!("{}.journal", mono_file);
+        let mut payload = std::fs::read_to_string(&journal).unwrap();
+        payload.push_str("{\"FeedUpserted\":{\"id\":");
+        std::fs::write(&journal, payload).unwrap();
+    }
+    {
+        let monolith_context = construct_monolith_journaled(mono_file).unwrap();
+        assert_eq!(monolith_context.feedings().len(), 1);
+    }
+    remove_all(mono_file);
+}
+
+#[test]
+fn removed_and_merged_persons_are_replayed() {
+    let mono_file: &str = "./test_output/journaled_context_03.json";
+    remove_all(mono_file);
+    {
+        let mut monolith_context = construct_monolith_journaled(mono_file).unwrap();
+        let ana = monolith_context.add_person("Ana").unwrap();
+        let duplicate = monolith_context.add_person("ana").unwrap();
+        let mistake = monolith_context.add_person("Mistake").unwrap();
+        let _ = monolith_context.add_feeding(&duplicate, 1, 1, 1).unwrap();
+        let _ = monolith_context.add_feeding(&mistake, 2, 2, 2).unwrap();
+
+        monolith_context.merge_persons(duplicate, &ana).unwrap();
+        monolith_context.remove_person(mistake, true).unwrap();
+    }
+    {
+        let monolith_context = construct_monolith_journaled(mono_file).unwrap();
+        assert_eq!(monolith_context.persons().len(), 1);
+        let feeds = monolith_context.feedings();
+        assert_eq!(feeds.len(), 1);
+        assert_eq!(feeds[0].person_name(), "Ana");
+    }
+    remove_all(mono_file);
+}
+
+#[test]
+fn records_that_failed_to_append_are_appended_with_the_next_mutation() {
+    let mono_file: &str = "./test_output/journaled_context_04.json";
+    let journal = format!("{}.journal", mono_file);
+    let _ignore_fail = std::fs::remove_dir(&journal);
+    remove_all(mono_file);
+    {
+        let mut monolith_context = construct_monolith_journaled(mono_file).unwrap();
+        let zardoz = monolith_context.add_person("Zardoz").unwrap();
+        monolith_context.compact_storage().unwrap();
+
+        // The journal can't be opened as a file
+        std::fs::create_dir(&journal).unwrap();
+        assert!(monolith_context.add_feeding(&zardoz, 1, 1, 1).is_err());
+        std::fs::remove_dir(&journal).unwrap();
+
+        let _ = monolith_context.add_feeding(&zardoz, 2, 2, 2).unwrap();
+    }
+    {
+        let monolith_context = construct_monolith_journaled(mono_file).unwrap();
+        assert_eq!(monolith_context.feedings().len(), 2);
+    }
+    remove_all(mono_file);
+}
+
+#[test]
+fn a_journal_left_behind_by_a_compaction_is_skipped() {
+    let mono_file: &str = "./test_output/journaled_context_05.json";
+    let journal = format!("{}.journal", mono_file);
+    remove_all(mono_file);
+    {
+        let mut monolith_context = construct_monolith_journaled(mono_file).unwrap();
+        let zardoz = monolith_context.add_person("Zardoz").unwrap();
+        let _ = monolith_context.add_person("Zed").unwrap();
+        monolith_context.compact_storage().unwrap();
+
+        let _ = monolith_context.add_feeding(&zardoz, 1, 1, 1).unwrap();
+        monolith_context.remove_person(zardoz, true).unwrap();
+        let left_behind = std::fs::read_to_string(&journal).unwrap();
+
+        // Crashing after the new snapshot is written, before the journal is removed
+        monolith_context.compact_storage().unwrap();
+        std::fs::write(&journal, left_behind).unwrap();
+    }
+    {
+        let mut monolith_context = construct_monolith_journaled(mono_file).unwrap();
+        assert_eq!(monolith_context.persons().len(), 1);
+        assert!(monolith_context.feedings().is_empty());
+
+        let zed = monolith_context.persons().pop().unwrap();
+        let _ = monolith_context.add_feeding(&zed, 2, 2, 2).unwrap();
+    }
+    {
+        let monolith_context = construct_monolith_journaled(mono_file).unwrap();
+        assert_eq!(monolith_context.feedings().len(), 1);
+    }
+    remove_all(mono_file);
+}
+
+#[test]
+fn a_record_for_a_missing_person_fails_the_load() {
+    let mono_file: &str = "./test_output/journaled_context_06.json";
+    remove_all(mono_file);
+    {
+        let _ = construct_monolith_journaled(mono_file).unwrap();
+    }
+    std::fs::write(
+        format!("{}.journal", mono_file),
+        "{\"FeedUpserted\":{\"id\":0,\"time_stamp\":\"2022-01-01T00:00:00Z\",\"person_id\":1,\
+         \"breast_milk\":1,\"formula\":1,\"solids\":1}}\n",
+    )
+    .unwrap();
+    assert!(construct_monolith_journaled(mono_file).is_err());
+    remove_all(mono_file);
+}
+
+#[test]
+fn replay_marks_are_replayed_from_the_journal() {
+    let mono_file: &str = "./test_output/journaled_context_07.json";
+    remove_all(mono_file);
+    let replay_id = ReplayId {
+        client: "a-client".to_string(),
+        id: 4,
+    };
+    let key = {
+        let mut monolith_context = construct_monolith_journaled(mono_file).unwrap();
+        let zardoz = monolith_context.add_person("Zardoz").unwrap();
+        let recorded = Utc::now() - Duration::hours(2);
+        let feed = monolith_context
+            .add_feeding_at(&zardoz, 1, 1, 1, recorded, Some(&replay_id))
+            .unwrap();
+        assert_eq!(*feed.time_stamp(), recorded);
+        feed.key()
+    };
+    {
+        let monolith_context = construct_monolith_journaled(mono_file).unwrap();
+        assert_eq!(
+            monolith_context.replay_mark("a-client"),
+            Some(ReplayMark { id: 4, key })
+        );
+        assert_eq!(monolith_context.replay_mark("another-client"), None);
+    }
+    remove_all(mono_file);
+}
@@ -0,0 +1,181 @@
+use ost::context::{
+    construct_monolith, construct_monolith_journaled, construct_sqlite,
+    construct_sqlite_from_monolith, Context,
+};
+use ost::error::OstError;
+use ost::event_key::EventType as KeyType;
+use ost::growth::{self, GrowthIndicator, CHART_Z_SCORES};
+use ost::measurement::Measures;
+use ost::person::{Age, Sex};
+use ost::query::{EventQuery, QueryItem};
+
+type Construct = fn(&str) -> Result<Box<dyn Context>, OstError>;
+
+fn remove_all(file: &str) {
+    for suffix in ["", ".journal", ".tmp", ".bak"] {
+        let _ignore_fail = std::fs::remove_file(format!("{}{}", file, suffix));
+    }
+}
+
+fn weighed(grams: u32) -> Measures {
+    Measures {
+        weight: Some(grams),
+        ..Measures::default()
+    }
+}
+
+#[test]
+fn measurements_persist_in_every_storage() {
+    let storages: [(&str, Construct); 3] = [
+        ("./test_output/measurements_00.json", construct_monolith),
+        (
+            "./test_output/measurements_01.json",
+            construct_monolith_journaled,
+        ),
+        ("./test_output/measurements_02.sqlite", construct_sqlite),
+    ];
+    for (file, construct) in storages {
+        remove_all(file);
+        {
+            let mut context = construct(file).unwrap();
+            let zardoz = context.add_person("Zardoz").unwrap();
+            let mut measurement = context.add_measurement(&zardoz, weighed(3900)).unwrap();
+            let measures = Measures {
+                length: Some(52.5),
+                ..measurement.measures()
+            };
+            measurement.modify_measurement(measures, *measurement.time_stamp());
+            context.modify_measurement(&measurement).unwrap();
+        }
+        {
+            let mut context = construct(file).unwrap();
+            let measurements = context.measurements();
+            assert_eq!(measurements.len(), 1, "{}", file);
+            assert_eq!(measurements[0].measures().weight, Some(3900));
+            assert_eq!(measurements[0].measures().length, Some(52.5));
+            assert_eq!(measurements[0].key().t, KeyType::Measurement);
+
+            let by_key = context.get_measurement_by_key(&measurements[0].key());
+            assert_eq!(by_key.unwrap().measures(), measurements[0].measures());
+
+            let zardoz = context.persons().pop().unwrap();
+            assert_eq!(context.measurements_by(&zardoz).len(), 1);
+            let measurement = measurements.into_iter().next().unwrap();
+            context.remove_measurement(measurement).unwrap();
+            assert!(context.measurements().is_empty());
+        }
+        remove_all(file);
+    }
+}
+
+#[test]
+fn measures_without_a_value_are_refused() {
+    let db_file: &str = "./test_output/measurements_03.sqlite";
+    let _ignore_fail = std::fs::remove_file(db_file);
+    let mut context = construct_sqlite(db_file).unwrap();
+    let zardoz = context.add_person("Zardoz").unwrap();
+
+    let refused = [
+        Measures::default(),
+        weighed(0),
+        Measures {
+            head_circumference: Some(-35.0),
+            ..Measures::default()
+        },
+        Measures {
+            length: Some(f64::NAN),
+            ..Measures::default()
+        },
+    ];
+    for measures in refused {
+        assert!(matches!(
+            context.add_measurement(&zardoz, measures),
+            Err(OstError::Validation(_))
+        ));
+    }
+    assert!(context.measurements().is_empty());
+    let _ignore_fail = std::fs::remove_file(db_file);
+}
+
+#[test]
+fn measurements_are_queried_and_removed_with_their_person() {
+    let mono_file: &str = "./test_output/measurements_04.json";
+    let _ignore_fail = std::fs::remove_file(mono_file);
+    let mut context = construct_monolith(mono_file).unwrap();
+    let zardoz = context.add_person("Zardoz").unwrap();
+    context.add_feeding(&zardoz, 120, 0, 0).unwrap();
+    context.add_measurement(&zardoz, weighed(4100)).unwrap();
+
+    let query = EventQuery {
+        kinds: vec![KeyType::Measurement],
+        ..EventQuery::default()
+    };
+    let result = context.query(&query).unwrap();
+    assert_eq!(result.total, 1);
+    assert!(matches!(result.items[0], QueryItem::Measurement(_)));
+
+    context.remove_person(zardoz, true).unwrap();
+    assert!(context.measurements().is_empty());
+    let _ignore_fail = std::fs::remove_file(mono_file);
+}
+
+#[test]
+fn a_v3_monolith_imports_its_measurements_into_sqlite() {
+    let db_file: &str = "./test_output/measurements_05.sqlite";
+    let _ignore_fail = std::fs::remove_file(db_file);
+    let context = construct_sqlite_from_monolith("./test_data/schema/v3.json", db_file).unwrap();
+    let measurements = context.measurements();
+    assert_eq!(measurements.len(), 1);
+    assert_eq!(measurements[0].measures().weight, Some(4350));
+    assert_eq!(measurements[0].measures().length, Some(55.5));
+    let _ignore_fail = std::fs::remove_file(db_file);
+}
+
+#[test]
+fn the_who_median_is_the_50th_percentile() {
+    let at_birth = Age { days: 0 };
+    let z = growth::z_score(GrowthIndicator::WeightForAge, Sex::Male, at_birth, 3.3464).unwrap();
+    assert!(z.abs() < 1e-9);
+    assert!((growth::percentile(z) - 50.0).abs() < 1e-6);
+
+    // WHO lists 2.5 kg as -2 SD for boys at birth
+    let z = growth::z_score(GrowthIndicator::WeightForAge, Sex::Male, at_birth, 2.46).unwrap();
+    assert!((z + 2.0).abs() < 0.05);
+
+    assert!((growth::percentile(CHART_Z_SCORES[0]) - 3.0).abs() < 0.05);
+    assert!((growth::percentile(CHART_Z_SCORES[4]) - 97.0).abs() < 0.05);
+}
+
+#[test]
+fn ages_between_months_are_interpolated() {
+    let lms = growth::lms(GrowthIndicator::LengthForAge, Sex::Female, Age { days: 15 }).unwrap();
+    assert!(lms.m > 49.1477 && lms.m < 53.6872);
+    for z in CHART_Z_SCORES {
+        assert!((lms.z_score(lms.value_at(z)) - z).abs() < 1e-9);
+    }
+    assert!(growth::lms(
+        GrowthIndicator::LengthForAge,
+        Sex::Female,
+        Age { days: 366 }
+    )
+    .is_none());
+}
+
+#[test]
+fn only_measured_values_are_assessed() {
+    let measures = Measures {
+        weight: Some(6400),
+        head_circumference: Some(40.5),
+        ..Measures::default()
+    };
+    let assessments = growth::assess(Sex::Female, Age { days: 122 }, &measures);
+    let indicators: Vec<GrowthIndicator> = assessments.iter().map(|a| a.indicator).collect();
+    assert_eq!(
+        indicators,
+        vec![
+            GrowthIndicator::WeightForAge,
+            GrowthIndicator::HeadCircumferenceForAge
+        ]
+    );
+    assert!(assessments.iter().all(|a| a.z_score.abs() < 0.2));
+}
@@ -0,0 +1,181 @@
+use chrono::prelude::*;
+use chrono::Duration;
+
+use ost::context::{construct_monolith, construct_monolith_journaled, construct_sqlite, Context};
+use ost::error::OstError;
+use ost::nursing::{BreastSide, NursingSession, NursingStretch, RunningNursing};
+use ost::person_key::OstPersonKey;
+
+type Construct = fn(&str) -> Result<Box<dyn Context>, OstError>;
+
+fn remove_all(file: &str) {
+    for suffix in ["", ".journal", ".tmp", ".bak"] {
+        let _ignore_fail = std::fs::remove_file(format!("{}{}", file, suffix));
+    }
+}
+
+fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.ymd(2022, 3, 14).and_hms(hour, minute, 0)
+}
+
+fn stretch(side: BreastSide, start: DateTime<Utc>, end: DateTime<Utc>) -> NursingStretch {
+    NursingStretch { side, start, end }
+}
+
+/// Left from 10:00 to 10:12, then right until 10:20
+fn left_then_right() -> NursingSession {
+    NursingSession {
+        stretches: vec![
+            stretch(BreastSide::Left, at(10, 0), at(10, 12)),
+            stretch(BreastSide::Right, at(10, 12), at(10, 20)),
+        ],
+    }
+}
+
+#[test]
+fn nursing_persists_in_every_storage() {
+    let storages: [(&str, Construct); 3] = [
+        ("./test_output/nursing_00.json", construct_monolith),
+        (
+            "./test_output/nursing_01.json",
+            construct_monolith_journaled,
+        ),
+        ("./test_output/nursing_02.sqlite", construct_sqlite),
+    ];
+    for (file, construct) in storages {
+        remove_all(file);
+        {
+            let mut context = construct(file).unwrap();
+            let zardoz = context.add_person("Zardoz").unwrap();
+            let feed = context.add_nursing(&zardoz, left_then_right()).unwrap();
+            assert_eq!(*feed.time_stamp(), at(10, 0));
+            context.add_feeding(&zardoz, 0, 90, 0).unwrap();
+        }
+        {
+            let mut context = construct(file).unwrap();
+            let mut nursed: Vec<_> = context
+                .feedings()
+                .into_iter()
+                .filter(|f| f.nursing().is_some())
+                .collect();
+            assert_eq!(nursed.len(), 1, "{}", file);
+            let mut feed = nursed.pop().unwrap();
+            assert_eq!(feed.nursing(), Some(left_then_right()));
+
+            feed.modify_nursing(None);
+            context.modify_feeding(&feed).unwrap();
+            let modified = context.get_feeding_by_key(&feed.key()).unwrap();
+            assert!(modified.nursing().is_none(), "{}", file);
+        }
+        remove_all(file);
+    }
+}
+
+#[test]
+fn malformed_sessions_are_refused() {
+    let db_file: &str = "./test_output/nursing_03.sqlite";
+    let _ignore_fail = std::fs::remove_file(db_file);
+    let mut context = construct_sqlite(db_file).unwrap();
+    let zardoz = context.add_person("Zardoz").unwrap();
+
+    let refused = [
+        NursingSession::default(),
+        NursingSession {
+            stretches: vec![stretch(BreastSide::Left, at(10, 12), at(10, 0))],
+        },
+        NursingSession {
+            stretches: vec![
+                stretch(BreastSide::Left, at(10, 0), at(10, 12)),
+                stretch(BreastSide::Right, at(10, 10), at(10, 20)),
+            ],
+        },
+    ];
+    for nursing in refused {
+        assert!(matches!(
+            context.add_nursing(&zardoz, nursing),
+            Err(OstError::Validation(_))
+        ));
+    }
+    assert!(context.feedings().is_empty());
+    let _ignore_fail = std::fs::remove_file(db_file);
+}
+
+#[test]
+fn the_next_side_follows_the_latest_session() {
+    let mono_file: &str = "./test_output/nursing_04.json";
+    let _ignore_fail = std::fs::remove_file(mono_file);
+    let mut context = construct_monolith(mono_file).unwrap();
+    let zardoz = context.add_person("Zardoz").unwrap();
+    let yoda = context.add_person("Yoda").unwrap();
+    assert_eq!(context.next_nursing_side(&zardoz), None);
+
+    context.add_nursing(&zardoz, left_then_right()).unwrap();
+    context.add_feeding(&zardoz, 0, 90, 0).unwrap();
+    assert_eq!(context.next_nursing_side(&zardoz), Some(BreastSide::Right));
+
+    // Later, only on the right
+    let later = NursingSession {
+        stretches: vec![stretch(BreastSide::Right, at(13, 0), at(13, 15))],
+    };
+    context.add_nursing(&zardoz, later).unwrap();
+    assert_eq!(context.next_nursing_side(&zardoz), Some(BreastSide::Left));
+    assert_eq!(context.next_nursing_side(&yoda), None);
+    let _ignore_fail = std::fs::remove_file(mono_file);
+}
+
+#[test]
+fn the_suggestion_is_the_side_with_less_time() {
+    assert_eq!(NursingSession::default().suggested_next_side(), None);
+    assert_eq!(
+        left_then_right().duration_on(BreastSide::Left),
+        Duration::minutes(12)
+    );
+    assert_eq!(
+        left_then_right().suggested_next_side(),
+        Some(BreastSide::Right)
+    );
+
+    // Ten minutes each, the side not started on goes first next time
+    let even = NursingSession {
+        stretches: vec![
+            stretch(BreastSide::Right, at(10, 0), at(10, 5)),
+            stretch(BreastSide::Left, at(10, 5), at(10, 15)),
+            stretch(BreastSide::Right, at(10, 15), at(10, 20)),
+        ],
+    };
+    assert_eq!(even.suggested_next_side(), Some(BreastSide::Left));
+    assert_eq!(even.to_string(), "L 10m R 10m");
+}
+
+#[test]
+fn a_running_session_finishes_into_its_stretches() {
+    let person_key = OstPersonKey { id: 1 };
+    let mut running = RunningNursing::start(person_key, BreastSide::Left, at(10, 0));
+    assert_eq!(
+        running.elapsed_on(BreastSide::Left, at(10, 7)),
+        Duration::minutes(7)
+    );
+
+    running.switch_side(at(10, 12));
+    assert_eq!(running.side, BreastSide::Right);
+    assert_eq!(
+        running.elapsed_on(BreastSide::Left, at(10, 15)),
+        Duration::minutes(12)
+    );
+    assert_eq!(
+        running.elapsed_on(BreastSide::Right, at(10, 15)),
+        Duration::minutes(3)
+    );
+
+    // A clock going backwards doesn't make negative stretches
+    assert_eq!(
+        running.elapsed_on(BreastSide::Right, at(10, 11)),
+        Duration::zero()
+    );
+
+    let restored: RunningNursing =
+        serde_json::from_str(&serde_json::to_string(&running).unwrap()).unwrap();
+    let session = restored.finish(at(10, 20));
+    assert_eq!(session, left_then_right());
+    assert!(session.validate().is_ok());
+}
@@ -0,0 +1,179 @@
+use chrono::{prelude::*, Duration};
+
+use ost::context::construct_monolith_in_memory;
+use ost::event::EventType;
+use ost::expulsion::ExpulsionDegree;
+use ost::offline::{Mutation, Outbox, Target};
+use ost::person_key::OstPersonKey;
+
+#[test]
+fn outbox_keeps_the_recording_order() {
+    let person_key = OstPersonKey { id: 0 };
+    let mut outbox = Outbox::default();
+    let first = outbox.push(
+        Mutation::AddExpulsion {
+            person_key,
+            degree: ExpulsionDegree::Pee,
+        },
+        Utc::now(),
+    );
+    let second = outbox.push(
+        Mutation::AddEvent {
+            person_key,
+            event: EventType::Note("Slept through".to_string()),
+        },
+        Utc::now(),
+    );
+    assert_eq!(outbox.oldest().unwrap().id, first);
+
+    assert!(outbox.confirm(first));
+    assert!(!outbox.confirm(first));
+    assert_eq!(outbox.oldest().unwrap().id, second);
+
+    // Ids of confirmed entries aren't handed out again, not even after a reload
+    let mut reloaded: Outbox =
+        serde_json::from_str(&serde_json::to_string(&outbox).unwrap()).unwrap();
+    assert_eq!(reloaded, outbox);
+    let third = reloaded.push(
+        Mutation::AddExpulsion {
+            person_key,
+            degree: ExpulsionDegree::Poopies,
+        },
+        Utc::now(),
+    );
+    assert!(third > second);
+    assert_eq!(reloaded.len(), 2);
+}
+
+#[test]
+fn pending_entries_keep_their_time() {
+    let mut ost = construct_monolith_in_memory().unwrap();
+    let person = ost.add_person("Night owl").unwrap();
+    let recorded = Utc::now() - Duration::hours(3);
+
+    let mut outbox = Outbox::default();
+    outbox.push(
+        Mutation::AddFeeding {
+            person_key: person.key(),
+            breast_milk: 0,
+            formula: 90,
+            solids: 0,
+        },
+        recorded,
+    );
+    outbox.push(
+        Mutation::AddFeeding {
+            person_key: OstPersonKey { id: 404 },
+            breast_milk: 10,
+            formula: 0,
+            solids: 0,
+        },
+        recorded,
+    );
+
+    let pending = outbox.apply_to(ost.as_mut());
+    assert_eq!(pending.len(), 1);
+    let feed = ost.get_feeding_by_key(&pending[0].key).unwrap();
+    assert_eq!(*feed.time_stamp(), recorded);
+    assert_eq!(feed.formula(), 90);
+    assert_eq!(ost.feedings().len(), 1);
+}
+
+#[test]
+fn modifications_and_removals_reach_the_replica() {
+    let mut ost = construct_monolith_in_memory().unwrap();
+    let person = ost.add_person("Early bird").unwrap();
+    let saved = ost.add_feeding(&person, 0, 60, 0).unwrap().key();
+    let removed = ost
+        .add_expulsion(&person, ExpulsionDegree::Pee)
+        .unwrap()
+        .key();
+    let moved = Utc::now() - Duration::hours(1);
+
+    let mut outbox = Outbox::default();
+    outbox.push(
+        Mutation::ModifyFeeding {
+            target: Target::Saved(saved),
+            breast_milk: 0,
+            formula: 120,
+            solids: 0,
+            nursing: None,
+            time_stamp: moved,
+        },
+        Utc::now(),
+    );
+    outbox.push(
+        Mutation::RemoveExpulsion {
+            target: Target::Saved(removed),
+        },
+        Utc::now(),
+    );
+
+    let pending = outbox.apply_to(ost.as_mut());
+    assert!(pending.is_empty());
+    let feed = ost.get_feeding_by_key(&saved).unwrap();
+    assert_eq!(feed.formula(), 120);
+    assert_eq!(*feed.time_stamp(), moved);
+    assert!(ost.get_expulsion_by_key(&removed).is_none());
+}
+
+#[test]
+fn queued_entries_are_changed_before_and_after_they_are_saved() {
+    let mut ost = construct_monolith_in_memory().unwrap();
+    let person = ost.add_person("Night owl").unwrap();
+
+    let mut outbox = Outbox::default();
+    let added = outbox.push(
+        Mutation::AddEvent {
+            person_key: person.key(),
+            event: EventType::Note("Hiccups".to_string()),
+        },
+        Utc::now(),
+    );
+    outbox.push(
+        Mutation::ModifyEvent {
+            target: Target::Queued(added),
+            event: EventType::Note("Hiccups, twice".to_string()),
+            time_stamp: Utc::now(),
+        },
+        Utc::now(),
+    );
+    let gone = outbox.push(
+        Mutation::AddExpulsion {
+            person_key: person.key(),
+            degree: ExpulsionDegree::Pee,
+        },
+        Utc::now(),
+    );
+    outbox.push(
+        Mutation::RemoveExpulsion {
+            target: Target::Queued(gone),
+        },
+        Utc::now(),
+    );
+
+    let mut replica = construct_monolith_in_memory().unwrap();
+    replica.add_person("Night owl").unwrap();
+    let pending = outbox.apply_to(replica.as_mut());
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].id, added);
+    let event = replica.get_event_by_key(&pending[0].key).unwrap();
+    assert_eq!(event.event(), EventType::Note("Hiccups, twice".to_string()));
+    assert!(replica.expulsions().is_empty());
+
+    // Once the backend saved the addition, what was queued about it names the saved entry
+    let saved = ost
+        .add_event(&person, EventType::Note("Hiccups".to_string()))
+        .unwrap()
+        .key();
+    outbox.confirm(added);
+    outbox.resolve(added, saved);
+    assert_eq!(
+        outbox.oldest().unwrap().mutation.target(),
+        Some(Target::Saved(saved))
+    );
+    assert_eq!(
+        outbox.pending()[2].mutation.target(),
+        Some(Target::Queued(gone))
+    );
+}
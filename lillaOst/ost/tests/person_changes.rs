@@ -1,4 +1,7 @@
+use chrono::{Duration, NaiveDate};
+
 use ost::context::construct_monolith;
+use ost::person::Sex;
 
 #[test]
 fn person_changes_active() {
@@ -69,3 +72,36 @@ fn person_changes_propagate_to_feedings() {
 
     let _ignore_fail = std::fs::remove_file(mono_file);
 }
+
+#[test]
+fn person_profile_persists_and_gives_the_age() {
+    let mono_file: &str = "./test_output/person_changes_03.json";
+    let _ignore_fail = std::fs::remove_file(mono_file);
+    let birth_date = NaiveDate::from_ymd_opt(2021, 11, 4).unwrap();
+    {
+        let mut monolith_context = construct_monolith(mono_file).unwrap();
+        let mut zardoz = monolith_context.add_person("Zardoz").unwrap();
+        assert_eq!(zardoz.age_on(birth_date), None);
+
+        zardoz.set_birth_date(Some(birth_date));
+        zardoz.set_sex(Some(Sex::Female));
+        zardoz.set_birth_weight(Some(3400));
+        zardoz.set_colour(Some("#3273dc"));
+        monolith_context.modify_person(&zardoz).unwrap();
+    }
+    {
+        let monolith_context = construct_monolith(mono_file).unwrap();
+        let zardoz = monolith_context.persons().pop().unwrap();
+        assert_eq!(zardoz.birth_date(), Some(birth_date));
+        assert_eq!(zardoz.sex(), Some(Sex::Female));
+        assert_eq!(zardoz.birth_weight(), Some(3400));
+        assert_eq!(zardoz.colour(), Some("#3273dc"));
+
+        let six_weeks = zardoz.age_on(birth_date + Duration::days(44)).unwrap();
+        assert_eq!(six_weeks.days, 44);
+        assert_eq!(six_weeks.weeks(), 6);
+        assert_eq!(six_weeks.to_string(), "week 6");
+        assert_eq!(zardoz.age_on(birth_date - Duration::days(1)), None);
+    }
+    let _ignore_fail = std::fs::remove_file(mono_file);
+}
@@ -0,0 +1,140 @@
+use chrono::prelude::*;
+
+use ost::context::{construct_monolith, construct_sqlite, Context};
+use ost::event::EventType;
+use ost::event_key::{EventType as EventKind, OstEventKey};
+use ost::expulsion::ExpulsionDegree;
+use ost::query::{EventQuery, SortOrder};
+
+fn fill(context: &mut dyn Context) {
+    let zardoz = context.add_person("Zardoz").unwrap();
+    let zed = context.add_person("Zed").unwrap();
+    for amount in 1..=5 {
+        let _ = context.add_feeding(&zardoz, amount, 0, 0).unwrap();
+    }
+    let _ = context.add_feeding(&zed, 100, 0, 0).unwrap();
+    let _ = context
+        .add_expulsion(&zardoz, ExpulsionDegree::Pee)
+        .unwrap();
+    let _ = context.add_event(&zed, EventType::Bath).unwrap();
+
+    let mut zed = context.get_person_by_key(zed.key()).unwrap();
+    zed.set_is_active(false);
+    context.modify_person(&zed).unwrap();
+}
+
+fn check_queries(context: &dyn Context) {
+    let everything = context.query(&EventQuery::default()).unwrap();
+    assert_eq!(everything.total, 8);
+    assert_eq!(everything.items.len(), 8);
+    let time_stamps: Vec<DateTime<Utc>> = everything
+        .items
+        .iter()
+        .map(|item| *item.event_base().time_stamp())
+        .collect();
+    assert!(time_stamps.windows(2).all(|w| w[0] >= w[1]));
+
+    let zardoz = context
+        .persons()
+        .into_iter()
+        .find(|p| p.name() == "Zardoz")
+        .unwrap();
+    let page = context
+        .query(&EventQuery {
+            person: Some(zardoz.key()),
+            kinds: vec![EventKind::Feed],
+            sort: SortOrder::OldestFirst,
+            offset: 1,
+            limit: Some(2),
+            ..EventQuery::default()
+        })
+        .unwrap();
+    assert_eq!(page.total, 5);
+    assert_eq!(page.items.len(), 2);
+    assert!(page
+        .items
+        .iter()
+        .all(|item| item.event_base().person_name() == "Zardoz"));
+
+    // Pages put back together give the unpaged order, whatever the sort
+    for sort in [SortOrder::NewestFirst, SortOrder::OldestFirst] {
+        let keys = |offset: usize, limit: Option<usize>| -> Vec<OstEventKey> {
+            context
+                .query(&EventQuery {
+                    sort,
+                    offset,
+                    limit,
+                    ..EventQuery::default()
+                })
+                .unwrap()
+                .items
+                .iter()
+                .map(|item| item.event_base().key())
+                .collect()
+        };
+        let paged: Vec<OstEventKey> = (0..8).step_by(3).flat_map(|o| keys(o, Some(3))).collect();
+        assert_eq!(paged, keys(0, None));
+    }
+
+    let active = context
+        .query(&EventQuery {
+            active_persons_only: true,
+            ..EventQuery::default()
+        })
+        .unwrap();
+    assert_eq!(active.total, 6);
+
+    let future = context
+        .query(&EventQuery {
+            from: Some(Utc::now() + chrono::Duration::days(1)),
+            ..EventQuery::default()
+        })
+        .unwrap();
+    assert_eq!(future.total, 0);
+    assert!(future.items.is_empty());
+}
+
+#[test]
+fn monolith_queries_filter_sort_and_page() {
+    let mono_file: &str = "./test_output/query_00.json";
+    let _ignore_fail = std::fs::remove_file(mono_file);
+    {
+        let mut context = construct_monolith(mono_file).unwrap();
+        fill(context.as_mut());
+        check_queries(context.as_ref());
+    }
+    let _ignore_fail = std::fs::remove_file(mono_file);
+}
+
+#[test]
+fn sqlite_queries_filter_sort_and_page() {
+    let db_file: &str = "./test_output/query_01.sqlite";
+    let _ignore_fail = std::fs::remove_file(db_file);
+    {
+        let mut context = construct_sqlite(db_file).unwrap();
+        fill(context.as_mut());
+        check_queries(context.as_ref());
+    }
+    let _ignore_fail = std::fs::remove_file(db_file);
+}
+
+#[test]
+fn queries_survive_the_query_string() {
+    let query = EventQuery {
+        person: Some(ost::person_key::OstPersonKey { id: 3 }),
+        kinds: vec![EventKind::Feed, EventKind::Event],
+        from: Some("2021-12-27T10:00:00Z".parse().unwrap()),
+        to: None,
+        active_persons_only: true,
+        sort: SortOrder::OldestFirst,
+        offset: 30,
+        limit: Some(15),
+    };
+    let parsed = EventQuery::from_query_string(&query.to_query_string()).unwrap();
+    assert_eq!(parsed, query);
+
+    assert_eq!(
+        EventQuery::from_query_string("").unwrap(),
+        EventQuery::default()
+    );
+}
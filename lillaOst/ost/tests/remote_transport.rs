@@ -0,0 +1,127 @@
+use std::cell::RefCell;
+
+use ost::context_async::AsyncContext;
+use ost::context_remote_async::{
+    ApiRequest, ApiResponse, AsyncRemoteMonolith, Endpoint, Method, TransportFuture,
+};
+use ost::error::OstError;
+
+thread_local! {
+    static SENT: RefCell<Vec<ApiRequest>> = const { RefCell::new(Vec::new()) };
+}
+
+fn answer(status: u16, body: &str) -> TransportFuture {
+    let response = ApiResponse {
+        status,
+        body: body.to_string(),
+    };
+    Box::pin(async move { Ok(response) })
+}
+
+fn stub_backend(request: ApiRequest) -> TransportFuture {
+    SENT.with(|sent| sent.borrow_mut().push(request.clone()));
+    match (request.method, request.url.as_str()) {
+        (Method::Get, "https://ost.example.com/api/v2/persons") => answer(
+            200,
+            r#"[{"id":0,"name":"Tiny","is_active":true},{"id":1,"name":"Big","is_active":false}]"#,
+        ),
+        (Method::Post, _) => answer(200, r#"{"Ok":null}"#),
+        _ => answer(404, ""),
+    }
+}
+
+fn stub_proxy_without_backend(_: ApiRequest) -> TransportFuture {
+    answer(503, "Service Unavailable")
+}
+
+fn stub_viewer_session(_: ApiRequest) -> TransportFuture {
+    answer(403, "")
+}
+
+fn stub_v1_duplicate(_: ApiRequest) -> TransportFuture {
+    answer(200, r#"{"Err":"Duplicate name: Zardoz already exists"}"#)
+}
+
+fn stub_v1_not_found(_: ApiRequest) -> TransportFuture {
+    answer(200, r#"{"Err":"Not found: Person not found"}"#)
+}
+
+fn remote(transport: fn(ApiRequest) -> TransportFuture) -> AsyncRemoteMonolith {
+    AsyncRemoteMonolith::new(
+        Endpoint {
+            base_url: "https://ost.example.com/".to_string(),
+            headers: vec![("x-client".to_string(), "tests".to_string())],
+        },
+        transport,
+    )
+}
+
+#[tokio::test]
+async fn calls_go_to_the_endpoint_with_its_headers() {
+    let remote = remote(stub_backend);
+
+    let persons = remote.persons().await;
+    assert_eq!(persons.len(), 2);
+    assert_eq!(persons[0].name(), "Tiny");
+
+    remote.add_fake_persons(3).await.unwrap();
+    let sent = SENT.with(|sent| sent.borrow().clone());
+    assert_eq!(sent.len(), 2);
+
+    let post = &sent[1];
+    assert_eq!(
+        post.url,
+        "https://ost.example.com/api/persons/add-fake-count"
+    );
+    assert_eq!(post.body.as_deref(), Some(r#"{"count":3}"#));
+    assert!(post
+        .headers
+        .contains(&("x-client".to_string(), "tests".to_string())));
+    assert!(post
+        .headers
+        .contains(&("content-type".to_string(), "application/json".to_string())));
+    // No session outside of a browser
+    assert!(!post.headers.iter().any(|(name, _)| name == "authorization"));
+}
+
+#[tokio::test]
+async fn statuses_turn_into_errors() {
+    assert!(matches!(
+        remote(stub_proxy_without_backend).purge_all_events().await,
+        Err(OstError::Transport(_))
+    ));
+    assert!(matches!(
+        remote(stub_viewer_session).purge_all_events().await,
+        Err(OstError::Forbidden(_))
+    ));
+    // An unreachable backend reads as empty
+    assert!(remote(stub_proxy_without_backend)
+        .persons()
+        .await
+        .is_empty());
+}
+
+#[tokio::test]
+async fn v1_string_errors_keep_their_kind() {
+    match remote(stub_v1_duplicate).add_person("Zardoz").await {
+        Err(OstError::DuplicateName(message)) => assert_eq!(message, "Zardoz already exists"),
+        _ => panic!("Expected a duplicate name"),
+    }
+    assert_eq!(
+        OstError::from_message("No person with name Zardoz found"),
+        OstError::Serialization("No person with name Zardoz found".to_string())
+    );
+}
+
+#[tokio::test]
+async fn v1_errors_without_a_payload_are_not_taken_for_success() {
+    let remote = remote(stub_v1_not_found);
+    assert!(matches!(
+        remote.add_fake_feedings(1).await,
+        Err(OstError::NotFound(_))
+    ));
+    assert!(matches!(
+        remote.purge_all_events().await,
+        Err(OstError::NotFound(_))
+    ));
+}
@@ -136,3 +136,60 @@ fn context_events_can_be_purged() {
 
     let _ignore_fail = std::fs::remove_file(mono_file);
 }
+
+#[test]
+fn persons_with_entries_are_only_removed_with_cascade() {
+    let mono_file: &str = "./test_output/removing_entities_04.json";
+    let _ignore_fail = std::fs::remove_file(mono_file);
+    {
+        let mut monolith_context = construct_monolith(mono_file).unwrap();
+        let zardoz = monolith_context.add_person("Zardoz").unwrap();
+        let zed = monolith_context.add_person("Zed").unwrap();
+        let _ = monolith_context.add_feeding(&zardoz, 1, 1, 1).unwrap();
+        let _ = monolith_context.add_feeding(&zed, 2, 2, 2).unwrap();
+
+        let zardoz_key = zardoz.key();
+        assert!(monolith_context.remove_person(zardoz, false).is_err());
+        let zardoz = monolith_context.get_person_by_key(zardoz_key).unwrap();
+        assert!(monolith_context.remove_person(zardoz, true).is_ok());
+    }
+    {
+        let monolith_context = construct_monolith(mono_file).unwrap();
+        let persons = monolith_context.persons();
+        assert_eq!(persons.len(), 1);
+        assert_eq!(persons[0].name(), "Zed");
+        let feeds = monolith_context.feedings();
+        assert_eq!(feeds.len(), 1);
+        assert_eq!(feeds[0].person_name(), "Zed");
+    }
+    let _ignore_fail = std::fs::remove_file(mono_file);
+}
+
+#[test]
+fn merged_persons_keep_every_entry() {
+    let mono_file: &str = "./test_output/removing_entities_05.json";
+    let _ignore_fail = std::fs::remove_file(mono_file);
+    {
+        let mut monolith_context = construct_monolith(mono_file).unwrap();
+        let ana = monolith_context.add_person("Ana").unwrap();
+        let duplicate = monolith_context.add_person("ana").unwrap();
+        let _ = monolith_context.add_feeding(&ana, 1, 1, 1).unwrap();
+        let _ = monolith_context.add_feeding(&duplicate, 2, 2, 2).unwrap();
+        let _ = monolith_context
+            .add_expulsion(&duplicate, ost::expulsion::ExpulsionDegree::Pee)
+            .unwrap();
+
+        let duplicate_key = duplicate.key();
+        assert!(monolith_context.merge_persons(duplicate, &ana).is_ok());
+        assert!(monolith_context.get_person_by_key(duplicate_key).is_none());
+    }
+    {
+        let monolith_context = construct_monolith(mono_file).unwrap();
+        assert_eq!(monolith_context.persons().len(), 1);
+        let feeds = monolith_context.feedings();
+        assert_eq!(feeds.len(), 2);
+        assert!(feeds.iter().all(|f| f.person_name() == "Ana"));
+        assert_eq!(monolith_context.expulsions()[0].person_name(), "Ana");
+    }
+    let _ignore_fail = std::fs::remove_file(mono_file);
+}
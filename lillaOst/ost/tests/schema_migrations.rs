@@ -0,0 +1,114 @@
+use std::path::Path;
+
+use ost::context::{construct_monolith, construct_monolith_from_file, CURRENT_SCHEMA_VERSION};
+use ost::event::EventType;
+use ost::expulsion::ExpulsionDegree;
+
+fn sample(version: u32) -> String {
+    format!("./test_data/schema/v{}.json", version)
+}
+
+#[test]
+fn every_schema_version_has_a_pinned_sample() {
+    for version in 1..=CURRENT_SCHEMA_VERSION {
+        assert!(
+            Path::new(&sample(version)).exists(),
+            "missing sample for schema version {}",
+            version
+        );
+    }
+}
+
+#[test]
+fn every_pinned_sample_loads_and_upgrades() {
+    for version in 1..=CURRENT_SCHEMA_VERSION {
+        let mono_file = format!("./test_output/schema_migrations_v{}.json", version);
+        let _ignore_fail = std::fs::remove_file(&mono_file);
+        {
+            let context = construct_monolith_from_file(&sample(version), &mono_file).unwrap();
+
+            let persons = context.persons();
+            assert_eq!(persons.len(), 2, "v{}", version);
+            assert!(!persons
+                .iter()
+                .find(|p| p.name() == "Zed")
+                .unwrap()
+                .is_active());
+
+            let feeds = context.feedings();
+            assert_eq!(feeds.len(), 1, "v{}", version);
+            assert_eq!(feeds[0].key().id, 7);
+            assert_eq!(feeds[0].breast_milk(), 120);
+            assert_eq!(feeds[0].person_name(), "Zardoz");
+
+            let expulsions = context.expulsions();
+            assert_eq!(expulsions[0].degree(), ExpulsionDegree::Poopies);
+
+            let events = context.events();
+            assert_eq!(events[0].event(), EventType::Temperature(37.5));
+            assert_eq!(events[0].person_name(), "Zed");
+        }
+        {
+            let payload = std::fs::read_to_string(&mono_file).unwrap();
+            let document: serde_json::Value = serde_json::from_str(&payload).unwrap();
+            assert_eq!(
+                document["schema_version"].as_u64(),
+                Some(CURRENT_SCHEMA_VERSION as u64)
+            );
+        }
+        let _ignore_fail = std::fs::remove_file(&mono_file);
+    }
+}
+
+#[test]
+fn ids_keep_counting_after_a_migration() {
+    let mono_file: &str = "./test_output/schema_migrations_ids.json";
+    let _ignore_fail = std::fs::remove_file(mono_file);
+    {
+        let mut context = construct_monolith_from_file(&sample(1), mono_file).unwrap();
+        let zardoz = context
+            .persons()
+            .into_iter()
+            .find(|p| p.name() == "Zardoz")
+            .unwrap();
+        let feed = context.add_feeding(&zardoz, 1, 1, 1).unwrap();
+        assert_eq!(feed.key().id, 8);
+    }
+    let _ignore_fail = std::fs::remove_file(mono_file);
+}
+
+#[test]
+fn documents_from_a_newer_version_are_rejected() {
+    let origin: &str = "./test_output/schema_migrations_future_origin.json";
+    let mono_file: &str = "./test_output/schema_migrations_future.json";
+    let future = format!(
+        "{{ \"schema_version\": {}, \"persons\": [], \"feeds\": [], \"expulsions\": [], \"events\": [] }}",
+        CURRENT_SCHEMA_VERSION + 1
+    );
+    std::fs::write(origin, future).unwrap();
+    assert!(construct_monolith_from_file(origin, mono_file).is_err());
+    let _ignore_fail = std::fs::remove_file(origin);
+    let _ignore_fail = std::fs::remove_file(mono_file);
+}
+
+#[test]
+fn documents_from_a_newer_version_are_left_untouched() {
+    let mono_file: &str = "./test_output/schema_migrations_future_untouched.json";
+    let _ignore_fail = std::fs::remove_file(format!("{}.bak", mono_file));
+    let future = format!(
+        "{{ \"schema_version\": {}, \"persons\": [], \"feeds\": [], \"expulsions\": [], \"events\": [] }}",
+        CURRENT_SCHEMA_VERSION + 1
+    );
+    std::fs::write(mono_file, &future).unwrap();
+    std::fs::write(
+        format!("{}.bak", mono_file),
+        std::fs::read_to_string(sample(CURRENT_SCHEMA_VERSION)).unwrap(),
+    )
+    .unwrap();
+
+    assert!(construct_monolith(mono_file).is_err());
+    assert_eq!(std::fs::read_to_string(mono_file).unwrap(), future);
+
+    let _ignore_fail = std::fs::remove_file(mono_file);
+    let _ignore_fail = std::fs::remove_file(format!("{}.bak", mono_file));
+}
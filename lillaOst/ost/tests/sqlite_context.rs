@@ -0,0 +1,287 @@
+use chrono::{NaiveDate, Utc};
+
+use ost::context::{construct_monolith, construct_sqlite, construct_sqlite_from_monolith};
+use ost::error::OstError;
+use ost::event::EventType;
+use ost::expulsion::ExpulsionDegree;
+use ost::offline::{ReplayId, ReplayMark};
+use ost::person::Sex;
+
+static BACKENDEVENTS: &str = "./test_data/backend_events.json";
+
+#[test]
+fn entities_persist_between_executions() {
+    let db_file: &str = "./test_output/sqlite_context_00.sqlite";
+    let _ignore_fail = std::fs::remove_file(db_file);
+    {
+        let mut context = construct_sqlite(db_file).unwrap();
+        let zardoz = context.add_person("Zardoz").unwrap();
+        let _ = context.add_feeding(&zardoz, 1, 2, 3).unwrap();
+        let _ = context
+            .add_expulsion(&zardoz, ExpulsionDegree::Poopies)
+            .unwrap();
+        let _ = context
+            .add_event(&zardoz, EventType::Medicine("Dalsy".to_string()))
+            .unwrap();
+        assert!(context.add_person("Zardoz").is_err());
+    }
+    {
+        let context = construct_sqlite(db_file).unwrap();
+        assert_eq!(context.persons().len(), 1);
+
+        let feeds = context.feedings();
+        assert_eq!(feeds.len(), 1);
+        assert_eq!(feeds[0].formula(), 2);
+        assert_eq!(feeds[0].person_name(), "Zardoz");
+
+        let expulsions = context.expulsions();
+        assert_eq!(expulsions[0].degree(), ExpulsionDegree::Poopies);
+
+        let events = context.events();
+        assert_eq!(events[0].event(), EventType::Medicine("Dalsy".to_string()));
+    }
+    let _ignore_fail = std::fs::remove_file(db_file);
+}
+
+#[test]
+fn entities_can_be_modified_and_removed() {
+    let db_file: &str = "./test_output/sqlite_context_01.sqlite";
+    let _ignore_fail = std::fs::remove_file(db_file);
+    {
+        let mut context = construct_sqlite(db_file).unwrap();
+        let zardoz = context.add_person("Zardoz").unwrap();
+        let mut feed = context.add_feeding(&zardoz, 1, 1, 1).unwrap();
+        let to_remove = context.add_feeding(&zardoz, 2, 2, 2).unwrap();
+
+        let time_stamp = *feed.time_stamp();
+        feed.modify_feed(10, 10, 10, time_stamp);
+        context.modify_feeding(&feed).unwrap();
+
+        let removed_id = to_remove.key().id;
+        context.remove_feeding(to_remove).unwrap();
+
+        let nu_feed = context.add_feeding(&zardoz, 3, 3, 3).unwrap();
+        assert!(nu_feed.key().id > removed_id);
+
+        let mut renamed = zardoz;
+        renamed.set_name("Zed");
+        context.modify_person(&renamed).unwrap();
+    }
+    {
+        let context = construct_sqlite(db_file).unwrap();
+        let feeds = context.feedings();
+        assert_eq!(feeds.len(), 2);
+        assert!(feeds.iter().any(|f| f.breast_milk() == 10));
+        assert!(feeds.iter().all(|f| f.breast_milk() != 2));
+        assert!(feeds.iter().all(|f| f.person_name() == "Zed"));
+    }
+    let _ignore_fail = std::fs::remove_file(db_file);
+}
+
+#[test]
+fn feedings_by_only_returns_the_persons_entries() {
+    let db_file: &str = "./test_output/sqlite_context_02.sqlite";
+    let _ignore_fail = std::fs::remove_file(db_file);
+    {
+        let mut context = construct_sqlite(db_file).unwrap();
+        let zardoz = context.add_person("Zardoz").unwrap();
+        let zed = context.add_person("Zed").unwrap();
+        let _ = context.add_feeding(&zardoz, 1, 1, 1).unwrap();
+        let _ = context.add_feeding(&zed, 2, 2, 2).unwrap();
+        let _ = context.add_feeding(&zed, 3, 3, 3).unwrap();
+
+        assert_eq!(context.feedings_by(&zardoz).len(), 1);
+        assert_eq!(context.feedings_by(&zed).len(), 2);
+    }
+    let _ignore_fail = std::fs::remove_file(db_file);
+}
+
+#[test]
+fn monolith_files_can_be_imported() {
+    let db_file: &str = "./test_output/sqlite_context_03.sqlite";
+    let _ignore_fail = std::fs::remove_file(db_file);
+    {
+        let monolith = construct_monolith(BACKENDEVENTS).unwrap();
+        let context = construct_sqlite_from_monolith(BACKENDEVENTS, db_file).unwrap();
+
+        assert_eq!(context.persons().len(), monolith.persons().len());
+        assert_eq!(context.feedings().len(), monolith.feedings().len());
+        assert_eq!(context.expulsions().len(), monolith.expulsions().len());
+        assert_eq!(context.events().len(), monolith.events().len());
+
+        for feed in monolith.feedings().iter() {
+            let imported = context.get_feeding_by_key(&feed.key()).unwrap();
+            assert_eq!(imported.breast_milk(), feed.breast_milk());
+            assert_eq!(imported.time_stamp(), feed.time_stamp());
+        }
+    }
+    assert!(construct_sqlite_from_monolith(BACKENDEVENTS, db_file).is_err());
+    let _ignore_fail = std::fs::remove_file(db_file);
+}
+
+#[test]
+fn imported_databases_dont_hand_out_removed_ids_again() {
+    let mono_file: &str = "./test_output/sqlite_context_07.json";
+    let db_file: &str = "./test_output/sqlite_context_07.sqlite";
+    let _ignore_fail = std::fs::remove_file(mono_file);
+    let _ignore_fail = std::fs::remove_file(db_file);
+    {
+        let mut monolith = construct_monolith(mono_file).unwrap();
+        let zardoz = monolith.add_person("Zardoz").unwrap();
+        let _ = monolith.add_feeding(&zardoz, 1, 1, 1).unwrap();
+        let removed = monolith.add_feeding(&zardoz, 2, 2, 2).unwrap();
+        monolith.remove_feeding(removed).unwrap();
+        let removed = monolith.add_person("Removed").unwrap();
+        monolith.remove_person(removed, true).unwrap();
+    }
+    {
+        let mut context = construct_sqlite_from_monolith(mono_file, db_file).unwrap();
+        let added = context.add_person("Zed").unwrap();
+        assert_eq!(added.key().id, 2);
+        let feed = context.add_feeding(&added, 3, 3, 3).unwrap();
+        assert_eq!(feed.key().id, 2);
+    }
+    let _ignore_fail = std::fs::remove_file(mono_file);
+    let _ignore_fail = std::fs::remove_file(db_file);
+}
+
+#[test]
+fn monoliths_with_missing_persons_are_not_imported() {
+    let mono_file: &str = "./test_output/sqlite_context_08.json";
+    let db_file: &str = "./test_output/sqlite_context_08.sqlite";
+    let _ignore_fail = std::fs::remove_file(mono_file);
+    let _ignore_fail = std::fs::remove_file(db_file);
+    {
+        let mut monolith = construct_monolith(mono_file).unwrap();
+        let zardoz = monolith.add_person("Zardoz").unwrap();
+        let _ = monolith.add_feeding(&zardoz, 1, 1, 1).unwrap();
+    }
+    let mut document: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(mono_file).unwrap()).unwrap();
+    document["persons"] = serde_json::json!([]);
+    std::fs::write(mono_file, document.to_string()).unwrap();
+
+    assert!(matches!(
+        construct_sqlite_from_monolith(mono_file, db_file),
+        Err(OstError::Validation(_))
+    ));
+    let _ignore_fail = std::fs::remove_file(mono_file);
+    let _ignore_fail = std::fs::remove_file(db_file);
+}
+
+#[test]
+fn replay_marks_are_imported_and_kept() {
+    let mono_file: &str = "./test_output/sqlite_context_09.json";
+    let db_file: &str = "./test_output/sqlite_context_09.sqlite";
+    let _ignore_fail = std::fs::remove_file(mono_file);
+    let _ignore_fail = std::fs::remove_file(db_file);
+    let replayed = |id: u32| ReplayId {
+        client: "a-client".to_string(),
+        id,
+    };
+    let imported_key = {
+        let mut monolith = construct_monolith(mono_file).unwrap();
+        let zardoz = monolith.add_person("Zardoz").unwrap();
+        monolith
+            .add_event_at(&zardoz, EventType::Bath, Utc::now(), Some(&replayed(1)))
+            .unwrap()
+            .key()
+    };
+    {
+        let mut context = construct_sqlite_from_monolith(mono_file, db_file).unwrap();
+        assert_eq!(
+            context.replay_mark("a-client"),
+            Some(ReplayMark {
+                id: 1,
+                key: imported_key
+            })
+        );
+        let zardoz = context.persons().pop().unwrap();
+        let _ = context
+            .add_expulsion_at(
+                &zardoz,
+                ExpulsionDegree::Pee,
+                Utc::now(),
+                Some(&replayed(2)),
+            )
+            .unwrap();
+    }
+    {
+        let context = construct_sqlite(db_file).unwrap();
+        let mark = context.replay_mark("a-client").unwrap();
+        assert_eq!(mark.id, 2);
+        assert!(context.get_expulsion_by_key(&mark.key).is_some());
+    }
+    let _ignore_fail = std::fs::remove_file(mono_file);
+    let _ignore_fail = std::fs::remove_file(db_file);
+}
+
+#[test]
+fn persons_can_be_merged_and_removed() {
+    let db_file: &str = "./test_output/sqlite_context_04.sqlite";
+    let _ignore_fail = std::fs::remove_file(db_file);
+    {
+        let mut context = construct_sqlite(db_file).unwrap();
+        let ana = context.add_person("Ana").unwrap();
+        let duplicate = context.add_person("ana").unwrap();
+        let _ = context.add_feeding(&duplicate, 1, 1, 1).unwrap();
+        let _ = context
+            .add_event(&duplicate, EventType::Note("first".to_string()))
+            .unwrap();
+
+        context.merge_persons(duplicate, &ana).unwrap();
+        assert_eq!(context.persons().len(), 1);
+        assert_eq!(context.feedings_by(&ana).len(), 1);
+        assert_eq!(context.events_by(&ana).len(), 1);
+
+        let ana_key = ana.key();
+        assert!(context.remove_person(ana, false).is_err());
+        assert_eq!(context.feedings().len(), 1);
+
+        let ana = context.get_person_by_key(ana_key).unwrap();
+        context.remove_person(ana, true).unwrap();
+        assert!(context.persons().is_empty());
+        assert!(context.feedings().is_empty());
+        assert!(context.events().is_empty());
+    }
+    let _ignore_fail = std::fs::remove_file(db_file);
+}
+
+#[test]
+fn databases_from_before_person_profiles_still_open() {
+    let db_file: &str = "./test_output/sqlite_context_05.sqlite";
+    let _ignore_fail = std::fs::remove_file(db_file);
+    {
+        let connection = rusqlite::Connection::open(db_file).unwrap();
+        connection
+            .execute_batch(
+                "CREATE TABLE persons (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    name TEXT NOT NULL,
+                    is_active INTEGER NOT NULL
+                );
+                INSERT INTO persons (name, is_active) VALUES ('Zardoz', 1);",
+            )
+            .unwrap();
+    }
+    {
+        let mut context = construct_sqlite(db_file).unwrap();
+        let mut zardoz = context.persons().pop().unwrap();
+        assert_eq!(zardoz.birth_date(), None);
+        assert_eq!(zardoz.sex(), None);
+
+        zardoz.set_birth_date(NaiveDate::from_ymd_opt(2021, 11, 4));
+        zardoz.set_sex(Some(Sex::Male));
+        zardoz.set_birth_weight(Some(3100));
+        context.modify_person(&zardoz).unwrap();
+    }
+    {
+        let context = construct_sqlite(db_file).unwrap();
+        let zardoz = context.persons().pop().unwrap();
+        assert_eq!(zardoz.birth_date(), NaiveDate::from_ymd_opt(2021, 11, 4));
+        assert_eq!(zardoz.sex(), Some(Sex::Male));
+        assert_eq!(zardoz.birth_weight(), Some(3100));
+        assert_eq!(zardoz.colour(), None);
+    }
+    let _ignore_fail = std::fs::remove_file(db_file);
+}